@@ -0,0 +1,27 @@
+//! Expose lyric labels to screen readers
+//!
+//! GTK4 talks AT-SPI through `gtk::Accessible` rather than ATK. Lyric labels keep
+//! their default `AccessibleRole::Label`, their accessible name follows the line shown.
+//! Screen readers don't read out name changes of a label that isn't focused, so with
+//! `accessibility-announce` each new current line is announced as well.
+
+use gtk::accessible::{Property, State};
+use gtk::prelude::*;
+
+use crate::utils::gettext;
+
+/// Update accessible name and description of `label` at `position` to `text`
+pub fn update_line(label: &gtk::Label, position: &str, text: &str) {
+    let description = if position == "above" {
+        gettext("current lyric line")
+    } else {
+        gettext("secondary lyric line")
+    };
+    label.update_property(&[Property::Label(text), Property::Description(&description)]);
+    // an empty line is left out of the accessibility tree
+    label.update_state(&[State::Hidden(text.is_empty())]);
+    #[cfg(feature = "accessibility-announce")]
+    if position == "above" && !text.is_empty() {
+        label.announce(text, gtk::AccessibleAnnouncementPriority::Medium);
+    }
+}
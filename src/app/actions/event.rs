@@ -21,6 +21,10 @@ pub enum UIAction {
     SetDisplayMode(String),
     /// set new lyric alignment mode
     SetLyricAlign(String),
+    /// toggles offline mode
+    ToggleOffline,
+    /// toggles keeping the window above others
+    ToggleAlwaysOnTop,
     /// Quit Waylyrics
     Quit,
 }
@@ -57,6 +61,12 @@ fn register_ui_action(app: WeakRef<Application>, wind: WeakRef<Window>) -> Sende
                     "set-lyric-align",
                     Some(&lyric_align.to_variant()),
                 ),
+                UIAction::ToggleOffline => {
+                    ActionGroupExt::activate_action(&wind, "toggle-offline", None)
+                }
+                UIAction::ToggleAlwaysOnTop => {
+                    ActionGroupExt::activate_action(&wind, "toggle-always-on-top", None)
+                }
                 UIAction::Quit => {
                     wind.close();
                 }
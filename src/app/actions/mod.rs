@@ -4,10 +4,14 @@ mod event;
 #[cfg(feature = "action-event")]
 pub use event::{init_ui_action_channel, UIAction, UI_ACTION};
 
-use crate::app::{utils::set_click_pass_through, Window};
+use crate::app::{
+    input_region::set_click_through, monitor::move_to_monitor,
+    search_window::history::clear_history, stacking::set_always_on_top, Window,
+};
 
+use crate::config::persist::persist_config_value;
 use crate::config::Align;
-use crate::log::error;
+use crate::log::{error, info};
 use crate::utils::bind_shortcut;
 
 use glib_macros::clone;
@@ -15,7 +19,7 @@ use gtk::gio::SimpleAction;
 use gtk::glib::{self, VariantTy};
 use gtk::{prelude::*, subclass::prelude::*, Application};
 
-use super::set_lyric_align;
+use super::{set_lyric_align, set_offline_mode, set_window_opacity};
 
 pub fn register_switch_decoration(wind: &Window, trigger: &str) {
     let action = SimpleAction::new("switch-decoration", None);
@@ -23,7 +27,9 @@ pub fn register_switch_decoration(wind: &Window, trigger: &str) {
         #[weak]
         wind,
         move |_, _| {
-            wind.set_decorated(!wind.is_decorated());
+            let decorated = !wind.is_decorated();
+            wind.set_decorated(decorated);
+            info!("set decorated: {decorated}");
         }
     ));
     wind.add_action(&action);
@@ -51,8 +57,8 @@ pub fn register_switch_passthrough(wind: &Window, trigger: &str) {
         wind,
         move |_, _| {
             let clickthrough = !wind.imp().clickthrough.get();
-            wind.imp().clickthrough.set(clickthrough);
-            set_click_pass_through(&wind, clickthrough);
+            set_click_through(&wind, clickthrough);
+            info!("click through: {clickthrough}");
             wind.present();
         }
     ));
@@ -61,6 +67,130 @@ pub fn register_switch_passthrough(wind: &Window, trigger: &str) {
     bind_shortcut("win.switch-passthrough", wind, trigger);
 }
 
+/// Keep the window above others, written to `always-on-top` in config
+pub fn register_toggle_always_on_top(wind: &Window) {
+    let action = SimpleAction::new("toggle-always-on-top", None);
+    action.connect_activate(clone!(
+        #[weak]
+        wind,
+        move |_, _| {
+            let always_on_top = !wind.imp().always_on_top.get();
+            set_always_on_top(&wind, always_on_top);
+            info!("always on top: {always_on_top}");
+            if let Err(e) = persist_config_value("always-on-top", always_on_top) {
+                error!("cannot save always-on-top: {e}");
+            }
+        }
+    ));
+    wind.add_action(&action);
+}
+
+/// Apply `opacity` and write it to `window-opacity` in config
+fn change_opacity(wind: &Window, opacity: f64) {
+    let opacity = set_window_opacity(wind, opacity);
+    info!("window opacity: {opacity}");
+    if let Err(e) = persist_config_value("window-opacity", opacity) {
+        error!("cannot save window-opacity: {e}");
+    }
+}
+
+pub fn register_set_opacity(wind: &Window) {
+    let action = SimpleAction::new("set-opacity", Some(VariantTy::DOUBLE));
+    action.connect_activate(clone!(
+        #[weak]
+        wind,
+        move |_, opacity| {
+            let Some(opacity) = opacity.and_then(|o| o.get::<f64>()) else {
+                return;
+            };
+            change_opacity(&wind, opacity);
+        }
+    ));
+    wind.add_action(&action);
+}
+
+pub fn register_adjust_opacity(wind: &Window, decrease_trigger: &str, increase_trigger: &str) {
+    for (name, direction, trigger) in [
+        ("decrease-opacity", -1., decrease_trigger),
+        ("increase-opacity", 1., increase_trigger),
+    ] {
+        let action = SimpleAction::new(name, None);
+        action.connect_activate(clone!(
+            #[weak]
+            wind,
+            move |_, _| {
+                let step = wind.imp().opacity_step.get();
+                change_opacity(&wind, wind.opacity() + direction * step);
+            }
+        ));
+        wind.add_action(&action);
+
+        bind_shortcut(&format!("win.{name}"), wind, trigger);
+    }
+}
+
+pub fn register_move_to_monitor(wind: &Window) {
+    let action = SimpleAction::new("move-to-monitor", Some(VariantTy::INT32));
+    action.connect_activate(clone!(
+        #[weak]
+        wind,
+        move |_, index| {
+            let Some(index) = index.and_then(|i| i.get::<i32>()) else {
+                return;
+            };
+            let Ok(index) = usize::try_from(index) else {
+                error!("invalid monitor index: {index}");
+                return;
+            };
+            move_to_monitor(&wind, index);
+        }
+    ));
+    wind.add_action(&action);
+}
+
+pub fn register_toggle_offline(wind: &Window) {
+    let action = SimpleAction::new("toggle-offline", None);
+    action.connect_activate(clone!(
+        #[weak]
+        wind,
+        move |_, _| {
+            let offline = !wind.imp().offline_mode.get();
+            set_offline_mode(&wind, offline);
+            info!("offline mode: {offline}");
+        }
+    ));
+    wind.add_action(&action);
+}
+
+/// Copy the line shown in `above` label to clipboard
+pub fn register_copy_current_line(wind: &Window) {
+    let action = SimpleAction::new("copy-current-line", None);
+    action.connect_activate(clone!(
+        #[weak]
+        wind,
+        move |_, _| {
+            let label = super::get_label(&wind, "above");
+            // the full line if it was truncated by `lyrics-max-line-length`
+            let text = label.tooltip_text().unwrap_or_else(|| label.text());
+            if text.trim().is_empty() {
+                return;
+            }
+            wind.clipboard().set_text(&text);
+            info!("copied current line");
+        }
+    ));
+    wind.add_action(&action);
+}
+
+pub fn register_clear_search_history(app: &Application) {
+    let action = SimpleAction::new("clear-search-history", None);
+    action.connect_activate(move |_, _| {
+        clear_history();
+        info!("cleared search history");
+    });
+    app.add_action(&action);
+}
+
 pub fn register_set_display_mode(wind: &Window) {
     let action = SimpleAction::new("set-display-mode", Some(VariantTy::STRING));
     action.connect_activate(clone!(
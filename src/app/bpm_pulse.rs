@@ -0,0 +1,144 @@
+//! Pulse the current line on the beat of the track, see `animate-to-bpm`.
+//!
+//! Only `transform` is animated, so labels are never measured or allocated again.
+//! Beats are counted from `lyric_start`, the pulse is restarted whenever it changes by a seek.
+
+use std::cell::RefCell;
+use std::time::{Duration, SystemTime};
+
+use gtk::glib::{self, ControlFlow};
+use gtk::prelude::*;
+use gtk::subclass::prelude::ObjectSubclassIsExt;
+use gtk::CssProvider;
+
+use super::{get_label, Window};
+use crate::sync::{TrackMeta, TrackState, TRACK_PLAYING_STATE};
+
+/// css class added to `above` label on each beat
+pub const PULSE_CSS_CLASS: &str = "pulse";
+/// tempos out of this range are likely wrong tags, and would flicker or crawl
+const BPM_RANGE: std::ops::RangeInclusive<u32> = 30..=300;
+
+/// below the theme, so that themes can restyle or disable it
+const PULSE_CSS: &str = "
+label#above {
+  transition: transform 120ms ease-in-out;
+}
+label#above.pulse {
+  transform: scale(1.02);
+}
+";
+
+thread_local! {
+    static PULSE_PROVIDER: RefCell<Option<CssProvider>> = const { RefCell::new(None) };
+}
+
+/// Time between beats, `None` for tempos out of `BPM_RANGE`
+pub fn beat_interval(bpm: u32) -> Option<Duration> {
+    BPM_RANGE
+        .contains(&bpm)
+        .then(|| Duration::from_secs(60) / bpm)
+}
+
+/// Time until the next beat, `elapsed` after the first one
+pub fn until_next_beat(elapsed: Duration, beat: Duration) -> Duration {
+    let into_beat = Duration::from_nanos((elapsed.as_nanos() % beat.as_nanos()) as u64);
+    if into_beat.is_zero() {
+        Duration::ZERO
+    } else {
+        beat - into_beat
+    }
+}
+
+pub fn set_animate_to_bpm(window: &Window, enabled: bool) {
+    window.imp().animate_to_bpm.set(enabled);
+    if !enabled {
+        stop_pulse(window);
+        return;
+    }
+    PULSE_PROVIDER.with_borrow_mut(|provider| {
+        if provider.is_some() {
+            return;
+        }
+        let css_provider = CssProvider::new();
+        css_provider.load_from_data(PULSE_CSS);
+        gtk::style_context_add_provider_for_display(
+            &WidgetExt::display(window),
+            &css_provider,
+            gtk::STYLE_PROVIDER_PRIORITY_APPLICATION,
+        );
+        *provider = Some(css_provider);
+    });
+}
+
+fn stop_pulse(window: &Window) {
+    let imp = window.imp();
+    imp.pulse_state.set(None);
+    if let Some(source) = imp.pulse_source.take() {
+        source.remove();
+    }
+    get_label(window, "above").remove_css_class(PULSE_CSS_CLASS);
+}
+
+fn beat(window: &Window) {
+    let label = get_label(window, "above");
+    label.add_css_class(PULSE_CSS_CLASS);
+    glib::timeout_add_local_once(
+        Duration::from_millis(120),
+        glib::clone!(
+            #[weak]
+            label,
+            move || label.remove_css_class(PULSE_CSS_CLASS)
+        ),
+    );
+}
+
+fn start_pulse(window: &Window, beat_interval: Duration, lyric_start: SystemTime) {
+    let delay = until_next_beat(lyric_start.elapsed().unwrap_or_default(), beat_interval);
+    let source = glib::timeout_add_local_once(
+        delay,
+        glib::clone!(
+            #[weak]
+            window,
+            move || {
+                beat(&window);
+                let source = glib::timeout_add_local(
+                    beat_interval,
+                    glib::clone!(
+                        #[weak]
+                        window,
+                        #[upgrade_or]
+                        ControlFlow::Break,
+                        move || {
+                            beat(&window);
+                            ControlFlow::Continue
+                        }
+                    ),
+                );
+                window.imp().pulse_source.replace(Some(source));
+            }
+        ),
+    );
+    window.imp().pulse_source.replace(Some(source));
+}
+
+/// Called by `refresh_lyric`, (re)starts the pulse if the tempo or `lyric_start` changed
+pub fn update_pulse(window: &Window, paused: bool) {
+    let imp = window.imp();
+    if !imp.animate_to_bpm.get() {
+        return;
+    }
+    let bpm = TRACK_PLAYING_STATE.with_borrow(|TrackState { metainfo, .. }| {
+        metainfo.as_ref().and_then(|TrackMeta { bpm, .. }| *bpm)
+    });
+    let interval = bpm.and_then(beat_interval).filter(|_| !paused);
+    let state = interval.zip(imp.lyric_start.get());
+    if state == imp.pulse_state.get() {
+        return;
+    }
+    stop_pulse(window);
+    if let Some((interval, lyric_start)) = state {
+        imp.pulse_state.set(state);
+        start_pulse(window, interval, lyric_start);
+    }
+}
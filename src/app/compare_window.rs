@@ -0,0 +1,238 @@
+//! Shows the lyric found by each provider side by side
+
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::time::Duration;
+
+use glib_macros::clone;
+use gtk::glib;
+use gtk::prelude::*;
+
+use crate::app::dialog::show_dialog;
+use crate::glib_spawn;
+use crate::log::{error, info};
+use crate::lyric_providers::utils::http_cache::{self, LyricSource};
+use crate::lyric_providers::{LyricLineOwned, LyricOwned};
+use crate::sync::lyric::cache::update_lyric_cache;
+use crate::sync::lyric::fetch::search_each_provider;
+use crate::sync::{
+    get_lyric_cache_path, set_current_lyric, set_lyric_source, LyricState, TrackMeta, TrackState,
+    TRACK_PLAYING_STATE,
+};
+use crate::utils::gettext;
+
+/// lines within this distance to all other providers are considered in sync
+pub const TIMING_MATCH: Duration = Duration::from_millis(300);
+/// lines beyond this distance to any other provider are considered off
+pub const TIMING_OFF: Duration = Duration::from_secs(1);
+
+/// For each line of `lyric`, the largest distance to the closest line of each one in `others`.
+///
+/// `None` if there's nothing to compare with.
+pub fn timing_diffs(
+    lyric: &[LyricLineOwned],
+    others: &[&[LyricLineOwned]],
+) -> Vec<Option<Duration>> {
+    let distance = |a: Duration, b: Duration| if a > b { a - b } else { b - a };
+    lyric
+        .iter()
+        .map(|line| {
+            others
+                .iter()
+                .filter_map(|other| {
+                    other
+                        .iter()
+                        .map(|o| distance(o.start_time, line.start_time))
+                        .min()
+                })
+                .max()
+        })
+        .collect()
+}
+
+/// css class coloring a line by its `timing_diffs`
+pub fn timing_class(diff: Option<Duration>) -> Option<&'static str> {
+    match diff? {
+        diff if diff <= TIMING_MATCH => Some("success"),
+        diff if diff <= TIMING_OFF => Some("warning"),
+        _ => Some("error"),
+    }
+}
+
+fn format_line(line: &LyricLineOwned) -> String {
+    let millis = line.start_time.as_millis();
+    format!(
+        "[{:02}:{:02}.{:02}] {}",
+        millis / 60_000,
+        millis / 1000 % 60,
+        millis / 10 % 100,
+        line.text.trim()
+    )
+}
+
+fn lines(lyric: &LyricOwned) -> &[LyricLineOwned] {
+    match lyric {
+        LyricOwned::LineTimestamp(lines) => lines,
+        _ => &[],
+    }
+}
+
+/// Where and for which track a selected lyric is applied, fixed when the window is opened
+struct Target {
+    track_meta: TrackMeta,
+    cache_path: Option<PathBuf>,
+}
+
+impl Target {
+    fn is_playing(&self) -> bool {
+        TRACK_PLAYING_STATE
+            .with_borrow(|TrackState { metainfo, .. }| metainfo.as_ref() == Some(&self.track_meta))
+    }
+}
+
+pub fn show_compare_window(track_meta: TrackMeta, length_toleration_ms: u128, use_cache: bool) {
+    let target = Rc::new(Target {
+        track_meta: track_meta.clone(),
+        cache_path: get_lyric_cache_path(),
+    });
+    let window = gtk::Window::builder()
+        .title(gettext("Compare lyrics"))
+        .default_width(900)
+        .default_height(600)
+        .build();
+
+    let vbox = gtk::Box::new(gtk::Orientation::Vertical, 6);
+    let save_to_cache = gtk::CheckButton::with_label(&gettext("Save to cache"));
+    save_to_cache.set_active(use_cache);
+    save_to_cache.set_sensitive(use_cache);
+    let columns = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+    columns.set_homogeneous(true);
+    columns.set_vexpand(true);
+    columns.append(&gtk::Label::new(Some(&gettext("Searching..."))));
+    vbox.append(&columns);
+    vbox.append(&save_to_cache);
+    window.set_child(Some(&vbox));
+    window.present();
+
+    glib_spawn!(clone!(
+        #[weak]
+        window,
+        async move {
+            let results = match search_each_provider(&track_meta, length_toleration_ms).await {
+                Ok(results) => results,
+                Err(e) => {
+                    error!("{e} when comparing lyrics");
+                    vec![]
+                }
+            };
+            while let Some(child) = columns.first_child() {
+                columns.remove(&child);
+            }
+            if results.is_empty() {
+                columns.append(&gtk::Label::new(Some(&gettext("No lyric found"))));
+                return;
+            }
+
+            let results = Rc::new(results);
+            for idx in 0..results.len() {
+                let column = build_column(&results, idx);
+                let use_this = gtk::Button::with_label(&gettext("Use this one"));
+                use_this.connect_clicked(clone!(
+                    #[strong]
+                    results,
+                    #[strong]
+                    target,
+                    #[weak]
+                    save_to_cache,
+                    #[weak]
+                    window,
+                    move |_| {
+                        let (lyric, source) = results[idx].clone();
+                        apply_lyric(&window, &target, lyric, source, save_to_cache.is_active());
+                    }
+                ));
+                column.append(&use_this);
+                columns.append(&column);
+            }
+        }
+    ));
+}
+
+fn build_column(results: &[(LyricState, LyricSource)], idx: usize) -> gtk::Box {
+    let (lyric, source) = &results[idx];
+    let lyric = lines(&lyric.origin);
+    let others: Vec<&[LyricLineOwned]> = results
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| *i != idx)
+        .map(|(_, (other, _))| lines(&other.origin))
+        .filter(|other| !other.is_empty())
+        .collect();
+
+    let list = gtk::Box::new(gtk::Orientation::Vertical, 2);
+    for (line, diff) in lyric.iter().zip(timing_diffs(lyric, &others)) {
+        let label = gtk::Label::new(Some(&format_line(line)));
+        label.set_xalign(0.);
+        label.set_wrap(true);
+        if let Some(class) = timing_class(diff) {
+            label.add_css_class(class);
+        }
+        list.append(&label);
+    }
+    if lyric.is_empty() {
+        list.append(&gtk::Label::new(Some(&gettext("No timestamped lyric"))));
+    }
+
+    let scrolled_window = gtk::ScrolledWindow::builder()
+        .child(&list)
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .vexpand(true)
+        .build();
+
+    let title = gtk::Label::new(Some(&source.provider));
+    title.add_css_class("heading");
+
+    let column = gtk::Box::new(gtk::Orientation::Vertical, 6);
+    column.append(&title);
+    column.append(&scrolled_window);
+    column
+}
+
+fn apply_lyric(
+    window: &gtk::Window,
+    target: &Target,
+    lyric: LyricState,
+    source: LyricSource,
+    save: bool,
+) {
+    // lyrics of the previous track must not replace the playing one, or its cache
+    if !target.is_playing() {
+        window.close();
+        show_dialog(
+            gtk::Window::NONE,
+            &gettext("the track has changed since comparing"),
+            gtk::MessageType::Error,
+        );
+        return;
+    }
+    info!("selected {} from {} to compare", source.id, source.provider);
+    set_current_lyric(lyric);
+
+    if save {
+        let saved = target.cache_path.as_ref().is_some_and(|cache_path| {
+            let saved = update_lyric_cache(cache_path);
+            if saved {
+                http_cache::write_sidecar(&cache_path, Some(&source));
+            }
+            saved
+        });
+        if !saved {
+            show_dialog(
+                Some(window),
+                &gettext("cannot save lyric to cache"),
+                gtk::MessageType::Error,
+            );
+        }
+    }
+    set_lyric_source(Some(source));
+}
@@ -8,3 +8,71 @@ pub fn show_dialog(parent: Option<&impl IsA<Window>>, msg: &str, level: gtk::Mes
     });
     msg_dialog.present();
 }
+
+/// Ask for a line of text, `on_submit` is called with it only if OK is clicked
+pub fn prompt_dialog(
+    parent: Option<&impl IsA<Window>>,
+    msg: &str,
+    on_submit: impl Fn(String) + 'static,
+) {
+    let msg_dialog = gtk::MessageDialog::new(
+        parent,
+        gtk::DialogFlags::MODAL,
+        gtk::MessageType::Question,
+        ButtonsType::OkCancel,
+        msg,
+    );
+    let entry = gtk::Entry::builder().activates_default(true).build();
+    if let Ok(message_area) = msg_dialog.message_area().downcast::<gtk::Box>() {
+        message_area.append(&entry);
+    }
+    msg_dialog.set_default_response(gtk::ResponseType::Ok);
+    msg_dialog.connect_response(move |dialog, response| {
+        if response == gtk::ResponseType::Ok {
+            on_submit(entry.text().to_string());
+        }
+        dialog.close();
+    });
+    msg_dialog.present();
+}
+
+/// Window showing progress of a long task, closing it is how the task is cancelled
+pub fn progress_dialog(parent: &impl IsA<Window>, title: &str) -> (Window, gtk::ProgressBar) {
+    let progress_bar = gtk::ProgressBar::builder()
+        .show_text(true)
+        .margin_top(12)
+        .margin_bottom(12)
+        .margin_start(12)
+        .margin_end(12)
+        .width_request(300)
+        .build();
+    let window = Window::builder()
+        .title(title)
+        .transient_for(parent)
+        .child(&progress_bar)
+        .build();
+    window.present();
+    (window, progress_bar)
+}
+
+/// Ask to confirm `msg`, `on_confirm` is called only if OK is clicked
+pub fn confirm_dialog(
+    parent: Option<&impl IsA<Window>>,
+    msg: &str,
+    on_confirm: impl Fn() + 'static,
+) {
+    let msg_dialog = gtk::MessageDialog::new(
+        parent,
+        gtk::DialogFlags::MODAL,
+        gtk::MessageType::Question,
+        ButtonsType::OkCancel,
+        msg,
+    );
+    msg_dialog.connect_response(move |dialog, response| {
+        if response == gtk::ResponseType::Ok {
+            on_confirm();
+        }
+        dialog.close();
+    });
+    msg_dialog.present();
+}
@@ -0,0 +1,73 @@
+use std::cell::{Cell, RefCell};
+use std::path::PathBuf;
+
+use glib::Properties;
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+use gtk::{gio, glib};
+
+use crate::sync::TrackMeta;
+
+#[derive(Default)]
+pub struct LineData {
+    pub index: u32,
+    pub start_time: u64,
+    pub text: String,
+}
+
+#[derive(Properties, Default)]
+#[properties(wrapper_type = super::LineObject)]
+pub struct LineObject {
+    #[property(name = "index", get, set, type = u32, member = index)]
+    #[property(name = "start-time", get, set, type = u64, member = start_time)]
+    #[property(name = "text", get, set, type = String, member = text)]
+    pub data: RefCell<LineData>,
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for LineObject {
+    const NAME: &'static str = "EditorLineObject";
+    type Type = super::LineObject;
+}
+
+#[glib_macros::derived_properties]
+impl ObjectImpl for LineObject {}
+
+#[derive(Default)]
+pub struct Window {
+    pub vbox: gtk::Box,
+
+    pub scrolled_window: gtk::ScrolledWindow,
+    pub line_list: gtk::ListView,
+    pub lines: RefCell<Option<gio::ListStore>>,
+
+    pub save_button: gtk::Button,
+
+    pub use_cache: Cell<bool>,
+    /// track and cache the lyric being edited was loaded for
+    pub track_meta: RefCell<Option<TrackMeta>>,
+    pub cache_path: RefCell<Option<PathBuf>>,
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for Window {
+    const NAME: &'static str = "LyricEditorWindow";
+    type Type = super::Window;
+    type ParentType = gtk::Window;
+}
+
+impl ObjectImpl for Window {
+    fn constructed(&self) {
+        self.parent_constructed();
+
+        let obj = self.obj();
+        obj.setup_lines();
+
+        obj.setup_ui();
+        obj.setup_callbacks();
+        obj.setup_factory();
+    }
+}
+
+impl WidgetImpl for Window {}
+impl WindowImpl for Window {}
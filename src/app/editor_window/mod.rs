@@ -0,0 +1,269 @@
+mod imp;
+
+use std::time::Duration;
+
+use glib::Object;
+use gtk::glib::clone;
+use gtk::subclass::prelude::*;
+use gtk::{gio, glib};
+use gtk::{prelude::*, ListItem};
+
+use crate::app::dialog::show_dialog;
+use crate::log::info;
+use crate::lyric_providers::{LyricLineOwned, LyricOwned};
+use crate::sync::lyric::cache::update_lyric_cache;
+use crate::sync::{
+    get_current_lyric, get_lyric_cache_path, set_original_lyric_line, sort_lyric, TrackState,
+    TRACK_PLAYING_STATE,
+};
+use crate::utils::gettext;
+
+glib::wrapper! {
+    pub struct LineObject(ObjectSubclass<imp::LineObject>);
+}
+
+impl LineObject {
+    pub fn new(
+        index: usize,
+        LyricLineOwned {
+            text, start_time, ..
+        }: LyricLineOwned,
+    ) -> Self {
+        Object::builder()
+            .property("index", index as u32)
+            .property("start-time", start_time.as_millis() as u64)
+            .property("text", text)
+            .build()
+    }
+
+    fn to_lyric_line(&self) -> LyricLineOwned {
+        LyricLineOwned {
+            text: self.text(),
+            start_time: Duration::from_millis(self.start_time()),
+            confidence: None,
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct Window(ObjectSubclass<imp::Window>)
+        @extends gtk::Window, gtk::Widget,
+        @implements gio::ActionGroup, gio::ActionMap, gtk::Accessible, gtk::Buildable,
+                    gtk::ConstraintTarget, gtk::Native, gtk::Root, gtk::ShortcutManager;
+}
+
+impl Window {
+    pub fn new(use_cache: bool) -> Self {
+        let window: Self = Object::builder().build();
+        window.set_title(Some(&gettext("Edit lyric")));
+
+        window.imp().use_cache.set(use_cache);
+        window.imp().save_button.set_sensitive(use_cache);
+        window.imp().line_list.set_widget_name("editor-window-list");
+        window.imp().track_meta.replace(
+            TRACK_PLAYING_STATE.with_borrow(|TrackState { metainfo, .. }| metainfo.clone()),
+        );
+        window.imp().cache_path.replace(get_lyric_cache_path());
+
+        window.load_lines();
+        window
+    }
+
+    fn lines(&self) -> gio::ListStore {
+        self.imp()
+            .lines
+            .borrow()
+            .clone()
+            .expect("Could not get lines")
+    }
+
+    fn setup_lines(&self) {
+        let model = gio::ListStore::new::<LineObject>();
+
+        self.imp().lines.replace(Some(model));
+
+        let selection_model = gtk::NoSelection::new(Some(self.lines()));
+        self.imp().line_list.set_model(Some(&selection_model));
+    }
+
+    /// (re)load lines from current original lyric
+    fn load_lines(&self) {
+        self.lines().remove_all();
+
+        let LyricOwned::LineTimestamp(lines) = get_current_lyric().origin else {
+            return;
+        };
+        let lines: Vec<LineObject> = lines
+            .into_iter()
+            .enumerate()
+            .map(|(idx, line)| LineObject::new(idx, line))
+            .collect();
+        self.lines().extend_from_slice(&lines);
+    }
+
+    /// Whether the track the editor was opened for is still playing, closes the editor if not.
+    ///
+    /// Edits apply to the current lyric by line index, so they must not reach the next track's.
+    fn check_track(&self) -> bool {
+        let playing = TRACK_PLAYING_STATE.with_borrow(|TrackState { metainfo, .. }| {
+            *metainfo == *self.imp().track_meta.borrow()
+        });
+        if !playing {
+            self.close();
+            show_dialog(
+                gtk::Window::NONE,
+                &gettext("the track has changed since opening the editor"),
+                gtk::MessageType::Error,
+            );
+        }
+        playing
+    }
+
+    /// `resort` after a timestamp edit to keep lines in order, they're reloaded later as the
+    /// edited row is still in use
+    fn edit_line(&self, line: &LineObject, resort: bool) {
+        if !self.check_track() {
+            return;
+        }
+        set_original_lyric_line(line.index() as _, line.to_lyric_line());
+        if resort && sort_lyric() {
+            glib::idle_add_local_once(clone!(
+                #[weak(rename_to = window)]
+                self,
+                move || window.load_lines()
+            ));
+        }
+    }
+
+    fn setup_ui(&self) {
+        let imp = self.imp();
+
+        imp.vbox
+            .set_properties(&[("orientation", &gtk::Orientation::Vertical)]);
+
+        imp.vbox.append(&imp.scrolled_window);
+        imp.vbox.append(&imp.save_button);
+
+        imp.scrolled_window.set_child(Some(&imp.line_list));
+        imp.scrolled_window.set_vexpand(true);
+        imp.scrolled_window
+            .set_hscrollbar_policy(gtk::PolicyType::Never);
+        imp.scrolled_window.set_height_request(400);
+        imp.scrolled_window.set_width_request(400);
+
+        imp.save_button.set_label(&gettext("Save to cache"));
+
+        self.set_child(Some(&imp.vbox));
+    }
+
+    fn setup_callbacks(&self) {
+        self.imp().save_button.connect_clicked(clone!(
+            #[weak(rename_to = window)]
+            self,
+            move |_| {
+                if !window.imp().use_cache.get() || !window.check_track() {
+                    return;
+                }
+                let Some(cache_path) = window.imp().cache_path.borrow().clone() else {
+                    show_dialog(
+                        Some(&window),
+                        &gettext("cannot cache lyric for an unknown song"),
+                        gtk::MessageType::Error,
+                    );
+                    return;
+                };
+
+                if update_lyric_cache(&cache_path) {
+                    info!("saved edited lyric to {cache_path:?}");
+                }
+                window.load_lines();
+            }
+        ));
+    }
+
+    fn setup_factory(&self) {
+        let factory = gtk::SignalListItemFactory::new();
+        factory.connect_setup(clone!(
+            #[weak(rename_to = window)]
+            self,
+            move |_, list_item| {
+                let list_item = list_item
+                    .downcast_ref::<ListItem>()
+                    .expect("Needs to be ListItem")
+                    .clone();
+
+                let start_time = gtk::SpinButton::with_range(0., u32::MAX as f64, 10.);
+                start_time.set_digits(0);
+                start_time.set_tooltip_text(Some(&gettext("start time (ms)")));
+                let text = gtk::Entry::new();
+                text.set_hexpand(true);
+
+                start_time.connect_value_changed(clone!(
+                    #[weak]
+                    window,
+                    #[weak]
+                    list_item,
+                    move |spin| {
+                        let Some(line) = list_item.item().and_downcast::<LineObject>() else {
+                            return;
+                        };
+                        let value = spin.value() as u64;
+                        if line.start_time() == value {
+                            return;
+                        }
+                        line.set_start_time(value);
+                        window.edit_line(&line, true);
+                    }
+                ));
+                text.connect_changed(clone!(
+                    #[weak]
+                    window,
+                    #[weak]
+                    list_item,
+                    move |entry| {
+                        let Some(line) = list_item.item().and_downcast::<LineObject>() else {
+                            return;
+                        };
+                        let value = entry.text().to_string();
+                        if line.text() == value {
+                            return;
+                        }
+                        line.set_text(value);
+                        window.edit_line(&line, false);
+                    }
+                ));
+
+                let row = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+                row.append(&start_time);
+                row.append(&text);
+                list_item.set_child(Some(&row));
+            }
+        ));
+        factory.connect_bind(move |_, list_item| {
+            let list_item = list_item
+                .downcast_ref::<ListItem>()
+                .expect("Needs to be ListItem");
+            let line = list_item
+                .item()
+                .and_downcast::<LineObject>()
+                .expect("The item has to be an `LineObject`.");
+            let row = list_item
+                .child()
+                .and_downcast::<gtk::Box>()
+                .expect("The child has to be a `Box`.");
+
+            let start_time = row
+                .first_child()
+                .and_downcast::<gtk::SpinButton>()
+                .expect("The first child has to be a `SpinButton`.");
+            let text = row
+                .last_child()
+                .and_downcast::<gtk::Entry>()
+                .expect("The last child has to be an `Entry`.");
+
+            start_time.set_value(line.start_time() as f64);
+            text.set_text(&line.text());
+        });
+        self.imp().line_list.set_factory(Some(&factory));
+    }
+}
@@ -0,0 +1,111 @@
+//! Shrink font of lyric labels so that a line fits the window, see `font-size-auto-fit`.
+
+use std::cell::RefCell;
+
+use ahash::HashMap;
+use gtk::pango::{self, AttrList, AttrSize, FontDescription};
+use gtk::{prelude::*, Label};
+
+/// font sizes closer than this are considered the same
+pub const FONT_SIZE_PRECISION: f64 = 0.5;
+
+thread_local! {
+    /// text and width each label was last fitted for
+    static FITTED: RefCell<HashMap<String, (String, i32)>> = RefCell::new(HashMap::default());
+}
+
+/// Largest size in `[min, max]` that `fits`, found by binary search.
+///
+/// `fits` must be monotonic, `min` is returned if nothing fits.
+pub fn fit_font_size(min: f64, max: f64, fits: impl Fn(f64) -> bool) -> f64 {
+    if fits(max) {
+        return max;
+    }
+    let (mut low, mut high) = (min, max);
+    while high - low > FONT_SIZE_PRECISION {
+        let mid = (low + high) / 2.;
+        if fits(mid) {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    low
+}
+
+fn with_size(desc: &FontDescription, size: f64) -> FontDescription {
+    let mut desc = desc.clone();
+    let size = (size * pango::SCALE as f64) as i32;
+    if desc.is_size_absolute() {
+        desc.set_absolute_size(size as f64);
+    } else {
+        desc.set_size(size);
+    }
+    desc
+}
+
+/// horizontal space taken by CSS padding and border of `label`
+#[allow(deprecated)]
+fn horizontal_extra(label: &Label) -> i32 {
+    let style = label.style_context();
+    let (padding, border) = (style.padding(), style.border());
+    (padding.left() + padding.right() + border.left() + border.right()) as i32
+        + label.margin_start()
+        + label.margin_end()
+}
+
+/// Shrink font of `label` down to `min_size` until its text fits in `available_width` on one line
+pub fn fit_label(label: &Label, available_width: i32, min_size: f64) {
+    // without markup
+    let text = label.text().to_string();
+    let fitted = FITTED.with_borrow_mut(|fitted| {
+        let key = label.widget_name().to_string();
+        let entry = (text.clone(), available_width);
+        fitted.insert(key, entry.clone()) == Some(entry)
+    });
+    if fitted {
+        return;
+    }
+
+    // measure with font from theme
+    label.set_attributes(None);
+    if text.is_empty() {
+        return;
+    }
+    let desc = label.pango_context().font_description();
+    let Some(desc) = desc.filter(|desc| desc.size() > 0) else {
+        return;
+    };
+    let base_size = desc.size() as f64 / pango::SCALE as f64;
+    if base_size <= min_size {
+        return;
+    }
+
+    let available_width = available_width - horizontal_extra(label);
+    let layout = label.create_pango_layout(Some(&text));
+    let fits = |size: f64| {
+        layout.set_font_description(Some(&with_size(&desc, size)));
+        layout.pixel_size().0 <= available_width
+    };
+    let size = fit_font_size(min_size, base_size, fits);
+    if size >= base_size {
+        return;
+    }
+
+    let size = (size * pango::SCALE as f64) as i32;
+    let attrs = AttrList::new();
+    if desc.is_size_absolute() {
+        attrs.insert(AttrSize::new_size_absolute(size));
+    } else {
+        attrs.insert(AttrSize::new(size));
+    }
+    label.set_attributes(Some(&attrs));
+}
+
+/// Drop fitted sizes, e.g. when auto fit is turned off
+pub fn reset_fit(labels: &[Label]) {
+    FITTED.with_borrow_mut(|fitted| fitted.clear());
+    for label in labels {
+        label.set_attributes(None);
+    }
+}
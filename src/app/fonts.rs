@@ -0,0 +1,126 @@
+//! Fonts of lyric labels set by `lyrics-font` and its per-label variants, over the theme.
+//!
+//! Applied as CSS like `font-size` of players, so that `font-size-auto-fit` measures with them.
+
+use std::cell::RefCell;
+
+use anyhow::{anyhow, Result};
+use gtk::glib::translate::IntoGlib;
+use gtk::pango::{self, FontDescription, FontMask};
+use gtk::CssProvider;
+
+thread_local! {
+    static FONT_PROVIDER: RefCell<Option<CssProvider>> = const { RefCell::new(None) };
+}
+
+/// Pango font description like `Sans Italic 20`, `None` if `font` is empty.
+///
+/// Fails if nothing could be parsed from `font`.
+pub fn parse_font(font: &str) -> Result<Option<FontDescription>> {
+    if font.trim().is_empty() {
+        return Ok(None);
+    }
+    let desc = FontDescription::from_string(font);
+    if desc.set_fields().is_empty() {
+        return Err(anyhow!("invalid font description {font:?}"));
+    }
+    Ok(Some(desc))
+}
+
+/// Pango weight name like `Bold` or `Semi-Light`, `None` if `weight` is empty
+pub fn parse_weight(weight: &str) -> Result<Option<pango::Weight>> {
+    let weight = weight.trim();
+    if weight.is_empty() {
+        return Ok(None);
+    }
+    // Pango reads `Normal` as "nothing set"
+    if weight.eq_ignore_ascii_case("normal") {
+        return Ok(Some(pango::Weight::Normal));
+    }
+    // anything else is read as family, and the weight left at normal
+    let desc = FontDescription::from_string(weight);
+    let fields = desc.set_fields();
+    if fields.intersects(FontMask::FAMILY | FontMask::SIZE)
+        || desc.weight() == pango::Weight::Normal
+        || desc.style() != pango::Style::Normal
+    {
+        return Err(anyhow!("invalid font weight {weight:?}"));
+    }
+    Ok(Some(desc.weight()))
+}
+
+/// `desc` with `weight` replacing its weight, family and size are left to `desc` or the theme
+pub fn with_weight(
+    desc: Option<FontDescription>,
+    weight: Option<pango::Weight>,
+) -> Option<FontDescription> {
+    let Some(weight) = weight else {
+        return desc;
+    };
+    let mut desc = desc.unwrap_or_else(FontDescription::new);
+    desc.set_weight(weight);
+    Some(desc)
+}
+
+/// CSS declarations of fields set in `desc`
+pub fn font_css(desc: &FontDescription) -> String {
+    let fields = desc.set_fields();
+    let mut css = String::new();
+    if let Some(family) = desc.family().filter(|_| fields.contains(FontMask::FAMILY)) {
+        let families = family
+            .split(',')
+            .map(|family| format!("\"{}\"", family.trim().replace('"', "")))
+            .collect::<Vec<_>>()
+            .join(", ");
+        css += &format!("font-family: {families}; ");
+    }
+    if fields.contains(FontMask::STYLE) {
+        let style = match desc.style() {
+            pango::Style::Italic => "italic",
+            pango::Style::Oblique => "oblique",
+            _ => "normal",
+        };
+        css += &format!("font-style: {style}; ");
+    }
+    if fields.contains(FontMask::WEIGHT) {
+        css += &format!("font-weight: {}; ", desc.weight().into_glib());
+    }
+    if fields.contains(FontMask::SIZE) && desc.size() > 0 {
+        let size = desc.size() as f64 / pango::SCALE as f64;
+        let unit = if desc.is_size_absolute() { "px" } else { "pt" };
+        css += &format!("font-size: {size}{unit}; ");
+    }
+    css.trim_end().to_owned()
+}
+
+/// Font of `above` and `below` label, `None` to keep the theme's
+pub fn set_label_fonts(fonts: [Option<FontDescription>; 2]) {
+    use gtk::gdk::Display as GdkDisplay;
+
+    let Some(display) = GdkDisplay::default() else {
+        return;
+    };
+    FONT_PROVIDER.with_borrow_mut(|provider| {
+        if let Some(provider) = provider.take() {
+            gtk::style_context_remove_provider_for_display(&display, &provider);
+        }
+        let css = ["above", "below"]
+            .iter()
+            .zip(fonts)
+            .filter_map(|(position, font)| Some(format!("#{position} {{ {} }}", font_css(&font?))))
+            .collect::<Vec<_>>()
+            .join("\n");
+        if css.is_empty() {
+            return;
+        }
+        let css_provider = CssProvider::new();
+        css_provider.load_from_data(&css);
+        gtk::style_context_add_provider_for_display(
+            &display,
+            &css_provider,
+            // above the theme, below `font-size` of players
+            gtk::STYLE_PROVIDER_PRIORITY_USER + 1,
+        );
+        *provider = Some(css_provider);
+    });
+}
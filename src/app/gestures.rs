@@ -0,0 +1,132 @@
+//! Touch swipes on the lyric window: left/right shifts lyric offset, up/down resizes lyric font.
+//! Double taps run `double-tap-action`.
+//!
+//! Faster swipes take larger steps. Swipes are ignored while clicks pass through the window.
+
+use std::cell::RefCell;
+
+use gtk::prelude::*;
+use gtk::subclass::prelude::ObjectSubclassIsExt;
+use gtk::{pango, CssProvider};
+
+use super::{get_label, Window};
+use crate::log::{debug, info, warn};
+use crate::sync::adjust_lyric_offset;
+
+/// offset step of a swipe at `REFERENCE_VELOCITY`
+pub const OFFSET_STEP_MS: f64 = 200.;
+/// font size step of a swipe at `REFERENCE_VELOCITY`, in points
+pub const FONT_SIZE_STEP: f64 = 2.;
+/// swipe velocity in px/s taking a single step
+pub const REFERENCE_VELOCITY: f64 = 1000.;
+/// slower swipes are ignored
+pub const MIN_VELOCITY: f64 = 300.;
+/// bounds of the step multiplier
+const STEP_SCALE: (f64, f64) = (0.5, 5.);
+const MIN_FONT_SIZE: f64 = 6.;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SwipeAction {
+    /// in milliseconds, positive delays the lyric
+    Offset(i64),
+    /// in points
+    FontSize(f64),
+}
+
+thread_local! {
+    static FONT_SIZE_PROVIDER: RefCell<Option<CssProvider>> = const { RefCell::new(None) };
+}
+
+/// What a swipe with velocity `(velocity_x, velocity_y)` in px/s does, along its dominant axis
+pub fn swipe_action(velocity_x: f64, velocity_y: f64) -> Option<SwipeAction> {
+    let horizontal = velocity_x.abs() >= velocity_y.abs();
+    let velocity = if horizontal { velocity_x } else { velocity_y };
+    if velocity.abs() < MIN_VELOCITY {
+        return None;
+    }
+    let scale = (velocity.abs() / REFERENCE_VELOCITY).clamp(STEP_SCALE.0, STEP_SCALE.1);
+    let direction = velocity.signum();
+    if horizontal {
+        Some(SwipeAction::Offset(
+            (direction * OFFSET_STEP_MS * scale).round() as i64,
+        ))
+    } else {
+        // y grows downwards, swiping up enlarges
+        Some(SwipeAction::FontSize(-direction * FONT_SIZE_STEP * scale))
+    }
+}
+
+/// Resize lyric font by `delta` points from what labels are using now
+fn adjust_font_size(window: &Window, delta: f64) {
+    let label = get_label(window, "above");
+    let desc = label.pango_context().font_description();
+    let Some(current) = desc.map(|desc| desc.size() as f64 / pango::SCALE as f64) else {
+        return;
+    };
+    let font_size = (current + delta).max(MIN_FONT_SIZE);
+
+    let display = WidgetExt::display(window);
+    FONT_SIZE_PROVIDER.with_borrow_mut(|provider| {
+        if let Some(provider) = provider.take() {
+            gtk::style_context_remove_provider_for_display(&display, &provider);
+        }
+        let css_provider = CssProvider::new();
+        css_provider.load_from_data(&format!(
+            "#above, #below {{ font-size: {font_size:.1}pt; }}"
+        ));
+        gtk::style_context_add_provider_for_display(
+            &display,
+            &css_provider,
+            // above the theme and `per-player` font size
+            gtk::STYLE_PROVIDER_PRIORITY_USER + 3,
+        );
+        *provider = Some(css_provider);
+    });
+    info!("font size set to {font_size:.1}pt by swipe");
+}
+
+/// Run `double-tap-action` on double taps.
+///
+/// Taps are never claimed, so that dragging the window still works.
+pub fn attach_double_tap(window: &Window) {
+    let gesture = gtk::GestureClick::new();
+    gesture.set_touch_only(true);
+    gesture.connect_pressed(glib_macros::clone!(
+        #[weak]
+        window,
+        move |_, n_press, _, _| {
+            if n_press != 2 || window.imp().clickthrough.get() {
+                return;
+            }
+            let Some(action) = window.imp().double_tap_action.get().action_name() else {
+                return;
+            };
+            debug!("double tapped, activating {action}");
+            if let Err(e) = window.activate_action(action, None) {
+                warn!("cannot activate {action}: {e}");
+            }
+        }
+    ));
+    window.add_controller(gesture);
+}
+
+pub fn attach_swipe_gestures(window: &Window) {
+    let gesture = gtk::GestureSwipe::new();
+    gesture.set_touch_only(true);
+    gesture.connect_swipe(glib_macros::clone!(
+        #[weak]
+        window,
+        move |_, velocity_x, velocity_y| {
+            if window.imp().clickthrough.get() {
+                return;
+            }
+            debug!("swiped at ({velocity_x:.0}, {velocity_y:.0}) px/s");
+            match swipe_action(velocity_x, velocity_y) {
+                Some(SwipeAction::Offset(delta_ms)) => adjust_lyric_offset(&window, delta_ms),
+                Some(SwipeAction::FontSize(delta)) => adjust_font_size(&window, delta),
+                None => (),
+            }
+        }
+    ));
+    window.add_controller(gesture);
+}
@@ -0,0 +1,69 @@
+use std::cell::RefCell;
+
+use glib::Properties;
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+use gtk::{gio, glib};
+
+#[derive(Default)]
+pub struct RecordData {
+    pub played_at: String,
+    pub title: String,
+    pub artist: String,
+    pub album: String,
+    pub cache_path: String,
+}
+
+#[derive(Properties, Default)]
+#[properties(wrapper_type = super::RecordObject)]
+pub struct RecordObject {
+    #[property(name = "played-at", get, set, type = String, member = played_at)]
+    #[property(name = "title", get, set, type = String, member = title)]
+    #[property(name = "artist", get, set, type = String, member = artist)]
+    #[property(name = "album", get, set, type = String, member = album)]
+    #[property(name = "cache-path", get, set, type = String, member = cache_path)]
+    pub data: RefCell<RecordData>,
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for RecordObject {
+    const NAME: &'static str = "HistoryRecordObject";
+    type Type = super::RecordObject;
+}
+
+#[glib_macros::derived_properties]
+impl ObjectImpl for RecordObject {}
+
+#[derive(Default)]
+pub struct Window {
+    pub vbox: gtk::Box,
+
+    pub scrolled_window: gtk::ScrolledWindow,
+    pub record_list: gtk::ListView,
+    pub records: RefCell<Option<gio::ListStore>>,
+
+    pub refresh_button: gtk::Button,
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for Window {
+    const NAME: &'static str = "PlayHistoryWindow";
+    type Type = super::Window;
+    type ParentType = gtk::Window;
+}
+
+impl ObjectImpl for Window {
+    fn constructed(&self) {
+        self.parent_constructed();
+
+        let obj = self.obj();
+        obj.setup_records();
+
+        obj.setup_ui();
+        obj.setup_callbacks();
+        obj.setup_factory();
+    }
+}
+
+impl WidgetImpl for Window {}
+impl WindowImpl for Window {}
@@ -0,0 +1,231 @@
+mod imp;
+
+use std::path::PathBuf;
+
+use glib::Object;
+use gtk::glib::clone;
+use gtk::subclass::prelude::*;
+use gtk::{gio, glib};
+use gtk::{prelude::*, ListItem};
+
+use crate::app::dialog::show_dialog;
+use crate::history::{load_history, PlayRecord};
+use crate::log::info;
+use crate::sync::lyric::cache::load_lyric_cache;
+use crate::utils::gettext;
+use crate::{glib_spawn, MAIN_WINDOW};
+
+glib::wrapper! {
+    pub struct RecordObject(ObjectSubclass<imp::RecordObject>);
+}
+
+impl RecordObject {
+    pub fn new(
+        PlayRecord {
+            timestamp,
+            title,
+            artist,
+            album,
+            lyric_cache_path,
+        }: PlayRecord,
+    ) -> Self {
+        let played_at = glib::DateTime::from_unix_local(timestamp as i64)
+            .and_then(|time| time.format("%F %T"))
+            .map(|time| time.to_string())
+            .unwrap_or_default();
+        let cache_path = lyric_cache_path
+            .map(|path| path.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        Object::builder()
+            .property("played-at", played_at)
+            .property("title", title)
+            .property("artist", artist)
+            .property("album", album)
+            .property("cache-path", cache_path)
+            .build()
+    }
+}
+
+glib::wrapper! {
+    pub struct Window(ObjectSubclass<imp::Window>)
+        @extends gtk::Window, gtk::Widget,
+        @implements gio::ActionGroup, gio::ActionMap, gtk::Accessible, gtk::Buildable,
+                    gtk::ConstraintTarget, gtk::Native, gtk::Root, gtk::ShortcutManager;
+}
+
+impl Default for Window {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Window {
+    pub fn new() -> Self {
+        let window: Self = Object::builder().build();
+        window.set_title(Some(&gettext("Play history")));
+        window
+            .imp()
+            .record_list
+            .set_widget_name("history-window-list");
+
+        window.load_records();
+        window
+    }
+
+    fn records(&self) -> gio::ListStore {
+        self.imp()
+            .records
+            .borrow()
+            .clone()
+            .expect("Could not get records")
+    }
+
+    fn setup_records(&self) {
+        let model = gio::ListStore::new::<RecordObject>();
+
+        self.imp().records.replace(Some(model));
+
+        let selection_model = gtk::NoSelection::new(Some(self.records()));
+        self.imp().record_list.set_model(Some(&selection_model));
+    }
+
+    /// (re)load records from history file, newest first
+    fn load_records(&self) {
+        self.records().remove_all();
+
+        let records: Vec<RecordObject> =
+            load_history().into_iter().map(RecordObject::new).collect();
+        self.records().extend_from_slice(&records);
+    }
+
+    fn setup_ui(&self) {
+        let imp = self.imp();
+
+        imp.vbox
+            .set_properties(&[("orientation", &gtk::Orientation::Vertical)]);
+
+        imp.vbox.append(&imp.scrolled_window);
+        imp.vbox.append(&imp.refresh_button);
+
+        imp.scrolled_window.set_child(Some(&imp.record_list));
+        imp.scrolled_window.set_vexpand(true);
+        imp.scrolled_window
+            .set_hscrollbar_policy(gtk::PolicyType::Never);
+        imp.scrolled_window.set_height_request(400);
+        imp.scrolled_window.set_width_request(400);
+
+        imp.refresh_button.set_label(&gettext("Refresh"));
+
+        self.set_child(Some(&imp.vbox));
+    }
+
+    fn setup_callbacks(&self) {
+        self.imp().refresh_button.connect_clicked(clone!(
+            #[weak(rename_to = window)]
+            self,
+            move |_| {
+                window.load_records();
+            }
+        ));
+    }
+
+    fn setup_factory(&self) {
+        let factory = gtk::SignalListItemFactory::new();
+        factory.connect_setup(move |_, list_item| {
+            let list_item = list_item
+                .downcast_ref::<ListItem>()
+                .expect("Needs to be ListItem")
+                .clone();
+
+            let track = gtk::Label::new(None);
+            track.set_xalign(0.);
+            track.set_ellipsize(gtk::pango::EllipsizeMode::End);
+            let detail = gtk::Label::new(None);
+            detail.set_xalign(0.);
+            detail.set_ellipsize(gtk::pango::EllipsizeMode::End);
+            detail.add_css_class("dim-label");
+
+            let labels = gtk::Box::new(gtk::Orientation::Vertical, 2);
+            labels.set_hexpand(true);
+            labels.append(&track);
+            labels.append(&detail);
+
+            let load = gtk::Button::with_label(&gettext("Load lyric"));
+            load.set_valign(gtk::Align::Center);
+            load.connect_clicked(clone!(
+                #[weak]
+                list_item,
+                move |button| {
+                    let Some(record) = list_item.item().and_downcast::<RecordObject>() else {
+                        return;
+                    };
+                    let parent = button.root().and_downcast::<gtk::Window>();
+                    let cache_path = PathBuf::from(record.cache_path());
+                    let Some(wind) = MAIN_WINDOW.with_borrow(|wind| wind.as_ref().cloned()) else {
+                        return;
+                    };
+                    glib_spawn!(async move {
+                        match load_lyric_cache(&cache_path, &wind).await {
+                            Ok(true) => info!("loaded lyric from history: {cache_path:?}"),
+                            _ => show_dialog(
+                                parent.as_ref(),
+                                &gettext("lyric cache of this track is missing or broken"),
+                                gtk::MessageType::Error,
+                            ),
+                        }
+                    });
+                }
+            ));
+
+            let row = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+            row.append(&labels);
+            row.append(&load);
+            list_item.set_child(Some(&row));
+        });
+        factory.connect_bind(move |_, list_item| {
+            let list_item = list_item
+                .downcast_ref::<ListItem>()
+                .expect("Needs to be ListItem");
+            let record = list_item
+                .item()
+                .and_downcast::<RecordObject>()
+                .expect("The item has to be an `RecordObject`.");
+            let row = list_item
+                .child()
+                .and_downcast::<gtk::Box>()
+                .expect("The child has to be a `Box`.");
+
+            let labels = row
+                .first_child()
+                .and_downcast::<gtk::Box>()
+                .expect("The first child has to be a `Box`.");
+            let track = labels
+                .first_child()
+                .and_downcast::<gtk::Label>()
+                .expect("The first child has to be a `Label`.");
+            let detail = labels
+                .last_child()
+                .and_downcast::<gtk::Label>()
+                .expect("The last child has to be a `Label`.");
+            let load = row
+                .last_child()
+                .and_downcast::<gtk::Button>()
+                .expect("The last child has to be a `Button`.");
+
+            let title = [record.title(), record.artist()]
+                .into_iter()
+                .filter(|s| !s.is_empty())
+                .collect::<Vec<_>>()
+                .join(" - ");
+            let detail_text = [record.played_at(), record.album()]
+                .into_iter()
+                .filter(|s| !s.is_empty())
+                .collect::<Vec<_>>()
+                .join(" · ");
+            track.set_text(&title);
+            detail.set_text(&detail_text);
+            load.set_sensitive(!record.cache_path().is_empty());
+        });
+        self.imp().record_list.set_factory(Some(&factory));
+    }
+}
@@ -0,0 +1,89 @@
+//! Input region of the main window, to let clicks pass through it.
+//!
+//! On Wayland and X11 GDK takes care of the backend, by `wl_surface.set_input_region`
+//! or the XShape extension respectively. Windows needs `WS_EX_TRANSPARENT` instead.
+
+use gtk::prelude::*;
+use gtk::subclass::prelude::ObjectSubclassIsExt;
+
+use super::window;
+
+/// Apply and remember click through state of `window`
+pub fn set_click_through(window: &window::Window, enabled: bool) {
+    window.imp().clickthrough.set(enabled);
+    set_click_pass_through(window, enabled);
+}
+
+#[cfg(target_os = "windows")]
+pub fn set_click_pass_through(window: &window::Window, enabled: bool) {
+    fn set_window_click_through(hwnd: isize, enabled: bool) {
+        use windows::Win32::Foundation::HWND;
+        use windows::Win32::UI::WindowsAndMessaging::{
+            GetWindowLongPtrW, SetWindowLongPtrW, GWL_EXSTYLE,
+        };
+        let hwnd = HWND(hwnd as _);
+
+        const WS_EX_TRANSPARENT: isize = 0x00000020;
+        const WS_EX_LAYERED: isize = 0x00080000;
+        unsafe {
+            let ex_style = GetWindowLongPtrW(hwnd, GWL_EXSTYLE);
+            if enabled {
+                SetWindowLongPtrW(
+                    hwnd,
+                    GWL_EXSTYLE,
+                    ex_style | WS_EX_TRANSPARENT | WS_EX_LAYERED,
+                );
+            } else {
+                SetWindowLongPtrW(
+                    hwnd,
+                    GWL_EXSTYLE,
+                    ex_style & !WS_EX_TRANSPARENT & !WS_EX_LAYERED,
+                );
+            }
+        }
+    }
+
+    let Some(surface) = window.surface().and_downcast::<gdk4_win32::Win32Surface>() else {
+        return;
+    };
+
+    let handle = surface.handle().0;
+
+    set_window_click_through(handle, enabled);
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn set_click_pass_through(window: &window::Window, enabled: bool) {
+    use gtk::{
+        cairo::{RectangleInt, Region},
+        subclass::prelude::*,
+    };
+
+    let obj = window;
+    let Some(surface) = obj.surface() else {
+        return;
+    };
+
+    if enabled {
+        if !window.is_decorated() {
+            surface.set_input_region(&Region::create_rectangle(&RectangleInt::new(0, 0, 0, 0)));
+        } else {
+            let headerbar = &window.imp().headerbar;
+            let allocation = headerbar.allocation();
+
+            surface.set_input_region(&Region::create_rectangle(&RectangleInt::new(
+                allocation.x(),
+                allocation.y(),
+                allocation.width(),
+                allocation.height(),
+            )));
+        }
+    } else {
+        surface.set_input_region(&Region::create_rectangle(&RectangleInt::new(
+            0,
+            0,
+            i32::MAX,
+            i32::MAX,
+        )));
+    }
+}
@@ -0,0 +1,49 @@
+//! Show which lyric line is being sung, like `12 / 48`, see `show-line-indicator`.
+
+use std::time::Duration;
+
+use gtk::prelude::*;
+
+use super::Window;
+use crate::lyric_providers::LyricLineOwned;
+
+/// Current line counted from 1 and the number of lines in `lyric`.
+///
+/// 0 before the first line starts, and stays at the last line after it.
+pub fn line_index(elapsed: &Duration, lyric: &[LyricLineOwned]) -> (usize, usize) {
+    let started = lyric
+        .iter()
+        .take_while(|line| line.start_time <= *elapsed)
+        .count();
+    (started, lyric.len())
+}
+
+pub fn format_line_indicator(current: usize, total: usize) -> String {
+    format!("{current} / {total}")
+}
+
+/// The label at the bottom of the window
+pub fn get_line_indicator(window: &Window) -> Option<gtk::Label> {
+    window
+        .child()
+        .and_downcast::<gtk::Box>()?
+        .last_child()
+        .and_downcast()
+        .filter(|label: &gtk::Label| label.widget_name() == "line-indicator")
+}
+
+pub fn set_line_indicator_visible(window: &Window, visible: bool) -> Option<()> {
+    let label = get_line_indicator(window)?;
+    label.set_label("");
+    label.set_visible(visible);
+    Some(())
+}
+
+/// Called by `refresh_lyric`, does nothing while the indicator is hidden
+pub fn update_line_indicator(window: &Window, elapsed: &Duration, lyric: &[LyricLineOwned]) {
+    let Some(label) = get_line_indicator(window).filter(|label| label.is_visible()) else {
+        return;
+    };
+    let (current, total) = line_index(elapsed, lyric);
+    label.set_label(&format_line_indicator(current, total));
+}
@@ -1,16 +1,37 @@
+pub mod compare_window;
+pub mod editor_window;
+pub mod history_window;
 pub mod search_window;
+pub mod track_info_window;
 mod window;
 
 use gtk::{prelude::*, subclass::prelude::ObjectSubclassIsExt, Application, Label};
 pub use window::Window;
 
-use crate::{app::utils::set_click_pass_through, config, DEFAULT_TEXT};
+use shadow::{Background, Gradient, Outline, Shadow, ShadowBin};
+
+use crate::{app::input_region::set_click_pass_through, config, utils::gettext, DEFAULT_TEXT};
 
 const WINDOW_MIN_HEIGHT: i32 = 120;
 
+#[cfg(feature = "accessibility")]
+pub mod accessibility;
 pub mod actions;
+pub mod bpm_pulse;
 pub mod dialog;
+pub mod font_fit;
+pub mod fonts;
+pub mod gestures;
+pub mod input_region;
+pub mod line_indicator;
+pub mod monitor;
+pub mod position;
+pub mod repeat_animation;
+pub mod shadow;
+pub mod snap;
+pub mod stacking;
 pub mod utils;
+pub mod vertical_offset;
 
 pub fn build_main_window(
     app: &Application,
@@ -56,8 +77,28 @@ pub fn build_main_window(
         .vexpand(true)
         .build();
 
-    verical_box.insert_child_after(&above_label, gtk::Box::NONE);
-    verical_box.insert_child_after(&below_label, Some(&above_label));
+    let progress_bar = gtk::ProgressBar::builder()
+        .name("line-progress")
+        .visible(false)
+        .build();
+    let position_label = Label::builder()
+        .name("position")
+        .css_classes(["dim-label"])
+        .visible(false)
+        .build();
+    let line_indicator = Label::builder()
+        .name("line-indicator")
+        .css_classes(["dim-label"])
+        .visible(false)
+        .build();
+
+    let above_bin = ShadowBin::new(&above_label);
+    let below_bin = ShadowBin::new(&below_label);
+    verical_box.insert_child_after(&above_bin, gtk::Box::NONE);
+    verical_box.insert_child_after(&progress_bar, Some(&above_bin));
+    verical_box.insert_child_after(&below_bin, Some(&progress_bar));
+    verical_box.insert_child_after(&position_label, Some(&below_bin));
+    verical_box.insert_child_after(&line_indicator, Some(&position_label));
 
     window.set_child(Some(&verical_box));
 
@@ -70,25 +111,141 @@ pub fn build_main_window(
         set_click_pass_through(window, clickthrough)
     });
 
+    gestures::attach_swipe_gestures(&window);
+    gestures::attach_double_tap(&window);
+    window.connect_default_height_notify(vertical_offset::apply_vertical_offset);
+
     window.set_icon_name(Some(crate::APP_ID_FIXED));
 
     window
 }
 
+/// Toggle network providers, and show current mode in window title
+pub fn set_offline_mode(window: &Window, offline: bool) {
+    window.imp().offline_mode.set(offline);
+    update_title(window);
+}
+
+/// `Waylyrics`, followed by offline mode and playback position if they're shown
+pub fn window_title(offline: bool, position: Option<&str>) -> String {
+    let mut title = DEFAULT_TEXT.to_owned();
+    if offline {
+        title += &format!(" ({})", gettext("offline"));
+    }
+    if let Some(position) = position {
+        title += &format!(" [{position}]");
+    }
+    title
+}
+
+pub fn update_title(window: &Window) {
+    let imp = window.imp();
+    let title = window_title(
+        imp.offline_mode.get(),
+        imp.title_position.borrow().as_deref(),
+    );
+    window.set_title(Some(&title));
+}
+
+/// Set opacity of `window` clamped into `[0.0, 1.0]`, returns the opacity set
+pub fn set_window_opacity(window: &Window, opacity: f64) -> f64 {
+    // avoid drifting like 0.7999999 after several steps
+    let opacity = (opacity.clamp(0., 1.) * 100.).round() / 100.;
+    window.set_opacity(opacity);
+    opacity
+}
+
 pub fn set_lyric_align(window: &Window, align: config::Align) -> Option<()> {
     let labels = get_labels(window)?;
-    for label in labels {
-        label.set_halign(align.into());
+    let label_aligns = window.imp().label_aligns.get();
+    for (label, label_align) in labels.iter().zip(label_aligns) {
+        match label_align.to_gtk() {
+            Some((halign, justify)) => {
+                label.set_halign(halign);
+                label.set_justify(justify);
+            }
+            None => {
+                label.set_halign(align.into());
+                label.set_justify(gtk::Justification::Left);
+            }
+        }
     }
     window.imp().lyric_align.set(align);
     Some(())
 }
 
+/// Override align chosen in menu for `above` and `below` label
+pub fn set_label_aligns(window: &Window, aligns: [config::LabelAlign; 2]) -> Option<()> {
+    window.imp().label_aligns.set(aligns);
+    set_lyric_align(window, window.imp().lyric_align.get())
+}
+
+fn get_shadow_bins(window: &Window) -> Option<[ShadowBin; 2]> {
+    let vbox: gtk::Box = window.child()?.downcast().ok()?;
+    let mut bins = std::iter::successors(vbox.first_child(), |child| child.next_sibling())
+        .filter_map(|child| child.downcast::<ShadowBin>().ok());
+    Some([bins.next()?, bins.next()?])
+}
+
 fn get_labels(window: &Window) -> Option<[Label; 2]> {
+    let [above_bin, below_bin] = get_shadow_bins(window)?;
+    Some([above_bin.label()?, below_bin.label()?])
+}
+
+/// The bar between `above` and `below` label
+pub fn get_progress_bar(window: &Window) -> Option<gtk::ProgressBar> {
+    let [above_bin, _] = get_shadow_bins(window)?;
+    above_bin.next_sibling()?.downcast().ok()
+}
+
+pub fn set_progress_bar_visible(window: &Window, visible: bool) -> Option<()> {
+    let progress_bar = get_progress_bar(window)?;
+    progress_bar.set_fraction(0.);
+    progress_bar.set_visible(visible);
+    Some(())
+}
+
+/// Paint `shadow` under both lyric labels, `None` to remove it
+pub fn set_lyric_shadow(window: &Window, shadow: Option<Shadow>) -> Option<()> {
+    for bin in get_shadow_bins(window)? {
+        bin.set_shadow(shadow);
+    }
+    Some(())
+}
+
+/// Stroke `outline` around both lyric labels, `None` to remove it
+pub fn set_lyric_outline(window: &Window, outline: Option<Outline>) -> Option<()> {
+    for bin in get_shadow_bins(window)? {
+        bin.set_outline(outline);
+    }
+    Some(())
+}
+
+/// Fill text of both lyric labels with `gradient`, `None` for theme color
+pub fn set_lyric_gradient(window: &Window, gradient: Option<Gradient>) -> Option<()> {
+    for bin in get_shadow_bins(window)? {
+        bin.set_gradient(gradient.clone());
+    }
+    Some(())
+}
+
+/// Paint `background` behind the current line in `above` label only, `None` to remove it
+pub fn set_lyric_background(window: &Window, background: Option<Background>) -> Option<()> {
+    let [above_bin, below_bin] = get_shadow_bins(window)?;
+    above_bin.set_background(background);
+    below_bin.set_background(None);
+    Some(())
+}
+
+/// Space between lyric labels, with extra `above` over the current line and `below` between it
+/// and the `below` label, all in logical pixels so they scale with the display
+pub fn set_lyric_spacing(window: &Window, spacing: u32, above: u32, below: u32) -> Option<()> {
     let vbox: gtk::Box = window.child()?.downcast().ok()?;
-    let above_label: Label = vbox.first_child()?.downcast().ok()?;
-    let below_label: Label = vbox.last_child()?.downcast().ok()?;
-    Some([above_label, below_label])
+    vbox.set_spacing(spacing as i32);
+    let [above_bin, below_bin] = get_shadow_bins(window)?;
+    above_bin.set_margin_top(above as i32);
+    below_bin.set_margin_top(below as i32);
+    Some(())
 }
 
 pub fn get_label(window: &Window, position: &str) -> Label {
@@ -0,0 +1,101 @@
+//! Move the main window to a monitor chosen by index, as ordered by `gdk::Display::monitors`.
+//!
+//! Like `snap`, only Windows is supported, as clients cannot position their own windows on
+//! Wayland, and GTK4 dropped `gtk_window_move`.
+
+use gtk::gdk;
+use gtk::prelude::*;
+use gtk::subclass::prelude::ObjectSubclassIsExt;
+
+use super::snap::Rect;
+use super::window;
+use crate::log::{info, warn};
+
+/// `index` if such a monitor is connected, otherwise the first (primary) one
+pub fn monitor_or_fallback(index: usize, count: usize) -> Option<usize> {
+    match count {
+        0 => None,
+        _ if index < count => Some(index),
+        _ => Some(0),
+    }
+}
+
+/// Top-left corner to place `window` at the center of `monitor`
+pub fn centered_in(window: Rect, monitor: Rect) -> (i32, i32) {
+    (
+        monitor.x + (monitor.width - window.width) / 2,
+        monitor.y + (monitor.height - window.height) / 2,
+    )
+}
+
+fn connected_monitors(window: &window::Window) -> Vec<gdk::Monitor> {
+    WidgetExt::display(window)
+        .monitors()
+        .iter::<gdk::Monitor>()
+        .flatten()
+        .collect()
+}
+
+/// Move `window` to monitor `index`, or the first one if it's disconnected
+pub fn move_to_monitor(window: &window::Window, index: usize) {
+    window.imp().monitor_index.set(index);
+
+    let monitors = connected_monitors(window);
+    let Some(target) = monitor_or_fallback(index, monitors.len()) else {
+        return;
+    };
+    if target != index {
+        warn!("monitor {index} is not connected, falling back to the first monitor");
+    }
+    let monitor = &monitors[target];
+
+    let current = window
+        .surface()
+        .and_then(|surface| WidgetExt::display(window).monitor_at_surface(&surface));
+    if current.as_ref() == Some(monitor) {
+        return;
+    }
+    info!("moving main window to monitor {target}");
+    place_on(window, monitor);
+}
+
+/// Move to `monitor-index` now, and again whenever monitors are plugged or unplugged
+pub fn watch_monitors(window: &window::Window, index: usize) {
+    // don't warn about what was never asked for
+    if cfg!(not(target_os = "windows")) && index == 0 {
+        return;
+    }
+    move_to_monitor(window, index);
+    WidgetExt::display(window)
+        .monitors()
+        .connect_items_changed(glib_macros::clone!(
+            #[weak]
+            window,
+            move |_, _, _, _| move_to_monitor(&window, window.imp().monitor_index.get())
+        ));
+}
+
+#[cfg(target_os = "windows")]
+fn place_on(window: &window::Window, monitor: &gdk::Monitor) {
+    use super::snap::win32;
+
+    let Some(rect) = win32::window_rect(window) else {
+        return;
+    };
+    // gdk geometry is in logical pixels, win32 in physical ones
+    let scale = monitor.scale_factor();
+    let geometry = monitor.geometry();
+    let monitor = Rect {
+        x: geometry.x() * scale,
+        y: geometry.y() * scale,
+        width: geometry.width() * scale,
+        height: geometry.height() * scale,
+    };
+    let (x, y) = centered_in(rect, monitor);
+    win32::move_window(window, x, y);
+}
+
+#[cfg(not(target_os = "windows"))]
+fn place_on(_window: &window::Window, _monitor: &gdk::Monitor) {
+    warn!("moving to another monitor is only supported on Windows");
+}
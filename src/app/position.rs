@@ -0,0 +1,137 @@
+//! Show playback position in window title or a label under the lyric, see
+//! `show-position-in-title` and `show-position-label`.
+
+use std::time::Duration;
+
+use gtk::glib::{self, ControlFlow};
+use gtk::prelude::*;
+use gtk::subclass::prelude::ObjectSubclassIsExt;
+
+use super::Window;
+use crate::sync::lyric::scroll::shifted_elapsed;
+use crate::sync::{TrackState, TRACK_PLAYING_STATE};
+use crate::utils::gettext;
+
+/// how often the position is redrawn, independent of `lyric-update-interval`
+const UPDATE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// `MM:SS`, minutes are not wrapped into hours
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    format!("{:02}:{:02}", secs / 60, secs % 60)
+}
+
+/// `MM:SS / MM:SS`, or only the position if `length` is unknown
+pub fn format_position(position: Duration, length: Option<Duration>) -> String {
+    match length.filter(|length| !length.is_zero()) {
+        Some(length) => format!(
+            "{} / {}",
+            format_duration(position),
+            format_duration(length)
+        ),
+        None => format_duration(position),
+    }
+}
+
+/// Position of the player, as of the last sync if paused
+fn current_position(window: &Window) -> Option<(Duration, Option<Duration>, bool)> {
+    let (paused, synced, length) = TRACK_PLAYING_STATE.with_borrow(
+        |TrackState {
+             metainfo,
+             paused,
+             position,
+             ..
+         }| {
+            let length = metainfo.as_ref()?.length;
+            Some((*paused, *position, length))
+        },
+    )?;
+    let imp = window.imp();
+    let position = if paused {
+        synced?
+    } else {
+        // `lyric_start` includes lyric and player offset
+        let elapsed = imp.lyric_start.get()?.elapsed().ok()?;
+        shifted_elapsed(
+            elapsed,
+            imp.lyric_offset_ms.get() + imp.player_offset_ms.get(),
+        )
+    };
+    Some((position, length, paused))
+}
+
+/// The label under `below` label
+pub fn get_position_label(window: &Window) -> Option<gtk::Label> {
+    window
+        .child()
+        .and_downcast::<gtk::Box>()?
+        .last_child()?
+        .prev_sibling()
+        .and_downcast()
+        .filter(|label: &gtk::Label| label.widget_name() == "position")
+}
+
+fn update_position(window: &Window) {
+    let imp = window.imp();
+    let in_title = imp.show_position_in_title.get();
+    let label = get_position_label(window).filter(|_| imp.show_position_label.get());
+    if !in_title && label.is_none() {
+        return;
+    }
+
+    let position = current_position(window);
+    if in_title {
+        let text = position.map(|(position, length, _)| format_position(position, length));
+        imp.title_position.replace(text);
+        super::update_title(window);
+    }
+    if let Some(label) = label {
+        let text = position
+            .map(|(position, length, paused)| {
+                let text = format_position(position, length);
+                if paused {
+                    format!("{text} ({})", gettext("paused"))
+                } else {
+                    text
+                }
+            })
+            .unwrap_or_default();
+        label.set_label(&text);
+    }
+}
+
+/// Where the position is shown, it's updated only while shown somewhere
+pub fn set_position_display(window: &Window, in_title: bool, in_label: bool) {
+    let imp = window.imp();
+    imp.show_position_in_title.set(in_title);
+    imp.show_position_label.set(in_label);
+
+    if !in_title && imp.title_position.take().is_some() {
+        super::update_title(window);
+    }
+    if let Some(label) = get_position_label(window) {
+        label.set_visible(in_label);
+    }
+    if let Some(source) = imp.position_source.take() {
+        source.remove();
+    }
+    if !in_title && !in_label {
+        return;
+    }
+
+    update_position(window);
+    let source = glib::timeout_add_local(
+        UPDATE_INTERVAL,
+        glib::clone!(
+            #[weak]
+            window,
+            #[upgrade_or]
+            ControlFlow::Break,
+            move || {
+                update_position(&window);
+                ControlFlow::Continue
+            }
+        ),
+    );
+    imp.position_source.replace(Some(source));
+}
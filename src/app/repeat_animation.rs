@@ -0,0 +1,89 @@
+//! Bounce the `above` label when the next line has the same text as the one it shows,
+//! see `enable-repeat-animation`.
+
+use std::cell::RefCell;
+use std::time::Duration;
+
+use gtk::glib;
+use gtk::prelude::*;
+use gtk::subclass::prelude::ObjectSubclassIsExt;
+use gtk::CssProvider;
+
+use super::Window;
+use crate::lyric_providers::LyricLineOwned;
+
+/// css class added to `above` label while it bounces
+pub const BOUNCE_CSS_CLASS: &str = "bounce";
+const BOUNCE_DURATION: Duration = Duration::from_millis(400);
+
+/// below the theme, so that themes can restyle or disable it
+const BOUNCE_CSS: &str = "
+@keyframes bounce {
+  from { transform: scale(1); }
+  50% { transform: scale(1.08); }
+  to { transform: scale(1); }
+}
+label#above.bounce {
+  animation: bounce 400ms ease-in-out;
+}
+";
+
+thread_local! {
+    static BOUNCE_PROVIDER: RefCell<Option<CssProvider>> = const { RefCell::new(None) };
+}
+
+/// Whether `next` is another line with the text of `shown`, `(text, start_time)` of each
+pub fn is_repeated_line(shown: Option<(&str, Duration)>, next: Option<(&str, Duration)>) -> bool {
+    matches!(
+        (shown, next),
+        (Some((shown, shown_start)), Some((next, next_start)))
+            if shown == next && shown_start != next_start && !next.trim().is_empty()
+    )
+}
+
+pub fn set_repeat_animation(window: &Window, enabled: bool) {
+    window.imp().enable_repeat_animation.set(enabled);
+    if !enabled {
+        return;
+    }
+    BOUNCE_PROVIDER.with_borrow_mut(|provider| {
+        if provider.is_some() {
+            return;
+        }
+        let css_provider = CssProvider::new();
+        css_provider.load_from_data(BOUNCE_CSS);
+        gtk::style_context_add_provider_for_display(
+            &WidgetExt::display(window),
+            &css_provider,
+            gtk::STYLE_PROVIDER_PRIORITY_APPLICATION,
+        );
+        *provider = Some(css_provider);
+    });
+}
+
+/// Called each time `line` is set to `above` label, bounces it if `line` repeats the last one
+pub fn on_line_shown(window: &Window, label: &gtk::Label, line: Option<&LyricLineOwned>) {
+    let imp = window.imp();
+    let next = line.map(|line| (line.text.clone(), line.start_time));
+    let shown = imp.shown_line.replace(next.clone());
+    if !imp.enable_repeat_animation.get() || label.has_css_class(BOUNCE_CSS_CLASS) {
+        return;
+    }
+    let as_ref = |line: &Option<(String, Duration)>| {
+        line.as_ref()
+            .map(|(text, start_time)| (text.as_str(), *start_time))
+    };
+    if !is_repeated_line(as_ref(&shown), as_ref(&next)) {
+        return;
+    }
+
+    label.add_css_class(BOUNCE_CSS_CLASS);
+    glib::timeout_add_local_once(
+        BOUNCE_DURATION,
+        glib::clone!(
+            #[weak]
+            label,
+            move || label.remove_css_class(BOUNCE_CSS_CLASS)
+        ),
+    );
+}
@@ -0,0 +1,135 @@
+//! Recent queries of the search window.
+
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::log::error;
+
+pub const MAX_HISTORY: usize = 50;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchQuery {
+    pub title: String,
+    pub album: String,
+    /// as entered, split by '&'
+    pub artists: String,
+    /// seconds since unix epoch
+    pub timestamp: u64,
+    /// whether the query produced any result
+    pub found: bool,
+}
+
+impl SearchQuery {
+    pub fn new(title: String, album: String, artists: String, found: bool) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        Self {
+            title,
+            album,
+            artists,
+            timestamp,
+            found,
+        }
+    }
+
+    /// the query string shown in history
+    pub fn query(&self) -> String {
+        [&self.title, &self.artists, &self.album]
+            .into_iter()
+            .filter(|s| !s.is_empty())
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(" - ")
+    }
+
+    fn same_query(&self, other: &Self) -> bool {
+        (&self.title, &self.album, &self.artists) == (&other.title, &other.album, &other.artists)
+    }
+}
+
+/// Most recent query comes first
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SearchHistory {
+    queries: VecDeque<SearchQuery>,
+}
+
+impl SearchHistory {
+    /// Missing or broken history is treated as empty
+    pub fn load(path: &Path) -> Self {
+        let Ok(content) = std::fs::read_to_string(path) else {
+            return Self::default();
+        };
+        serde_json::from_str(&content)
+            .inspect_err(|e| error!("search history parse error: {e} from {path:?}"))
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Add `query` to the front, replacing the same query if any
+    pub fn push(&mut self, query: SearchQuery) {
+        self.queries.retain(|q| !q.same_query(&query));
+        self.queries.push_front(query);
+        self.queries.truncate(MAX_HISTORY);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &SearchQuery> {
+        self.queries.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.queries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queries.is_empty()
+    }
+}
+
+/// `$XDG_STATE_HOME/waylyrics/search_history.json`
+pub fn history_path() -> Option<PathBuf> {
+    let base = directories::BaseDirs::new()?;
+    let state_home = base.state_dir().unwrap_or_else(|| base.data_local_dir());
+    Some(state_home.join("waylyrics").join("search_history.json"))
+}
+
+pub fn load_history() -> SearchHistory {
+    history_path()
+        .map(|path| SearchHistory::load(&path))
+        .unwrap_or_default()
+}
+
+pub fn record_search(query: SearchQuery) {
+    let Some(path) = history_path() else {
+        return;
+    };
+    let mut history = SearchHistory::load(&path);
+    history.push(query);
+    if let Err(e) = history.save(&path) {
+        error!("cannot save search history to {path:?}: {e}");
+    }
+}
+
+pub fn clear_history() {
+    let Some(path) = history_path() else {
+        return;
+    };
+    if let Err(e) = std::fs::remove_file(&path) {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            error!("cannot remove search history {path:?}: {e}");
+        }
+    }
+}
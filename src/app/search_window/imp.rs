@@ -5,6 +5,8 @@ use gtk::prelude::*;
 use gtk::subclass::prelude::*;
 use gtk::{gio, glib};
 
+use super::history::SearchQuery;
+
 #[derive(Default)]
 pub struct ResultData {
     pub id: String,
@@ -43,10 +45,16 @@ impl ObjectImpl for ResultObject {}
 pub struct Window {
     pub vbox: gtk::Box,
 
+    pub title_box: gtk::Box,
     pub input_title: gtk::Entry,
+    pub history_button: gtk::MenuButton,
+    pub history_list: gtk::ListBox,
+    pub history: RefCell<Vec<SearchQuery>>,
     pub input_album: gtk::Entry,
     pub input_artists: gtk::Entry,
+    pub fuzzy_button: gtk::CheckButton,
 
+    pub pin_button: gtk::CheckButton,
     pub set_button: gtk::Button,
 
     pub result_scrolled_window: gtk::ScrolledWindow,
@@ -1,9 +1,10 @@
+pub mod history;
 mod imp;
 
 use std::sync::Arc;
 
-use crate::log::{error, info};
-use crate::lyric_providers::SongInfo;
+use crate::log::{debug, error, info};
+use crate::lyric_providers::{split_artists, SongInfo};
 use crate::utils::gettext;
 use glib::Object;
 use gtk::glib::{clone, IntoGStr};
@@ -15,8 +16,14 @@ use tokio::task::JoinSet;
 use crate::{glib_spawn, tokio_spawn, LYRIC_PROVIDERS};
 
 use crate::app::dialog::show_dialog;
-use crate::sync::lyric::cache::update_lyric_cache;
-use crate::sync::{fuzzy_match_song, get_lyric_cache_path, set_current_lyric, LyricState};
+use crate::lyric_providers::utils::http_cache::{self, LyricSource};
+use crate::lyric_providers::utils::normalise;
+use crate::sync::lyric::cache::{set_lyric_pinned, update_lyric_cache};
+use crate::sync::{
+    fuzzy_match_song, get_lyric_cache_path, set_current_lyric, set_lyric_source, LyricState,
+};
+
+use history::SearchQuery;
 
 glib::wrapper! {
     pub struct ResultObject(ObjectSubclass<imp::ResultObject>);
@@ -93,11 +100,17 @@ impl Window {
         imp.vbox
             .set_properties(&[("orientation", &gtk::Orientation::Vertical)]);
 
-        for entry in [&imp.input_title, &imp.input_artists, &imp.input_album] {
+        imp.title_box.append(&imp.input_title);
+        imp.title_box.append(&imp.history_button);
+        imp.input_title.set_hexpand(true);
+        imp.vbox.append(&imp.title_box);
+        for entry in [&imp.input_artists, &imp.input_album] {
             imp.vbox.append(entry);
         }
+        imp.vbox.append(&imp.fuzzy_button);
 
         imp.vbox.append(&imp.result_scrolled_window);
+        imp.vbox.append(&imp.pin_button);
         imp.vbox.append(&imp.set_button);
 
         imp.result_scrolled_window.set_child(Some(&imp.result_list));
@@ -134,13 +147,36 @@ impl Window {
             .set_placeholder_text(Some(&gettext("Enter album...")));
         imp.input_album.set_tooltip_text(Some(&gettext("album")));
         imp.input_artists
-            .set_placeholder_text(Some(&gettext("Enter artists split by '/'...")));
+            .set_placeholder_text(Some(&gettext("Enter artists split by '&'...")));
         imp.input_artists
             .set_tooltip_text(Some(&gettext("artists")));
 
         imp.input_title
             .set_secondary_icon_name(Some("system-search-symbolic"));
 
+        imp.history_button.set_icon_name("pan-down-symbolic");
+        imp.history_button
+            .set_tooltip_text(Some(&gettext("Recent searches")));
+        let history_popover = gtk::Popover::new();
+        let history_scrolled_window = gtk::ScrolledWindow::new();
+        history_scrolled_window.set_child(Some(&imp.history_list));
+        history_scrolled_window.set_hscrollbar_policy(gtk::PolicyType::Never);
+        history_scrolled_window.set_propagate_natural_height(true);
+        history_scrolled_window.set_max_content_height(300);
+        history_popover.set_child(Some(&history_scrolled_window));
+        imp.history_button.set_popover(Some(&history_popover));
+
+        imp.fuzzy_button.set_label(Some(&gettext("Fuzzy search")));
+        imp.fuzzy_button.set_tooltip_text(Some(&gettext(
+            "strip suffixes and featured artists, and try alternate romanizations",
+        )));
+
+        imp.pin_button.set_label(Some(&gettext("Pin this lyric")));
+        imp.pin_button.set_tooltip_text(Some(&gettext(
+            "keep this lyric when refetching, until unpinned",
+        )));
+        imp.pin_button.set_visible(false);
+
         imp.set_button.set_label(&gettext("Set as lyric"));
         self.imp().set_button.set_visible(false);
 
@@ -162,62 +198,25 @@ impl Window {
             return;
         }
 
-        let mut results = vec![];
-        let providers = LYRIC_PROVIDERS
-            .get()
-            .expect("lyric providers should be initialized");
-
-        let _title = query_title.clone();
-        let _album = query_album.clone();
-        let _artists = Arc::new(
-            query_artists
-                .split("/")
-                .map(|s| s.trim())
-                .map(str::to_owned)
-                .collect::<Vec<String>>(),
-        );
-        let (error_tx, error_rx) = async_channel::bounded(providers.len());
-
-        let mut results = tokio_spawn!(async move {
-            let mut set = JoinSet::new();
-            for (idx, provider) in providers.iter().enumerate() {
-                let provider_id = provider.unique_name();
-                let album = _album.clone();
-                let title = _title.clone();
-                let artists = _artists.clone();
-                set.spawn(async move {
-                    let artists = artists.iter().map(|a| &**a).collect::<Vec<&str>>();
-                    let result = provider
-                        .search_song_detailed(&album, &artists, &title)
-                        .await;
-                    (result, provider_id, idx, title)
-                });
-            }
+        let raw_artists = split_artists(&query_artists);
+        let mut queries = vec![];
+        if self.imp().fuzzy_button.is_active() {
+            queries = normalise::fuzzy_queries(&query_title, &raw_artists);
+        }
+        // fall back to the raw query
+        queries.push((query_title.to_string(), raw_artists));
 
-            while let Some(Ok((search_result, provider_name, idx, title))) = set.join_next().await {
-                let tracks = match search_result {
-                    Ok(songs) => songs,
-                    Err(e) => {
-                        let error_msg =
-                            format!("{e} occurs when searching {title} on {}", provider_name);
-                        error!(error_msg);
-                        let _ = error_tx.send(error_msg).await;
-                        continue;
-                    }
-                };
-                for track in tracks {
-                    // ResultObject contains RefCell, so it cannot be used inside tokio_spawn!()
-                    results.push((track, idx, provider_name));
-                }
+        let mut results = vec![];
+        let mut errors = vec![];
+        for (title, artists) in queries {
+            debug!("searching {title} by {artists:?}");
+            let (query_results, query_errors) =
+                search_providers(Arc::new(title), query_album.clone(), Arc::new(artists)).await;
+            errors.extend(query_errors);
+            if !query_results.is_empty() {
+                results = query_results;
+                break;
             }
-            results
-        })
-        .await
-        .expect("Tokio runtime failure");
-
-        let mut errors = Vec::new();
-        while let Ok(msg) = error_rx.try_recv() {
-            errors.push(msg);
         }
 
         if !errors.is_empty() {
@@ -271,6 +270,12 @@ impl Window {
                 )
             })
             .collect();
+        history::record_search(SearchQuery::new(
+            query_title.to_string(),
+            query_album.to_string(),
+            query_artists.to_string(),
+            !results.is_empty(),
+        ));
         self.results().remove_all();
 
         if results.is_empty() {
@@ -280,14 +285,44 @@ impl Window {
                 gtk::MessageType::Error,
             );
             self.imp().set_button.set_visible(false);
+            self.imp().pin_button.set_visible(false);
             return;
         } else {
             self.imp().set_button.set_visible(true);
+            self.imp().pin_button.set_visible(true);
         }
 
         self.results().extend_from_slice(&results);
     }
 
+    /// (re)load recent queries into the history popover
+    fn load_history(&self) {
+        let imp = self.imp();
+        while let Some(row) = imp.history_list.first_child() {
+            imp.history_list.remove(&row);
+        }
+
+        let history: Vec<SearchQuery> = history::load_history().iter().cloned().collect();
+        if history.is_empty() {
+            let label = gtk::Label::new(Some(&gettext("No recent search")));
+            label.set_sensitive(false);
+            imp.history_list.append(&label);
+        }
+        for query in &history {
+            let text = if query.found {
+                query.query()
+            } else {
+                format!("{} ({})", query.query(), gettext("no result"))
+            };
+            let label = gtk::Label::new(Some(&text));
+            label.set_halign(gtk::Align::Start);
+            label.set_ellipsize(gtk::pango::EllipsizeMode::End);
+            label.set_max_width_chars(40);
+            imp.history_list.append(&label);
+        }
+        imp.history.replace(history);
+    }
+
     fn get_selected_result(&self) -> Option<ResultObject> {
         let selection_model = self
             .imp()
@@ -337,6 +372,27 @@ impl Window {
             }
         ));
 
+        imp.history_button.set_create_popup_func(clone!(
+            #[weak(rename_to = window)]
+            self,
+            move |_| window.load_history()
+        ));
+
+        imp.history_list.connect_row_activated(clone!(
+            #[weak(rename_to = window)]
+            self,
+            move |_, row| {
+                let imp = window.imp();
+                let Some(query) = imp.history.borrow().get(row.index() as usize).cloned() else {
+                    return;
+                };
+                imp.input_title.buffer().set_text(&query.title);
+                imp.input_album.buffer().set_text(&query.album);
+                imp.input_artists.buffer().set_text(&query.artists);
+                imp.history_button.popdown();
+            }
+        ));
+
         imp.set_button.connect_clicked(clone!(
             #[weak(rename_to = window)]
             self,
@@ -368,13 +424,24 @@ impl Window {
                             set_current_lyric(LyricState {
                                 origin,
                                 translation,
+                                source_url: provider.source_url(&song_id),
                             });
+                            let source = LyricSource {
+                                provider: provider.unique_name().to_owned(),
+                                id: song_id.to_string(),
+                                validator: Default::default(),
+                            };
 
                             if window.imp().use_cache.get() {
                                 if let Some(cache_path) = get_lyric_cache_path() {
-                                    update_lyric_cache(&cache_path);
+                                    if update_lyric_cache(&cache_path) {
+                                        http_cache::write_sidecar(&cache_path, Some(&source));
+                                        let pinned = window.imp().pin_button.is_active();
+                                        set_lyric_pinned(&cache_path, pinned);
+                                    }
                                 }
                             }
+                            set_lyric_source(Some(source));
                         }
                         Err(e) => {
                             let error_msg = format!("{e} when getting lyric.");
@@ -401,6 +468,62 @@ impl Window {
     }
 }
 
+/// Search all providers, returns results with their provider index/name, and errors
+async fn search_providers(
+    title: Arc<String>,
+    album: Arc<String>,
+    artists: Arc<Vec<String>>,
+) -> (Vec<(SongInfo, usize, &'static str)>, Vec<String>) {
+    let providers = LYRIC_PROVIDERS
+        .get()
+        .expect("lyric providers should be initialized");
+    let (error_tx, error_rx) = async_channel::bounded(providers.len());
+
+    let results = tokio_spawn!(async move {
+        let mut results = vec![];
+        let mut set = JoinSet::new();
+        for (idx, provider) in providers.iter().enumerate() {
+            let provider_id = provider.unique_name();
+            let album = album.clone();
+            let title = title.clone();
+            let artists = artists.clone();
+            set.spawn(async move {
+                let artists = artists.iter().map(|a| &**a).collect::<Vec<&str>>();
+                let result = provider
+                    .search_song_detailed(&album, &artists, &title)
+                    .await;
+                (result, provider_id, idx, title)
+            });
+        }
+
+        while let Some(Ok((search_result, provider_name, idx, title))) = set.join_next().await {
+            let tracks = match search_result {
+                Ok(songs) => songs,
+                Err(e) => {
+                    let error_msg =
+                        format!("{e} occurs when searching {title} on {}", provider_name);
+                    error!(error_msg);
+                    let _ = error_tx.send(error_msg).await;
+                    continue;
+                }
+            };
+            for track in tracks {
+                // ResultObject contains RefCell, so it cannot be used inside tokio_spawn!()
+                results.push((track, idx, provider_name));
+            }
+        }
+        results
+    })
+    .await
+    .expect("Tokio runtime failure");
+
+    let mut errors = Vec::new();
+    while let Ok(msg) = error_rx.try_recv() {
+        errors.push(msg);
+    }
+    (results, errors)
+}
+
 fn connect_factory(
     column: &ColumnViewColumn,
     get_field: impl 'static + Fn(ResultObject) -> String,
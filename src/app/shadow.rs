@@ -0,0 +1,419 @@
+//! Drop shadow, outline and gradient of lyric labels, painted from their Pango layout.
+//!
+//! CSS `text-shadow` is not respected everywhere, and `gtk::Label` cannot be subclassed,
+//! so each lyric label is wrapped in a [`ShadowBin`], which paints the layout of the label
+//! once more in shadow color, then strokes its path in outline color,
+//! before drawing the label itself.
+//!
+//! With a gradient, the label is not drawn by GTK, its text is filled with the gradient instead.
+//!
+//! A background is a rounded rectangle behind the text, resized smoothly when the text changes.
+
+use gtk::gdk::RGBA;
+use gtk::glib;
+use gtk::graphene::{Point, Rect};
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Shadow {
+    pub offset_x: f32,
+    pub offset_y: f32,
+    pub blur: f32,
+    pub color: RGBA,
+}
+
+impl Shadow {
+    /// Returns `None` if `color` is empty, that is, shadow is disabled
+    pub fn new(
+        offset_x: f32,
+        offset_y: f32,
+        blur: f32,
+        color: &str,
+    ) -> Result<Option<Self>, glib::BoolError> {
+        let color = color.trim();
+        if color.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            offset_x,
+            offset_y,
+            blur: blur.max(0.),
+            color: RGBA::parse(color)?,
+        }))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Outline {
+    /// in pixels, may be fractional
+    pub width: f32,
+    pub color: RGBA,
+}
+
+impl Outline {
+    /// Returns `None` if `width` is not positive or `color` is empty, that is, outline is disabled
+    pub fn new(width: f32, color: &str) -> Result<Option<Self>, glib::BoolError> {
+        let color = color.trim();
+        if width <= 0. || color.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            width,
+            color: RGBA::parse(color)?,
+        }))
+    }
+}
+
+/// Horizontal gradient filling lyric text, from the first color at the left
+#[derive(Clone, Debug, PartialEq)]
+pub struct Gradient {
+    pub colors: Vec<RGBA>,
+}
+
+impl Gradient {
+    /// Returns `None` if `colors` is empty, that is, gradient is disabled
+    pub fn new(colors: &[String]) -> Result<Option<Self>, glib::BoolError> {
+        let colors = colors
+            .iter()
+            .map(|color| color.trim())
+            .filter(|color| !color.is_empty())
+            .map(RGBA::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+        if colors.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(Self { colors }))
+    }
+
+    /// `(offset, color)` spread evenly from 0 to 1
+    pub fn stops(&self) -> impl Iterator<Item = (f64, RGBA)> + '_ {
+        let last = self.colors.len().saturating_sub(1).max(1) as f64;
+        self.colors
+            .iter()
+            .enumerate()
+            .map(move |(index, color)| (index as f64 / last, *color))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Background {
+    pub color: RGBA,
+    /// of corners in pixels, clamped to half the height so that it's pill-shaped at most
+    pub radius: f32,
+}
+
+impl Background {
+    /// Returns `None` if `color` is empty, that is, background is disabled
+    pub fn new(color: &str, radius: u32) -> Result<Option<Self>, glib::BoolError> {
+        let color = color.trim();
+        if color.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            color: RGBA::parse(color)?,
+            radius: radius as f32,
+        }))
+    }
+}
+
+/// space between the text and edges of the background, in pixels
+const BACKGROUND_PADDING: (f32, f32) = (12., 4.);
+/// part of the remaining distance the background moves towards its target size each frame
+const BACKGROUND_EASING: f32 = 0.25;
+
+/// `current` size moved towards `target` by one frame, snapped once it's closer than a pixel
+pub fn approach_size(current: (f32, f32), target: (f32, f32)) -> (f32, f32) {
+    let step = |current: f32, target: f32| {
+        let next = current + (target - current) * BACKGROUND_EASING;
+        if (target - next).abs() < 1. {
+            target
+        } else {
+            next
+        }
+    };
+    (step(current.0, target.0), step(current.1, target.1))
+}
+
+mod imp {
+    use std::cell::{Cell, RefCell};
+
+    use gtk::glib;
+    use gtk::prelude::*;
+    use gtk::subclass::prelude::*;
+
+    use super::{
+        paint_background, paint_gradient, paint_outline, paint_shadow, Background, Gradient,
+        Outline, Shadow,
+    };
+
+    #[derive(Default)]
+    pub struct ShadowBin {
+        pub shadow: Cell<Option<Shadow>>,
+        pub outline: Cell<Option<Outline>>,
+        pub gradient: RefCell<Option<Gradient>>,
+        pub background: Cell<Option<Background>>,
+        /// size the background is painted in, on its way to fit the text
+        pub background_size: Cell<Option<(f32, f32)>>,
+        /// size fitting the text currently shown
+        pub background_target: Cell<(f32, f32)>,
+        pub background_animating: Cell<bool>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ShadowBin {
+        const NAME: &'static str = "LyricShadowBin";
+        type Type = super::ShadowBin;
+        type ParentType = gtk::Widget;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.set_layout_manager_type::<gtk::BinLayout>();
+        }
+    }
+
+    impl ObjectImpl for ShadowBin {
+        fn dispose(&self) {
+            while let Some(child) = self.obj().first_child() {
+                child.unparent();
+            }
+        }
+    }
+
+    impl WidgetImpl for ShadowBin {
+        fn snapshot(&self, snapshot: &gtk::Snapshot) {
+            let obj = self.obj();
+            let Some(label) = obj.label() else {
+                return;
+            };
+            let mut painted = false;
+            if label.is_visible() && !label.label().is_empty() {
+                if let Some(background) = self.background.get() {
+                    paint_background(&obj, &label, background, snapshot);
+                }
+                if let Some(shadow) = self.shadow.get() {
+                    paint_shadow(&obj, &label, shadow, snapshot);
+                }
+                if let Some(outline) = self.outline.get() {
+                    paint_outline(&obj, &label, outline, snapshot);
+                }
+                if let Some(gradient) = self.gradient.borrow().as_ref() {
+                    painted = paint_gradient(&obj, &label, gradient, snapshot);
+                }
+            } else {
+                // fit the next line at once instead of growing from the last one
+                self.background_size.set(None);
+            }
+            // in theme color if the gradient cannot be painted
+            if !painted {
+                obj.snapshot_child(&label, snapshot);
+            }
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct ShadowBin(ObjectSubclass<imp::ShadowBin>)
+        @extends gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl ShadowBin {
+    pub fn new(label: &gtk::Label) -> Self {
+        let bin: Self = glib::Object::new();
+        label.set_parent(&bin);
+        bin
+    }
+
+    pub fn label(&self) -> Option<gtk::Label> {
+        self.first_child().and_downcast()
+    }
+
+    pub fn set_shadow(&self, shadow: Option<Shadow>) {
+        self.imp().shadow.set(shadow);
+        self.queue_draw();
+    }
+
+    pub fn set_outline(&self, outline: Option<Outline>) {
+        self.imp().outline.set(outline);
+        self.queue_draw();
+    }
+
+    pub fn set_gradient(&self, gradient: Option<Gradient>) {
+        self.imp().gradient.replace(gradient);
+        self.queue_draw();
+    }
+
+    pub fn set_background(&self, background: Option<Background>) {
+        self.imp().background.set(background);
+        self.queue_draw();
+    }
+
+    /// Move the painted background size to `background_target` frame by frame,
+    /// see `approach_size`
+    fn animate_background(&self) {
+        let imp = self.imp();
+        if imp.background_animating.replace(true) {
+            return;
+        }
+        self.add_tick_callback(|bin, _| {
+            let imp = bin.imp();
+            let target = imp.background_target.get();
+            let Some(current) = imp.background_size.get() else {
+                imp.background_animating.set(false);
+                return glib::ControlFlow::Break;
+            };
+            let next = approach_size(current, target);
+            imp.background_size.set(Some(next));
+            bin.queue_draw();
+            if next == target {
+                imp.background_animating.set(false);
+                return glib::ControlFlow::Break;
+            }
+            glib::ControlFlow::Continue
+        });
+    }
+}
+
+/// Fill a rounded rectangle behind the label layout, centered on its text
+fn paint_background(
+    bin: &ShadowBin,
+    label: &gtk::Label,
+    background: Background,
+    snapshot: &gtk::Snapshot,
+) {
+    let (x, y) = label.layout_offsets();
+    let Some((x, y)) = label.translate_coordinates(bin, x as f64, y as f64) else {
+        return;
+    };
+
+    let (_, logical) = label.layout().pixel_extents();
+    let target = (
+        logical.width() as f32 + BACKGROUND_PADDING.0 * 2.,
+        logical.height() as f32 + BACKGROUND_PADDING.1 * 2.,
+    );
+    let imp = bin.imp();
+    imp.background_target.set(target);
+    let (width, height) = match imp.background_size.get() {
+        Some(current) => {
+            if current != target {
+                bin.animate_background();
+            }
+            current
+        }
+        None => {
+            imp.background_size.set(Some(target));
+            target
+        }
+    };
+
+    let center_x = x as f32 + logical.x() as f32 + logical.width() as f32 / 2.;
+    let center_y = y as f32 + logical.y() as f32 + logical.height() as f32 / 2.;
+    let bounds = Rect::new(center_x - width / 2., center_y - height / 2., width, height);
+    let radius = background.radius.min(height / 2.);
+    let rounded = gtk::gsk::RoundedRect::from_rect(bounds, radius);
+    snapshot.push_rounded_clip(&rounded);
+    snapshot.append_color(&background.color, &bounds);
+    snapshot.pop();
+}
+
+fn paint_shadow(bin: &ShadowBin, label: &gtk::Label, shadow: Shadow, snapshot: &gtk::Snapshot) {
+    // where the label draws its layout, in coordinates of `bin`
+    let (x, y) = label.layout_offsets();
+    let Some((x, y)) = label.translate_coordinates(bin, x as f64, y as f64) else {
+        return;
+    };
+
+    snapshot.save();
+    snapshot.translate(&Point::new(
+        x as f32 + shadow.offset_x,
+        y as f32 + shadow.offset_y,
+    ));
+    if shadow.blur > 0. {
+        snapshot.push_blur(shadow.blur as f64);
+    }
+    snapshot.append_layout(&label.layout(), &shadow.color);
+    if shadow.blur > 0. {
+        snapshot.pop();
+    }
+    snapshot.restore();
+}
+
+/// Stroke the path of the label layout, the label then fills it over the inner half
+fn paint_outline(bin: &ShadowBin, label: &gtk::Label, outline: Outline, snapshot: &gtk::Snapshot) {
+    let (x, y) = label.layout_offsets();
+    let Some((x, y)) = label.translate_coordinates(bin, x as f64, y as f64) else {
+        return;
+    };
+
+    let layout = label.layout();
+    let (ink, _) = layout.pixel_extents();
+    let margin = outline.width.ceil();
+    let bounds = Rect::new(
+        x as f32 + ink.x() as f32 - margin,
+        y as f32 + ink.y() as f32 - margin,
+        ink.width() as f32 + margin * 2.,
+        ink.height() as f32 + margin * 2.,
+    );
+
+    let cr = snapshot.append_cairo(&bounds);
+    cr.translate(x, y);
+    pangocairo::functions::layout_path(&cr, &layout);
+    cr.set_source_rgba(
+        outline.color.red() as f64,
+        outline.color.green() as f64,
+        outline.color.blue() as f64,
+        outline.color.alpha() as f64,
+    );
+    cr.set_line_width(outline.width as f64 * 2.);
+    cr.set_line_join(gtk::cairo::LineJoin::Round);
+    if let Err(e) = cr.stroke() {
+        crate::log::error!("cannot paint lyric outline: {e}");
+    }
+}
+
+/// Fill glyphs of the label layout with `gradient` stretched over the text width,
+/// returns `false` if nothing was painted
+fn paint_gradient(
+    bin: &ShadowBin,
+    label: &gtk::Label,
+    gradient: &Gradient,
+    snapshot: &gtk::Snapshot,
+) -> bool {
+    let (x, y) = label.layout_offsets();
+    let Some((x, y)) = label.translate_coordinates(bin, x as f64, y as f64) else {
+        return false;
+    };
+
+    let layout = label.layout();
+    let (ink, _) = layout.pixel_extents();
+    if ink.width() <= 0 || ink.height() <= 0 {
+        return false;
+    }
+    let bounds = Rect::new(
+        x as f32 + ink.x() as f32,
+        y as f32 + ink.y() as f32,
+        ink.width() as f32,
+        ink.height() as f32,
+    );
+
+    let cr = snapshot.append_cairo(&bounds);
+    cr.translate(x, y);
+    pangocairo::functions::layout_path(&cr, &layout);
+    cr.clip();
+    let pattern =
+        gtk::cairo::LinearGradient::new(ink.x() as f64, 0., (ink.x() + ink.width()) as f64, 0.);
+    for (offset, color) in gradient.stops() {
+        pattern.add_color_stop_rgba(
+            offset,
+            color.red() as f64,
+            color.green() as f64,
+            color.blue() as f64,
+            color.alpha() as f64,
+        );
+    }
+    if let Err(e) = cr.set_source(&pattern).and_then(|()| cr.paint()) {
+        crate::log::error!("cannot paint lyric gradient: {e}");
+        return false;
+    }
+    true
+}
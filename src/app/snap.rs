@@ -0,0 +1,169 @@
+//! Snap the main window to edges (or center axes) of its monitor when dragged close.
+//!
+//! Clients cannot position their own windows on Wayland, and GTK4 dropped `gtk_window_move`,
+//! so only Windows is supported for now.
+
+use super::window;
+
+/// css class added to the main window for a moment after snapping
+pub const SNAPPED_CSS_CLASS: &str = "snapped";
+#[cfg(target_os = "windows")]
+const INDICATOR_DURATION: std::time::Duration = std::time::Duration::from_millis(400);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+/// Where `window` should be snapped to inside `monitor`, it may be where `window` is already.
+///
+/// `None` if it's not within `threshold` pixels of any edge or center axis.
+pub fn snap_position(window: Rect, monitor: Rect, threshold: i32) -> Option<(i32, i32)> {
+    let snap_axis = |pos: i32, size: i32, start: i32, length: i32| {
+        [start, start + length - size, start + (length - size) / 2]
+            .into_iter()
+            .map(|target| (target, (pos - target).abs()))
+            .filter(|(_, distance)| *distance <= threshold)
+            .min_by_key(|(_, distance)| *distance)
+            .map(|(target, _)| target)
+    };
+
+    let x = snap_axis(window.x, window.width, monitor.x, monitor.width);
+    let y = snap_axis(window.y, window.height, monitor.y, monitor.height);
+    if x.is_none() && y.is_none() {
+        return None;
+    }
+    Some((x.unwrap_or(window.x), y.unwrap_or(window.y)))
+}
+
+#[cfg(target_os = "windows")]
+fn flash_indicator(window: &window::Window) {
+    use gtk::{glib, prelude::*};
+
+    window.add_css_class(SNAPPED_CSS_CLASS);
+    glib::timeout_add_local_once(
+        INDICATOR_DURATION,
+        glib::clone!(
+            #[weak]
+            window,
+            move || window.remove_css_class(SNAPPED_CSS_CLASS)
+        ),
+    );
+}
+
+/// Restore the last snapped position, and snap `window` each time it stops moving
+#[cfg(target_os = "windows")]
+pub fn watch_snap(window: &window::Window, threshold: i32) {
+    use gtk::glib::{self, ControlFlow};
+    use gtk::prelude::ObjectExt;
+    use gtk::subclass::prelude::ObjectSubclassIsExt;
+    use std::cell::Cell;
+
+    const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(200);
+
+    if let Some((x, y)) = window.saved_snap_position() {
+        win32::move_window(window, x, y);
+        window.imp().snapped_position.set(Some((x, y)));
+    }
+
+    let last_rect = Cell::new(win32::window_rect(window));
+    let moving = Cell::new(false);
+    let window = window.downgrade();
+    glib::timeout_add_local(POLL_INTERVAL, move || {
+        let Some(window) = window.upgrade() else {
+            return ControlFlow::Break;
+        };
+        let rect = win32::window_rect(&window);
+        if rect != last_rect.replace(rect) {
+            moving.set(true);
+            return ControlFlow::Continue;
+        }
+        // only snap once a drag ended
+        if !moving.replace(false) {
+            return ControlFlow::Continue;
+        }
+
+        let (Some(rect), Some(monitor)) = (rect, win32::monitor_rect(&window)) else {
+            return ControlFlow::Continue;
+        };
+        let snapped = snap_position(rect, monitor, threshold);
+        if let Some((x, y)) = snapped.filter(|&position| position != (rect.x, rect.y)) {
+            crate::log::debug!("snapped main window to ({x}, {y})");
+            win32::move_window(&window, x, y);
+            last_rect.set(win32::window_rect(&window));
+            flash_indicator(&window);
+        }
+        // forgotten once dragged away from edges
+        window.imp().snapped_position.set(snapped);
+        ControlFlow::Continue
+    });
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn watch_snap(_window: &window::Window, _threshold: i32) {
+    crate::log::warn!("snap-to-edge is only supported on Windows");
+}
+
+#[cfg(target_os = "windows")]
+pub(super) mod win32 {
+    use gtk::prelude::*;
+    use windows::Win32::Foundation::{HWND, RECT};
+    use windows::Win32::Graphics::Gdi::{
+        GetMonitorInfoW, MonitorFromWindow, MONITORINFO, MONITOR_DEFAULTTONEAREST,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetWindowRect, SetWindowPos, SWP_NOACTIVATE, SWP_NOSIZE, SWP_NOZORDER,
+    };
+
+    use super::{window, Rect};
+
+    fn hwnd(window: &window::Window) -> Option<HWND> {
+        let surface = window
+            .surface()
+            .and_downcast::<gdk4_win32::Win32Surface>()?;
+        Some(HWND(surface.handle().0 as _))
+    }
+
+    fn to_rect(rect: RECT) -> Rect {
+        Rect {
+            x: rect.left,
+            y: rect.top,
+            width: rect.right - rect.left,
+            height: rect.bottom - rect.top,
+        }
+    }
+
+    pub fn window_rect(window: &window::Window) -> Option<Rect> {
+        let hwnd = hwnd(window)?;
+        let mut rect = RECT::default();
+        unsafe { GetWindowRect(hwnd, &mut rect) }.ok()?;
+        Some(to_rect(rect))
+    }
+
+    /// work area of the monitor `window` is mostly on
+    pub fn monitor_rect(window: &window::Window) -> Option<Rect> {
+        let hwnd = hwnd(window)?;
+        let mut info = MONITORINFO {
+            cbSize: std::mem::size_of::<MONITORINFO>() as u32,
+            ..Default::default()
+        };
+        unsafe {
+            let monitor = MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST);
+            GetMonitorInfoW(monitor, &mut info).ok().ok()?;
+        }
+        Some(to_rect(info.rcWork))
+    }
+
+    pub fn move_window(window: &window::Window, x: i32, y: i32) {
+        let Some(hwnd) = hwnd(window) else {
+            return;
+        };
+        let flags = SWP_NOSIZE | SWP_NOZORDER | SWP_NOACTIVATE;
+        if let Err(e) = unsafe { SetWindowPos(hwnd, None, x, y, 0, 0, flags) } {
+            crate::log::error!("cannot move main window: {e}");
+        }
+    }
+}
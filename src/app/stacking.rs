@@ -0,0 +1,60 @@
+//! Keep the main window above others, see `always-on-top`.
+//!
+//! GTK 4 dropped `gtk_window_set_keep_above`, so it's done by the backend where possible.
+//! Windows has `HWND_TOPMOST`. Wayland has no protocol for it, and X11 would need
+//! `_NET_WM_STATE_ABOVE` sent through Xlib, so there it's left to rules of the compositor
+//! or window manager, like `keep above` in KWin or `pin` in Hyprland.
+
+use gtk::prelude::*;
+use gtk::subclass::prelude::ObjectSubclassIsExt;
+
+use super::Window;
+use crate::log::warn;
+
+/// Apply and remember always-on-top state of `window`
+pub fn set_always_on_top(window: &Window, enabled: bool) {
+    window.imp().always_on_top.set(enabled);
+    if !apply_always_on_top(window, enabled) {
+        warn!(
+            "always-on-top is not supported on this platform, \
+            configure a window rule in your compositor instead"
+        );
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn apply_always_on_top(window: &Window, enabled: bool) -> bool {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        SetWindowPos, HWND_NOTOPMOST, HWND_TOPMOST, SWP_NOACTIVATE, SWP_NOMOVE, SWP_NOSIZE,
+    };
+
+    let Some(surface) = window.surface().and_downcast::<gdk4_win32::Win32Surface>() else {
+        return false;
+    };
+    let hwnd = HWND(surface.handle().0 as _);
+    let insert_after = if enabled {
+        HWND_TOPMOST
+    } else {
+        HWND_NOTOPMOST
+    };
+    unsafe {
+        SetWindowPos(
+            hwnd,
+            Some(insert_after),
+            0,
+            0,
+            0,
+            0,
+            SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE,
+        )
+    }
+    .inspect_err(|e| warn!("cannot set always-on-top: {e}"))
+    .is_ok()
+}
+
+#[cfg(not(target_os = "windows"))]
+fn apply_always_on_top(_window: &Window, enabled: bool) -> bool {
+    // nothing to undo
+    !enabled
+}
@@ -0,0 +1,122 @@
+//! Shows the metadata used to find lyric of the playing track, for debugging mismatches
+
+use std::path::PathBuf;
+
+use gtk::prelude::*;
+use gtk::subclass::prelude::ObjectSubclassIsExt;
+use serde_json::{json, Value};
+
+use crate::app;
+use crate::lyric_providers::utils::http_cache::LyricSource;
+use crate::sync::{
+    get_current_lyric, get_lyric_cache_path, get_lyric_source, OsImp, TrackMeta, TrackState, OS,
+    TRACK_PLAYING_STATE,
+};
+use crate::utils::gettext;
+
+/// What `show_track_info_window` shows, copied out of global state
+#[derive(Clone, Debug, Default)]
+pub struct TrackInfo {
+    pub meta: Option<TrackMeta>,
+    pub file: Option<PathBuf>,
+    pub cache_path: Option<PathBuf>,
+    pub source: Option<LyricSource>,
+    pub source_url: Option<String>,
+    /// including the offset of the player
+    pub offset_ms: i64,
+}
+
+impl TrackInfo {
+    pub fn current(window: &app::Window) -> Self {
+        let meta = TRACK_PLAYING_STATE.with_borrow(|TrackState { metainfo, .. }| metainfo.clone());
+        let imp = window.imp();
+        Self {
+            meta,
+            file: OS::playing_file(),
+            cache_path: get_lyric_cache_path(),
+            source: get_lyric_source(),
+            source_url: get_current_lyric().source_url,
+            offset_ms: imp.lyric_offset_ms.get() + imp.player_offset_ms.get(),
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        let meta = self.meta.as_ref();
+        json!({
+            "title": meta.and_then(|meta| meta.title.as_deref()),
+            "artists": meta.and_then(|meta| meta.artists.as_deref()),
+            "album": meta.and_then(|meta| meta.album.as_deref()),
+            "length_ms": meta.and_then(|meta| meta.length).map(|length| length.as_millis() as u64),
+            "year": meta.and_then(|meta| meta.year),
+            "track_number": meta.and_then(|meta| meta.track_number),
+            "disc_number": meta.and_then(|meta| meta.disc_number),
+            "genre": meta.and_then(|meta| meta.genre.as_deref()),
+            "track_id": meta
+                .and_then(|meta| meta.unique_song_id.as_ref())
+                .map(ToString::to_string),
+            "file": self.file,
+            "cache_path": self.cache_path,
+            "provider": self.source.as_ref().map(|source| &source.provider),
+            "provider_song_id": self.source.as_ref().map(|source| &source.id),
+            "source_url": self.source_url,
+            "offset_ms": self.offset_ms,
+        })
+    }
+}
+
+fn display_value(value: &Value) -> String {
+    match value {
+        Value::Null => gettext("(unknown)"),
+        Value::String(s) => s.clone(),
+        Value::Array(values) => values
+            .iter()
+            .map(display_value)
+            .collect::<Vec<_>>()
+            .join(", "),
+        value => value.to_string(),
+    }
+}
+
+pub fn show_track_info_window(parent: &app::Window) {
+    let info = TrackInfo::current(parent).to_json();
+    let json = serde_json::to_string_pretty(&info).expect("cannot serialize track info!");
+
+    let grid = gtk::Grid::builder()
+        .row_spacing(6)
+        .column_spacing(12)
+        .margin_top(12)
+        .margin_bottom(12)
+        .margin_start(12)
+        .margin_end(12)
+        .build();
+    if let Value::Object(fields) = &info {
+        for (row, (key, value)) in fields.iter().enumerate() {
+            let key = gtk::Label::new(Some(key));
+            key.set_xalign(1.);
+            key.add_css_class("dim-label");
+            let value = gtk::Label::new(Some(&display_value(value)));
+            value.set_xalign(0.);
+            value.set_selectable(true);
+            value.set_wrap(true);
+            grid.attach(&key, 0, row as i32, 1, 1);
+            grid.attach(&value, 1, row as i32, 1, 1);
+        }
+    }
+
+    let copy = gtk::Button::with_label(&gettext("Copy to clipboard"));
+    copy.set_halign(gtk::Align::End);
+    copy.connect_clicked(move |button| {
+        button.clipboard().set_text(&json);
+    });
+
+    let vbox = gtk::Box::new(gtk::Orientation::Vertical, 6);
+    vbox.append(&grid);
+    vbox.append(&copy);
+
+    let window = gtk::Window::builder()
+        .title(gettext("Track info"))
+        .transient_for(parent)
+        .child(&vbox)
+        .build();
+    window.present();
+}
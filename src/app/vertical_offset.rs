@@ -0,0 +1,57 @@
+//! Shift lyric labels up or down inside the main window, see `lyrics-vertical-offset-pct`.
+
+use gtk::prelude::*;
+use gtk::subclass::prelude::ObjectSubclassIsExt;
+
+use super::Window;
+use crate::log::warn;
+
+/// Top and bottom margin of the centered label box to move it by `offset_pct` of
+/// `window_height`, downwards if positive, and whether it was limited to keep
+/// `content_height` inside the window
+pub fn vertical_margins(
+    offset_pct: f32,
+    window_height: i32,
+    content_height: i32,
+) -> ((i32, i32), bool) {
+    let offset_pct = offset_pct.clamp(-100., 100.);
+    // a margin on one side moves the centered box by half of it
+    let wanted = (window_height as f32 * offset_pct / 100. * 2.).round() as i32;
+    let room = (window_height - content_height).max(0);
+    let margin = wanted.clamp(-room, room);
+    let margins = if margin >= 0 {
+        (margin, 0)
+    } else {
+        (0, -margin)
+    };
+    (margins, margin != wanted)
+}
+
+/// Move the label box by `lyrics-vertical-offset-pct` of current window height
+pub fn apply_vertical_offset(window: &Window) {
+    let Some(vbox) = window.child().and_downcast::<gtk::Box>() else {
+        return;
+    };
+    let imp = window.imp();
+    let offset_pct = imp.lyrics_vertical_offset_pct.get();
+    let (_, window_height) = window.default_size();
+    let (_, natural, _, _) = vbox.measure(gtk::Orientation::Vertical, -1);
+    let content_height = natural - vbox.margin_top() - vbox.margin_bottom();
+
+    let ((top, bottom), clipped) = vertical_margins(offset_pct, window_height, content_height);
+    vbox.set_margin_top(top);
+    vbox.set_margin_bottom(bottom);
+
+    // once each time it starts to clip, not on every resize
+    if clipped && !imp.vertical_offset_clipped.replace(true) {
+        warn!("lyrics-vertical-offset-pct {offset_pct} would move lyrics out of the window, limited to its edge");
+    } else if !clipped {
+        imp.vertical_offset_clipped.set(false);
+    }
+}
+
+pub fn set_lyrics_vertical_offset(window: &Window, offset_pct: f32) {
+    window.imp().lyrics_vertical_offset_pct.set(offset_pct);
+    window.imp().vertical_offset_clipped.set(false);
+    apply_vertical_offset(window);
+}
@@ -1,5 +1,5 @@
-use std::cell::Cell;
-use std::time::SystemTime;
+use std::cell::{Cell, RefCell};
+use std::time::{Duration, SystemTime};
 
 use crate::utils::gettext;
 use gio::Settings;
@@ -11,8 +11,8 @@ use gtk::subclass::prelude::*;
 use gtk::{gio, glib, ApplicationWindow, PopoverMenu};
 use std::sync::OnceLock;
 
-use crate::app::utils::set_click_pass_through;
-use crate::config::{Align, LyricDisplayMode};
+use crate::app::input_region::set_click_pass_through;
+use crate::config::{Align, LabelAlign, LyricDisplayMode};
 use crate::sync::{OsImp, OS};
 
 #[derive(Default)]
@@ -20,15 +20,80 @@ pub struct Window {
     pub settings: OnceLock<Settings>,
 
     pub clickthrough: Cell<bool>,
+    pub always_on_top: Cell<bool>,
+    /// set by `snap-to-edge`, `None` if the window is not on an edge
+    pub snapped_position: Cell<Option<(i32, i32)>>,
+    /// from `monitor-index` or `win.move-to-monitor`, kept when the monitor is unplugged
+    pub monitor_index: Cell<usize>,
     pub cache_lyrics: Cell<bool>,
+    pub offline_mode: Cell<bool>,
+    /// by `win.decrease-opacity` and `win.increase-opacity`
+    pub opacity_step: Cell<f64>,
 
     pub lyric_align: Cell<Align>,
+    /// of `above` and `below` label from config, `Auto` follows `lyric_align`
+    pub label_aligns: Cell<[LabelAlign; 2]>,
     pub lyric_display_mode: Cell<LyricDisplayMode>,
+    /// of `above` and `below` label, shows lines earlier by this many milliseconds
+    pub lyric_spacing_ms: Cell<[i64; 2]>,
+    /// of `above` and `below` label while `lyric_spacing_ms` shifts them off the current line
+    pub context_opacity: Cell<[f32; 2]>,
+    /// left margin of labels showing translation
+    pub translation_indent_px: Cell<u32>,
+    /// percentage of window height to move labels down, negative to move them up
+    pub lyrics_vertical_offset_pct: Cell<f32>,
+    /// whether `lyrics_vertical_offset_pct` was limited to keep labels inside the window
+    pub vertical_offset_clipped: Cell<bool>,
+    pub double_tap_action: Cell<crate::config::DoubleTapAction>,
     pub show_default_text_on_idle: Cell<bool>,
     pub show_lyric_on_pause: Cell<bool>,
+    pub gap_fill_text: RefCell<String>,
+    /// written by `print-debug-state` besides stderr, if not empty
+    pub debug_state_file: RefCell<String>,
+    pub font_size_auto_fit: Cell<bool>,
+    /// in points
+    pub font_size_min: Cell<f64>,
+    /// in grapheme clusters, zero for no limit
+    pub lyrics_max_line_length: Cell<usize>,
+    pub show_position_in_title: Cell<bool>,
+    pub show_position_label: Cell<bool>,
+    /// appended to window title by `show-position-in-title`
+    pub title_position: RefCell<Option<String>>,
+    pub position_source: RefCell<Option<glib::SourceId>>,
+    pub enable_repeat_animation: Cell<bool>,
+    pub animate_to_bpm: Cell<bool>,
+    /// beat interval and `lyric_start` the pulse is running with
+    pub pulse_state: Cell<Option<(Duration, SystemTime)>>,
+    pub pulse_source: RefCell<Option<glib::SourceId>>,
+    /// `(text, start_time)` of the line shown in `above` label, see `repeat_animation`
+    pub shown_line: RefCell<Option<(String, Duration)>>,
+    #[cfg(feature = "transliterate-japanese")]
+    pub romaji_display: Cell<crate::config::RomajiDisplay>,
+    pub gap_fill_threshold: Cell<Duration>,
+    /// lyric labels are blanked if the player is not synced within this, zero to disable
+    pub lyric_line_timeout: Cell<Duration>,
+    pub line_timeout_source: RefCell<Option<glib::SourceId>>,
+    /// lines shorter than this are skipped, zero to show all
+    pub lyric_line_min_duration: Cell<Duration>,
+    /// set when `lyric_line_timeout` passed, until the player is synced again
+    pub line_timed_out: Cell<bool>,
+    /// lines per second of lyrics without timestamp if track length is unknown, zero to disable
+    pub no_timestamp_scroll_speed: Cell<f64>,
+    /// line of origin lyric shown in place of the playing one, see `set_lyric_override`
+    pub lyric_override_index: Cell<Option<usize>>,
+    pub lyric_override_source: RefCell<Option<glib::SourceId>>,
+    /// the override is cleared after this, zero to keep it until cleared by action
+    pub override_duration: Cell<Duration>,
+    pub show_confidence_indicators: Cell<bool>,
+    pub confidence_warn_threshold: Cell<f32>,
 
     pub lyric_start: Cell<Option<SystemTime>>,
     pub lyric_offset_ms: Cell<i64>,
+    /// from `lyric-offset-ms`, restored by `app.offset-reset`
+    pub default_lyric_offset_ms: Cell<i64>,
+    pub reset_offset_on_track_change: Cell<bool>,
+    /// from `per-player.*.lyric-offset-ms`, added to `lyric_offset_ms`
+    pub player_offset_ms: Cell<i64>,
     pub length_toleration_ms: Cell<u128>,
 
     // widgets
@@ -67,14 +132,68 @@ impl ObjectImpl for Window {
             Some(&gettext("Hide Decoration")),
             Some("win.switch-decoration"),
         );
+        let always_on_top = MenuItem::new(
+            Some(&gettext("Always on Top")),
+            Some("win.toggle-always-on-top"),
+        );
         let passthrough = MenuItem::new(
             Some(&gettext("Toggle Passthrough")),
             Some("win.switch-passthrough"),
         );
         let reload_theme = MenuItem::new(Some(&gettext("Reload theme")), Some("app.reload-theme"));
+        let reload_config =
+            MenuItem::new(Some(&gettext("Reload config")), Some("app.reload-config"));
+        let save_config = MenuItem::new(Some(&gettext("Save config")), Some("app.save-config"));
         let search_lyric = MenuItem::new(Some(&gettext("Search lyric")), Some("app.search-lyric"));
+        let compare_lyrics =
+            MenuItem::new(Some(&gettext("Compare lyrics")), Some("app.compare-lyrics"));
+        let edit_lyric = MenuItem::new(Some(&gettext("Edit lyric")), Some("app.open-editor"));
+        let show_history = MenuItem::new(Some(&gettext("Play history")), Some("app.show-history"));
+        let show_track_info =
+            MenuItem::new(Some(&gettext("Track info")), Some("app.show-track-info"));
+        let offset_reset = MenuItem::new(
+            Some(&gettext("Reset lyric offset")),
+            Some("app.offset-reset"),
+        );
         let refetch_lyric =
             MenuItem::new(Some(&gettext("Refetch lyric")), Some("app.refetch-lyric"));
+        let copy_lyric = MenuItem::new(
+            Some(&gettext("Copy lyric as LRC")),
+            Some("app.copy-lyric-as-lrc"),
+        );
+        let swap_lyric_roles = MenuItem::new(
+            Some(&gettext("Swap origin and translation")),
+            Some("app.swap-lyric-roles"),
+        );
+        let unpin_lyric = MenuItem::new(Some(&gettext("Unpin lyric")), Some("app.unpin-lyric"));
+        let import_netease_playlist = MenuItem::new(
+            Some(&gettext("Import NetEase playlist")),
+            Some("app.import-netease-playlist"),
+        );
+        let disable_auto_offset = MenuItem::new(
+            Some(&gettext("Disable auto offset for this track")),
+            Some("app.disable-auto-offset"),
+        );
+        let toggle_offline = MenuItem::new(
+            Some(&gettext("Toggle Offline Mode")),
+            Some("win.toggle-offline"),
+        );
+        let clear_search_history = MenuItem::new(
+            Some(&gettext("Clear search history")),
+            Some("app.clear-search-history"),
+        );
+        let confess_wrong_lyric = MenuItem::new(
+            Some(&gettext("Mark lyric as wrong")),
+            Some("app.confess-wrong-lyric"),
+        );
+        let open_provider_url = MenuItem::new(
+            Some(&gettext("Open lyric page")),
+            Some("app.open-provider-url"),
+        );
+        let clear_blacklist = MenuItem::new(
+            Some(&gettext("Clear lyric blacklist")),
+            Some("app.clear-blacklist"),
+        );
         let remove_lyric = MenuItem::new(
             Some(&if self.cache_lyrics.get() {
                 gettext("Remove lyric")
@@ -98,7 +217,14 @@ impl ObjectImpl for Window {
             &self.align_mode_menu,
         );
 
-        for item in [&passthrough, &hide_decoration, &reload_theme] {
+        for item in [
+            &passthrough,
+            &hide_decoration,
+            &always_on_top,
+            &reload_theme,
+            &reload_config,
+            &save_config,
+        ] {
             ui_section.append_item(item);
         }
 
@@ -120,6 +246,15 @@ impl ObjectImpl for Window {
                 Some(&gettext("Translated Lyric")),
                 Some("app.import-lyric(false)"),
             );
+            self.import_lyric_menu.append(
+                Some(&gettext("Original Lyric from Clipboard")),
+                Some("app.import-lyric-from-clipboard"),
+            );
+            #[cfg(feature = "import-translation-from-srt")]
+            self.import_lyric_menu.append(
+                Some(&gettext("Translation from SRT")),
+                Some("app.import-srt-translation"),
+            );
             play_section.append_submenu(Some(&gettext("Import Lyric")), &self.import_lyric_menu);
         }
         #[cfg(feature = "export-lyric")]
@@ -135,7 +270,26 @@ impl ObjectImpl for Window {
             play_section.append_submenu(Some(&gettext("Export Lyric")), &self.export_lyric_menu);
         }
 
-        for item in [&search_lyric, &remove_lyric, &refetch_lyric] {
+        for item in [
+            &search_lyric,
+            &compare_lyrics,
+            &edit_lyric,
+            &show_history,
+            &show_track_info,
+            &remove_lyric,
+            &swap_lyric_roles,
+            &copy_lyric,
+            &refetch_lyric,
+            &unpin_lyric,
+            &offset_reset,
+            &disable_auto_offset,
+            &confess_wrong_lyric,
+            &open_provider_url,
+            &toggle_offline,
+            &clear_search_history,
+            &clear_blacklist,
+            &import_netease_playlist,
+        ] {
             play_section.append_item(item);
         }
 
@@ -69,9 +69,24 @@ impl Window {
         self.settings()
             .set_string("lyric-display-mode", &display_mode)?;
 
+        let snapped_position = self.imp().snapped_position.get();
+        let (x, y) = snapped_position.unwrap_or_default();
+        self.settings()
+            .set_boolean("window-snapped", snapped_position.is_some())?;
+        self.settings().set_int("window-x", x)?;
+        self.settings().set_int("window-y", y)?;
+
         Ok(())
     }
 
+    /// Position saved by `snap-to-edge`
+    pub fn saved_snap_position(&self) -> Option<(i32, i32)> {
+        let settings = self.settings();
+        settings
+            .boolean("window-snapped")
+            .then(|| (settings.int("window-x"), settings.int("window-y")))
+    }
+
     fn load_window_state(&self) {
         let height = self.settings().int("window-height");
         let width = self.settings().int("window-width");
@@ -0,0 +1,45 @@
+//! Command line flags handled by waylyrics itself.
+//!
+//! Arguments not recognized here are passed to GTK as-is.
+
+#[derive(Debug, Default)]
+pub struct Cli {
+    /// verify api keys configured in `[providers.*]`, then exit
+    pub check_auth: bool,
+    /// search a test song on each provider in `lyric-search-source`, then exit
+    pub check_providers: bool,
+    /// same as `offline-mode = true`
+    pub offline: bool,
+    /// fetch every cached lyric again from `lyric-search-source`, then exit
+    pub rebuild_cache: bool,
+    /// with `--rebuild-cache`, only print caches that would be fetched
+    pub dry_run: bool,
+    /// print version and config for bug reports, then exit
+    pub dump_state: bool,
+    /// print players that can be connected to, then exit
+    pub list_players: bool,
+    /// with `--list-players`, print JSON instead
+    pub json: bool,
+}
+
+impl Cli {
+    /// Returns parsed flags, and the remaining arguments for `Application::run_with_args`
+    pub fn parse(args: impl IntoIterator<Item = String>) -> (Self, Vec<String>) {
+        let mut cli = Self::default();
+        let mut rest = vec![];
+        for arg in args {
+            match arg.as_str() {
+                "--check-auth" => cli.check_auth = true,
+                "--check-providers" => cli.check_providers = true,
+                "--offline" => cli.offline = true,
+                "--rebuild-cache" => cli.rebuild_cache = true,
+                "--dry-run" => cli.dry_run = true,
+                "--dump-state" => cli.dump_state = true,
+                "--list-players" => cli.list_players = true,
+                "--json" => cli.json = true,
+                _ => rest.push(arg),
+            }
+        }
+        (cli, rest)
+    }
+}
@@ -4,7 +4,10 @@ use anyhow::Result;
 use documented::DocumentedFields;
 use toml_edit::{DocumentMut, Item, Table};
 
-use crate::config::{Config, QQMusicConfig, Triggers};
+use crate::config::{
+    Config, PlayerConfig, ProviderConfig, ProvidersConfig, QQMusicConfig, RankingWeights,
+    SubsonicConfig, Triggers,
+};
 
 pub fn append_comments(toml: &str) -> Result<String> {
     let mut new_doc = toml.parse::<DocumentMut>()?;
@@ -34,6 +37,28 @@ fn comment_sub_struct(
                     "qqmusic" => {
                         comment_sub_struct(t, QQMusicConfig::get_field_docs);
                     }
+                    "per-player" => {
+                        for (_, item) in t.iter_mut() {
+                            if let Item::Table(t) = item {
+                                comment_sub_struct(t, PlayerConfig::get_field_docs);
+                            }
+                        }
+                    }
+                    "ranking-weights" => {
+                        comment_sub_struct(t, RankingWeights::get_field_docs);
+                    }
+                    "providers" => {
+                        comment_sub_struct(t, ProvidersConfig::get_field_docs);
+                        for (name, item) in t.iter_mut() {
+                            if let Item::Table(t) = item {
+                                let get_field = match name.get() {
+                                    "subsonic" => SubsonicConfig::get_field_docs,
+                                    _ => ProviderConfig::get_field_docs,
+                                };
+                                comment_sub_struct(t, get_field);
+                            }
+                        }
+                    }
                     _ => (),
                 }
             }
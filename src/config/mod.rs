@@ -1,8 +1,15 @@
+use std::collections::BTreeMap;
+
 use documented::DocumentedFields;
 use serde::{Deserialize, Serialize};
 use strum::{EnumIter, EnumString};
 
-use crate::lyric_providers::{netease::Netease, qqmusic::QQMusicConfig, LyricProvider};
+use crate::lyric_providers::{
+    lrclib::LRCLib,
+    netease::Netease,
+    qqmusic::{QQMusic, QQMusicConfig},
+    LyricProvider,
+};
 
 #[derive(Clone, Copy, Default, EnumIter, strum::Display, EnumString, Serialize, Deserialize)]
 #[strum(serialize_all = "lowercase")]
@@ -14,6 +21,21 @@ pub enum ColorScheme {
     Light,
 }
 
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, strum::Display, EnumString, Serialize, Deserialize,
+)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ColorMode {
+    /// colors from theme
+    #[default]
+    Fixed,
+    /// a color derived from text of the line
+    Random,
+    /// next color of `color-palette` on each line change
+    Cycle,
+}
+
 #[derive(Clone, Copy, Default, EnumIter, strum::Display, EnumString)]
 pub enum Align {
     /// left align
@@ -25,6 +47,99 @@ pub enum Align {
     Fill,
 }
 
+/// How romaji of Japanese lyric lines is shown, with `transliterate-japanese` feature
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RomajiDisplay {
+    /// in a smaller line above the Japanese text
+    Above,
+    /// instead of the Japanese text
+    Replace,
+    #[default]
+    Off,
+}
+
+/// What double tapping lyrics on a touch screen does
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DoubleTapAction {
+    #[default]
+    None,
+    /// open the search window
+    Search,
+    /// reload lyric of current track
+    Reload,
+    /// copy the current line to clipboard
+    Copy,
+}
+
+impl DoubleTapAction {
+    /// detailed name of the action activated
+    pub fn action_name(self) -> Option<&'static str> {
+        match self {
+            Self::None => None,
+            Self::Search => Some("app.search-lyric"),
+            Self::Reload => Some("app.reload-lyric"),
+            Self::Copy => Some("win.copy-current-line"),
+        }
+    }
+}
+
+/// Align of a lyric label set in config, overriding the one chosen in menu
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LabelAlign {
+    /// follow the less specific option, or the menu
+    #[default]
+    Auto,
+    Left,
+    Center,
+    Right,
+}
+
+impl LabelAlign {
+    /// `self`, or `fallback` if it's `Auto`
+    pub fn or(self, fallback: Self) -> Self {
+        match self {
+            Self::Auto => fallback,
+            align => align,
+        }
+    }
+
+    /// `None` for `Auto`
+    pub fn to_gtk(self) -> Option<(gtk::Align, gtk::Justification)> {
+        match self {
+            Self::Auto => None,
+            Self::Left => Some((gtk::Align::Start, gtk::Justification::Left)),
+            Self::Center => Some((gtk::Align::Center, gtk::Justification::Center)),
+            Self::Right => Some((gtk::Align::End, gtk::Justification::Right)),
+        }
+    }
+}
+
+/// Font of `above` and `below` label, the more specific non-empty option wins
+pub fn label_fonts<'a>(
+    global: &'a str,
+    current: &'a str,
+    above: &'a str,
+    below: &'a str,
+) -> [&'a str; 2] {
+    let or = |font: &'a str, fallback: &'a str| if font.is_empty() { fallback } else { font };
+    let current = or(current, global);
+    [or(above, current), or(below, current)]
+}
+
+/// Align of `above` and `below` label, the more specific option wins
+pub fn label_aligns(
+    global: LabelAlign,
+    current: LabelAlign,
+    above: LabelAlign,
+    below: LabelAlign,
+) -> [LabelAlign; 2] {
+    let current = current.or(global);
+    [above.or(current), below.or(current)]
+}
+
 #[derive(Clone, Copy, Default, EnumIter, strum::Display, EnumString)]
 #[strum(serialize_all = "snake_case")]
 pub enum LyricDisplayMode {
@@ -41,6 +156,9 @@ pub struct Config {
     /// the interval waylyrics updates position/metadata from player
     pub player_sync_interval: String,
 
+    /// `player-sync-interval` while running on battery, checked every 30s
+    pub player_sync_interval_battery: String,
+
     /// the interval waylyrics refreshes lyric labels
     pub lyric_update_interval: String,
 
@@ -56,6 +174,45 @@ pub struct Config {
     /// note: persistenced lyric offset depends on this
     pub cache_lyrics: bool,
 
+    /// if enabled, year of the track is part of the lyric cache key when known
+    ///
+    /// note: lyrics cached before enabling this will not be found for tracks with a year
+    pub year_in_cache_key: bool,
+
+    /// if enabled, lyric caches are only loaded if their `.sig` detached GnuPG signature
+    /// is valid by a key in `cache-signing-keyring`, checked by `gpgv`
+    ///
+    /// useful if the cache dir is shared over network; unsigned caches are treated as missing
+    pub verify_cache_signatures: bool,
+
+    /// path to the keyring signatures of lyric caches are checked against
+    pub cache_signing_keyring: String,
+
+    /// key id of gpg to sign newly written lyric caches with, empty to not sign them
+    pub cache_signing_key: String,
+
+    /// if enabled, lyrics provided by the player in `xesam:asText` are cached too
+    ///
+    /// they are not by default, as cached ones would hide updates from the player
+    pub cache_player_lyric: bool,
+
+    /// lyric offset in milliseconds used unless the lyric cache has its own
+    pub lyric_offset_ms: i64,
+
+    /// if enabled, lyrics just fetched are compared with ones of other providers, and a lyric
+    /// all of them find early or late by the same amount is shifted, with the offset cached
+    ///
+    /// `app.disable-auto-offset` turns it off for the current track
+    pub auto_offset_detect: bool,
+
+    /// detected offsets smaller than this are ignored
+    pub auto_offset_threshold_ms: u64,
+
+    /// if enabled, offset persisted in lyric cache is dropped on track change,
+    ///
+    /// restoring `lyric-offset-ms`
+    pub reset_offset_on_track_change: bool,
+
     /// theme to load (<name>.css)
     pub theme: String,
 
@@ -80,8 +237,46 @@ pub struct Config {
     pub filter_regexies: Vec<String>,
 
     /// avaliable options: 网易云音乐, QQ音乐, LRCLib
+    ///
+    /// with `lua-provider` feature, `lua:<name>` for scripts at `providers/<name>.lua` next to this file
     pub lyric_search_source: Vec<String>,
 
+    /// directory of LRC files laid out as `Artist/Album/Title.lrc`, or `Artist - Title.lrc`
+    /// all in the directory itself, empty to disable
+    ///
+    /// tried before `lyric-search-source`, names are also matched ignoring case and symbols
+    pub local_lyrics_dir: String,
+    /// root of the music library, for LRC files kept next to the tracks,
+    /// looked up like `local-lyrics-dir` after it. empty to disable
+    pub local_lrc_root: String,
+
+    /// shell command printing LRC of the track to stdout, empty to disable
+    ///
+    /// `{title}`, `{artist}`, `{album}` and `{duration}` (in seconds) are replaced by quoted
+    /// metadata. queried as `command` provider, first if not in `lyric-search-source`
+    pub custom_lyric_command: String,
+
+    /// how search results are scored against the playing track,
+    ///
+    /// the candidate with highest weighted sum is used
+    pub ranking_weights: RankingWeights,
+
+    /// results whose title and artist similarity to the playing track is below this are discarded,
+    ///
+    /// in `[0, 1]`, `0.0` accepts all
+    pub min_confidence_score: f32,
+
+    /// extra regexies stripped from the title when searching with the raw title finds nothing,
+    /// and in fuzzy search
+    ///
+    /// featured artists, years and version suffixes like ` - Live` are stripped by default
+    pub title_strip_patterns: Vec<String>,
+
+    /// languages to prefer when multiple lyrics are found, e.g. `["zh", "ja", "en"]`
+    ///
+    /// this is a soft rank: if none matches, the first non-empty lyric is used
+    pub language_preference: Vec<String>,
+
     /// if enabled, waylyrics will set `DEFAULT_TEXT` on idle,
     ///
     /// otherwise it just show nothing
@@ -90,10 +285,195 @@ pub struct Config {
     /// if enabled, waylyrics will show lyric on `Pause`
     pub show_lyric_on_pause: bool,
 
+    /// text shown dimmed in the `above` label during instrumental sections,
+    ///
+    /// empty string to disable
+    pub gap_fill_text: String,
+
+    /// seconds without lyric needed to show `gap-fill-text`
+    pub gap_fill_threshold_s: f64,
+
+    /// lyric is blanked if the player sends no update within this many milliseconds,
+    ///
+    /// so that a hung player doesn't leave the last line on screen. 0 to disable
+    pub lyric_line_timeout_ms: u64,
+
+    /// lines lasting shorter than this many milliseconds are not shown,
+    ///
+    /// the line before stays until a longer one starts. 0 to show all lines
+    pub lyric_line_min_duration_ms: u64,
+
+    /// when the player repeats a track, playback going back by more than this many milliseconds
+    /// restarts its lyric instead of fetching it again
+    pub lyric_loop_threshold_ms: u64,
+
+    /// scroll through lyrics without timestamp during playback, 0 to keep them hidden
+    ///
+    /// lines are spread evenly over the track, at this many lines per second if the
+    /// player doesn't tell track length
+    pub no_timestamp_scroll_speed: f64,
+
+    /// use lyric without timestamp if no provider has a synced one, instead of showing nothing
+    ///
+    /// synced lyrics are always preferred
+    pub fallback_to_nosync: bool,
+
+    /// milliseconds a line picked by `random-lyric-line` stays shown,
+    ///
+    /// 0 to keep it until `clear-lyric-override`
+    pub override_duration_ms: u64,
+
+    /// mark lines their provider is unsure about, if it tells how sure it is
+    ///
+    /// with css class `uncertain`, or `low-confidence` below `confidence-warn-threshold`
+    pub show_confidence_indicators: bool,
+
+    /// confidence from 0 to 1 below which a line is marked `low-confidence`
+    pub confidence_warn_threshold: f32,
+
+    /// show lines in the `above` label this many milliseconds before they start,
+    ///
+    /// e.g. to sing along, negative to show them late. timestamps of lyrics are not changed
+    pub lyric_spacing_above_ms: i64,
+
+    /// same as `lyric-spacing-above-ms`, for the `below` label
+    pub lyric_spacing_below_ms: i64,
+
+    /// opacity from 0 to 1 of the `above` label while it shows another line than the current one,
+    ///
+    /// i.e. `lyric-spacing-above-ms` is not 0
+    pub lyrics_above_opacity: f32,
+
+    /// same as `lyrics-above-opacity`, for the `below` label
+    pub lyrics_below_opacity: f32,
+
+    /// pixels to indent the label showing translation by, to tell it apart from the origin
+    pub translation_indent_px: u32,
+
+    /// if enabled, a thin bar under the current line shows how far it has been sung
+    pub show_progress_bar: bool,
+
+    /// append playback position like `[01:23 / 04:56]` to the window title
+    pub show_position_in_title: bool,
+
+    /// show playback position in a small label under the lyric, updated every second
+    pub show_position_label: bool,
+
+    /// show which line is sung like `12 / 48` in a small label at the bottom of the window
+    pub show_line_indicator: bool,
+
+    /// bounce the current line a little when the next line repeats it
+    pub enable_repeat_animation: bool,
+
+    /// if enabled, the current line pulses a little on the beat, if the player sets `xesam:audioBPM`
+    pub animate_to_bpm: bool,
+
+    /// if enabled, font of a lyric line too wide for the window is shrinked to fit
+    pub font_size_auto_fit: bool,
+
+    /// font size in points that `font-size-auto-fit` never shrinks below,
+    ///
+    /// lines still too wide are wrapped
+    pub font_size_min: f64,
+
+    /// lyric lines longer than this many characters are cut at a word and end with `…`,
+    ///
+    /// only when shown, current line is still copied in full. 0 for no limit
+    pub lyrics_max_line_length: usize,
+
+    /// horizontal align of lyric labels -- auto (chosen in menu), left, center or right
+    pub lyrics_align: LabelAlign,
+
+    /// align of labels showing the current line, overrides `lyrics-align`
+    ///
+    /// both labels show the current line (as origin and translation) in this window
+    pub lyrics_align_current: LabelAlign,
+
+    /// align of the `above` label, overrides `lyrics-align-current`
+    pub lyrics_align_above: LabelAlign,
+
+    /// align of the `below` label, overrides `lyrics-align-current`
+    pub lyrics_align_below: LabelAlign,
+
+    /// font of lyric labels as a Pango font description, like `Sans Bold 24`, empty to use the theme's
+    ///
+    /// family or size left out, like size in `Noto Serif Italic`, is kept from the theme
+    pub lyrics_font: String,
+
+    /// font of labels showing the current line, overrides `lyrics-font`
+    pub lyrics_font_current: String,
+
+    /// font of the `above` label, overrides `lyrics-font-current`
+    pub lyrics_font_above: String,
+
+    /// font of the `below` label, overrides `lyrics-font-current`
+    pub lyrics_font_below: String,
+
+    /// weight of the `above` label showing the current line, like `Bold`, empty to keep its font's
+    ///
+    /// only the weight changes, family and size stay those of `lyrics-font-above` or the theme
+    pub lyric_font_weight_current: String,
+
+    /// same as `lyric-font-weight-current`, for the `below` label, e.g. `Normal`
+    pub lyric_font_weight_context: String,
+
+    /// move lyrics down by this percentage of window height, from -100.0 to 100.0,
+    ///
+    /// negative to move them up, e.g. 25.0 centers lyrics at 75% of the height. lyrics are
+    /// kept inside the window
+    pub lyrics_vertical_offset_pct: f32,
+
+    /// what double tapping lyrics on a touch screen does -- none, search, reload or copy
+    pub double_tap_action: DoubleTapAction,
+
+    /// if enabled, the main window snaps to edges and center axes of its monitor when dragged close
+    ///
+    /// only works on Windows, as Wayland compositors and X11 window managers place windows
+    pub snap_to_edge: bool,
+
+    /// distance in pixels `snap-to-edge` starts snapping
+    pub snap_threshold_px: i32,
+
+    /// monitor to show the window on, counting from 0, the first one is used if it's unplugged
+    ///
+    /// only supported on Windows for now. `win.move-to-monitor` moves the window at runtime
+    pub monitor_index: usize,
+
+    /// romaji of lines with kana -- above, replace or off
+    #[cfg(feature = "transliterate-japanese")]
+    pub romaji_display: RomajiDisplay,
+
     /// whether to run tray-icon service
     #[cfg(feature = "tray-icon")]
     pub show_tray_icon: bool,
 
+    /// whether to show current lyric line in an OBS text source, through OBS WebSocket
+    #[cfg(feature = "obs-integration")]
+    pub obs_enabled: bool,
+
+    /// host of OBS WebSocket server
+    #[cfg(feature = "obs-integration")]
+    pub obs_host: String,
+
+    /// port of OBS WebSocket server
+    #[cfg(feature = "obs-integration")]
+    pub obs_port: u16,
+
+    /// password of OBS WebSocket server, leave empty if authentication is disabled
+    #[cfg(feature = "obs-integration")]
+    pub obs_password: String,
+
+    /// name of the Text (GDI+/FreeType 2) source to show lyric in
+    #[cfg(feature = "obs-integration")]
+    pub obs_text_source_name: String,
+
+    /// address to serve `/metrics` on for Prometheus, e.g. `127.0.0.1:9100`, disabled if empty
+    #[cfg(feature = "http-api")]
+    pub http_api_address: String,
+
+    /// `print-debug-state` also writes to this file, only stderr if empty
+    pub debug_state_file: String,
+
     /// player with these name will be ignored
     pub player_name_blacklist: Vec<String>,
 
@@ -120,6 +500,274 @@ pub struct Config {
 
     /// Whether to use <name>-dark.css when system is in dark mode
     pub theme_dark_switch: bool,
+
+    /// if enabled, clicks always pass through the lyric window at startup,
+    ///
+    /// otherwise the state from last run is restored. `win.switch-passthrough` flips it
+    pub click_through: bool,
+
+    /// if disabled, the lyric window starts without title bar,
+    ///
+    /// otherwise the state from last run is restored. `win.switch-decoration` flips it
+    pub window_decorated: bool,
+
+    /// if enabled, the lyric window is kept above other windows, `win.toggle-always-on-top` flips it
+    ///
+    /// note: only supported on Windows, use a window rule of your compositor elsewhere
+    pub always_on_top: bool,
+
+    /// opacity of the lyric window, from 0.0 (invisible) to 1.0 (opaque),
+    ///
+    /// also written by `win.set-opacity`, `win.decrease-opacity` and `win.increase-opacity`
+    pub window_opacity: f64,
+
+    /// how much `win.decrease-opacity` and `win.increase-opacity` change opacity
+    pub opacity_step: f64,
+
+    /// CSS color of lyric text shadow, e.g. `rgba(0, 0, 0, 0.8)`, empty to disable
+    ///
+    /// painted by waylyrics, as CSS `text-shadow` is not respected everywhere
+    pub shadow_color: String,
+
+    /// horizontal offset of lyric text shadow in pixels
+    pub shadow_offset_x: f32,
+
+    /// vertical offset of lyric text shadow in pixels
+    pub shadow_offset_y: f32,
+
+    /// blur radius of lyric text shadow in pixels
+    pub shadow_blur: f32,
+
+    /// width of lyric text outline in pixels, fractional values are fine. 0 to disable
+    ///
+    /// keeps lyrics readable on backgrounds with colors close to the text
+    pub text_outline_width: f32,
+
+    /// CSS color of lyric text outline, e.g. `black`
+    pub text_outline_color: String,
+
+    /// CSS colors of a horizontal gradient filling labels of the current line, empty to disable
+    ///
+    /// e.g. `["#ff0000", "#0000ff"]`, replaces the theme color and `color-mode`
+    pub lyrics_color_current_gradient: Vec<String>,
+
+    /// CSS color of a rounded rectangle behind the current line, empty to disable
+    ///
+    /// e.g. `rgba(255, 255, 255, 0.2)`, it's never shown behind the `below` label
+    pub lyrics_background_color: String,
+
+    /// corner radius of `lyrics-background-color` in pixels, large values make it pill-shaped
+    pub lyrics_background_radius: u32,
+
+    /// space between lyric labels in logical pixels, scaled along with the display
+    pub lyric_spacing_px: u32,
+
+    /// extra space over the current line in logical pixels, added to `lyric-spacing-px`
+    pub lyric_spacing_above_px: u32,
+
+    /// extra space between the current line and the `below` label in logical pixels,
+    /// added to `lyric-spacing-px`
+    pub lyric_spacing_below_px: u32,
+
+    /// how lyric labels are colored -- fixed (by theme), random or cycle
+    ///
+    /// labels without lyric, like the gap filler, always use the theme color
+    pub color_mode: ColorMode,
+
+    /// CSS colors used by `color-mode = "cycle"`
+    pub color_palette: Vec<String>,
+
+    /// if enabled, waylyrics only loads lyrics from cache and local files,
+    ///
+    /// an `--offline` flag does the same
+    pub offline_mode: bool,
+
+    /// per-provider settings
+    ///
+    /// example: `[providers.lrclib]` with `api-key = "..."`
+    pub providers: ProvidersConfig,
+
+    /// settings overridden for players whose identity starts with the key
+    ///
+    /// example: `[per-player."Spotify"]` with `font-size = 32`
+    #[serde(alias = "per_player")]
+    pub per_player: BTreeMap<String, PlayerConfig>,
+
+    /// providers of `lyric-search-source` searched first for tracks whose `xesam:genre` matches the key
+    ///
+    /// keys are matched ignoring case, with `*` and `?` as wildcards.
+    /// the rest of `lyric-search-source` is searched if they found nothing.
+    /// example: `[provider-genre-preference]` with `"*classical*" = ["LRCLib"]`
+    pub provider_genre_preference: BTreeMap<String, Vec<String>>,
+
+    /// search `preferred-providers-for-rated` first for tracks rated 5 stars,
+    /// that is `xesam:userRating` of at least 0.8
+    pub boost_rated_tracks: bool,
+
+    /// providers of `lyric-search-source` searched first for highly rated tracks,
+    /// before those of `provider-genre-preference`. see `boost-rated-tracks`
+    pub preferred_providers_for_rated: Vec<String>,
+
+    /// regex replacements applied to each lyric line, like watermarks added by providers
+    ///
+    /// example: `[[lyric-filters]]` with `pattern = "^Lyrics provided by .*"` and `drop-line = true`
+    #[serde(alias = "lyric_filters")]
+    pub lyric_filters: Vec<LyricFilter>,
+
+    /// providers listed in `lyric-search-source` by the name of their group, searched as a unit
+    ///
+    /// units of `lyric-search-source` are searched in order until one finds lyric,
+    /// providers listed next to each other outside groups make up one unit.
+    /// example: `[[provider-group]]` with `name = "local"` and `members = ["Subsonic", "LRCLib"]`
+    #[serde(alias = "provider_group")]
+    pub provider_group: Vec<ProviderGroup>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize, DocumentedFields)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct ProviderGroup {
+    /// listed in `lyric-search-source` in place of the members
+    pub name: String,
+    /// providers in the group, same options as `lyric-search-source`
+    pub members: Vec<String>,
+    /// if enabled, members are tried one by one in order, instead of all at once
+    pub sequential: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize, DocumentedFields)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct LyricFilter {
+    /// regex matched against each line of origin and translation
+    pub pattern: String,
+    /// replaces matches, `$1` or `${name}` refers to capture groups
+    pub replacement: String,
+    /// if enabled, matching lines are removed instead
+    #[serde(alias = "drop_line")]
+    pub drop_line: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize, DocumentedFields)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct PlayerConfig {
+    /// font size of lyrics in pt, overrides the theme
+    pub font_size: Option<f64>,
+    /// offset added to every lyric played by this player
+    pub lyric_offset_ms: Option<i64>,
+    /// providers to search, same options as `lyric-search-source`
+    ///
+    /// only providers enabled in `lyric-search-source` are available
+    pub providers: Option<Vec<String>>,
+    /// overrides `cache-lyrics`
+    pub cache_lyrics: Option<bool>,
+}
+
+#[derive(Default, Deserialize, Serialize, DocumentedFields)]
+#[serde(default)]
+pub struct ProvidersConfig {
+    /// settings for 网易云音乐
+    pub netease: ProviderConfig,
+    /// settings for QQ音乐
+    pub qqmusic: ProviderConfig,
+    /// settings for LRCLib
+    pub lrclib: ProviderConfig,
+    /// settings for AcoustID, which identifies tracks without metadata by audio fingerprint
+    ///
+    /// used with `acoustid` feature, get an api key at https://acoustid.org/new-application
+    pub acoustid: ProviderConfig,
+    /// Subsonic compatible server like Navidrome to fetch lyrics from, listed as `Subsonic`
+    ///
+    /// used with `subsonic-provider` feature
+    pub subsonic: SubsonicConfig,
+}
+
+impl ProvidersConfig {
+    /// get settings by `LyricProvider::unique_name`
+    pub fn get(&self, unique_name: &str) -> Option<&ProviderConfig> {
+        match unique_name {
+            n if n == Netease.unique_name() => Some(&self.netease),
+            n if n == QQMusic.unique_name() => Some(&self.qqmusic),
+            n if n == LRCLib.unique_name() => Some(&self.lrclib),
+            _ => None,
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, &ProviderConfig)> {
+        [
+            (Netease.unique_name(), &self.netease),
+            (QQMusic.unique_name(), &self.qqmusic),
+            (LRCLib.unique_name(), &self.lrclib),
+            // not a lyric provider, listed for its api key
+            ("AcoustID", &self.acoustid),
+        ]
+        .into_iter()
+    }
+}
+
+#[derive(Clone, Deserialize, Serialize, DocumentedFields)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct ProviderConfig {
+    /// API key or Bearer token, sent as `Authorization: Bearer <api-key>`
+    ///
+    /// note: 网易云音乐 is accessed through ncmapi, which does not accept custom headers,
+    /// so this key is currently unused for it
+    pub api_key: Option<String>,
+
+    /// how many times to retry on timeout, HTTP 429 or 5xx
+    pub max_retries: u32,
+
+    /// delay before the first retry, doubled on each retry
+    ///
+    /// `Retry-After` from HTTP 429 takes precedence
+    pub retry_base_delay: String,
+
+    /// after 5 consecutive failures, skip this provider for `circuit-cool-down`
+    pub circuit_cool_down: String,
+}
+
+#[derive(Clone, Default, Deserialize, Serialize, DocumentedFields)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct SubsonicConfig {
+    /// address of the server, e.g. `https://music.example.com`, empty to disable
+    pub url: String,
+    /// user to log in as
+    pub username: String,
+    /// only sent as a salted MD5 token
+    pub password: String,
+}
+
+impl Default for ProviderConfig {
+    fn default() -> Self {
+        Self {
+            api_key: None,
+            max_retries: 3,
+            retry_base_delay: "500ms".into(),
+            circuit_cool_down: "60s".into(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize, DocumentedFields)]
+#[serde(default)]
+pub struct RankingWeights {
+    /// weight of title similarity (by Levenshtein distance), from 0 to 1
+    pub title: f64,
+    /// weight of artist similarity (by Levenshtein distance), from 0 to 1
+    pub artist: f64,
+    /// weight of duration similarity, from 1 (same length) to 0 (10s or more apart)
+    pub duration: f64,
+    /// weight of an exact album match
+    pub album: f64,
+}
+
+impl Default for RankingWeights {
+    fn default() -> Self {
+        Self {
+            title: 1.0,
+            artist: 0.5,
+            duration: 1.0,
+            album: 0.3,
+        }
+    }
 }
 
 /// check [GTK+'s official document](https://docs.gtk.org/gtk4/ctor.ShortcutTrigger.parse_string.html) for trigger format
@@ -130,12 +778,22 @@ pub struct Triggers {
     pub switch_decoration: String,
     /// reapply current theme file
     pub reload_theme: String,
+    /// read config file again and apply changes
+    pub reload_config: String,
+    /// write settings changed at runtime back to config file
+    pub save_config: String,
+    /// print state for bug reports to stderr, no shortcut if empty
+    pub print_debug_state: String,
     /// manually search lyric
     pub search_lyric: String,
     /// try to refetch lyric
     pub refetch_lyric: String,
     /// whether to allow mouse click-through
     pub switch_passthrough: String,
+    /// make the window more transparent by `opacity-step`
+    pub decrease_opacity: String,
+    /// make the window less transparent by `opacity-step`
+    pub increase_opacity: String,
 }
 
 impl Default for Triggers {
@@ -143,9 +801,43 @@ impl Default for Triggers {
         Self {
             switch_decoration: "<Control>d".into(),
             reload_theme: "<Control><Shift>t".into(),
+            reload_config: "<Control><Shift>r".into(),
+            save_config: "<Control><Shift>s".into(),
+            print_debug_state: String::new(),
             search_lyric: "<Control>s".into(),
             refetch_lyric: "<Alt><Shift>l".into(),
             switch_passthrough: "<Alt>p".into(),
+            decrease_opacity: "<Alt>minus".into(),
+            increase_opacity: "<Alt>equal".into(),
+        }
+    }
+}
+
+/// milliseconds overriding `lyric-line-timeout-ms`, for tests driving a mock player
+pub const LYRIC_TIMEOUT_ENV: &str = "WAYLYRICS_LYRIC_TIMEOUT_MS";
+/// milliseconds overriding `player-sync-interval`
+pub const REFRESH_INTERVAL_ENV: &str = "WAYLYRICS_REFRESH_INTERVAL_MS";
+
+impl Config {
+    /// Override fields by `LYRIC_TIMEOUT_ENV` and `REFRESH_INTERVAL_ENV`, looked up by `var`.
+    ///
+    /// Invalid values are ignored with a warning. Never written back to the config file.
+    pub fn apply_env_overrides(&mut self, var: impl Fn(&str) -> Option<String>) {
+        let millis = |name: &str| {
+            let value = var(name)?;
+            value
+                .trim()
+                .parse::<u64>()
+                .inspect_err(|e| crate::log::warn!("invalid {name} `{value}`: {e}, ignored"))
+                .ok()
+        };
+        if let Some(timeout) = millis(LYRIC_TIMEOUT_ENV) {
+            crate::log::info!("lyric-line-timeout-ms set to {timeout} by {LYRIC_TIMEOUT_ENV}");
+            self.lyric_line_timeout_ms = timeout;
+        }
+        if let Some(interval) = millis(REFRESH_INTERVAL_ENV) {
+            crate::log::info!("player-sync-interval set to {interval}ms by {REFRESH_INTERVAL_ENV}");
+            self.player_sync_interval = format!("{interval}ms");
         }
     }
 }
@@ -154,18 +846,89 @@ impl Default for Config {
     fn default() -> Self {
         Self {
             player_sync_interval: "2s".to_owned(),
+            player_sync_interval_battery: "5s".to_owned(),
             lyric_update_interval: "20ms".to_owned(),
             length_toleration: "2s".to_owned(),
             theme: "default".into(),
             cache_lyrics: true,
+            year_in_cache_key: false,
+            verify_cache_signatures: false,
+            cache_signing_keyring: String::new(),
+            cache_signing_key: String::new(),
+            cache_player_lyric: false,
+            lyric_offset_ms: 0,
+            auto_offset_detect: false,
+            auto_offset_threshold_ms: 300,
+            reset_offset_on_track_change: false,
             enable_filter_regex: false,
             enable_local_lyric: true,
             filter_regexies: default_filter_regexies(),
             lyric_search_source: vec![Netease.unique_name().into()],
+            local_lyrics_dir: String::new(),
+            local_lrc_root: String::new(),
+            custom_lyric_command: String::new(),
+            ranking_weights: RankingWeights::default(),
+            min_confidence_score: 0.,
+            title_strip_patterns: vec![],
+            language_preference: vec![],
             show_default_text_on_idle: true,
             show_lyric_on_pause: true,
+            gap_fill_text: "♪".into(),
+            gap_fill_threshold_s: 5.,
+            lyric_line_timeout_ms: 5000,
+            lyric_line_min_duration_ms: 0,
+            lyric_loop_threshold_ms: 500,
+            no_timestamp_scroll_speed: 0.,
+            fallback_to_nosync: false,
+            override_duration_ms: 5000,
+            show_confidence_indicators: false,
+            confidence_warn_threshold: 0.5,
+            lyric_spacing_above_ms: 0,
+            lyric_spacing_below_ms: 0,
+            lyrics_above_opacity: 0.5,
+            lyrics_below_opacity: 0.5,
+            translation_indent_px: 0,
+            show_progress_bar: false,
+            show_position_in_title: false,
+            show_position_label: false,
+            show_line_indicator: false,
+            enable_repeat_animation: false,
+            animate_to_bpm: false,
+            font_size_auto_fit: false,
+            font_size_min: 12.,
+            lyrics_max_line_length: 0,
+            lyrics_align: LabelAlign::Auto,
+            lyrics_align_current: LabelAlign::Auto,
+            lyrics_align_above: LabelAlign::Auto,
+            lyrics_align_below: LabelAlign::Auto,
+            lyrics_font: String::new(),
+            lyrics_font_current: String::new(),
+            lyrics_font_above: String::new(),
+            lyrics_font_below: String::new(),
+            lyric_font_weight_current: String::new(),
+            lyric_font_weight_context: String::new(),
+            lyrics_vertical_offset_pct: 0.,
+            double_tap_action: DoubleTapAction::None,
+            snap_to_edge: false,
+            snap_threshold_px: 20,
+            monitor_index: 0,
+            #[cfg(feature = "transliterate-japanese")]
+            romaji_display: RomajiDisplay::Off,
             #[cfg(feature = "tray-icon")]
             show_tray_icon: true,
+            #[cfg(feature = "obs-integration")]
+            obs_enabled: false,
+            #[cfg(feature = "obs-integration")]
+            obs_host: "127.0.0.1".into(),
+            #[cfg(feature = "obs-integration")]
+            obs_port: 4455,
+            #[cfg(feature = "obs-integration")]
+            obs_password: String::new(),
+            #[cfg(feature = "obs-integration")]
+            obs_text_source_name: "Lyrics".into(),
+            #[cfg(feature = "http-api")]
+            http_api_address: String::new(),
+            debug_state_file: String::new(),
             player_name_blacklist: vec!["firefox".into()],
             player_identity_blacklist: vec![],
             extract_translated_lyric: true,
@@ -173,6 +936,33 @@ impl Default for Config {
             qqmusic: QQMusicConfig::default(),
             color_scheme: ColorScheme::default(),
             theme_dark_switch: false,
+            click_through: false,
+            always_on_top: false,
+            window_decorated: true,
+            window_opacity: 1.,
+            opacity_step: 0.1,
+            shadow_color: String::new(),
+            shadow_offset_x: 2.,
+            shadow_offset_y: 2.,
+            shadow_blur: 2.,
+            text_outline_width: 0.,
+            text_outline_color: "black".into(),
+            lyrics_color_current_gradient: vec![],
+            lyrics_background_color: String::new(),
+            lyrics_background_radius: 12,
+            lyric_spacing_px: 0,
+            lyric_spacing_above_px: 0,
+            lyric_spacing_below_px: 0,
+            color_mode: ColorMode::default(),
+            color_palette: default_color_palette(),
+            offline_mode: false,
+            providers: ProvidersConfig::default(),
+            per_player: BTreeMap::new(),
+            provider_genre_preference: BTreeMap::new(),
+            boost_rated_tracks: false,
+            preferred_providers_for_rated: vec![],
+            lyric_filters: vec![],
+            provider_group: vec![],
         }
     }
 }
@@ -188,6 +978,14 @@ impl From<Align> for gtk::Align {
     }
 }
 
+fn default_color_palette() -> Vec<String> {
+    [
+        "#ff6b6b", "#feca57", "#48dbfb", "#1dd1a1", "#a29bfe", "#ff9ff3",
+    ]
+    .map(String::from)
+    .to_vec()
+}
+
 #[rustfmt::skip]
 fn default_filter_regexies() -> Vec<String> {
     [
@@ -200,4 +998,6 @@ fn default_filter_regexies() -> Vec<String> {
 }
 
 mod merge;
+pub mod persist;
+pub mod watcher;
 pub use merge::append_comments;
@@ -0,0 +1,54 @@
+//! Write values changed at runtime back to the config file, keeping its comments
+
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use anyhow::{anyhow, Result};
+use toml_edit::DocumentMut;
+
+static CONFIG_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+pub fn set_config_path(config_path: PathBuf) {
+    let _ = CONFIG_PATH.set(config_path);
+}
+
+/// `config` with top-level `key` set to `value`, formatting of other keys untouched
+pub fn with_config_value(
+    config: &str,
+    key: &str,
+    value: impl Into<toml_edit::Value>,
+) -> Result<String> {
+    with_config_values(config, vec![(key, value.into())])
+}
+
+/// `config` with each top-level key in `values` set, formatting of other keys untouched
+pub fn with_config_values(config: &str, values: Vec<(&str, toml_edit::Value)>) -> Result<String> {
+    let mut doc = config.parse::<DocumentMut>()?;
+    for (key, value) in values {
+        match doc.get_mut(key).and_then(|item| item.as_value_mut()) {
+            // keep the doc comment above the key
+            Some(old) => {
+                let decor = old.decor().clone();
+                *old = value;
+                *old.decor_mut() = decor;
+            }
+            None => doc[key] = toml_edit::value(value),
+        }
+    }
+    Ok(doc.to_string())
+}
+
+/// Set top-level `key` to `value` in the config file
+pub fn persist_config_value(key: &str, value: impl Into<toml_edit::Value>) -> Result<()> {
+    persist_config_values(vec![(key, value.into())])
+}
+
+/// Set each top-level key in `values` in the config file, all at once
+pub fn persist_config_values(values: Vec<(&str, toml_edit::Value)>) -> Result<()> {
+    let config_path = CONFIG_PATH
+        .get()
+        .ok_or_else(|| anyhow!("config path is not set"))?;
+    let config = std::fs::read_to_string(config_path)?;
+    std::fs::write(config_path, with_config_values(&config, values)?)?;
+    Ok(())
+}
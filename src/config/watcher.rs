@@ -0,0 +1,524 @@
+//! Reload config when the config file changes, or when asked to by `reload-config`.
+
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use gtk::glib::{self, WeakRef};
+use gtk::subclass::prelude::*;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use regex::RegexSet;
+
+use crate::app::{
+    self,
+    dialog::show_dialog,
+    font_fit::reset_fit,
+    shadow::{Background, Gradient, Outline, Shadow},
+};
+use crate::config::{label_aligns, label_fonts, Config};
+use crate::log::{error, info};
+use crate::sync::lyric::color::set_color_mode;
+use crate::sync::per_player::reload_per_player;
+use crate::utils::{gettext, load_theme, parse_time};
+use crate::{EXCLUDED_REGEXES, PLAYER_IDENTITY_BLACKLIST, PLAYER_NAME_BLACKLIST};
+
+/// wait for editors to finish writing before reading the file
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Config applied to the main window, and where it was read from
+struct LoadedConfig {
+    config_path: PathBuf,
+    theme_dir: PathBuf,
+    current: toml::Table,
+}
+
+thread_local! {
+    /// the watcher stops when dropped
+    static CONFIG_WATCHER: RefCell<Option<RecommendedWatcher>> = const { RefCell::new(None) };
+    static LOADED_CONFIG: RefCell<Option<LoadedConfig>> = const { RefCell::new(None) };
+}
+
+/// Config as last read from the config file, `None` before `watch_config`
+pub fn loaded_config() -> Option<toml::Table> {
+    LOADED_CONFIG.with_borrow(|loaded| loaded.as_ref().map(|loaded| loaded.current.clone()))
+}
+
+/// Keys found changed by `reload_config`
+#[derive(Debug, Default)]
+pub struct Reloaded {
+    pub changed: Vec<String>,
+    /// changed but cannot be applied without restarting
+    pub restart: Vec<String>,
+}
+
+/// Read the config file again and apply keys changed since it was last read to `window`.
+///
+/// Fails if the file cannot be parsed, leaving the running config untouched.
+pub fn reload_config(window: &app::Window) -> Result<Reloaded> {
+    let (config_path, theme_dir, current) = LOADED_CONFIG
+        .with_borrow(|loaded| {
+            loaded.as_ref().map(|loaded| {
+                (
+                    loaded.config_path.clone(),
+                    loaded.theme_dir.clone(),
+                    loaded.current.clone(),
+                )
+            })
+        })
+        .ok_or_else(|| anyhow!("config was never loaded"))?;
+
+    let config = read_config(&config_path)?;
+    let new = toml::Table::try_from(&config)?;
+    let changed = changed_keys(&current, &new);
+    if changed.is_empty() {
+        return Ok(Reloaded::default());
+    }
+    info!("config changed: {changed:?}");
+    let restart = apply_config(window, &config, &changed, &theme_dir);
+    LOADED_CONFIG.with_borrow_mut(|loaded| {
+        if let Some(loaded) = loaded {
+            loaded.current = new;
+        }
+    });
+    Ok(Reloaded { changed, restart })
+}
+
+/// Watch `config_path` and apply changes to `window`.
+///
+/// Should be called right after the config was loaded.
+pub fn watch_config(
+    config_path: PathBuf,
+    theme_dir: PathBuf,
+    window: WeakRef<app::Window>,
+) -> notify::Result<()> {
+    let current = read_config(&config_path)
+        .ok()
+        .and_then(|config| toml::Table::try_from(&config).ok())
+        .unwrap_or_default();
+    LOADED_CONFIG.set(Some(LoadedConfig {
+        config_path: config_path.clone(),
+        theme_dir,
+        current,
+    }));
+
+    let (tx, rx) = async_channel::unbounded();
+
+    let watched = config_path.clone();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let Ok(event) = event else {
+            return;
+        };
+        // editors might replace the file instead of writing to it
+        if matches!(event.kind, EventKind::Modify(_) | EventKind::Create(_))
+            && event.paths.iter().any(|p| p == &watched)
+        {
+            let _ = tx.send_blocking(());
+        }
+    })?;
+    // watch the parent, or we would lose the file after it was replaced
+    let config_dir = config_path.parent().unwrap_or(Path::new("."));
+    watcher.watch(config_dir, RecursiveMode::NonRecursive)?;
+
+    CONFIG_WATCHER.set(Some(watcher));
+
+    glib::spawn_future_local(async move {
+        while rx.recv().await.is_ok() {
+            glib::timeout_future(DEBOUNCE).await;
+            while rx.try_recv().is_ok() {}
+
+            let Some(window) = window.upgrade() else {
+                break;
+            };
+            match reload_config(&window) {
+                Ok(Reloaded { restart, .. }) if !restart.is_empty() => {
+                    show_dialog(
+                        Some(&window),
+                        &format!(
+                            "{}\n{}",
+                            gettext("Restart waylyrics to apply these changes:"),
+                            restart.join(", ")
+                        ),
+                        gtk::MessageType::Info,
+                    );
+                }
+                Ok(_) => (),
+                Err(e) => error!("cannot reload config: {e}"),
+            }
+        }
+    });
+
+    Ok(())
+}
+
+fn read_config(config_path: &Path) -> Result<Config> {
+    let config = std::fs::read_to_string(config_path)?;
+    let mut config: Config = toml_edit::de::from_str(&config)?;
+    config.apply_env_overrides(|name| std::env::var(name).ok());
+    Ok(config)
+}
+
+/// Top-level keys differing between `old` and `new`
+pub fn changed_keys(old: &toml::Table, new: &toml::Table) -> Vec<String> {
+    let mut keys: Vec<String> = new
+        .iter()
+        .filter(|(k, v)| old.get(*k) != Some(v))
+        .map(|(k, _)| k.clone())
+        .chain(old.keys().filter(|k| !new.contains_key(*k)).cloned())
+        .collect();
+    keys.sort();
+    keys
+}
+
+/// Apply `changed` keys of `config` to `window`, returns keys requiring restart
+fn apply_config(
+    window: &app::Window,
+    config: &Config,
+    changed: &[String],
+    theme_dir: &Path,
+) -> Vec<String> {
+    let imp = window.imp();
+    let mut restart = vec![];
+
+    for key in changed {
+        let applied = match key.as_str() {
+            "theme" => load_theme(theme_dir, &config.theme)
+                .inspect_err(|e| error!("cannot load theme: {e}"))
+                .is_ok(),
+            // overridden by player configs, which are applied again
+            "cache-lyrics" | "per-player" => {
+                reload_per_player(config.per_player.clone(), config.cache_lyrics);
+                true
+            }
+            // takes effect from next track
+            "lyric-offset-ms" => {
+                imp.default_lyric_offset_ms.set(config.lyric_offset_ms);
+                true
+            }
+            "reset-offset-on-track-change" => {
+                imp.reset_offset_on_track_change
+                    .set(config.reset_offset_on_track_change);
+                true
+            }
+            "length-toleration" => match parse_time(&config.length_toleration) {
+                Ok(toleration) => {
+                    imp.length_toleration_ms.set(toleration.as_millis());
+                    true
+                }
+                Err(e) => {
+                    error!("invalid length-toleration: {e}");
+                    false
+                }
+            },
+            "show-default-text-on-idle" => {
+                imp.show_default_text_on_idle
+                    .set(config.show_default_text_on_idle);
+                true
+            }
+            "show-lyric-on-pause" => {
+                imp.show_lyric_on_pause.set(config.show_lyric_on_pause);
+                true
+            }
+            "lyric-line-timeout-ms" => {
+                imp.lyric_line_timeout
+                    .set(Duration::from_millis(config.lyric_line_timeout_ms));
+                true
+            }
+            "lyric-line-min-duration-ms" => {
+                imp.lyric_line_min_duration
+                    .set(Duration::from_millis(config.lyric_line_min_duration_ms));
+                true
+            }
+            "lyric-loop-threshold-ms" => {
+                crate::sync::set_loop_threshold(Duration::from_millis(
+                    config.lyric_loop_threshold_ms,
+                ));
+                true
+            }
+            "no-timestamp-scroll-speed" => {
+                imp.no_timestamp_scroll_speed
+                    .set(config.no_timestamp_scroll_speed);
+                true
+            }
+            "fallback-to-nosync" => {
+                crate::sync::lyric::fetch::set_fallback_to_nosync(config.fallback_to_nosync);
+                true
+            }
+            "override-duration-ms" => {
+                imp.override_duration
+                    .set(Duration::from_millis(config.override_duration_ms));
+                true
+            }
+            "show-confidence-indicators" => {
+                imp.show_confidence_indicators
+                    .set(config.show_confidence_indicators);
+                true
+            }
+            "confidence-warn-threshold" => {
+                imp.confidence_warn_threshold
+                    .set(config.confidence_warn_threshold);
+                true
+            }
+            "lyric-spacing-above-ms" | "lyric-spacing-below-ms" => {
+                imp.lyric_spacing_ms
+                    .set([config.lyric_spacing_above_ms, config.lyric_spacing_below_ms]);
+                true
+            }
+            "lyrics-above-opacity" | "lyrics-below-opacity" => {
+                imp.context_opacity
+                    .set([config.lyrics_above_opacity, config.lyrics_below_opacity]);
+                true
+            }
+            "translation-indent-px" => {
+                imp.translation_indent_px.set(config.translation_indent_px);
+                true
+            }
+            "debug-state-file" => {
+                imp.debug_state_file
+                    .replace(config.debug_state_file.clone());
+                true
+            }
+            "gap-fill-text" => {
+                imp.gap_fill_text.replace(config.gap_fill_text.clone());
+                true
+            }
+            "gap-fill-threshold-s" => {
+                match Duration::try_from_secs_f64(config.gap_fill_threshold_s) {
+                    Ok(threshold) => {
+                        imp.gap_fill_threshold.set(threshold);
+                        true
+                    }
+                    Err(e) => {
+                        error!("invalid gap-fill-threshold-s: {e}");
+                        false
+                    }
+                }
+            }
+            "show-progress-bar" => {
+                app::set_progress_bar_visible(window, config.show_progress_bar);
+                true
+            }
+            "show-line-indicator" => {
+                app::line_indicator::set_line_indicator_visible(window, config.show_line_indicator);
+                true
+            }
+            "animate-to-bpm" => {
+                app::bpm_pulse::set_animate_to_bpm(window, config.animate_to_bpm);
+                true
+            }
+            "enable-repeat-animation" => {
+                app::repeat_animation::set_repeat_animation(window, config.enable_repeat_animation);
+                true
+            }
+            "show-position-in-title" | "show-position-label" => {
+                app::position::set_position_display(
+                    window,
+                    config.show_position_in_title,
+                    config.show_position_label,
+                );
+                true
+            }
+            "color-mode" | "color-palette" => {
+                set_color_mode(config.color_mode, config.color_palette.clone());
+                true
+            }
+            "shadow-color" | "shadow-offset-x" | "shadow-offset-y" | "shadow-blur" => {
+                match Shadow::new(
+                    config.shadow_offset_x,
+                    config.shadow_offset_y,
+                    config.shadow_blur,
+                    &config.shadow_color,
+                ) {
+                    Ok(shadow) => {
+                        app::set_lyric_shadow(window, shadow);
+                        true
+                    }
+                    Err(e) => {
+                        error!("invalid shadow-color: {e}");
+                        false
+                    }
+                }
+            }
+            "lyrics-color-current-gradient" => {
+                match Gradient::new(&config.lyrics_color_current_gradient) {
+                    Ok(gradient) => {
+                        app::set_lyric_gradient(window, gradient);
+                        true
+                    }
+                    Err(e) => {
+                        error!("invalid lyrics-color-current-gradient: {e}");
+                        false
+                    }
+                }
+            }
+            "lyric-spacing-px" | "lyric-spacing-above-px" | "lyric-spacing-below-px" => {
+                app::set_lyric_spacing(
+                    window,
+                    config.lyric_spacing_px,
+                    config.lyric_spacing_above_px,
+                    config.lyric_spacing_below_px,
+                );
+                true
+            }
+            "lyrics-background-color" | "lyrics-background-radius" => {
+                match Background::new(
+                    &config.lyrics_background_color,
+                    config.lyrics_background_radius,
+                ) {
+                    Ok(background) => {
+                        app::set_lyric_background(window, background);
+                        true
+                    }
+                    Err(e) => {
+                        error!("invalid lyrics-background-color: {e}");
+                        false
+                    }
+                }
+            }
+            "text-outline-width" | "text-outline-color" => {
+                match Outline::new(config.text_outline_width, &config.text_outline_color) {
+                    Ok(outline) => {
+                        app::set_lyric_outline(window, outline);
+                        true
+                    }
+                    Err(e) => {
+                        error!("invalid text-outline-color: {e}");
+                        false
+                    }
+                }
+            }
+            "font-size-auto-fit" | "font-size-min" => {
+                imp.font_size_auto_fit.set(config.font_size_auto_fit);
+                imp.font_size_min.set(config.font_size_min);
+                let labels = ["above", "below"].map(|position| app::get_label(window, position));
+                reset_fit(&labels);
+                true
+            }
+            "lyrics-max-line-length" => {
+                imp.lyrics_max_line_length
+                    .set(config.lyrics_max_line_length);
+                true
+            }
+            "lyrics-align"
+            | "lyrics-align-current"
+            | "lyrics-align-above"
+            | "lyrics-align-below" => {
+                let aligns = label_aligns(
+                    config.lyrics_align,
+                    config.lyrics_align_current,
+                    config.lyrics_align_above,
+                    config.lyrics_align_below,
+                );
+                app::set_label_aligns(window, aligns);
+                true
+            }
+            "lyrics-font"
+            | "lyrics-font-current"
+            | "lyrics-font-above"
+            | "lyrics-font-below"
+            | "lyric-font-weight-current"
+            | "lyric-font-weight-context" => {
+                let fonts = label_fonts(
+                    &config.lyrics_font,
+                    &config.lyrics_font_current,
+                    &config.lyrics_font_above,
+                    &config.lyrics_font_below,
+                );
+                let weights = [
+                    &config.lyric_font_weight_current,
+                    &config.lyric_font_weight_context,
+                ];
+                match (
+                    fonts.map(app::fonts::parse_font),
+                    weights.map(|weight| app::fonts::parse_weight(weight)),
+                ) {
+                    ([Ok(above), Ok(below)], [Ok(above_weight), Ok(below_weight)]) => {
+                        app::fonts::set_label_fonts([
+                            app::fonts::with_weight(above, above_weight),
+                            app::fonts::with_weight(below, below_weight),
+                        ]);
+                        true
+                    }
+                    ([Err(e), _] | [_, Err(e)], _) | (_, [Err(e), _] | [_, Err(e)]) => {
+                        error!("cannot set lyric fonts: {e}");
+                        false
+                    }
+                }
+            }
+            #[cfg(feature = "transliterate-japanese")]
+            "romaji-display" => {
+                imp.romaji_display.set(config.romaji_display);
+                true
+            }
+            "click-through" => {
+                app::input_region::set_click_through(window, config.click_through);
+                true
+            }
+            "always-on-top" => {
+                app::stacking::set_always_on_top(window, config.always_on_top);
+                true
+            }
+            "window-decorated" => {
+                gtk::prelude::GtkWindowExt::set_decorated(window, config.window_decorated);
+                true
+            }
+            "lyrics-vertical-offset-pct" => {
+                app::vertical_offset::set_lyrics_vertical_offset(
+                    window,
+                    config.lyrics_vertical_offset_pct,
+                );
+                true
+            }
+            "double-tap-action" => {
+                imp.double_tap_action.set(config.double_tap_action);
+                true
+            }
+            "monitor-index" => {
+                app::monitor::move_to_monitor(window, config.monitor_index);
+                true
+            }
+            "window-opacity" => {
+                app::set_window_opacity(window, config.window_opacity);
+                true
+            }
+            "opacity-step" => {
+                imp.opacity_step.set(config.opacity_step);
+                true
+            }
+            "offline-mode" => {
+                app::set_offline_mode(window, config.offline_mode);
+                true
+            }
+            // labels only check regexies if it was enabled at startup
+            "filter-regexies" if config.enable_filter_regex => {
+                match RegexSet::new(&config.filter_regexies) {
+                    Ok(regexies) => {
+                        EXCLUDED_REGEXES.set(regexies);
+                        true
+                    }
+                    Err(e) => {
+                        error!("invalid filter-regexies: {e}");
+                        false
+                    }
+                }
+            }
+            "player-name-blacklist" => {
+                PLAYER_NAME_BLACKLIST.set(config.player_name_blacklist.clone());
+                true
+            }
+            "player-identity-blacklist" => {
+                PLAYER_IDENTITY_BLACKLIST.set(config.player_identity_blacklist.clone());
+                true
+            }
+            _ => {
+                restart.push(key.clone());
+                continue;
+            }
+        };
+        if applied {
+            info!("applied {key}");
+        }
+    }
+
+    restart
+}
@@ -0,0 +1,119 @@
+//! Log of recently played tracks and the lyric cache used for them.
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::log::error;
+use crate::lyric_providers::join_artists;
+use crate::sync::TrackMeta;
+
+/// history file is rotated to `history.jsonl.1` once it grows beyond this
+pub const MAX_HISTORY_SIZE: u64 = 10 * 1024 * 1024;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlayRecord {
+    /// seconds since unix epoch
+    pub timestamp: u64,
+    pub title: String,
+    /// artists joined by `ARTIST_SEPARATOR`
+    pub artist: String,
+    pub album: String,
+    pub lyric_cache_path: Option<PathBuf>,
+}
+
+impl PlayRecord {
+    pub fn new(track_meta: &TrackMeta, lyric_cache_path: Option<PathBuf>) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        Self {
+            timestamp,
+            title: track_meta.title.clone().unwrap_or_default(),
+            artist: track_meta
+                .artists
+                .as_deref()
+                .map(join_artists)
+                .unwrap_or_default(),
+            album: track_meta.album.clone().unwrap_or_default(),
+            lyric_cache_path,
+        }
+    }
+}
+
+/// `$XDG_STATE_HOME/waylyrics/history.jsonl`
+pub fn history_path() -> Option<PathBuf> {
+    let base = directories::BaseDirs::new()?;
+    let state_home = base.state_dir().unwrap_or_else(|| base.data_local_dir());
+    Some(state_home.join("waylyrics").join("history.jsonl"))
+}
+
+/// Move `path` to `path.1` if it exceeds `max_size`, replacing the previous rotation
+pub fn rotate_history(path: &Path, max_size: u64) -> Result<bool> {
+    let Ok(metadata) = std::fs::metadata(path) else {
+        return Ok(false);
+    };
+    if metadata.len() <= max_size {
+        return Ok(false);
+    }
+    let mut rotated = path.as_os_str().to_owned();
+    rotated.push(".1");
+    std::fs::rename(path, rotated)?;
+    Ok(true)
+}
+
+/// Append `record` as one JSON line to `path`
+pub fn append_record(path: &Path, record: &PlayRecord) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    rotate_history(path, MAX_HISTORY_SIZE)?;
+
+    let mut line = serde_json::to_string(record)?;
+    line.push('\n');
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(line.as_bytes())?;
+    Ok(())
+}
+
+/// Read records from `path`, newest first. Broken lines are skipped.
+pub fn load_records(path: &Path) -> Vec<PlayRecord> {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return vec![];
+    };
+    let mut records: Vec<PlayRecord> = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| {
+            serde_json::from_str(line)
+                .inspect_err(|e| error!("play history parse error: {e} from {path:?}"))
+                .ok()
+        })
+        .collect();
+    // stable, so records of the same second keep the order they were played
+    records.reverse();
+    records.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    records
+}
+
+pub fn load_history() -> Vec<PlayRecord> {
+    history_path()
+        .map(|path| load_records(&path))
+        .unwrap_or_default()
+}
+
+/// Called on each track change
+pub fn record_track(track_meta: &TrackMeta, lyric_cache_path: Option<PathBuf>) {
+    let Some(path) = history_path() else {
+        return;
+    };
+    let record = PlayRecord::new(track_meta, lyric_cache_path);
+    if let Err(e) = append_record(&path, &record) {
+        error!("cannot append play history to {path:?}: {e}");
+    }
+}
@@ -0,0 +1,80 @@
+//! A tiny HTTP server for monitoring, serving `/metrics` for Prometheus.
+
+use anyhow::Result;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::log::{debug, error, info};
+use crate::{metrics, tokio_spawn};
+
+/// `Content-Type` of Prometheus text exposition format
+const METRICS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+/// requests with longer headers are dropped
+const MAX_REQUEST_LEN: usize = 8 * 1024;
+
+/// Status line, content type and body answering `request_line`, e.g. `GET /metrics HTTP/1.1`
+pub fn respond(request_line: &str) -> (&'static str, &'static str, String) {
+    let mut parts = request_line.split_whitespace();
+    let (method, path) = (parts.next(), parts.next());
+    // query string doesn't matter
+    let path = path.map(|path| path.split_once('?').map_or(path, |(path, _)| path));
+    match (method, path) {
+        (Some("GET"), Some("/metrics")) => ("200 OK", METRICS_CONTENT_TYPE, metrics::render()),
+        (Some(_), Some("/metrics")) => (
+            "405 Method Not Allowed",
+            "text/plain",
+            "method not allowed\n".into(),
+        ),
+        _ => ("404 Not Found", "text/plain", "not found\n".into()),
+    }
+}
+
+async fn handle(mut stream: TcpStream) -> Result<()> {
+    let mut request = Vec::new();
+    let mut buf = [0; 1024];
+    while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+        let n = stream.read(&mut buf).await?;
+        if n == 0 || request.len() + n > MAX_REQUEST_LEN {
+            return Ok(());
+        }
+        request.extend_from_slice(&buf[..n]);
+    }
+    let request = String::from_utf8_lossy(&request);
+    let request_line = request.lines().next().unwrap_or_default();
+    debug!("http-api: {request_line}");
+
+    let (status, content_type, body) = respond(request_line);
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+/// Listen on `address` like `127.0.0.1:9100` in background
+pub fn start_http_api(address: String) {
+    tokio_spawn!(async move {
+        let listener = match TcpListener::bind(&address).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                error!("cannot listen on {address} for http-api: {e}");
+                return;
+            }
+        };
+        info!("http-api listening on {address}");
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    tokio_spawn!(async move {
+                        if let Err(e) = handle(stream).await {
+                            debug!("http-api connection failed: {e}");
+                        }
+                    });
+                }
+                Err(e) => error!("http-api cannot accept connection: {e}"),
+            }
+        }
+    });
+}
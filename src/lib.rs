@@ -7,15 +7,19 @@ use once_cell::sync::Lazy;
 use regex::RegexSet;
 
 pub mod app;
+pub mod cli;
 pub mod config;
+pub mod history;
 pub mod log;
 pub mod lyric_providers;
+pub mod metrics;
 pub mod sync;
 pub mod utils;
 
 pub const DEFAULT_TEXT: &str = "Waylyrics";
 pub const PACKAGE_NAME: &str = env!("CARGO_PKG_NAME");
 pub const APP_ID_FIXED: &str = "io.github.waylyrics.Waylyrics";
+pub const THEME_PRESETS_DIR: Option<&str> = option_env!("WAYLYRICS_THEME_PRESETS_DIR");
 pub static INSTANCE_NAME: OnceLock<String> = OnceLock::new();
 
 thread_local! {
@@ -54,3 +58,9 @@ mod tests;
 
 #[cfg(feature = "tray-icon")]
 pub mod tray_icon;
+
+#[cfg(feature = "obs-integration")]
+pub mod obs;
+
+#[cfg(feature = "http-api")]
+pub mod http_api;
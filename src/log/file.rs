@@ -0,0 +1,118 @@
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use tracing_subscriber::fmt::MakeWriter;
+
+/// path of the log file, logging to stderr only if unset
+pub const LOG_FILE_ENV: &str = "WAYLYRICS_LOG_FILE";
+/// size in bytes at which the log file is rotated to `<path>.1`
+pub const LOG_MAX_SIZE_ENV: &str = "WAYLYRICS_LOG_MAX_SIZE";
+pub const DEFAULT_LOG_MAX_SIZE: u64 = 10 * 1024 * 1024;
+
+struct Inner {
+    file: File,
+    size: u64,
+}
+
+/// A log file in append mode, rotated once it grows beyond `max_size`
+pub struct RotatingFile {
+    path: PathBuf,
+    max_size: u64,
+    inner: Mutex<Inner>,
+}
+
+fn open_append(path: &Path) -> io::Result<File> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+fn rotated_path(path: &Path) -> PathBuf {
+    let mut rotated = path.as_os_str().to_owned();
+    rotated.push(".1");
+    rotated.into()
+}
+
+impl RotatingFile {
+    pub fn open(path: impl Into<PathBuf>, max_size: u64) -> io::Result<Self> {
+        let path = path.into();
+        let file = open_append(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            path,
+            max_size,
+            inner: Mutex::new(Inner { file, size }),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn rotate(&self, inner: &mut Inner) -> io::Result<()> {
+        inner.file.flush()?;
+        std::fs::rename(&self.path, rotated_path(&self.path))?;
+        inner.file = open_append(&self.path)?;
+        inner.size = 0;
+        Ok(())
+    }
+}
+
+/// Open the log file named by `WAYLYRICS_LOG_FILE`.
+///
+/// Logging is not set up yet, so failures are reported to stderr.
+pub fn log_file_from_env() -> Option<RotatingFile> {
+    let path = std::env::var_os(LOG_FILE_ENV).filter(|path| !path.is_empty())?;
+    let max_size = match std::env::var(LOG_MAX_SIZE_ENV) {
+        Ok(size) => size.parse().unwrap_or_else(|e| {
+            eprintln!("WARN: invalid {LOG_MAX_SIZE_ENV} `{size}`: {e}, using default");
+            DEFAULT_LOG_MAX_SIZE
+        }),
+        Err(_) => DEFAULT_LOG_MAX_SIZE,
+    };
+    RotatingFile::open(&path, max_size)
+        .inspect_err(|e| {
+            eprintln!("WARN: cannot open log file {path:?}: {e}, logging to stderr only");
+        })
+        .ok()
+}
+
+pub struct RotatingFileWriter<'a>(&'a RotatingFile);
+
+impl<'a> MakeWriter<'a> for RotatingFile {
+    type Writer = RotatingFileWriter<'a>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RotatingFileWriter(self)
+    }
+}
+
+impl Write for RotatingFileWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut inner = self
+            .0
+            .inner
+            .lock()
+            .map_err(|_| io::Error::other("log file lock poisoned"))?;
+        if inner.size > 0 && inner.size + buf.len() as u64 > self.0.max_size {
+            if let Err(e) = self.0.rotate(&mut inner) {
+                eprintln!("WARN: cannot rotate log file {:?}: {e}", self.0.path);
+            }
+        }
+        inner.file.write_all(buf)?;
+        inner.size += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0
+            .inner
+            .lock()
+            .map_err(|_| io::Error::other("log file lock poisoned"))?
+            .file
+            .flush()
+    }
+}
@@ -1 +1,10 @@
 pub use tracing::{debug, error, info, trace, warn};
+
+mod file;
+mod redact;
+pub use file::{
+    log_file_from_env, RotatingFile, DEFAULT_LOG_MAX_SIZE, LOG_FILE_ENV, LOG_MAX_SIZE_ENV,
+};
+pub use redact::{
+    needs_redaction, redact, register_secret, DropUnredacted, Redacted, SENSITIVE_HEADERS,
+};
@@ -0,0 +1,113 @@
+use std::borrow::Cow;
+use std::fmt::Write as _;
+use std::io::{self, Write};
+use std::sync::RwLock;
+
+use once_cell::sync::Lazy;
+use regex::{Regex, RegexBuilder};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Metadata};
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::{Context, Filter};
+
+/// header names whose values should never appear in log output
+pub const SENSITIVE_HEADERS: &[&str] = &["authorization", "cookie", "x-api-key"];
+
+static SECRETS: RwLock<Vec<String>> = RwLock::new(Vec::new());
+
+static HEADER_REGEX: Lazy<Regex> = Lazy::new(|| {
+    let names = SENSITIVE_HEADERS.join("|");
+    RegexBuilder::new(&format!(r#"("?(?:{names})"?\s*[:=]\s*)"?[^",}}\n]+"?"#))
+        .case_insensitive(true)
+        .build()
+        .expect("invalid sensitive header regex")
+});
+
+const REDACTED: &str = "<redacted>";
+
+/// Hide `secret` from log output
+pub fn register_secret(secret: impl Into<String>) {
+    let secret = secret.into();
+    if secret.is_empty() {
+        return;
+    }
+    if let Ok(mut secrets) = SECRETS.write() {
+        secrets.push(secret);
+    }
+}
+
+/// Replace registered secrets and values of `SENSITIVE_HEADERS` in `msg`
+pub fn redact(msg: &str) -> Cow<'_, str> {
+    let mut msg = HEADER_REGEX.replace_all(msg, format!("${{1}}{REDACTED}"));
+    if let Ok(secrets) = SECRETS.read() {
+        for secret in secrets.iter() {
+            if msg.contains(secret.as_str()) {
+                msg = Cow::Owned(msg.replace(secret.as_str(), REDACTED));
+            }
+        }
+    }
+    msg
+}
+
+/// Whether `redact` would change `msg`
+pub fn needs_redaction(msg: &str) -> bool {
+    // `redact` only allocates once something is replaced
+    matches!(redact(msg), Cow::Owned(_))
+}
+
+/// A per-layer filter dropping events with anything to redact in their fields.
+///
+/// For layers formatting events themselves, like journald, which `Redacted` cannot wrap.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DropUnredacted;
+
+/// fields of an event as text, to look for secrets in
+#[derive(Default)]
+struct FieldText(String);
+
+impl Visit for FieldText {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        let _ = write!(self.0, " {}={value}", field.name());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        let _ = write!(self.0, " {}={value:?}", field.name());
+    }
+}
+
+impl<S> Filter<S> for DropUnredacted {
+    fn enabled(&self, _meta: &Metadata<'_>, _cx: &Context<'_, S>) -> bool {
+        true
+    }
+
+    fn event_enabled(&self, event: &Event<'_>, _cx: &Context<'_, S>) -> bool {
+        let mut text = FieldText::default();
+        event.record(&mut text);
+        !needs_redaction(&text.0)
+    }
+}
+
+/// A `MakeWriter` which redacts everything written through it
+pub struct Redacted<M>(pub M);
+
+pub struct RedactedWriter<W>(W);
+
+impl<'a, M: MakeWriter<'a>> MakeWriter<'a> for Redacted<M> {
+    type Writer = RedactedWriter<M::Writer>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RedactedWriter(self.0.make_writer())
+    }
+}
+
+impl<W: Write> Write for RedactedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let msg = String::from_utf8_lossy(buf);
+        self.0.write_all(redact(&msg).as_bytes())?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
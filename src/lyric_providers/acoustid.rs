@@ -0,0 +1,242 @@
+//! Identify tracks without usable metadata by their audio fingerprint, using AcoustID.
+//!
+//! The recordings AcoustID returns are MusicBrainz recordings with title, artists and length,
+//! which are searched on lyric providers in place of the missing metadata.
+
+use std::fs::File;
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use base64::Engine;
+use rusty_chromaprint::{Configuration, FingerprintCompressor, Fingerprinter};
+use serde::Deserialize;
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::DecoderOptions;
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+use crate::log::{debug, info, warn};
+use crate::lyric_providers::auth;
+use crate::sync::{OsImp, OS};
+use crate::sync::{TrackId, TrackMeta};
+use crate::tokio_spawn;
+
+/// key of `[providers.acoustid]` in `ProvidersConfig::iter`
+pub const NAME: &str = "AcoustID";
+
+const LOOKUP_URL: &str = "https://api.acoustid.org/v2/lookup";
+
+/// audio fingerprinted from the start of the track, same as `fpcalc`
+pub const FINGERPRINT_LENGTH: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Deserialize)]
+pub struct LookupResponse {
+    pub status: String,
+    #[serde(default)]
+    pub results: Vec<LookupResult>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LookupResult {
+    pub score: f64,
+    #[serde(default)]
+    pub recordings: Vec<Recording>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Recording {
+    /// MusicBrainz recording id
+    pub id: String,
+    pub title: Option<String>,
+    /// in seconds
+    pub duration: Option<f64>,
+    #[serde(default)]
+    pub artists: Vec<Artist>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Artist {
+    pub name: String,
+}
+
+/// Metadata of the best scored recording with a title
+pub fn best_recording(response: LookupResponse) -> Option<TrackMeta> {
+    let mut results = response.results;
+    results.sort_by(|a, b| b.score.total_cmp(&a.score));
+    let recording = results
+        .into_iter()
+        .flat_map(|result| result.recordings)
+        .find(|recording| recording.title.is_some())?;
+
+    let artists = recording
+        .artists
+        .into_iter()
+        .map(|artist| artist.name)
+        .collect::<Vec<_>>();
+    Some(TrackMeta {
+        unique_song_id: TrackId::from_musicbrainz(&recording.id),
+        title: recording.title,
+        artists: (!artists.is_empty()).then_some(artists),
+        length: recording
+            .duration
+            .and_then(|secs| Duration::try_from_secs_f64(secs).ok()),
+        album: None,
+        year: None,
+        track_number: None,
+        disc_number: None,
+        genre: None,
+        bpm: None,
+        user_rating: None,
+    })
+}
+
+/// Compressed fingerprint of the first `FINGERPRINT_LENGTH` of `path`, in URL-safe base64,
+/// and the length of the track
+pub fn fingerprint_file(path: &Path) -> Result<(String, Duration)> {
+    let source = MediaSourceStream::new(Box::new(File::open(path)?), Default::default());
+    let mut hint = Hint::new();
+    if let Some(extension) = path.extension().and_then(|ext| ext.to_str()) {
+        hint.with_extension(extension);
+    }
+    let probed = symphonia::default::get_probe().format(
+        &hint,
+        source,
+        &FormatOptions::default(),
+        &MetadataOptions::default(),
+    )?;
+    let mut format = probed.format;
+    let track = format
+        .default_track()
+        .ok_or_else(|| anyhow!("no audio track in {path:?}"))?;
+    let track_id = track.id;
+    let sample_rate = track
+        .codec_params
+        .sample_rate
+        .ok_or_else(|| anyhow!("unknown sample rate of {path:?}"))?;
+    let channels = track
+        .codec_params
+        .channels
+        .map(|channels| channels.count())
+        .unwrap_or(2);
+    let track_length = track
+        .codec_params
+        .n_frames
+        .map(|frames| Duration::from_secs_f64(frames as f64 / sample_rate as f64));
+    let mut decoder =
+        symphonia::default::get_codecs().make(&track.codec_params, &DecoderOptions::default())?;
+
+    let config = Configuration::preset_test2();
+    let mut printer = Fingerprinter::new(&config);
+    printer
+        .start(sample_rate, channels as u32)
+        .map_err(|e| anyhow!("cannot fingerprint {path:?}: {e:?}"))?;
+
+    let max_samples = FINGERPRINT_LENGTH.as_secs() as usize * sample_rate as usize * channels;
+    let mut consumed = 0;
+    let mut buffer: Option<SampleBuffer<i16>> = None;
+    while consumed < max_samples {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                break;
+            }
+            Err(e) => return Err(e.into()),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            Err(SymphoniaError::DecodeError(e)) => {
+                debug!("skipped broken packet in {path:?}: {e}");
+                continue;
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let buffer = buffer
+            .get_or_insert_with(|| SampleBuffer::new(decoded.capacity() as u64, *decoded.spec()));
+        buffer.copy_interleaved_ref(decoded);
+        printer.consume(buffer.samples());
+        consumed += buffer.samples().len();
+    }
+    printer.finish();
+
+    let compressed = FingerprintCompressor::from(&config).compress(printer.fingerprint());
+    let fingerprint = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(compressed);
+    let decoded_length =
+        Duration::from_secs_f64(consumed as f64 / (sample_rate as usize * channels) as f64);
+    Ok((fingerprint, track_length.unwrap_or(decoded_length)))
+}
+
+/// Ask AcoustID for the recording of `path`
+pub async fn identify_file(path: &Path, api_key: &str) -> Result<Option<TrackMeta>> {
+    let path = path.to_owned();
+    let (fingerprint, length) =
+        tokio_spawn!(
+            async move { tokio::task::spawn_blocking(move || fingerprint_file(&path)).await }
+        )
+        .await???;
+
+    let api_key = api_key.to_owned();
+    let response: LookupResponse = tokio_spawn!(async move {
+        let duration = length.as_secs().to_string();
+        reqwest::Client::new()
+            .post(LOOKUP_URL)
+            .form(&[
+                ("client", api_key.as_str()),
+                ("meta", "recordings"),
+                ("duration", duration.as_str()),
+                ("fingerprint", fingerprint.as_str()),
+            ])
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<LookupResponse>()
+            .await
+    })
+    .await??;
+
+    if response.status != "ok" {
+        return Err(anyhow!(
+            "AcoustID lookup failed with status {}",
+            response.status
+        ));
+    }
+    Ok(best_recording(response))
+}
+
+/// `track_meta`, or the one identified from the playing file if it has neither title nor artists
+pub async fn identify_if_untagged(track_meta: &TrackMeta) -> TrackMeta {
+    if track_meta.title.is_some() || track_meta.artists.is_some() {
+        return track_meta.clone();
+    }
+    let Some(api_key) = auth::api_key(NAME) else {
+        debug!("skipped fingerprinting as api key of AcoustID is not set");
+        return track_meta.clone();
+    };
+    let Some(path) = OS::playing_file() else {
+        return track_meta.clone();
+    };
+
+    match identify_file(&path, api_key).await {
+        Ok(Some(identified)) => {
+            info!("identified {path:?} as {identified:?} by AcoustID");
+            TrackMeta {
+                length: track_meta.length.or(identified.length),
+                ..identified
+            }
+        }
+        Ok(None) => {
+            info!("AcoustID found no recording for {path:?}");
+            track_meta.clone()
+        }
+        Err(e) => {
+            warn!("cannot identify {path:?} by AcoustID: {e}");
+            track_meta.clone()
+        }
+    }
+}
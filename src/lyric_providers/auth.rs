@@ -0,0 +1,83 @@
+use std::sync::OnceLock;
+
+use ahash::HashMap;
+use anyhow::Result;
+use reqwest::{
+    header::{HeaderMap, HeaderValue, AUTHORIZATION},
+    ClientBuilder, StatusCode,
+};
+
+use crate::config::ProvidersConfig;
+use crate::log::{register_secret, warn};
+
+use super::LyricProvider;
+
+/// api keys configured in `[providers.*]`, keyed by `LyricProvider::unique_name`
+static API_KEYS: OnceLock<HashMap<&'static str, String>> = OnceLock::new();
+
+/// Should be called before any provider builds its client.
+pub fn set_api_keys(config: &ProvidersConfig) {
+    let keys = config
+        .iter()
+        .filter_map(|(name, conf)| {
+            let key = conf.api_key.as_ref().filter(|k| !k.is_empty())?;
+            register_secret(key);
+            Some((name, key.clone()))
+        })
+        .collect();
+    if API_KEYS.set(keys).is_err() {
+        warn!("api keys were already set");
+    }
+}
+
+pub fn api_key(provider: &str) -> Option<&'static str> {
+    API_KEYS.get()?.get(provider).map(String::as_str)
+}
+
+/// headers carrying the api key of `provider`, marked as sensitive
+pub fn auth_headers(provider: &str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    let Some(key) = api_key(provider) else {
+        return headers;
+    };
+    match HeaderValue::from_str(&format!("Bearer {key}")) {
+        Ok(mut value) => {
+            value.set_sensitive(true);
+            headers.insert(AUTHORIZATION, value);
+        }
+        Err(_) => warn!("api key of {provider} is not a valid header value"),
+    }
+    headers
+}
+
+/// A `reqwest::ClientBuilder` with auth headers of `provider` applied
+pub fn client_builder(provider: &str) -> ClientBuilder {
+    reqwest::Client::builder().default_headers(auth_headers(provider))
+}
+
+#[derive(Debug)]
+pub enum AuthStatus {
+    Valid,
+    Invalid(String),
+    NotConfigured,
+}
+
+/// Verify api key of `provider` by sending a search request
+pub async fn check_auth(provider: &dyn LyricProvider) -> AuthStatus {
+    if api_key(provider.unique_name()).is_none() {
+        return AuthStatus::NotConfigured;
+    }
+
+    let result: Result<_> = provider.search_song(crate::DEFAULT_TEXT).await;
+    match result {
+        Ok(_) => AuthStatus::Valid,
+        Err(e) => match e.downcast_ref::<reqwest::Error>().and_then(|e| e.status()) {
+            Some(StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN) => {
+                AuthStatus::Invalid(e.to_string())
+            }
+            // search result being empty does not mean the key is invalid
+            _ if e.downcast_ref::<super::Error>().is_some() => AuthStatus::Valid,
+            _ => AuthStatus::Invalid(e.to_string()),
+        },
+    }
+}
@@ -0,0 +1,83 @@
+//! Connectivity check of lyric providers, used by `--check-providers`.
+
+use std::time::{Duration, Instant};
+
+use super::LyricProvider;
+
+pub const TEST_TITLE: &str = "Yesterday";
+pub const TEST_ARTIST: &str = "Beatles";
+
+#[derive(Debug)]
+pub struct CheckResult {
+    pub provider: String,
+    pub latency: Duration,
+    /// number of search results, or the error message
+    pub outcome: Result<usize, String>,
+}
+
+impl CheckResult {
+    pub fn passed(&self) -> bool {
+        self.outcome.is_ok()
+    }
+}
+
+/// Search a well-known song on `provider`
+pub async fn check_provider(provider: &dyn LyricProvider) -> CheckResult {
+    let start = Instant::now();
+    let result = provider
+        .search_song_detailed("", &[TEST_ARTIST], TEST_TITLE)
+        .await;
+    let latency = start.elapsed();
+
+    let outcome = match result {
+        Ok(songs) => Ok(songs.len()),
+        // the provider replied, it just has nothing for us
+        Err(e) if e.downcast_ref::<super::Error>().is_some() => Ok(0),
+        Err(e) => Err(crate::log::redact(&e.to_string()).into_owned()),
+    };
+    CheckResult {
+        provider: provider.unique_name().to_owned(),
+        latency,
+        outcome,
+    }
+}
+
+/// Plain text table with provider name, latency, result count and status
+pub fn format_table(results: &[CheckResult]) -> String {
+    let header = ["provider", "latency", "results", "status"].map(String::from);
+    let rows: Vec<[String; 4]> = results
+        .iter()
+        .map(|result| {
+            let (count, status) = match &result.outcome {
+                Ok(count) => (count.to_string(), "pass".to_owned()),
+                Err(e) => ("-".to_owned(), format!("fail: {e}")),
+            };
+            [
+                result.provider.clone(),
+                format!("{}ms", result.latency.as_millis()),
+                count,
+                status,
+            ]
+        })
+        .collect();
+
+    let mut widths = header.clone().map(|h| h.len());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    std::iter::once(&header)
+        .chain(&rows)
+        .map(|row| {
+            let line = row
+                .iter()
+                .zip(widths)
+                .map(|(cell, width)| format!("{cell:<width$}"))
+                .collect::<Vec<_>>()
+                .join("  ");
+            line.trim_end().to_owned() + "\n"
+        })
+        .collect()
+}
@@ -0,0 +1,246 @@
+//! Lyrics printed by a user-defined shell command, see `custom-lyric-command`.
+//!
+//! `{title}`, `{artist}`, `{album}` and `{duration}` in the command are replaced by
+//! shell-quoted metadata of the track, `{duration}` being whole seconds or empty if unknown.
+//! The command succeeds by exiting with 0, its stdout is parsed as LRC, or as plain text if
+//! there's no timestamp. stderr is only logged. Commands running longer than
+//! `COMMAND_TIMEOUT` are killed.
+
+use std::collections::HashMap;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+
+use super::mpris_native::parse_as_text;
+use super::{join_artists, LyricOwned, LyricStore, SongInfo};
+use crate::log::debug;
+use crate::tokio_spawn;
+
+/// name to list in `lyric-search-source`, queried first if not listed
+pub const NAME: &str = "command";
+
+/// results kept for `query_lyric`, older ones are dropped beyond this
+const MAX_RESULTS: usize = 32;
+/// a hung command is killed after this, so it doesn't stall fetching
+pub const COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+
+static COMMAND_PROVIDER: OnceLock<CommandProvider> = OnceLock::new();
+
+pub struct CommandProvider {
+    template: String,
+    /// stdout of commands, keyed by the song id handed out
+    results: Mutex<HashMap<String, String>>,
+    next_id: AtomicU64,
+}
+
+/// Empty `template` disables the provider
+pub fn set_custom_lyric_command(template: &str) {
+    if !template.is_empty() {
+        let _ = COMMAND_PROVIDER.set(CommandProvider::new(template));
+    }
+}
+
+pub fn command_provider() -> Option<&'static CommandProvider> {
+    COMMAND_PROVIDER.get()
+}
+
+/// `sources` with `NAME` in front if `custom-lyric-command` is set and it's not listed
+pub fn with_command_source(mut sources: Vec<String>) -> Vec<String> {
+    if command_provider().is_some() && !sources.iter().any(|source| source == NAME) {
+        sources.insert(0, NAME.into());
+    }
+    sources
+}
+
+/// `value` as a single argument of `sh`, or `cmd` on Windows
+///
+/// `cmd` expands `%VAR%` even in quotes, so `%` is escaped by `^` between closed quotes.
+pub fn shell_quote(value: &str) -> String {
+    if cfg!(windows) {
+        format!("\"{}\"", value.replace('"', "").replace('%', "\"^%\""))
+    } else {
+        format!("'{}'", value.replace('\'', r"'\''"))
+    }
+}
+
+/// `template` with placeholders replaced by quoted values.
+///
+/// The template is scanned once, so placeholders in the inserted values are kept as they are.
+pub fn expand_template(
+    template: &str,
+    title: &str,
+    artist: &str,
+    album: &str,
+    length: Option<Duration>,
+) -> String {
+    let duration = length
+        .map(|length| length.as_secs().to_string())
+        .unwrap_or_default();
+    let placeholders = [
+        ("{title}", title),
+        ("{artist}", artist),
+        ("{album}", album),
+        ("{duration}", duration.as_str()),
+    ];
+
+    let mut command = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        command.push_str(&rest[..start]);
+        rest = &rest[start..];
+        match placeholders
+            .iter()
+            .find(|(placeholder, _)| rest.starts_with(placeholder))
+        {
+            Some((placeholder, value)) => {
+                command.push_str(&shell_quote(value));
+                rest = &rest[placeholder.len()..];
+            }
+            None => {
+                command.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    command.push_str(rest);
+    command
+}
+
+impl CommandProvider {
+    pub fn new(template: &str) -> Self {
+        Self {
+            template: template.to_owned(),
+            results: Mutex::new(HashMap::new()),
+            next_id: AtomicU64::new(0),
+        }
+    }
+
+    fn store_result(&self, stdout: String) -> String {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed).to_string();
+        if let Ok(mut results) = self.results.lock() {
+            if results.len() >= MAX_RESULTS {
+                results.clear();
+            }
+            results.insert(id.clone(), stdout);
+        }
+        id
+    }
+}
+
+/// stdout of `command` run by the shell, fails if it exits with non-zero
+pub async fn run_command(command: String) -> Result<String> {
+    let mut shell = if cfg!(windows) {
+        let mut shell = tokio::process::Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = tokio::process::Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    shell
+        .arg(&command)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    // the child is reaped by the tokio reactor, and killed if it times out
+    let output =
+        tokio_spawn!(async move { tokio::time::timeout(COMMAND_TIMEOUT, shell.output()).await })
+            .await?
+            .map_err(|_| anyhow!("`{command}` timed out after {COMMAND_TIMEOUT:?}"))??;
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !stderr.trim().is_empty() {
+        debug!("stderr of `{command}`: {}", stderr.trim_end());
+    }
+    if !output.status.success() {
+        return Err(anyhow!("`{command}` failed with {}", output.status));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+impl super::LyricParse for CommandProvider {
+    fn parse_lyric(&self, store: &LyricStore) -> LyricOwned {
+        store
+            .lyric
+            .as_deref()
+            .and_then(parse_as_text)
+            .unwrap_or_default()
+    }
+
+    fn parse_translated_lyric(&self, _store: &LyricStore) -> LyricOwned {
+        LyricOwned::None
+    }
+}
+
+#[async_trait::async_trait]
+impl super::LyricProvider for CommandProvider {
+    async fn query_lyric(&self, id: &str) -> Result<LyricStore> {
+        let lyric = self
+            .results
+            .lock()
+            .map_err(|_| anyhow!("command provider results poisoned"))?
+            .get(id)
+            .cloned()
+            .ok_or(super::Error::NoResult)?;
+        Ok(LyricStore {
+            lyric: Some(lyric),
+            tlyric: None,
+            confidence: vec![],
+        })
+    }
+
+    async fn search_song_detailed(
+        &self,
+        album: &str,
+        artists: &[&str],
+        title: &str,
+    ) -> Result<Vec<SongInfo>> {
+        self.search_song_with_length(album, artists, title, None)
+            .await
+    }
+
+    async fn search_song_with_length(
+        &self,
+        album: &str,
+        artists: &[&str],
+        title: &str,
+        length: Option<Duration>,
+    ) -> Result<Vec<SongInfo>> {
+        let singer = join_artists(artists);
+        let command = expand_template(&self.template, title, &singer, album, length);
+        let stdout = run_command(command).await?;
+        if stdout.trim().is_empty() {
+            return Err(super::Error::NoResult.into());
+        }
+
+        let id = self.store_result(stdout);
+        Ok(vec![SongInfo {
+            id,
+            title: title.to_owned(),
+            singer,
+            album: (!album.is_empty()).then(|| album.to_owned()),
+            length: length.unwrap_or_default(),
+        }])
+    }
+
+    async fn search_song(&self, keyword: &str) -> Result<Vec<SongInfo>> {
+        self.search_song_with_length("", &[], keyword, None).await
+    }
+
+    fn unique_name(&self) -> &'static str {
+        NAME
+    }
+
+    fn init(self, _config: &str) -> Result<()> {
+        Ok(())
+    }
+
+    fn is_likely_songid(&self, _s: &str) -> bool {
+        false
+    }
+}
@@ -0,0 +1,113 @@
+//! Providers searched as a unit, see `[[provider-group]]`.
+//!
+//! `lyric-search-source` is split into units searched one after another until one finds lyric.
+//! A group is a unit of its members, or one unit per member if it's sequential.
+//! Providers listed outside groups next to each other make up one unit,
+//! so without groups all providers are searched at once as before.
+
+use std::sync::OnceLock;
+
+use super::LyricProvider;
+use crate::config::ProviderGroup;
+
+/// names of providers in each unit of `lyric-search-source`
+static SEARCH_UNITS: OnceLock<Vec<Vec<String>>> = OnceLock::new();
+static PROVIDER_GROUPS: OnceLock<Vec<ProviderGroup>> = OnceLock::new();
+
+pub fn set_provider_groups(groups: &[ProviderGroup], sources: &[String]) {
+    let _ = SEARCH_UNITS.set(search_units(groups, sources));
+    let _ = PROVIDER_GROUPS.set(groups.to_vec());
+}
+
+fn find_group<'a>(groups: &'a [ProviderGroup], name: &str) -> Option<&'a ProviderGroup> {
+    groups.iter().find(|group| group.name == name)
+}
+
+/// `sources` with groups replaced by their members, without duplicates
+pub fn expand_sources_with(groups: &[ProviderGroup], sources: &[String]) -> Vec<String> {
+    let mut expanded: Vec<String> = vec![];
+    for source in sources {
+        let members = match find_group(groups, source) {
+            Some(group) => group.members.as_slice(),
+            None => std::slice::from_ref(source),
+        };
+        for member in members {
+            if !expanded.contains(member) {
+                expanded.push(member.clone());
+            }
+        }
+    }
+    expanded
+}
+
+/// `expand_sources_with` the configured `[[provider-group]]`
+pub fn expand_sources(sources: &[String]) -> Vec<String> {
+    match PROVIDER_GROUPS.get() {
+        Some(groups) => expand_sources_with(groups, sources),
+        None => sources.to_vec(),
+    }
+}
+
+/// Names of providers in each unit of `sources`, in the order they are searched
+pub fn search_units(groups: &[ProviderGroup], sources: &[String]) -> Vec<Vec<String>> {
+    let mut units: Vec<Vec<String>> = vec![];
+    // providers outside groups since the last group
+    let mut ungrouped: Vec<String> = vec![];
+    for source in sources {
+        let Some(group) = find_group(groups, source) else {
+            ungrouped.push(source.clone());
+            continue;
+        };
+        if !ungrouped.is_empty() {
+            units.push(std::mem::take(&mut ungrouped));
+        }
+        if group.sequential {
+            units.extend(group.members.iter().map(|member| vec![member.clone()]));
+        } else {
+            units.push(group.members.clone());
+        }
+    }
+    if !ungrouped.is_empty() {
+        units.push(ungrouped);
+    }
+    units
+}
+
+/// `tiers` of indexes into `providers` split further by `units`, keeping the order of tiers.
+///
+/// Providers in no unit, like `command` added in front, are searched with the first unit.
+pub fn split_with(
+    units: &[Vec<String>],
+    providers: &[&dyn LyricProvider],
+    tiers: Vec<Vec<usize>>,
+) -> Vec<Vec<usize>> {
+    let unit_of = |idx: usize| {
+        let name = providers[idx].unique_name();
+        units
+            .iter()
+            .position(|unit| unit.iter().any(|member| member == name))
+            .unwrap_or_default()
+    };
+    let mut split = vec![];
+    for tier in tiers {
+        for unit in 0..units.len().max(1) {
+            let in_unit: Vec<usize> = tier
+                .iter()
+                .copied()
+                .filter(|&idx| unit_of(idx) == unit)
+                .collect();
+            if !in_unit.is_empty() {
+                split.push(in_unit);
+            }
+        }
+    }
+    split
+}
+
+/// `split_with` the units of the configured `lyric-search-source`
+pub fn split(providers: &[&dyn LyricProvider], tiers: Vec<Vec<usize>>) -> Vec<Vec<usize>> {
+    match SEARCH_UNITS.get() {
+        Some(units) => split_with(units, providers, tiers),
+        None => tiers,
+    }
+}
@@ -0,0 +1,173 @@
+//! Lyrics from a local library laid out as `Artist/Album/Title.lrc`, or all in one directory
+//! as `Artist - Title.lrc`, see `local-lyrics-dir` and `local-lrc-root`
+//!
+//! Tried before any lyric provider, never retried and never cached, so edits show up next time
+//! the track is played.
+
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use anyhow::Result;
+
+use crate::lyric_providers::mpris_native::parse_as_text;
+use crate::lyric_providers::{join_artists, LyricOwned};
+use crate::sync::TrackMeta;
+
+/// `LyricSource::provider` of lyrics read from local files
+pub const NAME: &str = "Local files";
+
+static LOCAL_LYRICS_DIR: OnceLock<PathBuf> = OnceLock::new();
+static LOCAL_LRC_ROOT: OnceLock<PathBuf> = OnceLock::new();
+
+/// Empty `dir` disables local lyrics
+pub fn set_local_lyrics_dir(dir: &str) {
+    if !dir.is_empty() {
+        let _ = LOCAL_LYRICS_DIR.set(PathBuf::from(dir));
+    }
+}
+
+/// Empty `root` disables lyrics next to the tracks of the music library
+pub fn set_local_lrc_root(root: &str) {
+    if !root.is_empty() {
+        let _ = LOCAL_LRC_ROOT.set(PathBuf::from(root));
+    }
+}
+
+/// Lowercase letters and digits of `name`, so that "AC/DC" matches "acdc"
+pub fn fuzzy_key(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Entry of `dir` named `name`, or else the one with the same `fuzzy_key`.
+///
+/// Directories are looked for if `extension` is `None`, files with `extension` otherwise.
+fn find_entry(dir: &Path, name: &str, extension: Option<&str>) -> Option<PathBuf> {
+    let is_wanted = |path: &Path| match extension {
+        Some(ext) => {
+            path.is_file()
+                && path
+                    .extension()
+                    .is_some_and(|e| e.eq_ignore_ascii_case(ext))
+        }
+        None => path.is_dir(),
+    };
+
+    let file_name = name.replace(['/', '\\'], "_");
+    // `.` and `..` would name the directory itself or its parent
+    if file_name.chars().all(|c| c == '.') {
+        return None;
+    }
+    let exact = match extension {
+        Some(ext) => dir.join(format!("{file_name}.{ext}")),
+        None => dir.join(file_name),
+    };
+    if is_wanted(&exact) {
+        return Some(exact);
+    }
+
+    let key = fuzzy_key(name);
+    if key.is_empty() {
+        return None;
+    }
+    std::fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| is_wanted(path))
+        .find(|path| {
+            let stem = match extension {
+                Some(_) => path.file_stem(),
+                None => path.file_name(),
+            };
+            stem.and_then(OsStr::to_str)
+                .is_some_and(|stem| fuzzy_key(stem) == key)
+        })
+}
+
+fn subdirs(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return vec![];
+    };
+    let mut dirs: Vec<_> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+/// Whether `path` resolves to somewhere under `root`
+fn is_under(root: &Path, path: &Path) -> bool {
+    match (root.canonicalize(), path.canonicalize()) {
+        (Ok(root), Ok(path)) => path.starts_with(root),
+        _ => false,
+    }
+}
+
+/// `root/Artist/Album/Title.lrc`, with all artists joined or any one of them.
+///
+/// Every album of the artist is tried if `album` is unknown or not found,
+/// then `root/Artist - Title.lrc` for lyrics kept in a single directory.
+/// Files resolving outside of `root` are never returned.
+pub fn find_lyric_file(
+    root: &Path,
+    artists: &[String],
+    album: Option<&str>,
+    title: &str,
+) -> Option<PathBuf> {
+    find_lyric_file_in(root, artists, album, title).filter(|path| is_under(root, path))
+}
+
+fn find_lyric_file_in(
+    root: &Path,
+    artists: &[String],
+    album: Option<&str>,
+    title: &str,
+) -> Option<PathBuf> {
+    let mut artist_names = vec![join_artists(artists)];
+    if artists.len() > 1 {
+        artist_names.extend(artists.iter().cloned());
+    }
+
+    for artist in artist_names.iter().filter(|artist| !artist.is_empty()) {
+        let Some(artist_dir) = find_entry(root, artist, None) else {
+            continue;
+        };
+        let album_dir = album.and_then(|album| find_entry(&artist_dir, album, None));
+        let album_dirs = match album_dir {
+            Some(album_dir) => vec![album_dir],
+            None => subdirs(&artist_dir),
+        };
+        let found = album_dirs
+            .iter()
+            .find_map(|album_dir| find_entry(album_dir, title, Some("lrc")));
+        if found.is_some() {
+            return found;
+        }
+    }
+    artist_names
+        .iter()
+        .filter(|artist| !artist.is_empty())
+        .find_map(|artist| find_entry(root, &format!("{artist} - {title}"), Some("lrc")))
+}
+
+/// Lyric file of `track_meta` in `local-lyrics-dir`, then `local-lrc-root`, if they're set
+pub fn lyric_file_for(track_meta: &TrackMeta) -> Option<PathBuf> {
+    let title = track_meta.title.as_deref()?;
+    let artists = track_meta.artists.as_deref().unwrap_or_default();
+    [LOCAL_LYRICS_DIR.get(), LOCAL_LRC_ROOT.get()]
+        .into_iter()
+        .flatten()
+        .find_map(|root| find_lyric_file(root, artists, track_meta.album.as_deref(), title))
+}
+
+/// LRC, or plain text as `LyricOwned::NoTimestamp`
+pub fn read_lyric_file(path: &Path) -> Result<LyricOwned> {
+    let text = std::fs::read_to_string(path)?;
+    Ok(parse_as_text(&text).unwrap_or_default())
+}
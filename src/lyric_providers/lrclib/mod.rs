@@ -6,15 +6,23 @@ use lrclib_api_rs::{
     LRCLibAPI,
 };
 use once_cell::sync::Lazy;
-use reqwest::Client;
+use reqwest::{Client, Response, StatusCode};
 
-use super::{Lyric, LyricOwned, LyricStore, SongInfo};
+use super::utils::http_cache::{conditional, Conditional, Validator};
+use super::utils::retry::{check_status, with_retry};
+use super::{auth, Lyric, LyricOwned, LyricStore, SongInfo};
 use crate::tokio_spawn;
 
 pub struct LRCLib;
 
 pub static LRCLIB_API_CLIENT: Lazy<LRCLibAPI> = Lazy::new(LRCLibAPI::default);
-pub static REQWEST_CLIENT: Lazy<Client> = Lazy::new(Client::default);
+pub static REQWEST_CLIENT: Lazy<Client> = Lazy::new(|| {
+    auth::client_builder(NAME)
+        .build()
+        .unwrap_or_else(|_| Client::default())
+});
+
+const NAME: &str = "LRCLib";
 
 impl super::LyricParse for LRCLib {
     fn parse_lyric(&self, store: &LyricStore) -> LyricOwned {
@@ -31,17 +39,60 @@ impl super::LyricParse for LRCLib {
 #[async_trait::async_trait]
 impl super::LyricProvider for LRCLib {
     async fn query_lyric(&self, id: &str) -> Result<LyricStore> {
+        self.query_lyric_conditional(id, None).await?.into_body()
+    }
+
+    async fn query_lyric_conditional(
+        &self,
+        id: &str,
+        validator: Option<&Validator>,
+    ) -> Result<Conditional<LyricStore>> {
         let id: u64 = id.parse()?;
+        let validator = validator.cloned();
 
         tokio_spawn!(async move {
             let req = LRCLIB_API_CLIENT.get_lyrics_by_id(id)?;
-            let resp = REQWEST_CLIENT.get(req.uri().to_string()).send().await?;
+            let url = &req.uri().to_string();
+            let validator = &validator;
+            let resp = with_retry(NAME, move || async move {
+                let mut req = REQWEST_CLIENT.get(url);
+                if let Some(validator) = validator {
+                    req = validator.apply(req);
+                }
+                let resp = req.send().await?;
+                // missing lyric comes with an `ErrorResponse` body
+                if resp.status() == StatusCode::NOT_FOUND {
+                    return Ok(resp);
+                }
+                check_status(resp)
+            })
+            .await?;
+            let Conditional::Modified {
+                body: resp,
+                validator,
+            } = conditional(resp)
+            else {
+                return Ok(Conditional::NotModified);
+            };
             let result: GetLyricsResponse = resp.json().await?;
             match result {
-                GetLyricsResponse::Success(LyricsData { synced_lyrics, .. }) => Ok(LyricStore {
-                    lyric: synced_lyrics,
-                    tlyric: None,
-                }),
+                GetLyricsResponse::Success(LyricsData {
+                    synced_lyrics,
+                    plain_lyrics,
+                    ..
+                }) => {
+                    Ok(Conditional::Modified {
+                        body: LyricStore {
+                            // instrumental or unsynced tracks only have plain lyrics
+                            lyric: synced_lyrics
+                                .filter(|lyric| !lyric.trim().is_empty())
+                                .or(plain_lyrics),
+                            tlyric: None,
+                            confidence: vec![],
+                        },
+                        validator,
+                    })
+                }
                 GetLyricsResponse::Error(ErrorResponse { message, .. }) => {
                     crate::log::debug!("query failed: {message}");
                     Err(super::Error::NoResult)?
@@ -50,6 +101,7 @@ impl super::LyricProvider for LRCLib {
         })
         .await?
     }
+
     async fn search_song_detailed(
         &self,
         album: &str,
@@ -74,7 +126,7 @@ impl super::LyricProvider for LRCLib {
                 artist.as_deref(),
                 album.as_deref(),
             )?;
-            let resp = REQWEST_CLIENT.get(req.uri().to_string()).send().await?;
+            let resp = get(&req.uri().to_string()).await?;
             let result: Vec<LyricsData> = resp.json().await.map_err(|_| super::Error::NoResult)?;
             Ok(map_lyrics_result(result))
         })
@@ -86,14 +138,14 @@ impl super::LyricProvider for LRCLib {
 
         tokio_spawn!(async move {
             let req = LRCLIB_API_CLIENT.search_lyrics_query(&keyword)?;
-            let resp = REQWEST_CLIENT.get(req.uri().to_string()).send().await?;
+            let resp = get(&req.uri().to_string()).await?;
             let result: Vec<LyricsData> = resp.json().await.map_err(|_| super::Error::NoResult)?;
             Ok(map_lyrics_result(result))
         })
         .await?
     }
     fn unique_name(&self) -> &'static str {
-        "LRCLib"
+        NAME
     }
     fn init(self, _config: &str) -> Result<()> {
         Ok(())
@@ -101,18 +153,25 @@ impl super::LyricProvider for LRCLib {
     fn is_likely_songid(&self, s: &str) -> bool {
         s.parse::<u64>().is_ok()
     }
+    fn source_url(&self, id: &str) -> Option<String> {
+        Some(format!("https://lrclib.net/lyrics/{id}"))
+    }
+}
+
+async fn get(url: &str) -> Result<Response> {
+    with_retry(NAME, move || async move {
+        check_status(REQWEST_CLIENT.get(url).send().await?)
+    })
+    .await
 }
 
 fn verify_lyric(lyric: Option<&str>) -> Lyric<'_> {
     match lyric {
         Some("") | None => super::Lyric::None,
-        Some(lyric) => {
-            if let Ok(parsed) = super::utils::lrc_iter(lyric.lines()) {
-                Lyric::LineTimestamp(parsed)
-            } else {
-                Lyric::NoTimestamp
-            }
-        }
+        Some(lyric) => match super::utils::lrc_iter(lyric.lines()) {
+            Ok(parsed) if !parsed.is_empty() => Lyric::LineTimestamp(parsed),
+            _ => Lyric::NoTimestamp(super::utils::plain_lines(lyric)),
+        },
     }
 }
 
@@ -0,0 +1,271 @@
+//! Providers written in Lua, loaded from `$XDG_CONFIG_HOME/waylyrics/providers/*.lua`.
+//!
+//! A script defines `search(title, artist, album, duration_ms)`, returning
+//! `{ origin = "<lrc>", translation = "<lrc>" }`, or `nil` if nothing was found.
+//! `duration_ms` is `nil` if the player does not report track length.
+//! An optional `confidence = { 0.9, 0.4, ... }` gives how sure the script is about each line
+//! of `origin`, in order of time.
+//!
+//! Scripts run without `io`, `os` and `package`, `http_get(url, headers)` is
+//! provided instead, returning the response body.
+//! A script running longer than `SCRIPT_TIMEOUT`, in Lua or waiting for `http_get`, is stopped.
+//!
+//! Each script is named `lua:<file stem>`, and is queried only if listed in `lyric-search-source`.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use mlua::{Function, HookTriggers, Lua, LuaOptions, StdLib, Table, VmState};
+use once_cell::sync::Lazy;
+use reqwest::Client;
+
+use super::{join_artists, Lyric, LyricOwned, LyricStore, SongInfo};
+use crate::log::{error, info};
+use crate::tokio_spawn;
+
+pub const NAME_PREFIX: &str = "lua:";
+
+/// results kept for `query_lyric`, older ones are dropped beyond this
+const MAX_RESULTS: usize = 32;
+
+/// time a script may run for when loaded, or in each `search`
+pub const SCRIPT_TIMEOUT: Duration = Duration::from_secs(10);
+/// Lua instructions run between checks of the deadline
+const HOOK_INSTRUCTIONS: u32 = 10_000;
+
+static LUA_PROVIDERS: OnceLock<Vec<&'static LuaProvider>> = OnceLock::new();
+
+static HTTP_CLIENT: Lazy<Client> = Lazy::new(|| {
+    Client::builder()
+        .user_agent("Waylyrics/0.1")
+        .build()
+        .unwrap_or_default()
+});
+
+/// `(origin, translation, confidence)` returned by `search`
+type LuaResult = (Option<String>, Option<String>, Vec<f32>);
+
+pub struct LuaProvider {
+    name: &'static str,
+    lua: Lua,
+    /// lyrics returned by `search`, keyed by the song id handed out
+    results: Mutex<HashMap<String, LuaResult>>,
+    next_id: AtomicU64,
+    /// Lua code running past this errors out, pushed back by each `search`
+    deadline: Arc<Mutex<Instant>>,
+}
+
+fn new_sandbox(deadline: Arc<Mutex<Instant>>) -> mlua::Result<Lua> {
+    let lua = Lua::new_with(
+        StdLib::STRING | StdLib::TABLE | StdLib::MATH | StdLib::UTF8,
+        LuaOptions::default(),
+    )?;
+    // a timeout alone cannot stop a busy loop, which never yields back to tokio
+    lua.set_hook(
+        HookTriggers::new().every_nth_instruction(HOOK_INSTRUCTIONS),
+        move |_, _| match deadline.lock() {
+            Ok(deadline) if Instant::now() > *deadline => Err(mlua::Error::runtime(format!(
+                "script ran longer than {SCRIPT_TIMEOUT:?}"
+            ))),
+            _ => Ok(VmState::Continue),
+        },
+    );
+    let http_get = lua.create_async_function(
+        |_, (url, headers): (String, Option<HashMap<String, String>>)| async move {
+            let mut req = HTTP_CLIENT.get(&url);
+            for (name, value) in headers.unwrap_or_default() {
+                req = req.header(name, value);
+            }
+            let resp = req
+                .send()
+                .await
+                .and_then(|resp| resp.error_for_status())
+                .map_err(mlua::Error::external)?;
+            resp.text().await.map_err(mlua::Error::external)
+        },
+    )?;
+    lua.globals().set("http_get", http_get)?;
+    Ok(lua)
+}
+
+impl LuaProvider {
+    pub fn load(path: &Path) -> Result<Self> {
+        let stem = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .ok_or_else(|| anyhow!("invalid script name {path:?}"))?;
+        let deadline = Arc::new(Mutex::new(Instant::now() + SCRIPT_TIMEOUT));
+        let lua = new_sandbox(deadline.clone())?;
+        lua.load(std::fs::read_to_string(path)?)
+            .set_name(stem)
+            .exec()?;
+        lua.globals()
+            .get::<Function>("search")
+            .map_err(|_| anyhow!("{path:?} does not define `search`"))?;
+
+        Ok(Self {
+            name: Box::leak(format!("{NAME_PREFIX}{stem}").into_boxed_str()),
+            lua,
+            results: Mutex::new(HashMap::new()),
+            next_id: AtomicU64::new(0),
+            deadline,
+        })
+    }
+
+    fn store_result(&self, result: LuaResult) -> String {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed).to_string();
+        if let Ok(mut results) = self.results.lock() {
+            if results.len() >= MAX_RESULTS {
+                results.clear();
+            }
+            results.insert(id.clone(), result);
+        }
+        id
+    }
+}
+
+/// Load every `*.lua` in `dir`, scripts failing to load are skipped
+pub fn load_lua_providers(dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut paths: Vec<_> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "lua"))
+        .collect();
+    paths.sort();
+
+    let providers = paths
+        .iter()
+        .filter_map(|path| {
+            LuaProvider::load(path)
+                .inspect(|provider| info!("loaded lua provider {} from {path:?}", provider.name))
+                .inspect_err(|e| error!("cannot load lua provider {path:?}: {e}"))
+                .ok()
+        })
+        .map(|provider| &*Box::leak(Box::new(provider)))
+        .collect();
+    let _ = LUA_PROVIDERS.set(providers);
+}
+
+pub fn find_lua_provider(name: &str) -> Option<&'static LuaProvider> {
+    LUA_PROVIDERS
+        .get()?
+        .iter()
+        .find(|provider| provider.name == name)
+        .copied()
+}
+
+fn parse_lrc(lyric: Option<&str>) -> LyricOwned {
+    match lyric {
+        Some("") | None => LyricOwned::None,
+        Some(lyric) => match super::utils::lrc_iter(lyric.lines()) {
+            Ok(parsed) if !parsed.is_empty() => Lyric::LineTimestamp(parsed).into_owned(),
+            _ => Lyric::NoTimestamp(super::utils::plain_lines(lyric)).into_owned(),
+        },
+    }
+}
+
+impl super::LyricParse for LuaProvider {
+    fn parse_lyric(&self, store: &LyricStore) -> LyricOwned {
+        super::utils::with_confidence(parse_lrc(store.lyric.as_deref()), &store.confidence)
+    }
+
+    fn parse_translated_lyric(&self, store: &LyricStore) -> LyricOwned {
+        parse_lrc(store.tlyric.as_deref())
+    }
+}
+
+#[async_trait::async_trait]
+impl super::LyricProvider for LuaProvider {
+    async fn query_lyric(&self, id: &str) -> Result<LyricStore> {
+        let (lyric, tlyric, confidence) = self
+            .results
+            .lock()
+            .map_err(|_| anyhow!("lua provider results poisoned"))?
+            .get(id)
+            .cloned()
+            .ok_or(super::Error::NoResult)?;
+        Ok(LyricStore {
+            lyric,
+            tlyric,
+            confidence,
+        })
+    }
+
+    async fn search_song_detailed(
+        &self,
+        album: &str,
+        artists: &[&str],
+        title: &str,
+    ) -> Result<Vec<SongInfo>> {
+        self.search_song_with_length(album, artists, title, None)
+            .await
+    }
+
+    async fn search_song_with_length(
+        &self,
+        album: &str,
+        artists: &[&str],
+        title: &str,
+        length: Option<Duration>,
+    ) -> Result<Vec<SongInfo>> {
+        let search: Function = self.lua.globals().get("search")?;
+        let singer = join_artists(artists);
+        let args = (
+            title.to_owned(),
+            singer.clone(),
+            album.to_owned(),
+            length.map(|length| length.as_millis() as u64),
+        );
+        if let Ok(mut deadline) = self.deadline.lock() {
+            *deadline = Instant::now() + SCRIPT_TIMEOUT;
+        }
+        // http_get needs a tokio reactor
+        let result: Option<Table> = tokio_spawn!(async move {
+            tokio::time::timeout(SCRIPT_TIMEOUT, search.call_async(args)).await
+        })
+        .await?
+        .map_err(|_| anyhow!("{} timed out after {SCRIPT_TIMEOUT:?}", self.name))??;
+        let Some(result) = result else {
+            return Err(super::Error::NoResult.into());
+        };
+
+        let origin: Option<String> = result.get("origin")?;
+        let translation: Option<String> = result.get("translation")?;
+        let confidence: Option<Vec<f32>> = result.get("confidence")?;
+        if origin.as_deref().unwrap_or_default().is_empty() {
+            return Err(super::Error::NoResult.into());
+        }
+
+        let id = self.store_result((origin, translation, confidence.unwrap_or_default()));
+        Ok(vec![SongInfo {
+            id,
+            title: title.to_owned(),
+            singer,
+            album: (!album.is_empty()).then(|| album.to_owned()),
+            length: length.unwrap_or_default(),
+        }])
+    }
+
+    async fn search_song(&self, keyword: &str) -> Result<Vec<SongInfo>> {
+        self.search_song_with_length("", &[], keyword, None).await
+    }
+
+    fn unique_name(&self) -> &'static str {
+        self.name
+    }
+
+    fn init(self, _config: &str) -> Result<()> {
+        Ok(())
+    }
+
+    fn is_likely_songid(&self, _s: &str) -> bool {
+        false
+    }
+}
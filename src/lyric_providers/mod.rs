@@ -2,20 +2,35 @@ pub mod utils;
 use anyhow::Result;
 use strum::EnumIs;
 
+#[cfg(feature = "acoustid")]
+pub mod acoustid;
+pub mod auth;
+pub mod check;
+pub mod command;
 pub mod dict;
+pub mod group;
 
+pub mod local_files;
 pub mod lrclib;
+#[cfg(feature = "lua-provider")]
+pub mod lua;
+pub mod mpris_native;
 pub mod netease;
 pub mod qqmusic;
+pub mod router;
+#[cfg(feature = "subsonic-provider")]
+pub mod subsonic;
 
 use std::{fmt::Debug, time::Duration};
 
 use serde::{Deserialize, Serialize};
+use utils::http_cache::{Conditional, Validator};
 
 #[derive(Debug)]
 pub enum Lyric<'a> {
     None,
-    NoTimestamp,
+    /// plain text lines, see `utils::plain_lines`
+    NoTimestamp(Vec<&'a str>),
     LineTimestamp(Vec<LyricLine<'a>>),
 }
 
@@ -25,19 +40,22 @@ pub struct LyricLine<'a> {
     pub start_time: Duration,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Default, EnumIs)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Default, EnumIs)]
 #[serde(tag = "type", content = "content")]
 pub enum LyricOwned {
     #[default]
     None,
-    NoTimestamp,
+    NoTimestamp(Vec<String>),
     LineTimestamp(Vec<LyricLineOwned>),
 }
 
-#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
 pub struct LyricLineOwned {
     pub text: String,
     pub start_time: Duration,
+    /// how sure the provider is about this line, from 0 to 1. `None` if it didn't tell
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f32>,
 }
 
 #[derive(Debug)]
@@ -52,16 +70,47 @@ pub struct SongInfo {
 #[async_trait::async_trait]
 pub trait LyricProvider: LyricParse + Send + Sync {
     async fn query_lyric(&self, id: &str) -> Result<LyricStore>;
+    /// Like `query_lyric`, but sends `validator` along with the request.
+    ///
+    /// Providers not supporting HTTP caching always return `Conditional::Modified`
+    async fn query_lyric_conditional(
+        &self,
+        id: &str,
+        _validator: Option<&Validator>,
+    ) -> Result<Conditional<LyricStore>> {
+        Ok(Conditional::Modified {
+            body: self.query_lyric(id).await?,
+            validator: None,
+        })
+    }
     async fn search_song_detailed(
         &self,
         album: &str,
         artists: &[&str],
         title: &str,
     ) -> Result<Vec<SongInfo>>;
+    /// Like `search_song_detailed`, for providers which also make use of track length
+    async fn search_song_with_length(
+        &self,
+        album: &str,
+        artists: &[&str],
+        title: &str,
+        _length: Option<Duration>,
+    ) -> Result<Vec<SongInfo>> {
+        self.search_song_detailed(album, artists, title).await
+    }
     async fn search_song(&self, keyword: &str) -> Result<Vec<SongInfo>>;
     fn unique_name(&self) -> &'static str;
     fn init(self, config: &str) -> Result<()>;
     fn is_likely_songid(&self, s: &str) -> bool;
+    /// canonical page of lyric `id` on the provider's website, where errors can be reported
+    fn source_url(&self, _id: &str) -> Option<String> {
+        None
+    }
+    /// whether to query this provider when fetching lyric automatically
+    fn is_available(&self) -> bool {
+        true
+    }
 }
 
 impl Debug for dyn LyricProvider {
@@ -79,7 +128,9 @@ impl Lyric<'_> {
     pub fn into_owned(self) -> LyricOwned {
         match self {
             Lyric::None => LyricOwned::None,
-            Lyric::NoTimestamp => LyricOwned::NoTimestamp,
+            Lyric::NoTimestamp(lines) => {
+                LyricOwned::NoTimestamp(lines.into_iter().map(Into::into).collect())
+            }
             Lyric::LineTimestamp(lyrics) => LyricOwned::LineTimestamp(
                 lyrics
                     .into_iter()
@@ -95,6 +146,7 @@ impl LyricLine<'_> {
         LyricLineOwned {
             text: text.into(),
             start_time,
+            confidence: None,
         }
     }
 }
@@ -102,6 +154,18 @@ impl LyricLine<'_> {
 pub struct LyricStore {
     lyric: Option<String>,
     tlyric: Option<String>,
+    /// confidence of each line of `lyric` once parsed, empty if the provider has none
+    confidence: Vec<f32>,
+}
+
+impl LyricStore {
+    pub fn new(lyric: Option<String>, tlyric: Option<String>) -> Self {
+        Self {
+            lyric,
+            tlyric,
+            confidence: vec![],
+        }
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -110,6 +174,33 @@ pub enum Error {
     NoResult,
 }
 
+/// separator between artists shown to, or entered by users
+///
+/// `/` is not used since it appears in names like `AC/DC`
+pub const ARTIST_SEPARATOR: &str = " & ";
+
+/// artists for display, e.g. in the search window
+pub fn join_artists(artists: &[impl AsRef<str>]) -> String {
+    artists
+        .iter()
+        .map(AsRef::as_ref)
+        .collect::<Vec<&str>>()
+        .join(ARTIST_SEPARATOR)
+}
+
+/// reverse of `join_artists`, empty names are dropped
+///
+/// only the full separator splits, so an `&` not surrounded by spaces stays in the name
+pub fn split_artists(artists: &str) -> Vec<String> {
+    artists
+        .split(ARTIST_SEPARATOR)
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
+
+/// keyword for providers searching with a single string, each artist is a separate term
 pub fn default_search_query(album: &str, artists: &[&str], title: &str) -> String {
-    format!("{title} {album} {}", artists.join("/"))
+    format!("{title} {album} {}", artists.join(" "))
 }
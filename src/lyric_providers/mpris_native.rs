@@ -0,0 +1,36 @@
+//! Lyrics embedded by the player itself in `xesam:asText` of MPRIS metadata
+//!
+//! Tried before any lyric provider, and needs no network access.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::lyric_providers::utils::{lrc_iter, plain_lines};
+use crate::lyric_providers::{Lyric, LyricOwned};
+
+/// metadata key of lyrics as defined by the xesam ontology
+pub const AS_TEXT: &str = "xesam:asText";
+
+static CACHE_PLAYER_LYRIC: AtomicBool = AtomicBool::new(false);
+
+/// Whether lyrics from `xesam:asText` are written to the lyric cache
+pub fn set_cache_player_lyric(enabled: bool) {
+    CACHE_PLAYER_LYRIC.store(enabled, Ordering::Relaxed);
+}
+
+pub fn cache_player_lyric() -> bool {
+    CACHE_PLAYER_LYRIC.load(Ordering::Relaxed)
+}
+
+/// Parse `xesam:asText` as LRC, or `LyricOwned::NoTimestamp` if it has no timestamp.
+///
+/// `None` if there's no text at all.
+pub fn parse_as_text(text: &str) -> Option<LyricOwned> {
+    let text = text.trim_start_matches('\u{feff}');
+    if text.trim().is_empty() {
+        return None;
+    }
+    match lrc_iter(text.lines().map(str::trim_start)) {
+        Ok(lines) if !lines.is_empty() => Some(Lyric::LineTimestamp(lines).into_owned()),
+        _ => Some(Lyric::NoTimestamp(plain_lines(text)).into_owned()),
+    }
+}
@@ -11,12 +11,79 @@ use ncmapi::types::{LyricResp, SearchSongResp};
 use crate::tokio_spawn;
 
 use super::{
-    default_search_query, dict::ARTIST_ALIAS_MAP, Lyric, LyricLineOwned, LyricOwned, LyricStore,
+    default_search_query, dict::ARTIST_ALIAS_MAP, utils::retry::with_retry, Lyric, LyricLineOwned,
+    LyricOwned, LyricStore,
 };
 
 #[derive(Clone, Copy)]
 pub struct Netease;
 
+pub const NAME: &str = "网易云音乐";
+
+/// Track of a playlist, see `fetch_playlist_tracks`
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlaylistTrack {
+    pub id: String,
+    pub title: String,
+    pub artists: Vec<String>,
+    pub album: Option<String>,
+    pub length: Duration,
+}
+
+/// Tracks in `tracks` of a `playlist_detail` response
+pub fn parse_playlist_tracks(detail: &serde_json::Value) -> Result<Vec<PlaylistTrack>> {
+    let tracks = detail
+        .pointer("/playlist/tracks")
+        .and_then(serde_json::Value::as_array)
+        .ok_or(super::Error::NoResult)?;
+    let names = |value: Option<&serde_json::Value>| {
+        value
+            .and_then(serde_json::Value::as_array)
+            .map(|artists| {
+                artists
+                    .iter()
+                    .filter_map(|artist| artist.get("name")?.as_str())
+                    .map(ToOwned::to_owned)
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default()
+    };
+    Ok(tracks
+        .iter()
+        .filter_map(|track| {
+            Some(PlaylistTrack {
+                id: track.get("id")?.as_u64()?.to_string(),
+                title: track.get("name")?.as_str()?.to_owned(),
+                artists: names(track.get("ar")),
+                album: track
+                    .pointer("/al/name")
+                    .and_then(serde_json::Value::as_str)
+                    .map(ToOwned::to_owned),
+                length: Duration::from_millis(
+                    track
+                        .get("dt")
+                        .and_then(serde_json::Value::as_u64)
+                        .unwrap_or_default(),
+                ),
+            })
+        })
+        .collect())
+}
+
+/// Tracks of the playlist `id`, not part of `LyricProvider` as playlists are NetEase only
+pub async fn fetch_playlist_tracks(id: &str) -> Result<Vec<PlaylistTrack>> {
+    let id: usize = id.trim().parse()?;
+    tokio_spawn!(async move {
+        let api = &NcmApi::new(false, "");
+        let detail = with_retry(NAME, move || async move {
+            Ok(api.playlist_detail(id, None).await?)
+        })
+        .await?;
+        parse_playlist_tracks(&detail.deserialize()?)
+    })
+    .await?
+}
+
 #[async_trait::async_trait]
 impl super::LyricProvider for Netease {
     fn init(self, _config: &str) -> Result<()> {
@@ -24,7 +91,7 @@ impl super::LyricProvider for Netease {
     }
 
     fn unique_name(&self) -> &'static str {
-        "网易云音乐"
+        NAME
     }
 
     async fn search_song_detailed(
@@ -44,9 +111,10 @@ impl super::LyricProvider for Netease {
     async fn query_lyric(&self, id: &str) -> Result<LyricStore> {
         let id = id.to_owned();
         tokio_spawn!(async move {
-            let api = NcmApi::new(false, "");
+            let api = &NcmApi::new(false, "");
             let id = id.parse()?;
-            let query_result = api.lyric(id).await?;
+            let query_result =
+                with_retry(NAME, move || async move { Ok(api.lyric(id).await?) }).await?;
 
             let lyric_resp: LyricResp = query_result.deserialize()?;
 
@@ -55,6 +123,7 @@ impl super::LyricProvider for Netease {
             Ok(LyricStore {
                 lyric: lyric_resp.lrc.map(|l| l.lyric),
                 tlyric: lyric_resp.tlyric.map(|l| l.lyric),
+                confidence: vec![],
             })
         })
         .await?
@@ -65,8 +134,14 @@ impl super::LyricProvider for Netease {
         tokio_spawn!(async move {
             crate::log::debug!("search keyword: {keyword}");
 
-            let api = NcmApi::new(false, "");
-            let search_result = api.search(&keyword, None).await?;
+            let api = &NcmApi::new(false, "");
+            let keyword = &keyword;
+            let search_result =
+                with_retry(
+                    NAME,
+                    move || async move { Ok(api.search(keyword, None).await?) },
+                )
+                .await?;
             let resp: SearchSongResp = search_result.deserialize()?;
             crate::log::debug!("search result: {resp:?}");
 
@@ -108,6 +183,10 @@ impl super::LyricProvider for Netease {
     fn is_likely_songid(&self, s: &str) -> bool {
         s.parse::<u32>().is_ok()
     }
+
+    fn source_url(&self, id: &str) -> Option<String> {
+        Some(format!("https://music.163.com/#/song?id={id}"))
+    }
 }
 
 impl super::LyricParse for Netease {
@@ -135,6 +214,7 @@ impl super::LyricParse for Netease {
                 tlyric.push(LyricLineOwned {
                     text: String::default(),
                     start_time: next_line_time,
+                    confidence: None,
                 });
             }
         }
@@ -145,12 +225,9 @@ impl super::LyricParse for Netease {
 fn verify_lyric(lyric: Option<&str>) -> Lyric<'_> {
     match lyric {
         Some("") | None => super::Lyric::None,
-        Some(lyric) => {
-            if let Ok(parsed) = super::utils::lrc_iter(lyric.lines()) {
-                Lyric::LineTimestamp(parsed)
-            } else {
-                Lyric::NoTimestamp
-            }
-        }
+        Some(lyric) => match super::utils::lrc_iter(lyric.lines()) {
+            Ok(parsed) if !parsed.is_empty() => Lyric::LineTimestamp(parsed),
+            _ => Lyric::NoTimestamp(super::utils::plain_lines(lyric)),
+        },
     }
 }
@@ -12,7 +12,12 @@ use reqwest::Client;
 use url::Url;
 
 use crate::{
-    lyric_providers::{default_search_query, SongInfo},
+    lyric_providers::{
+        auth, default_search_query,
+        utils::http_cache::{conditional, Conditional, Validator},
+        utils::retry::{check_status, with_retry},
+        SongInfo,
+    },
     tokio_spawn,
 };
 
@@ -24,6 +29,8 @@ pub use typo::QQMusicConfig;
 #[derive(Clone, Copy)]
 pub struct QQMusic;
 
+const NAME: &str = "QQ音乐";
+
 #[async_trait::async_trait]
 impl super::LyricProvider for QQMusic {
     fn init(self, config: &str) -> Result<()> {
@@ -41,7 +48,9 @@ impl super::LyricProvider for QQMusic {
             async fn login_qqmusic(cookies: &str, api: &QQMusicApi) -> Result<()> {
                 let req = api.set_cookie(cookies)?;
                 let reqw_req = reqwest::Request::try_from(req)?;
-                let client = Client::builder().user_agent("Waylyrics/0.1").build()?;
+                let client = auth::client_builder(NAME)
+                    .user_agent("Waylyrics/0.1")
+                    .build()?;
                 client.execute(reqw_req).await?;
                 Ok(())
             }
@@ -56,7 +65,7 @@ impl super::LyricProvider for QQMusic {
     }
 
     fn unique_name(&self) -> &'static str {
-        "QQ音乐"
+        NAME
     }
 
     async fn search_song_detailed(
@@ -70,9 +79,20 @@ impl super::LyricProvider for QQMusic {
     }
 
     async fn query_lyric(&self, id: &str) -> Result<LyricStore> {
+        self.query_lyric_conditional(id, None).await?.into_body()
+    }
+
+    async fn query_lyric_conditional(
+        &self,
+        id: &str,
+        validator: Option<&Validator>,
+    ) -> Result<Conditional<LyricStore>> {
         let id = id.to_owned();
+        let validator = validator.cloned();
         tokio_spawn!(async move {
-            let client = Client::builder().user_agent("Waylyrics/0.1").build()?;
+            let client = auth::client_builder(NAME)
+                .user_agent("Waylyrics/0.1")
+                .build()?;
 
             // might be a little tricky
             let songid = if id.parse::<usize>().is_ok() {
@@ -91,19 +111,42 @@ impl super::LyricProvider for QQMusic {
             };
 
             let url = api.query_lyric(&mid)?.uri().to_string();
-            let resp: QueryLyricResp =
-                serde_json::from_slice(client.get(url).send().await?.bytes().await?.as_ref())?;
+            let (client, url, validator) = (&client, &url, &validator);
+            let resp = with_retry(NAME, move || async move {
+                let mut req = client.get(url);
+                if let Some(validator) = validator {
+                    req = validator.apply(req);
+                }
+                check_status(req.send().await?)
+            })
+            .await?;
+            let Conditional::Modified {
+                body: resp,
+                validator,
+            } = conditional(resp)
+            else {
+                return Ok(Conditional::NotModified);
+            };
+            let resp: QueryLyricResp = serde_json::from_slice(&resp.bytes().await?)?;
 
             if resp.data.code == -1901 {
-                return Ok(LyricStore {
-                    lyric: None,
-                    tlyric: None,
+                return Ok(Conditional::Modified {
+                    body: LyricStore {
+                        lyric: None,
+                        tlyric: None,
+                        confidence: vec![],
+                    },
+                    validator: None,
                 });
             }
 
-            Ok(LyricStore {
-                lyric: Some(resp.data.lyric),
-                tlyric: Some(resp.data.trans),
+            Ok(Conditional::Modified {
+                body: LyricStore {
+                    lyric: Some(resp.data.lyric),
+                    tlyric: Some(resp.data.trans),
+                    confidence: vec![],
+                },
+                validator,
             })
         })
         .await?
@@ -114,7 +157,9 @@ impl super::LyricProvider for QQMusic {
         tokio_spawn!(async move {
             crate::log::debug!("search keyword: {keyword}");
 
-            let client = Client::builder().user_agent("Waylyrics/0.1").build()?;
+            let client = auth::client_builder(NAME)
+                .user_agent("Waylyrics/0.1")
+                .build()?;
 
             let Some(Some(api)) = QQMUSIC_API_CLIENT.get() else {
                 return Err(Error::ApiClientNotInit)?;
@@ -122,7 +167,7 @@ impl super::LyricProvider for QQMusic {
 
             let url = api.search::<Track>(&keyword, None, None)?.uri().to_string();
             let resp: <Track as SearchType>::Resp =
-                serde_json::from_slice(client.get(url).send().await?.bytes().await?.as_ref())?;
+                serde_json::from_slice(&get_bytes(&client, &url).await?)?;
 
             Ok(resp
                 .data
@@ -152,15 +197,29 @@ impl super::LyricProvider for QQMusic {
     fn is_likely_songid(&self, s: &str) -> bool {
         (s.len() == 14 && s.starts_with('0')) || s.parse::<usize>().is_ok()
     }
+
+    /// song pages are addressed by songmid only
+    fn source_url(&self, id: &str) -> Option<String> {
+        (id.parse::<usize>().is_err()).then(|| format!("https://y.qq.com/n/ryqq/songDetail/{id}"))
+    }
 }
 
 async fn get_songmid(api: &QQMusicApi, client: &Client, songid: &str) -> Result<String> {
     let url = api.song_detail(SongId::Songid(songid))?.uri().to_string();
-    let resp: SongDetailResp =
-        serde_json::from_slice(client.get(url).send().await?.bytes().await?.as_ref())?;
+    let resp: SongDetailResp = serde_json::from_slice(&get_bytes(client, &url).await?)?;
     Ok(resp.data.track_info.mid)
 }
 
+async fn get_bytes(client: &Client, url: &str) -> Result<Vec<u8>> {
+    with_retry(NAME, move || async move {
+        Ok(check_status(client.get(url).send().await?)?
+            .bytes()
+            .await?
+            .to_vec())
+    })
+    .await
+}
+
 impl super::LyricParse for QQMusic {
     fn parse_lyric(&self, store: &LyricStore) -> LyricOwned {
         let lyric = store.lyric.as_deref();
@@ -184,10 +243,9 @@ fn verify_lyric(lyric: Option<&str>) -> LyricOwned {
                 .replace("&quot;", "\"")
                 .replace("&apos;", "\'");
 
-            if let Ok(parsed) = super::utils::lrc_iter(lyric.lines()) {
-                Lyric::LineTimestamp(parsed).into_owned()
-            } else {
-                LyricOwned::NoTimestamp
+            match super::utils::lrc_iter(lyric.lines()) {
+                Ok(parsed) if !parsed.is_empty() => Lyric::LineTimestamp(parsed).into_owned(),
+                _ => Lyric::NoTimestamp(super::utils::plain_lines(&lyric)).into_owned(),
             }
         }
     }
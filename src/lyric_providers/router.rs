@@ -0,0 +1,151 @@
+//! Search providers preferred for the genre of the track first, see `provider-genre-preference`.
+//!
+//! The remaining providers are only searched if the preferred ones found nothing.
+//! Highly rated tracks search `preferred-providers-for-rated` before those,
+//! if `boost-rated-tracks` is on.
+
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+use super::LyricProvider;
+
+/// lowest `xesam:userRating` boosted, 5 stars in players rating by stars
+pub const RATED_THRESHOLD: f64 = 0.8;
+
+static GENRE_PREFERENCE: OnceLock<BTreeMap<String, Vec<String>>> = OnceLock::new();
+static RATED_PREFERENCE: OnceLock<Vec<String>> = OnceLock::new();
+
+pub fn set_genre_preference(preference: BTreeMap<String, Vec<String>>) {
+    let _ = GENRE_PREFERENCE.set(preference);
+}
+
+/// Nothing is boosted unless `boost` is on
+pub fn set_rated_preference(boost: bool, providers: Vec<String>) {
+    if boost {
+        let _ = RATED_PREFERENCE.set(providers);
+    }
+}
+
+pub fn is_highly_rated(user_rating: Option<f64>) -> bool {
+    user_rating.is_some_and(|rating| rating >= RATED_THRESHOLD)
+}
+
+/// Whether `text` matches `pattern` ignoring case, `*` matches any characters and `?` one
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().flat_map(char::to_lowercase).collect();
+    let text: Vec<char> = text.chars().flat_map(char::to_lowercase).collect();
+
+    // position of the last `*` and where it started matching in `text`
+    let mut star: Option<(usize, usize)> = None;
+    let (mut p, mut t) = (0, 0);
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                // let the `*` eat one more character
+                Some((star_p, star_t)) => {
+                    star = Some((star_p, star_t + 1));
+                    p = star_p + 1;
+                    t = star_t + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Providers of every pattern in `preference` matching any of `genres`, without duplicates
+pub fn preferred_providers<'a>(
+    preference: &'a BTreeMap<String, Vec<String>>,
+    genres: &[String],
+) -> Vec<&'a str> {
+    let mut preferred: Vec<&str> = vec![];
+    for (pattern, providers) in preference {
+        if !genres.iter().any(|genre| glob_match(pattern, genre)) {
+            continue;
+        }
+        for provider in providers {
+            if !preferred.contains(&provider.as_str()) {
+                preferred.push(provider);
+            }
+        }
+    }
+    preferred
+}
+
+/// Indexes of `providers` named in each of `preferred`, then the rest, skipping empty groups
+fn route_preferred(providers: &[&dyn LyricProvider], preferred: &[Vec<&str>]) -> Vec<Vec<usize>> {
+    let mut routed: Vec<usize> = vec![];
+    let mut tiers = vec![];
+    for names in preferred {
+        let tier: Vec<usize> = names
+            .iter()
+            .filter_map(|name| {
+                providers
+                    .iter()
+                    .position(|provider| provider.unique_name() == *name)
+            })
+            .filter(|idx| !routed.contains(idx))
+            .collect();
+        if !tier.is_empty() {
+            routed.extend(&tier);
+            tiers.push(tier);
+        }
+    }
+    let rest: Vec<usize> = (0..providers.len())
+        .filter(|idx| !routed.contains(idx))
+        .collect();
+    if !rest.is_empty() || tiers.is_empty() {
+        tiers.push(rest);
+    }
+    tiers
+}
+
+/// Indexes of `providers` in groups to search one after another, until one finds lyric.
+///
+/// A single group of all `providers` if none is preferred for `genres`.
+pub fn route_with(
+    preference: &BTreeMap<String, Vec<String>>,
+    providers: &[&dyn LyricProvider],
+    genres: Option<&[String]>,
+) -> Vec<Vec<usize>> {
+    let preferred = preferred_providers(preference, genres.unwrap_or_default());
+    route_preferred(providers, &[preferred])
+}
+
+/// `route_with`, searching `rated` providers before all others
+pub fn route_rated_with(
+    rated: &[String],
+    preference: &BTreeMap<String, Vec<String>>,
+    providers: &[&dyn LyricProvider],
+    genres: Option<&[String]>,
+) -> Vec<Vec<usize>> {
+    let rated = rated.iter().map(String::as_str).collect();
+    let preferred = preferred_providers(preference, genres.unwrap_or_default());
+    route_preferred(providers, &[rated, preferred])
+}
+
+/// `route_with` the configured `provider-genre-preference`, boosting tracks rated at least
+/// `RATED_THRESHOLD` by `user_rating` if `boost-rated-tracks` is on
+pub fn route(
+    providers: &[&dyn LyricProvider],
+    genres: Option<&[String]>,
+    user_rating: Option<f64>,
+) -> Vec<Vec<usize>> {
+    let no_preference = BTreeMap::new();
+    let preference = GENRE_PREFERENCE.get().unwrap_or(&no_preference);
+    match RATED_PREFERENCE.get() {
+        Some(rated) if is_highly_rated(user_rating) => {
+            route_rated_with(rated, preference, providers, genres)
+        }
+        _ => route_with(preference, providers, genres),
+    }
+}
@@ -0,0 +1,325 @@
+//! Lyrics stored on a Subsonic compatible server like Navidrome, see `[providers.subsonic]`.
+//!
+//! Songs are found by `search3`. Their lyrics are fetched by `getLyricsBySongId` of the
+//! OpenSubsonic extension, or by `getLyrics` with artist and title on servers without it.
+//! Requests are authenticated by a salted MD5 token of the password, never the password itself.
+
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, Result};
+use once_cell::sync::Lazy;
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::mpris_native::parse_as_text;
+use super::utils::retry::{check_status, with_retry};
+use super::{auth, LyricOwned, LyricStore, SongInfo};
+use crate::config::SubsonicConfig;
+use crate::log::debug;
+use crate::sync::actions::utils::make_lrc_line;
+use crate::tokio_spawn;
+
+/// name to list in `lyric-search-source`
+pub const NAME: &str = "Subsonic";
+
+const API_VERSION: &str = "1.16.1";
+const CLIENT_NAME: &str = "waylyrics";
+/// songs asked from `search3` per search
+const SEARCH_COUNT: &str = "20";
+/// error code of Subsonic for a method the server doesn't implement
+const ERROR_NOT_FOUND: i64 = 70;
+
+static SUBSONIC_PROVIDER: OnceLock<Subsonic> = OnceLock::new();
+static REQWEST_CLIENT: Lazy<Client> = Lazy::new(|| {
+    auth::client_builder(NAME)
+        .build()
+        .unwrap_or_else(|_| Client::default())
+});
+
+pub struct Subsonic {
+    url: String,
+    username: String,
+    password: String,
+}
+
+/// Empty `url` disables the provider
+pub fn set_subsonic_server(config: &SubsonicConfig) {
+    let url = config.url.trim().trim_end_matches('/');
+    if !url.is_empty() {
+        let _ = SUBSONIC_PROVIDER.set(Subsonic {
+            url: url.to_owned(),
+            username: config.username.clone(),
+            password: config.password.clone(),
+        });
+    }
+}
+
+pub fn subsonic_provider() -> Option<&'static Subsonic> {
+    SUBSONIC_PROVIDER.get()
+}
+
+/// `t` parameter of token authentication, MD5 of `password` followed by `salt` in hex
+pub fn auth_token(password: &str, salt: &str) -> String {
+    format!("{:x}", md5::compute(format!("{password}{salt}")))
+}
+
+/// different for each request, so that a leaked token cannot be replayed
+fn new_salt() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let digest = md5::compute(format!("{nanos}-{}", std::process::id()));
+    format!("{digest:x}")[..12].to_owned()
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SubsonicError {
+    pub code: i64,
+    #[serde(default)]
+    pub message: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Song {
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub artist: Option<String>,
+    #[serde(default)]
+    pub album: Option<String>,
+    /// in seconds
+    #[serde(default)]
+    pub duration: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StructuredLyrics {
+    #[serde(default)]
+    pub lang: Option<String>,
+    pub synced: bool,
+    #[serde(default)]
+    pub line: Vec<Line>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Line {
+    /// in milliseconds, missing for unsynced lyrics
+    #[serde(default)]
+    pub start: Option<u64>,
+    #[serde(default)]
+    pub value: String,
+}
+
+/// Body of `subsonic-response` in `response`, or the error the server replied with
+pub fn into_response(mut response: Value) -> Result<Value> {
+    let body = response
+        .get_mut("subsonic-response")
+        .map(Value::take)
+        .ok_or_else(|| anyhow!("not a subsonic response"))?;
+    if body.get("status").and_then(Value::as_str) == Some("ok") {
+        return Ok(body);
+    }
+    match body
+        .get("error")
+        .and_then(|error| SubsonicError::deserialize(error).ok())
+    {
+        Some(error) => Err(anyhow!("subsonic error {}: {}", error.code, error.message)),
+        None => Err(anyhow!("subsonic request failed")),
+    }
+}
+
+fn is_not_found(e: &anyhow::Error) -> bool {
+    e.to_string()
+        .starts_with(&format!("subsonic error {ERROR_NOT_FOUND}:"))
+}
+
+/// Songs in the `search3` response `body`
+pub fn parse_search_result(body: &Value) -> Vec<SongInfo> {
+    let Some(songs) = body.pointer("/searchResult3/song") else {
+        return vec![];
+    };
+    Vec::<Song>::deserialize(songs)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|song| SongInfo {
+            id: song.id,
+            title: song.title,
+            singer: song.artist.unwrap_or_default(),
+            album: song.album,
+            length: Duration::from_secs(song.duration.unwrap_or_default()),
+        })
+        .collect()
+}
+
+/// LRC or plain text of `lyrics`
+pub fn structured_to_text(lyrics: &StructuredLyrics) -> String {
+    lyrics
+        .line
+        .iter()
+        .map(|line| match line.start {
+            Some(start) if lyrics.synced => {
+                make_lrc_line(&line.value, Duration::from_millis(start))
+            }
+            _ => line.value.clone(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Lyric and translation in the `getLyricsBySongId` response `body`.
+///
+/// Synced lyrics are preferred, another one of a different language is taken as translation.
+pub fn parse_structured_lyrics(body: &Value) -> LyricStore {
+    let lyrics = body
+        .pointer("/lyricsList/structuredLyrics")
+        .and_then(|lyrics| Vec::<StructuredLyrics>::deserialize(lyrics).ok())
+        .unwrap_or_default();
+    let origin = lyrics
+        .iter()
+        .find(|lyrics| lyrics.synced)
+        .or_else(|| lyrics.first());
+    let translation = origin.and_then(|origin| {
+        lyrics
+            .iter()
+            .find(|lyrics| lyrics.synced && origin.synced && lyrics.lang != origin.lang)
+    });
+    LyricStore {
+        lyric: origin.map(structured_to_text),
+        tlyric: translation.map(structured_to_text),
+        confidence: vec![],
+    }
+}
+
+impl Subsonic {
+    async fn call(&self, method: &str, params: &[(&str, &str)]) -> Result<Value> {
+        let url = format!("{}/rest/{method}.view", self.url);
+        let salt = new_salt();
+        let token = auth_token(&self.password, &salt);
+        let query = [
+            ("u", self.username.as_str()),
+            ("t", token.as_str()),
+            ("s", salt.as_str()),
+            ("v", API_VERSION),
+            ("c", CLIENT_NAME),
+            ("f", "json"),
+        ]
+        .into_iter()
+        .chain(params.iter().copied())
+        .map(|(key, value)| (key.to_owned(), value.to_owned()))
+        .collect::<Vec<_>>();
+
+        let response: Value = tokio_spawn!(async move {
+            let (url, query) = (&url, &query);
+            with_retry(NAME, move || async move {
+                check_status(REQWEST_CLIENT.get(url).query(query).send().await?)
+            })
+            .await?
+            .json::<Value>()
+            .await
+            .map_err(anyhow::Error::from)
+        })
+        .await??;
+        into_response(response)
+    }
+
+    /// plain lyric by `getLyrics`, for servers without OpenSubsonic
+    async fn query_lyric_by_title(&self, id: &str) -> Result<LyricStore> {
+        let song = self.call("getSong", &[("id", id)]).await?;
+        let song = song
+            .get("song")
+            .and_then(|song| Song::deserialize(song).ok())
+            .ok_or(super::Error::NoResult)?;
+        let artist = song.artist.unwrap_or_default();
+        let body = self
+            .call("getLyrics", &[("artist", &artist), ("title", &song.title)])
+            .await?;
+        let lyric = body
+            .pointer("/lyrics/value")
+            .and_then(Value::as_str)
+            .filter(|lyric| !lyric.trim().is_empty())
+            .ok_or(super::Error::NoResult)?;
+        Ok(LyricStore {
+            lyric: Some(lyric.to_owned()),
+            tlyric: None,
+            confidence: vec![],
+        })
+    }
+}
+
+impl super::LyricParse for Subsonic {
+    fn parse_lyric(&self, store: &LyricStore) -> LyricOwned {
+        store
+            .lyric
+            .as_deref()
+            .and_then(parse_as_text)
+            .unwrap_or_default()
+    }
+
+    fn parse_translated_lyric(&self, store: &LyricStore) -> LyricOwned {
+        store
+            .tlyric
+            .as_deref()
+            .and_then(parse_as_text)
+            .unwrap_or_default()
+    }
+}
+
+#[async_trait::async_trait]
+impl super::LyricProvider for Subsonic {
+    async fn query_lyric(&self, id: &str) -> Result<LyricStore> {
+        match self.call("getLyricsBySongId", &[("id", id)]).await {
+            Ok(body) => {
+                let store = parse_structured_lyrics(&body);
+                if store.lyric.is_none() {
+                    return Err(super::Error::NoResult.into());
+                }
+                Ok(store)
+            }
+            Err(e) if is_not_found(&e) => {
+                debug!("{e}, falling back to getLyrics");
+                self.query_lyric_by_title(id).await
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn search_song_detailed(
+        &self,
+        _album: &str,
+        _artists: &[&str],
+        title: &str,
+    ) -> Result<Vec<SongInfo>> {
+        // not every server matches artists in the query, results are ranked by them anyway
+        self.search_song(title).await
+    }
+
+    async fn search_song(&self, keyword: &str) -> Result<Vec<SongInfo>> {
+        let body = self
+            .call(
+                "search3",
+                &[
+                    ("query", keyword),
+                    ("songCount", SEARCH_COUNT),
+                    ("artistCount", "0"),
+                    ("albumCount", "0"),
+                ],
+            )
+            .await?;
+        Ok(parse_search_result(&body))
+    }
+
+    fn unique_name(&self) -> &'static str {
+        NAME
+    }
+
+    fn init(self, _config: &str) -> Result<()> {
+        Ok(())
+    }
+
+    fn is_likely_songid(&self, _s: &str) -> bool {
+        false
+    }
+}
@@ -0,0 +1,126 @@
+//! Parser of ASS/SSA subtitles, as distributed by fansub groups
+
+use std::time::Duration;
+
+use crate::lyric_providers::LyricLineOwned;
+
+/// events of these styles are translation, compared case-insensitively
+pub const TRANSLATION_STYLES: [&str; 2] = ["Translation", "Romaji"];
+
+/// field order of `Dialogue:` events if `[Events]` has no `Format:` line
+const DEFAULT_FORMAT: [&str; 10] = [
+    "Layer", "Start", "End", "Style", "Name", "MarginL", "MarginR", "MarginV", "Effect", "Text",
+];
+
+#[derive(Debug, thiserror::Error)]
+pub enum AssError {
+    #[error("no [Events] section")]
+    NoEvents,
+    #[error("missing {0} field in Format line")]
+    MissingField(&'static str),
+    #[error("invalid timestamp {0:?} at line {1}")]
+    InvalidTimestamp(String, usize),
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct AssLyric {
+    pub origin: Vec<LyricLineOwned>,
+    /// events of `TRANSLATION_STYLES`
+    pub translation: Vec<LyricLineOwned>,
+}
+
+/// Parse ASS timestamp `H:MM:SS.cc`
+pub fn parse_timestamp(timestamp: &str) -> Option<Duration> {
+    let mut parts = timestamp.trim().splitn(3, ':');
+    let hours: u64 = parts.next()?.parse().ok()?;
+    let minutes: u64 = parts.next()?.parse().ok()?;
+    let secs = parts.next()?;
+    let (secs, centis) = secs.split_once('.').unwrap_or((secs, "0"));
+    let secs: u64 = secs.parse().ok()?;
+    let centis: u64 = centis.parse().ok()?;
+    Some(Duration::from_millis(
+        ((hours * 60 + minutes) * 60 + secs) * 1000 + centis * 10,
+    ))
+}
+
+/// Remove override tags like `{\an8\c&H00FFFF&}`, and turn line breaks into spaces
+pub fn strip_tags(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut in_tag = false;
+    for c in text.chars() {
+        match c {
+            '{' => in_tag = true,
+            '}' if in_tag => in_tag = false,
+            _ if !in_tag => stripped.push(c),
+            _ => (),
+        }
+    }
+    let stripped = stripped
+        .replace("\\N", " ")
+        .replace("\\n", " ")
+        .replace("\\h", " ");
+    stripped.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+pub fn parse_ass(ass: &str) -> Result<AssLyric, AssError> {
+    let mut in_events = false;
+    let mut found_events = false;
+    let mut format: Vec<String> = DEFAULT_FORMAT.iter().map(|f| f.to_string()).collect();
+    let mut lyric = AssLyric::default();
+
+    for (line_num, line) in ass.lines().enumerate() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_events = line.eq_ignore_ascii_case("[Events]");
+            found_events |= in_events;
+            continue;
+        }
+        if !in_events {
+            continue;
+        }
+        if let Some(fields) = line.strip_prefix("Format:") {
+            format = fields.split(',').map(|f| f.trim().to_owned()).collect();
+            continue;
+        }
+        let Some(event) = line.strip_prefix("Dialogue:") else {
+            continue;
+        };
+
+        let field = |name: &'static str| {
+            format
+                .iter()
+                .position(|f| f.eq_ignore_ascii_case(name))
+                .ok_or(AssError::MissingField(name))
+        };
+        let (start_idx, style_idx, text_idx) = (field("Start")?, field("Style")?, field("Text")?);
+        // Text is the last field, and may contain commas
+        let values: Vec<&str> = event.splitn(format.len(), ',').map(str::trim).collect();
+
+        let start = values.get(start_idx).copied().unwrap_or_default();
+        let start_time = parse_timestamp(start)
+            .ok_or_else(|| AssError::InvalidTimestamp(start.to_owned(), line_num + 1))?;
+        let text = strip_tags(values.get(text_idx).copied().unwrap_or_default());
+        let style = values.get(style_idx).copied().unwrap_or_default();
+
+        let line = LyricLineOwned {
+            text,
+            start_time,
+            confidence: None,
+        };
+        if TRANSLATION_STYLES
+            .iter()
+            .any(|s| s.eq_ignore_ascii_case(style))
+        {
+            lyric.translation.push(line);
+        } else {
+            lyric.origin.push(line);
+        }
+    }
+
+    if !found_events {
+        return Err(AssError::NoEvents);
+    }
+    lyric.origin.sort_by_key(|line| line.start_time);
+    lyric.translation.sort_by_key(|line| line.start_time);
+    Ok(lyric)
+}
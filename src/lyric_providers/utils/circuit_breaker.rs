@@ -0,0 +1,151 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+
+use super::http_cache::{Conditional, Validator};
+use crate::log::{info, warn};
+use crate::lyric_providers::{LyricOwned, LyricParse, LyricProvider, LyricStore, SongInfo};
+
+/// consecutive failures before the circuit opens
+pub const FAILURE_THRESHOLD: u32 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    Closed { failures: u32 },
+    Open { until: Instant },
+    HalfOpen,
+}
+
+/// Wraps a `LyricProvider`, and marks it unavailable for `cool_down`
+/// after `FAILURE_THRESHOLD` consecutive failures.
+pub struct CircuitBreaker {
+    inner: &'static dyn LyricProvider,
+    cool_down: Duration,
+    state: Mutex<CircuitState>,
+}
+
+impl CircuitBreaker {
+    pub fn new(inner: &'static dyn LyricProvider, cool_down: Duration) -> Self {
+        Self {
+            inner,
+            cool_down,
+            state: Mutex::new(CircuitState::Closed { failures: 0 }),
+        }
+    }
+
+    pub fn state(&self) -> CircuitState {
+        *self.state.lock().unwrap()
+    }
+
+    fn record<T>(&self, result: Result<T>) -> Result<T> {
+        let mut state = self.state.lock().unwrap();
+        match &result {
+            Ok(_) => *state = CircuitState::Closed { failures: 0 },
+            // an empty search result does not mean the provider is broken
+            Err(e) if e.downcast_ref::<crate::lyric_providers::Error>().is_some() => {
+                *state = CircuitState::Closed { failures: 0 }
+            }
+            Err(_) => {
+                let failures = match *state {
+                    CircuitState::Closed { failures } => failures + 1,
+                    _ => FAILURE_THRESHOLD,
+                };
+                if failures >= FAILURE_THRESHOLD {
+                    warn!(
+                        "circuit of {} opened after {failures} failures, skip it for {:?}",
+                        self.inner.unique_name(),
+                        self.cool_down
+                    );
+                    *state = CircuitState::Open {
+                        until: Instant::now() + self.cool_down,
+                    };
+                } else {
+                    *state = CircuitState::Closed { failures };
+                }
+            }
+        }
+        result
+    }
+}
+
+#[async_trait::async_trait]
+impl LyricProvider for CircuitBreaker {
+    async fn query_lyric(&self, id: &str) -> Result<LyricStore> {
+        self.record(self.inner.query_lyric(id).await)
+    }
+
+    async fn query_lyric_conditional(
+        &self,
+        id: &str,
+        validator: Option<&Validator>,
+    ) -> Result<Conditional<LyricStore>> {
+        self.record(self.inner.query_lyric_conditional(id, validator).await)
+    }
+
+    async fn search_song_detailed(
+        &self,
+        album: &str,
+        artists: &[&str],
+        title: &str,
+    ) -> Result<Vec<SongInfo>> {
+        self.record(self.inner.search_song_detailed(album, artists, title).await)
+    }
+
+    async fn search_song_with_length(
+        &self,
+        album: &str,
+        artists: &[&str],
+        title: &str,
+        length: Option<Duration>,
+    ) -> Result<Vec<SongInfo>> {
+        self.record(
+            self.inner
+                .search_song_with_length(album, artists, title, length)
+                .await,
+        )
+    }
+
+    async fn search_song(&self, keyword: &str) -> Result<Vec<SongInfo>> {
+        self.record(self.inner.search_song(keyword).await)
+    }
+
+    fn unique_name(&self) -> &'static str {
+        self.inner.unique_name()
+    }
+
+    fn init(self, _config: &str) -> Result<()> {
+        anyhow::bail!("initialize the wrapped provider instead")
+    }
+
+    fn is_likely_songid(&self, s: &str) -> bool {
+        self.inner.is_likely_songid(s)
+    }
+
+    fn source_url(&self, id: &str) -> Option<String> {
+        self.inner.source_url(id)
+    }
+
+    fn is_available(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        match *state {
+            CircuitState::Closed { .. } | CircuitState::HalfOpen => true,
+            CircuitState::Open { until } if Instant::now() >= until => {
+                info!("circuit of {} half-opened", self.inner.unique_name());
+                *state = CircuitState::HalfOpen;
+                true
+            }
+            CircuitState::Open { .. } => false,
+        }
+    }
+}
+
+impl LyricParse for CircuitBreaker {
+    fn parse_lyric(&self, store: &LyricStore) -> LyricOwned {
+        self.inner.parse_lyric(store)
+    }
+
+    fn parse_translated_lyric(&self, store: &LyricStore) -> LyricOwned {
+        self.inner.parse_translated_lyric(store)
+    }
+}
@@ -0,0 +1,123 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use reqwest::{
+    header::{HeaderMap, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED},
+    RequestBuilder, Response, StatusCode,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::log::{debug, error};
+
+/// `ETag` and `Last-Modified` of a response
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Validator {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+impl Validator {
+    /// Returns `None` if the server sent neither of the headers
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let get = |name| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_owned)
+        };
+        let validator = Self {
+            etag: get(ETAG),
+            last_modified: get(LAST_MODIFIED),
+        };
+        (validator.etag.is_some() || validator.last_modified.is_some()).then_some(validator)
+    }
+
+    /// Add `If-None-Match` and `If-Modified-Since` to `request`
+    pub fn apply(&self, mut request: RequestBuilder) -> RequestBuilder {
+        if let Some(etag) = &self.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &self.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+        request
+    }
+}
+
+/// Result of a conditional request
+#[derive(Debug)]
+pub enum Conditional<T> {
+    NotModified,
+    Modified {
+        body: T,
+        validator: Option<Validator>,
+    },
+}
+
+impl<T> Conditional<T> {
+    /// Body of an unconditional request
+    pub fn into_body(self) -> Result<T> {
+        match self {
+            Conditional::NotModified => anyhow::bail!("not modified without a validator"),
+            Conditional::Modified { body, .. } => Ok(body),
+        }
+    }
+
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Conditional<U> {
+        match self {
+            Conditional::NotModified => Conditional::NotModified,
+            Conditional::Modified { body, validator } => Conditional::Modified {
+                body: f(body),
+                validator,
+            },
+        }
+    }
+}
+
+/// Wrap `resp` according to its status. This does not check other status codes.
+pub fn conditional(resp: Response) -> Conditional<Response> {
+    if resp.status() == StatusCode::NOT_MODIFIED {
+        return Conditional::NotModified;
+    }
+    let validator = Validator::from_headers(resp.headers());
+    Conditional::Modified {
+        body: resp,
+        validator,
+    }
+}
+
+/// Where a cached lyric came from, stored in a sidecar file next to the lyric cache
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LyricSource {
+    /// `LyricProvider::unique_name`
+    pub provider: String,
+    pub id: String,
+    #[serde(flatten)]
+    pub validator: Validator,
+}
+
+pub fn sidecar_path(cache_path: &Path) -> PathBuf {
+    cache_path.with_extension("http.json")
+}
+
+pub fn read_sidecar(cache_path: &Path) -> Option<LyricSource> {
+    let path = sidecar_path(cache_path);
+    let content = std::fs::read_to_string(&path).ok()?;
+    serde_json::from_str(&content)
+        .inspect_err(|e| error!("sidecar parse error: {e} from {path:?}"))
+        .ok()
+}
+
+/// Write `source` next to `cache_path`, or remove the stale sidecar if `source` is `None`
+pub fn write_sidecar(cache_path: &Path, source: Option<&LyricSource>) {
+    let path = sidecar_path(cache_path);
+    let Some(source) = source else {
+        let _ = std::fs::remove_file(&path);
+        return;
+    };
+    let content = serde_json::to_string(source).expect("cannot serialize lyric source!");
+    match std::fs::write(&path, content) {
+        Ok(()) => debug!("saved validator to {path:?}"),
+        Err(e) => error!("cannot write sidecar {path:?}: {e}"),
+    }
+}
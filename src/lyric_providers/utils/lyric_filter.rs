@@ -0,0 +1,83 @@
+//! `lyric-filters` from config, applied to each line before lyrics are shown
+
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+use crate::config::LyricFilter;
+use crate::lyric_providers::LyricOwned;
+
+#[derive(Debug)]
+pub struct CompiledFilter {
+    pub regex: Regex,
+    pub replacement: String,
+    pub drop_line: bool,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("invalid pattern {pattern:?} in lyric-filters[{index}]: {source}")]
+pub struct FilterError {
+    pub index: usize,
+    pub pattern: String,
+    pub source: regex::Error,
+}
+
+static LYRIC_FILTERS: OnceLock<Vec<CompiledFilter>> = OnceLock::new();
+
+pub fn build_lyric_filters(filters: &[LyricFilter]) -> Result<Vec<CompiledFilter>, FilterError> {
+    filters
+        .iter()
+        .enumerate()
+        .map(|(index, filter)| {
+            Ok(CompiledFilter {
+                regex: Regex::new(&filter.pattern).map_err(|source| FilterError {
+                    index,
+                    pattern: filter.pattern.clone(),
+                    source,
+                })?,
+                replacement: filter.replacement.clone(),
+                drop_line: filter.drop_line,
+            })
+        })
+        .collect()
+}
+
+pub fn set_lyric_filters(filters: &[LyricFilter]) -> Result<(), FilterError> {
+    let _ = LYRIC_FILTERS.set(build_lyric_filters(filters)?);
+    Ok(())
+}
+
+/// Apply filters set by `set_lyric_filters` to each line of `lyric`
+pub fn filter_lyric(lyric: LyricOwned) -> LyricOwned {
+    match LYRIC_FILTERS.get() {
+        Some(filters) if !filters.is_empty() => filter_lyric_with(lyric, filters),
+        _ => lyric,
+    }
+}
+
+/// Filters are applied in order, a line dropped by one is not seen by the rest
+pub fn filter_lyric_with(lyric: LyricOwned, filters: &[CompiledFilter]) -> LyricOwned {
+    let LyricOwned::LineTimestamp(lines) = lyric else {
+        return lyric;
+    };
+    let lines = lines
+        .into_iter()
+        .filter_map(|mut line| {
+            for filter in filters {
+                if filter.drop_line {
+                    if filter.regex.is_match(&line.text) {
+                        return None;
+                    }
+                    continue;
+                }
+                if let std::borrow::Cow::Owned(text) =
+                    filter.regex.replace_all(&line.text, &filter.replacement)
+                {
+                    line.text = text;
+                }
+            }
+            Some(line)
+        })
+        .collect();
+    LyricOwned::LineTimestamp(lines)
+}
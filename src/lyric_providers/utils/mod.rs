@@ -0,0 +1,234 @@
+use lrc_nom::{parse_single, LrcParseError};
+use std::borrow::Cow;
+use std::time::Duration;
+
+use super::{LyricLine, LyricLineOwned, LyricOwned, LyricProvider};
+
+#[cfg(feature = "import-ass")]
+pub mod ass;
+pub mod circuit_breaker;
+pub mod http_cache;
+pub mod lyric_filter;
+pub mod normalise;
+pub mod ranking;
+pub mod retry;
+#[cfg(feature = "transliterate-japanese")]
+pub mod romaji;
+#[cfg(feature = "import-translation-from-srt")]
+pub mod srt;
+pub mod ttml;
+
+/// parses lrc tags in each line
+///
+/// ignores lines not started with '['
+///
+/// returned `Vec` is sorted by it's Duration
+///
+/// In the case multiple lyrics comes with the same start_time,
+///
+/// they are not reordered between them
+pub fn lrc_iter<'a>(
+    lyric_lines: impl Iterator<Item = &'a str>,
+) -> Result<Vec<LyricLine<'a>>, LrcParseError> {
+    let filtered_lines = lyric_lines.filter(|l| l.starts_with('['));
+    let mut lrc_vec: Vec<_> = filtered_lines
+        .enumerate()
+        .filter_map(|(line_num, line)| parse_single(line, line_num).ok())
+        .flatten()
+        .filter_map(|lrc_item| match lrc_item {
+            lrc_nom::LrcItem::Metadata(_) => None,
+            lrc_nom::LrcItem::Lyric(lyric, timestamps) => {
+                Some(timestamps.into_iter().map(|timestamp| LyricLine {
+                    text: lyric.trim(),
+                    start_time: Duration::from_millis(timestamp as _),
+                }))
+            }
+        })
+        .flatten()
+        .collect();
+    // handling malformed LRC timestamp by sorting them here
+    //
+    // DO NOT use sort_unstable_by_key, we should let lyrics
+    // preserve original relative order, that extracting
+    // translation will work.
+    lrc_vec.sort_by_key(|line| line.start_time);
+
+    Ok(lrc_vec)
+}
+
+pub fn find_next_lyric<'a>(
+    elapsed: &Duration,
+    lyric: &'a [LyricLineOwned],
+) -> Option<&'a LyricLineOwned> {
+    lyric
+        .iter()
+        .take_while(
+            |LyricLineOwned {
+                 start_time: off, ..
+             }| off <= elapsed,
+        )
+        .last()
+}
+
+/// Same as `find_next_lyric`, but lines lasting shorter than `min_duration` are skipped,
+/// leaving the line before them shown. The last line is never skipped.
+pub fn find_next_long_lyric<'a>(
+    elapsed: &Duration,
+    lyric: &'a [LyricLineOwned],
+    min_duration: Duration,
+) -> Option<&'a LyricLineOwned> {
+    if min_duration.is_zero() {
+        return find_next_lyric(elapsed, lyric);
+    }
+    let current = lyric.partition_point(|line| line.start_time <= *elapsed);
+    let is_long = |index: usize| {
+        lyric.get(index + 1).map_or(true, |next| {
+            next.start_time.saturating_sub(lyric[index].start_time) >= min_duration
+        })
+    };
+    let shown = (0..current).rev().find(|&index| is_long(index));
+    if current > 0 && shown != Some(current - 1) {
+        crate::log::trace!("skipped short line {:?}", lyric[current - 1].text);
+    }
+    shown.map(|index| &lyric[index])
+}
+
+/// If `elapsed` is in a section without text lasting longer than `threshold`,
+/// returns the time left before the next line with text.
+///
+/// The section ends at the last line, so no gap is found after it.
+pub fn find_gap(
+    elapsed: &Duration,
+    lyric: &[LyricLineOwned],
+    threshold: Duration,
+) -> Option<Duration> {
+    let has_text = |line: &LyricLineOwned| !line.text.trim().is_empty();
+
+    let current = lyric.partition_point(|line| line.start_time <= *elapsed);
+    let (played, upcoming) = lyric.split_at(current);
+    let gap_start = match played.iter().rposition(has_text) {
+        Some(last) if last + 1 == played.len() => return None,
+        Some(last) => played[last + 1].start_time,
+        None => Duration::ZERO,
+    };
+    let next = upcoming.iter().find(|line| has_text(line))?;
+
+    (next.start_time - gap_start > threshold).then(|| next.start_time - *elapsed)
+}
+
+/// How far `elapsed` is through the current line, in `[0.0, 1.0]`.
+///
+/// Stays at `1.0` on the last line, `None` before the first one.
+pub fn line_progress(elapsed: &Duration, lyric: &[LyricLineOwned]) -> Option<f64> {
+    let current = lyric
+        .partition_point(|line| line.start_time <= *elapsed)
+        .checked_sub(1)?;
+    let Some(next) = lyric.get(current + 1) else {
+        return Some(1.);
+    };
+    let start = lyric[current].start_time;
+    let length = next.start_time.saturating_sub(start).as_secs_f64();
+    if length == 0. {
+        return Some(1.);
+    }
+    Some(((*elapsed - start).as_secs_f64() / length).clamp(0., 1.))
+}
+
+/// `text` cut to at most `max_len` grapheme clusters, ending with `…` if it was cut.
+///
+/// Cut after the last whole word if that keeps more than half of it. 0 for no limit.
+pub fn truncate_line(text: &str, max_len: usize) -> Cow<'_, str> {
+    use unicode_segmentation::UnicodeSegmentation;
+
+    if max_len == 0 || text.graphemes(true).nth(max_len).is_none() {
+        return Cow::Borrowed(text);
+    }
+    // leave room for the ellipsis
+    let keep = max_len - 1;
+    let cut = text
+        .grapheme_indices(true)
+        .nth(keep)
+        .map_or(text.len(), |(idx, _)| idx);
+    let kept = &text[..cut];
+    if text[cut..].starts_with(char::is_whitespace) {
+        return Cow::Owned(format!("{}…", kept.trim_end()));
+    }
+    let word_end = kept
+        .split_word_bound_indices()
+        .filter(|(_, word)| word.chars().all(char::is_whitespace))
+        .map(|(idx, _)| idx)
+        .last()
+        .filter(|&idx| kept[..idx].graphemes(true).count() * 2 > keep);
+    let kept = word_end.map_or(kept, |idx| &kept[..idx]);
+    Cow::Owned(format!("{}…", kept.trim_end()))
+}
+
+/// Lines of lyric without timestamp, blank lines are dropped
+pub fn plain_lines(text: &str) -> Vec<&str> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+/// Line of plain text `lines` playing at `elapsed`, with a synthetic timestamp.
+///
+/// Lines are spread evenly over `length`, or shown at `lines_per_sec` if it's unknown.
+/// `None` if `lines_per_sec` is not positive and finite, which disables scrolling.
+pub fn plain_line_at(
+    elapsed: &Duration,
+    lines: &[String],
+    length: Option<Duration>,
+    lines_per_sec: f64,
+) -> Option<LyricLineOwned> {
+    if !(lines_per_sec.is_finite() && lines_per_sec > 0.) || lines.is_empty() {
+        return None;
+    }
+    let line_secs = match length.filter(|length| !length.is_zero()) {
+        Some(length) => length.as_secs_f64() / lines.len() as f64,
+        None => 1. / lines_per_sec,
+    };
+    let index = ((elapsed.as_secs_f64() / line_secs) as usize).min(lines.len() - 1);
+    Some(LyricLineOwned {
+        text: lines[index].clone(),
+        start_time: Duration::try_from_secs_f64(line_secs * index as f64).ok()?,
+        confidence: None,
+    })
+}
+
+/// Set `confidence` of each line of `lyric` in order, clamped to 0..=1.
+///
+/// Extra values are ignored, and lines without one keep theirs.
+pub fn with_confidence(mut lyric: LyricOwned, confidence: &[f32]) -> LyricOwned {
+    if let LyricOwned::LineTimestamp(lines) = &mut lyric {
+        for (line, confidence) in lines.iter_mut().zip(confidence) {
+            line.confidence = Some(confidence.clamp(0., 1.));
+        }
+    }
+    lyric
+}
+
+pub fn get_provider(provider_id: &str) -> Option<&'static dyn LyricProvider> {
+    use super::lrclib::LRCLib;
+    use super::netease::Netease;
+    use super::qqmusic::QQMusic;
+    let providers: [&'static dyn super::LyricProvider; 3] = [&Netease, &QQMusic, &LRCLib];
+    let provider = providers
+        .into_iter()
+        .find(|p| p.unique_name() == provider_id);
+    let provider = provider.or_else(|| {
+        super::command::command_provider()
+            .filter(|p| p.unique_name() == provider_id)
+            .map(|p| p as &dyn LyricProvider)
+    });
+    #[cfg(feature = "lua-provider")]
+    let provider = provider
+        .or_else(|| super::lua::find_lua_provider(provider_id).map(|p| p as &dyn LyricProvider));
+    #[cfg(feature = "subsonic-provider")]
+    let provider = provider.or_else(|| {
+        super::subsonic::subsonic_provider()
+            .filter(|p| p.unique_name() == provider_id)
+            .map(|p| p as &dyn LyricProvider)
+    });
+    provider
+}
@@ -0,0 +1,175 @@
+//! Query transformations used by fuzzy search, and when the raw title finds nothing.
+
+use std::sync::OnceLock;
+
+use regex::{Regex, RegexBuilder};
+
+use crate::lyric_providers::dict::ARTIST_ALIAS_MAP;
+
+const BRACKETS: [(char, char); 4] = [('(', ')'), ('[', ']'), ('（', '）'), ('【', '】')];
+
+/// suffix after " - " is stripped if it contains any of these
+const DASH_SUFFIX_KEYWORDS: [&str; 8] = [
+    "remaster", "live", "version", "mix", "edit", "mono", "stereo", "ver.",
+];
+
+/// markers of featured artists, matched case-insensitively
+const FEAT_MARKERS: [&str; 6] = ["(feat", "[feat", " feat.", " feat ", " ft.", " featuring "];
+
+/// Strip trailing parenthetical suffixes,
+/// e.g. `Yesterday (Remastered 2009)` -> `Yesterday`
+pub fn strip_parenthetical(title: &str) -> String {
+    let mut title = title.trim();
+    loop {
+        let Some(close) = title.chars().last() else {
+            break;
+        };
+        let Some(&(open, _)) = BRACKETS.iter().find(|(_, c)| *c == close) else {
+            break;
+        };
+        match title.rfind(open) {
+            // do not strip a title that is entirely bracketed
+            Some(idx) if !title[..idx].trim().is_empty() => title = title[..idx].trim_end(),
+            _ => break,
+        }
+    }
+
+    if let Some(idx) = title.rfind(" - ") {
+        let suffix = title[idx..].to_ascii_lowercase();
+        if DASH_SUFFIX_KEYWORDS.iter().any(|k| suffix.contains(k)) {
+            title = title[..idx].trim_end();
+        }
+    }
+
+    title.to_owned()
+}
+
+/// Remove featured artists, e.g. `Song feat. Someone` -> `Song`
+pub fn remove_feat(s: &str) -> String {
+    // ascii lowercase keeps byte offsets
+    let lower = s.to_ascii_lowercase();
+    let idx = FEAT_MARKERS
+        .iter()
+        .filter_map(|m| lower.find(m))
+        .filter(|&idx| idx > 0)
+        .min();
+    match idx {
+        Some(idx) => s[..idx].trim_end().to_owned(),
+        None => s.trim().to_owned(),
+    }
+}
+
+static TITLE_STRIP_PATTERNS: OnceLock<Vec<Regex>> = OnceLock::new();
+
+/// Compile user patterns, matched case-insensitively and removed from titles
+pub fn build_title_strip_patterns(extra: &[String]) -> Result<Vec<Regex>, regex::Error> {
+    extra
+        .iter()
+        .map(|p| RegexBuilder::new(p).case_insensitive(true).build())
+        .collect()
+}
+
+pub fn set_title_strip_patterns(extra: &[String]) -> Result<(), regex::Error> {
+    let _ = TITLE_STRIP_PATTERNS.set(build_title_strip_patterns(extra)?);
+    Ok(())
+}
+
+/// Strip featured artists and version suffixes from `title`,
+/// then patterns set by `set_title_strip_patterns`
+pub fn normalize_title(title: &str) -> String {
+    normalize_title_with(title, TITLE_STRIP_PATTERNS.get().map_or(&[], Vec::as_slice))
+}
+
+pub fn normalize_title_with(title: &str, patterns: &[Regex]) -> String {
+    let mut title = title.trim().to_owned();
+    loop {
+        let stripped = patterns
+            .iter()
+            .fold(remove_feat(&strip_parenthetical(&title)), |t, p| {
+                p.replace_all(&t, "").into_owned()
+            });
+        let stripped = stripped.trim();
+        // do not strip a title into nothing
+        if stripped.is_empty() || stripped == title {
+            return title;
+        }
+        title = stripped.to_owned();
+    }
+}
+
+/// Alternate spellings of `artist`, not including itself
+pub fn alternate_romanizations(artist: &str) -> Vec<String> {
+    let artist = artist.trim();
+    let mut alts = vec![];
+
+    if let Some(alias) = ARTIST_ALIAS_MAP.get(&*artist.to_lowercase()) {
+        alts.push(alias.to_string());
+    }
+
+    // classical metadata: `Beethoven, Ludwig van` -> `Ludwig van Beethoven`
+    if let Some((last, first)) = artist.split_once(", ") {
+        alts.push(format!("{first} {last}"));
+    }
+
+    // J-pop: `Hikaru Utada` <-> `Utada Hikaru`
+    let words: Vec<&str> = artist.split_whitespace().collect();
+    if let [a, b] = words.as_slice() {
+        if artist.is_ascii() {
+            alts.push(format!("{b} {a}"));
+        }
+    }
+
+    // Hepburn long vowels: `Ryūichi` -> `Ryuuichi`, `Ryuichi`
+    if artist.contains(['ā', 'ī', 'ū', 'ē', 'ō']) {
+        let doubled = artist
+            .replace('ā', "aa")
+            .replace('ī', "ii")
+            .replace('ū', "uu")
+            .replace('ē', "ei")
+            .replace('ō', "ou");
+        let plain = artist
+            .replace('ā', "a")
+            .replace('ī', "i")
+            .replace('ū', "u")
+            .replace('ē', "e")
+            .replace('ō', "o");
+        alts.extend([doubled, plain]);
+    } else if artist.is_ascii() {
+        let lower = artist.to_ascii_lowercase();
+        if lower.contains("ou") || lower.contains("uu") {
+            alts.push(artist.replace("ou", "o").replace("uu", "u"));
+        }
+    }
+
+    // K-pop: `Lee Ji-eun` -> `Lee Jieun`
+    if artist.contains('-') {
+        alts.push(artist.replace('-', ""));
+    }
+
+    alts.retain(|a| a != artist);
+    alts.dedup();
+    alts
+}
+
+/// Queries to try in order, as `(title, artists)`, not including the raw one
+pub fn fuzzy_queries(title: &str, artists: &[String]) -> Vec<(String, Vec<String>)> {
+    let fuzzy_title = normalize_title(title);
+    let fuzzy_artists: Vec<String> = artists
+        .iter()
+        .map(|a| remove_feat(a))
+        .filter(|a| !a.is_empty())
+        .collect();
+
+    let mut queries = vec![(fuzzy_title.clone(), fuzzy_artists.clone())];
+    for (idx, artist) in fuzzy_artists.iter().enumerate() {
+        for alt in alternate_romanizations(artist) {
+            let mut artists = fuzzy_artists.clone();
+            artists[idx] = alt;
+            queries.push((fuzzy_title.clone(), artists));
+        }
+    }
+
+    queries.retain(|(t, a)| !(t == title && a == artists));
+    queries.dedup();
+    queries
+}
@@ -0,0 +1,192 @@
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use whatlang::Lang;
+
+use crate::config::RankingWeights;
+use crate::log::warn;
+use crate::lyric_providers::LyricOwned;
+
+/// lines sampled for language detection
+const SAMPLE_LINES: usize = 20;
+
+/// candidates this far apart from the track length get no duration score
+pub const DURATION_SCALE: Duration = Duration::from_secs(10);
+
+static LANGUAGE_PREFERENCE: OnceLock<Vec<Lang>> = OnceLock::new();
+static RANKING_WEIGHTS: OnceLock<RankingWeights> = OnceLock::new();
+static MIN_CONFIDENCE_SCORE: OnceLock<f64> = OnceLock::new();
+
+/// Parse ISO 639-1 (`zh`) or ISO 639-3 (`cmn`) codes, ignoring unknown ones
+pub fn parse_languages(codes: &[String]) -> Vec<Lang> {
+    codes
+        .iter()
+        .filter_map(|code| {
+            let lang = match code.to_lowercase().as_str() {
+                "zh" => Some(Lang::Cmn),
+                "ja" => Some(Lang::Jpn),
+                "en" => Some(Lang::Eng),
+                "ko" => Some(Lang::Kor),
+                "fr" => Some(Lang::Fra),
+                "de" => Some(Lang::Deu),
+                "es" => Some(Lang::Spa),
+                "it" => Some(Lang::Ita),
+                "pt" => Some(Lang::Por),
+                "ru" => Some(Lang::Rus),
+                code => Lang::from_code(code),
+            };
+            if lang.is_none() {
+                warn!("unknown language code: {code}");
+            }
+            lang
+        })
+        .collect()
+}
+
+pub fn set_language_preference(codes: &[String]) {
+    let _ = LANGUAGE_PREFERENCE.set(parse_languages(codes));
+}
+
+pub fn language_preference() -> &'static [Lang] {
+    LANGUAGE_PREFERENCE
+        .get()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+pub fn detect_language(lyric: &LyricOwned) -> Option<Lang> {
+    let LyricOwned::LineTimestamp(lines) = lyric else {
+        return None;
+    };
+    let sample = lines
+        .iter()
+        .map(|l| l.text.trim())
+        .filter(|t| !t.is_empty())
+        .take(SAMPLE_LINES)
+        .collect::<Vec<_>>()
+        .join("\n");
+    whatlang::detect(&sample).map(|info| info.lang())
+}
+
+/// Index of the lyric to use.
+///
+/// Lyrics in a more preferred language come first, ties are broken by the given order.
+/// If no lyric matches `preference`, the first non-empty one is used.
+pub fn pick_by_language<'a>(
+    lyrics: impl IntoIterator<Item = &'a LyricOwned>,
+    preference: &[Lang],
+) -> Option<usize> {
+    let lyrics: Vec<&LyricOwned> = lyrics.into_iter().collect();
+
+    lyrics
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, lyric)| {
+            let lang = detect_language(lyric)?;
+            let rank = preference.iter().position(|p| *p == lang)?;
+            Some((rank, idx))
+        })
+        .min()
+        .map(|(_, idx)| idx)
+        .or_else(|| lyrics.iter().position(|l| !l.is_none()))
+        .or(if lyrics.is_empty() { None } else { Some(0) })
+}
+
+pub fn set_ranking_weights(weights: RankingWeights) {
+    let _ = RANKING_WEIGHTS.set(weights);
+}
+
+pub fn ranking_weights() -> RankingWeights {
+    RANKING_WEIGHTS.get().copied().unwrap_or_default()
+}
+
+pub fn set_min_confidence_score(score: f32) {
+    let _ = MIN_CONFIDENCE_SCORE.set(score.into());
+}
+
+pub fn min_confidence_score() -> f64 {
+    MIN_CONFIDENCE_SCORE.get().copied().unwrap_or_default()
+}
+
+/// Metadata compared when ranking, either of the playing track or a search result
+#[derive(Debug, Clone, Default)]
+pub struct TrackInfo {
+    pub title: String,
+    pub artists: Option<String>,
+    pub album: Option<String>,
+    pub length: Option<Duration>,
+}
+
+pub fn levenshtein(a: &[char], b: &[char]) -> usize {
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        curr[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev[j] + usize::from(ca != cb);
+            curr[j + 1] = substitution.min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    prev[b.len()]
+}
+
+/// 1 for equal strings, 0 for completely different ones, ignoring case
+pub fn similarity(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.trim().to_lowercase().chars().collect();
+    let b: Vec<char> = b.trim().to_lowercase().chars().collect();
+    let len = a.len().max(b.len());
+    if len == 0 {
+        return 1.;
+    }
+    1. - levenshtein(&a, &b) as f64 / len as f64
+}
+
+/// How likely `candidate` is the same song as `track`, in `[0, 1]`.
+///
+/// Mean of title and artist similarity, artist is ignored if either side misses it.
+pub fn confidence(track: &TrackInfo, candidate: &TrackInfo) -> f64 {
+    let title = similarity(&track.title, &candidate.title);
+    match (&track.artists, &candidate.artists) {
+        (Some(a), Some(b)) => (title + similarity(a, b)) / 2.,
+        _ => title,
+    }
+}
+
+/// Weighted sum of title, artist, duration and album matches.
+///
+/// Fields missing on either side do not contribute.
+pub fn score(track: &TrackInfo, candidate: &TrackInfo, weights: &RankingWeights) -> f64 {
+    let mut score = weights.title * similarity(&track.title, &candidate.title);
+
+    if let (Some(a), Some(b)) = (&track.artists, &candidate.artists) {
+        score += weights.artist * similarity(a, b);
+    }
+    if let (Some(a), Some(b)) = (track.length, candidate.length) {
+        let diff = (a.max(b) - a.min(b)).as_secs_f64();
+        score += weights.duration * (1. - diff / DURATION_SCALE.as_secs_f64()).max(0.);
+    }
+    if let (Some(a), Some(b)) = (&track.album, &candidate.album) {
+        if a.trim().to_lowercase() == b.trim().to_lowercase() {
+            score += weights.album;
+        }
+    }
+    score
+}
+
+/// Indices of `candidates` with their scores, best first.
+///
+/// Candidates with the same score keep their order.
+pub fn rank(
+    track: &TrackInfo,
+    candidates: impl IntoIterator<Item = TrackInfo>,
+    weights: &RankingWeights,
+) -> Vec<(usize, f64)> {
+    let mut ranked: Vec<(usize, f64)> = candidates
+        .into_iter()
+        .enumerate()
+        .map(|(idx, candidate)| (idx, score(track, &candidate, weights)))
+        .collect();
+    ranked.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+    ranked
+}
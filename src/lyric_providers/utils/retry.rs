@@ -0,0 +1,126 @@
+use std::future::Future;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use ahash::HashMap;
+use anyhow::Result;
+use reqwest::{header::RETRY_AFTER, Response, StatusCode};
+
+use crate::config::ProvidersConfig;
+use crate::log::debug;
+use crate::utils::{parse_time, ParseError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_millis(500),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// delay before the `attempt`-th retry, starting from 0
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        self.base_delay.saturating_mul(2u32.saturating_pow(attempt))
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("too many requests")]
+    TooManyRequests { retry_after: Option<Duration> },
+}
+
+/// Retry policies from `[providers.*]`, keyed by `LyricProvider::unique_name`
+static RETRY_POLICIES: OnceLock<HashMap<&'static str, RetryPolicy>> = OnceLock::new();
+
+pub fn set_retry_policies(config: &ProvidersConfig) -> Result<(), ParseError> {
+    let mut policies = HashMap::default();
+    for (name, conf) in config.iter() {
+        let policy = RetryPolicy {
+            max_retries: conf.max_retries,
+            base_delay: parse_time(&conf.retry_base_delay)?,
+        };
+        policies.insert(name, policy);
+    }
+    let _ = RETRY_POLICIES.set(policies);
+    Ok(())
+}
+
+pub fn retry_policy(provider: &str) -> RetryPolicy {
+    RETRY_POLICIES
+        .get()
+        .and_then(|p| p.get(provider))
+        .copied()
+        .unwrap_or_default()
+}
+
+/// Like `Response::error_for_status`, but keeps `Retry-After` of HTTP 429
+pub fn check_status(resp: Response) -> Result<Response> {
+    if resp.status() == StatusCode::TOO_MANY_REQUESTS {
+        let retry_after = resp
+            .headers()
+            .get(RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse().ok())
+            .map(Duration::from_secs);
+        Err(Error::TooManyRequests { retry_after })?
+    }
+    Ok(resp.error_for_status()?)
+}
+
+/// Returns `None` if `error` should not be retried,
+/// or `Some(Some(delay))` if the server asked us to wait for `delay`
+pub fn should_retry(error: &anyhow::Error) -> Option<Option<Duration>> {
+    for cause in error.chain() {
+        if let Some(Error::TooManyRequests { retry_after }) = cause.downcast_ref::<Error>() {
+            return Some(*retry_after);
+        }
+        if let Some(e) = cause.downcast_ref::<reqwest::Error>() {
+            return match e.status() {
+                Some(StatusCode::TOO_MANY_REQUESTS) => Some(None),
+                Some(status) if status.is_server_error() => Some(None),
+                Some(_) => None,
+                None if e.is_timeout() || e.is_connect() => Some(None),
+                None => None,
+            };
+        }
+    }
+    None
+}
+
+/// Run `f` until it succeeds, retrying transient errors with exponential backoff.
+///
+/// Should be called inside tokio runtime.
+pub async fn with_retry<T, F, Fut>(provider: &str, mut f: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let policy = retry_policy(provider);
+    let mut attempt = 0;
+    loop {
+        let e = match f().await {
+            Ok(t) => return Ok(t),
+            Err(e) => e,
+        };
+        if attempt >= policy.max_retries {
+            return Err(e);
+        }
+        let Some(retry_after) = should_retry(&e) else {
+            return Err(e);
+        };
+
+        let delay = retry_after.unwrap_or_else(|| policy.backoff(attempt));
+        debug!("{e} on {provider}, retry after {delay:?}");
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
@@ -0,0 +1,41 @@
+//! Romaji of Japanese lyrics, computed when displayed and never stored in cache
+//!
+//! Only kana are transliterated, kanji are kept as they are.
+
+use gtk::glib::markup_escape_text;
+use wana_kana::ConvertJapanese;
+
+use crate::config::RomajiDisplay;
+
+/// Lyric label content after transliteration
+#[derive(Debug, PartialEq, Eq)]
+pub enum Transliterated {
+    /// plain text replacing the line
+    Replace(String),
+    /// markup with romaji in a smaller line above the original one
+    Above(String),
+}
+
+/// hiragana, katakana and halfwidth katakana
+pub fn has_kana(text: &str) -> bool {
+    text.chars().any(|c| {
+        matches!(c,
+            '\u{3041}'..='\u{309F}' | '\u{30A0}'..='\u{30FF}' | '\u{FF66}'..='\u{FF9F}')
+    })
+}
+
+/// `None` if `text` has no kana, or `display` is `Off`
+pub fn transliterate(text: &str, display: RomajiDisplay) -> Option<Transliterated> {
+    if display == RomajiDisplay::Off || !has_kana(text) {
+        return None;
+    }
+    let romaji = text.to_romaji();
+    Some(match display {
+        RomajiDisplay::Replace => Transliterated::Replace(romaji),
+        _ => Transliterated::Above(format!(
+            "<span size=\"small\">{}</span>\n{}",
+            markup_escape_text(&romaji),
+            markup_escape_text(text)
+        )),
+    })
+}
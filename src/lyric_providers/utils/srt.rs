@@ -0,0 +1,94 @@
+//! Parser of SRT subtitles, imported as translation over the lyric playing
+
+use std::time::Duration;
+
+use crate::lyric_providers::LyricLineOwned;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SrtError {
+    #[error("invalid timing {0:?} at line {1}")]
+    InvalidTiming(String, usize),
+    #[error("no subtitle found")]
+    Empty,
+}
+
+/// Parse SRT timestamp `HH:MM:SS,mmm`, `.` is accepted in place of `,`
+pub fn parse_timestamp(timestamp: &str) -> Option<Duration> {
+    let mut parts = timestamp.trim().splitn(3, ':');
+    let hours: u64 = parts.next()?.parse().ok()?;
+    let minutes: u64 = parts.next()?.parse().ok()?;
+    let secs = parts.next()?;
+    let (secs, millis) = secs.split_once(|c| c == ',' || c == '.')?;
+    let secs: u64 = secs.parse().ok()?;
+    let millis: u64 = millis.parse().ok()?;
+    Some(Duration::from_millis(
+        ((hours * 60 + minutes) * 60 + secs) * 1000 + millis,
+    ))
+}
+
+/// Remove tags like `<i>` and `{\an8}`, joining lines of a subtitle with spaces
+pub fn strip_tags(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut closing = None;
+    for c in text.chars() {
+        match (closing, c) {
+            (None, '<') => closing = Some('>'),
+            (None, '{') => closing = Some('}'),
+            (Some(end), c) if c == end => closing = None,
+            (None, c) => stripped.push(c),
+            _ => (),
+        }
+    }
+    stripped.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Parse SRT into lines sorted by start time.
+///
+/// An empty line is inserted where a subtitle ends before the next one starts.
+pub fn parse_srt(srt: &str) -> Result<Vec<LyricLineOwned>, SrtError> {
+    let srt = srt.trim_start_matches('\u{feff}');
+    let mut subtitles = vec![];
+    let mut lines = srt.lines().enumerate().peekable();
+
+    while let Some((line_num, line)) = lines.next() {
+        let line = line.trim();
+        // sequence numbers are not required to find timings
+        let Some((start, end)) = line.split_once("-->") else {
+            continue;
+        };
+        let invalid = || SrtError::InvalidTiming(line.to_owned(), line_num + 1);
+        let start = parse_timestamp(start).ok_or_else(invalid)?;
+        // position like `X1:100 X2:200` may follow the end
+        let end = end.split_whitespace().next().unwrap_or_default();
+        let end = parse_timestamp(end).ok_or_else(invalid)?;
+
+        let mut text = vec![];
+        while let Some((_, line)) = lines.next_if(|(_, line)| !line.trim().is_empty()) {
+            text.push(line);
+        }
+        subtitles.push((start, end, strip_tags(&text.join(" "))));
+    }
+
+    if subtitles.is_empty() {
+        return Err(SrtError::Empty);
+    }
+    subtitles.sort_by_key(|(start, _, _)| *start);
+
+    let mut lyric = Vec::with_capacity(subtitles.len() * 2);
+    for (idx, (start, end, text)) in subtitles.iter().enumerate() {
+        lyric.push(LyricLineOwned {
+            text: text.clone(),
+            start_time: *start,
+            confidence: None,
+        });
+        let next_start = subtitles.get(idx + 1).map(|(start, _, _)| *start);
+        if next_start.map_or(true, |next_start| *end < next_start) {
+            lyric.push(LyricLineOwned {
+                text: String::new(),
+                start_time: *end,
+                confidence: None,
+            });
+        }
+    }
+    Ok(lyric)
+}
@@ -0,0 +1,109 @@
+//! Parser of TTML (Timed Text Markup Language) lyrics, as used by Apple Music
+//!
+//! Word-level timing of `<span>` elements is flattened into their `<p>` line.
+
+use std::time::Duration;
+
+use roxmltree::{Document, Node};
+
+use crate::lyric_providers::LyricLineOwned;
+
+#[derive(Debug, Default, PartialEq)]
+pub struct TtmlLyric {
+    /// lines of the first `<div>`
+    pub origin: Vec<LyricLineOwned>,
+    /// lines of the second `<div>`, empty if there's only one
+    pub translation: Vec<LyricLineOwned>,
+}
+
+/// Parse TTML clock time or offset time, like `01:02.5`, `00:01:02.500`, `62.5s` or `62500ms`
+pub fn parse_time(time: &str) -> Option<Duration> {
+    let time = time.trim();
+    let offset = |value: &str, scale: f64| {
+        value
+            .parse::<f64>()
+            .ok()
+            .and_then(|value| Duration::try_from_secs_f64(value * scale).ok())
+    };
+    if let Some(ms) = time.strip_suffix("ms") {
+        return offset(ms, 0.001);
+    }
+    if let Some(s) = time.strip_suffix('s') {
+        return offset(s, 1.);
+    }
+    if let Some(m) = time.strip_suffix('m') {
+        return offset(m, 60.);
+    }
+    if let Some(h) = time.strip_suffix('h') {
+        return offset(h, 3600.);
+    }
+
+    let mut secs = 0.;
+    for part in time.split(':') {
+        secs = secs * 60. + part.parse::<f64>().ok()?;
+    }
+    Duration::try_from_secs_f64(secs).ok()
+}
+
+/// `role` attribute in any namespace, like `ttm:role`
+fn role<'a>(node: &Node<'a, '_>) -> Option<&'a str> {
+    node.attributes()
+        .find(|attr| attr.name() == "role")
+        .map(|attr| attr.value())
+}
+
+fn collect_text(node: Node, text: &mut String) {
+    for child in node.children() {
+        if child.is_text() {
+            text.push_str(child.text().unwrap_or_default());
+        } else if child.tag_name().name() == "br" {
+            text.push(' ');
+        } else if child.is_element() && !matches!(role(&child), Some("x-translation" | "x-roman")) {
+            collect_text(child, text);
+        }
+    }
+}
+
+fn parse_lines(node: Node) -> Vec<LyricLineOwned> {
+    let mut lines: Vec<LyricLineOwned> = node
+        .descendants()
+        .filter(|node| node.tag_name().name() == "p")
+        .filter_map(|p| {
+            let start_time = p.attribute("begin").and_then(parse_time)?;
+            let mut text = String::new();
+            collect_text(p, &mut text);
+            let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+            Some(LyricLineOwned {
+                text,
+                start_time,
+                confidence: None,
+            })
+        })
+        .collect();
+    lines.sort_by_key(|line| line.start_time);
+    lines
+}
+
+/// Parse `ttml` into lines, `<p>` without `begin` are skipped
+pub fn parse_ttml(ttml: &str) -> Result<TtmlLyric, roxmltree::Error> {
+    let document = Document::parse(ttml)?;
+    let root = document.root_element();
+    let body = root
+        .descendants()
+        .find(|node| node.tag_name().name() == "body")
+        .unwrap_or(root);
+
+    let mut divs = body
+        .children()
+        .filter(|node| node.tag_name().name() == "div");
+    let Some(first) = divs.next() else {
+        return Ok(TtmlLyric {
+            origin: parse_lines(body),
+            translation: vec![],
+        });
+    };
+    Ok(TtmlLyric {
+        origin: parse_lines(first),
+        translation: divs.next().map(parse_lines).unwrap_or_default(),
+    })
+}
@@ -1,17 +1,41 @@
 use std::fs;
 use std::path::PathBuf;
+use std::time::Duration;
 
 use gtk::prelude::*;
+use gtk::subclass::prelude::ObjectSubclassIsExt;
 use gtk::{glib, Application};
 
 use anyhow::Result;
 
 use regex::RegexSet;
-use waylyrics::app::{self, build_main_window};
+use waylyrics::app::{
+    self, build_main_window,
+    shadow::{Background, Gradient, Outline, Shadow},
+};
+use waylyrics::cli::Cli;
 use waylyrics::config::append_comments;
-use waylyrics::config::{Config, Triggers};
+use waylyrics::config::{
+    label_aligns, label_fonts, watcher::watch_config, Config, ProvidersConfig, Triggers,
+};
+use waylyrics::lyric_providers::auth::{self, set_api_keys, AuthStatus};
+use waylyrics::lyric_providers::check;
+use waylyrics::lyric_providers::command::{set_custom_lyric_command, with_command_source};
+use waylyrics::lyric_providers::group::{expand_sources, set_provider_groups};
+use waylyrics::lyric_providers::local_files::{set_local_lrc_root, set_local_lyrics_dir};
+use waylyrics::lyric_providers::mpris_native::set_cache_player_lyric;
 use waylyrics::lyric_providers::qqmusic::QQMusic;
-use waylyrics::lyric_providers::utils::get_provider;
+use waylyrics::lyric_providers::router::{set_genre_preference, set_rated_preference};
+#[cfg(feature = "subsonic-provider")]
+use waylyrics::lyric_providers::subsonic::set_subsonic_server;
+use waylyrics::lyric_providers::utils::{
+    circuit_breaker::CircuitBreaker,
+    get_provider,
+    lyric_filter::set_lyric_filters,
+    normalise::set_title_strip_patterns,
+    ranking::{set_language_preference, set_min_confidence_score, set_ranking_weights},
+    retry::set_retry_policies,
+};
 use waylyrics::lyric_providers::LyricProvider;
 
 use waylyrics::utils::acquire_instance_name;
@@ -19,10 +43,17 @@ use waylyrics::{
     sync::lyric::fetch::tricks::EXTRACT_TRANSLATED_LYRIC,
     utils::{self, init_dirs},
     EXCLUDED_REGEXES, GTK_DBUS_CONNECTION, LYRIC_PROVIDERS, MAIN_WINDOW, PLAYER_IDENTITY_BLACKLIST,
-    PLAYER_NAME_BLACKLIST, THEME_PATH,
+    PLAYER_NAME_BLACKLIST,
 };
 
 use waylyrics::log;
+use waylyrics::sync::lyric::auto_offset::set_auto_offset;
+use waylyrics::sync::lyric::blacklist::load_blacklist;
+use waylyrics::sync::lyric::cache::{migrate_legacy_caches, set_year_in_cache_key};
+use waylyrics::sync::lyric::color::set_color_mode;
+use waylyrics::sync::lyric::fetch::set_fallback_to_nosync;
+use waylyrics::sync::lyric::signature::{set_cache_signing, SigningConfig};
+use waylyrics::sync::per_player::init_per_player;
 use waylyrics::sync::*;
 
 #[cfg(feature = "action-event")]
@@ -35,12 +66,12 @@ use tracing_subscriber::prelude::*;
 use tracing_subscriber::{fmt, Registry};
 
 use app::actions::{
-    register_reload_theme, register_set_display_mode, register_set_lyric_align,
-    register_switch_decoration, register_switch_passthrough,
+    register_adjust_opacity, register_clear_search_history, register_copy_current_line,
+    register_move_to_monitor, register_reload_theme, register_set_display_mode,
+    register_set_lyric_align, register_set_opacity, register_switch_decoration,
+    register_switch_passthrough, register_toggle_always_on_top, register_toggle_offline,
 };
 
-pub const THEME_PRESETS_DIR: Option<&str> = option_env!("WAYLYRICS_THEME_PRESETS_DIR");
-
 fn main() -> Result<glib::ExitCode> {
     #[cfg(feature = "i18n")]
     let i18n_result = {
@@ -61,10 +92,19 @@ fn main() -> Result<glib::ExitCode> {
                 .with_default_directive(LevelFilter::INFO.into())
                 .from_env()?,
         )
-        .with(fmt::Layer::new());
-
+        .with(fmt::Layer::new().with_writer(log::Redacted(std::io::stdout)))
+        .with(log::log_file_from_env().map(|file| {
+            fmt::Layer::new()
+                .with_ansi(false)
+                .with_target(true)
+                .with_writer(log::Redacted(file))
+        }));
+
+    // journald formats events itself, so those with secrets are left out instead
     #[cfg(feature = "journald")]
-    registry.with(tracing_journald::layer()?).init();
+    registry
+        .with(tracing_journald::layer()?.with_filter(log::DropUnredacted))
+        .init();
     #[cfg(not(feature = "journald"))]
     registry.init();
 
@@ -79,6 +119,23 @@ fn main() -> Result<glib::ExitCode> {
         ),
     }
 
+    let (cli, gtk_args) = Cli::parse(std::env::args());
+    if cli.check_auth {
+        return check_auth();
+    }
+    if cli.check_providers {
+        return check_providers();
+    }
+    if cli.rebuild_cache {
+        return rebuild_cache(cli.dry_run);
+    }
+    if cli.dump_state {
+        return dump_state();
+    }
+    if cli.list_players {
+        return list_players(cli.json);
+    }
+
     log::info!("process id: {}", std::process::id());
 
     acquire_instance_name()?;
@@ -95,8 +152,8 @@ fn main() -> Result<glib::ExitCode> {
 
     log::info!("successfully created application!");
 
-    app.connect_activate(|app| {
-        if let Err(e) = build_ui(app) {
+    app.connect_activate(move |app| {
+        if let Err(e) = build_ui(app, &cli) {
             log::error!("failed to start: {e}");
         }
     });
@@ -106,35 +163,244 @@ fn main() -> Result<glib::ExitCode> {
         GTK_DBUS_CONNECTION.set(dbus_conn);
     });
 
-    Ok(app.run())
+    Ok(app.run_with_args(&gtk_args))
 }
 
-fn build_ui(app: &Application) -> Result<()> {
-    use utils::parse_time;
-
+/// Read config and write it back with comments, returns `(config, config_path, theme_dir)`
+fn load_config() -> Result<(Config, PathBuf, PathBuf)> {
     let (config_path, theme_dir) = init_dirs()?;
 
     log::debug!("config path: {:?}", config_path);
     let config = std::fs::read_to_string(&config_path)?;
-    let config: Config = toml_edit::de::from_str(&config)?;
+    let mut config: Config = toml_edit::de::from_str(&config)?;
     let config_with_docs = append_comments(&toml::to_string(&config)?)?;
-    fs::write(config_path, config_with_docs)?;
+    fs::write(&config_path, config_with_docs)?;
+    config.apply_env_overrides(|name| std::env::var(name).ok());
+
+    Ok((config, config_path, theme_dir))
+}
+
+fn check_auth() -> Result<glib::ExitCode> {
+    let (config, ..) = load_config()?;
+    set_api_keys(&config.providers);
+    set_retry_policies(&config.providers)?;
+    QQMusic.init(&serde_json::to_string(&config.qqmusic)?)?;
+
+    let mut exit_code = glib::ExitCode::SUCCESS;
+    for (name, _) in config.providers.iter() {
+        let Some(provider) = get_provider(name) else {
+            continue;
+        };
+        let status = glib::MainContext::default().block_on(auth::check_auth(provider));
+        let status = match status {
+            AuthStatus::Valid => "valid".to_owned(),
+            AuthStatus::NotConfigured => "not configured".to_owned(),
+            AuthStatus::Invalid(e) => {
+                exit_code = glib::ExitCode::FAILURE;
+                format!("invalid ({})", log::redact(&e))
+            }
+        };
+        println!("{name}: {status}");
+    }
+
+    Ok(exit_code)
+}
+
+fn check_providers() -> Result<glib::ExitCode> {
+    let (config, _config_path, ..) = load_config()?;
+    #[cfg(feature = "lua-provider")]
+    load_lua_providers(&_config_path);
+    set_api_keys(&config.providers);
+    set_retry_policies(&config.providers)?;
+    QQMusic.init(&serde_json::to_string(&config.qqmusic)?)?;
+
+    // does not need GTK, but reqwest needs a tokio reactor
+    let runtime = tokio::runtime::Runtime::new()?;
+    set_custom_lyric_command(&config.custom_lyric_command);
+    #[cfg(feature = "subsonic-provider")]
+    set_subsonic_server(&config.providers.subsonic);
+    let mut results = vec![];
+    set_provider_groups(&config.provider_group, &config.lyric_search_source);
+    for name in &with_command_source(expand_sources(&config.lyric_search_source)) {
+        let Some(provider) = get_provider(name) else {
+            log::warn!("unknown provider: {name}");
+            continue;
+        };
+        results.push(runtime.block_on(check::check_provider(provider)));
+    }
+    print!("{}", check::format_table(&results));
+
+    if results.iter().all(check::CheckResult::passed) {
+        Ok(glib::ExitCode::SUCCESS)
+    } else {
+        Ok(glib::ExitCode::FAILURE)
+    }
+}
+
+fn rebuild_cache(dry_run: bool) -> Result<glib::ExitCode> {
+    use waylyrics::sync::lyric::rebuild;
+
+    let (config, _config_path, ..) = load_config()?;
+    #[cfg(feature = "lua-provider")]
+    load_lua_providers(&_config_path);
+    set_api_keys(&config.providers);
+    set_retry_policies(&config.providers)?;
+    QQMusic.init(&serde_json::to_string(&config.qqmusic)?)?;
+    set_custom_lyric_command(&config.custom_lyric_command);
+    #[cfg(feature = "subsonic-provider")]
+    set_subsonic_server(&config.providers.subsonic);
+    set_provider_groups(&config.provider_group, &config.lyric_search_source);
+    setup_providers(config.lyric_search_source.clone(), &config.providers)?;
+    set_language_preference(&config.language_preference);
+    set_ranking_weights(config.ranking_weights);
+    set_genre_preference(config.provider_genre_preference.clone());
+    set_rated_preference(
+        config.boost_rated_tracks,
+        config.preferred_providers_for_rated.clone(),
+    );
+    set_min_confidence_score(config.min_confidence_score);
+    set_title_strip_patterns(&config.title_strip_patterns)?;
+    set_lyric_filters(&config.lyric_filters)?;
+    load_blacklist();
+    set_year_in_cache_key(config.year_in_cache_key);
+    set_cache_signing(SigningConfig {
+        verify: config.verify_cache_signatures,
+        keyring: config.cache_signing_keyring.clone(),
+        signing_key: config.cache_signing_key.clone(),
+    });
+    set_fallback_to_nosync(config.fallback_to_nosync);
+
+    let length_toleration_ms = utils::parse_time(&config.length_toleration)?.as_millis();
+    let cache_dir = PathBuf::from(waylyrics::CACHE_DIR.with_borrow(Clone::clone));
+    let records = waylyrics::history::load_history();
+
+    // does not need GTK, but reqwest needs a tokio reactor
+    let runtime = tokio::runtime::Runtime::new()?;
+    let summary = runtime.block_on(rebuild::rebuild_cache(
+        &cache_dir,
+        &records,
+        length_toleration_ms,
+        dry_run,
+    ));
+
+    if summary.failed == 0 {
+        Ok(glib::ExitCode::SUCCESS)
+    } else {
+        Ok(glib::ExitCode::FAILURE)
+    }
+}
+
+/// player, lyric and window are left out as GTK is not started
+fn dump_state() -> Result<glib::ExitCode> {
+    use waylyrics::sync::debug_state::{debug_state, dump_state};
+
+    let (config, ..) = load_config()?;
+    // so that secrets in config are redacted
+    set_api_keys(&config.providers);
+    let state = debug_state(None, serde_json::to_value(&config).ok());
+    dump_state(&state, &config.debug_state_file)?;
+    Ok(glib::ExitCode::SUCCESS)
+}
+
+/// players are found over D-Bus or SMTC directly, GTK is not needed
+fn list_players(json: bool) -> Result<glib::ExitCode> {
+    println!("{}", format_player_summaries(&OS::player_summaries(), json));
+    Ok(glib::ExitCode::SUCCESS)
+}
+
+fn build_ui(app: &Application, cli: &Cli) -> Result<()> {
+    use utils::parse_time;
+
+    let (config, config_path, theme_dir) = load_config()?;
+    #[cfg(feature = "lua-provider")]
+    load_lua_providers(&config_path);
 
     #[cfg_attr(windows, allow(unused))]
     let Config {
         player_sync_interval,
+        player_sync_interval_battery,
         lyric_update_interval,
         theme,
         cache_lyrics,
+        year_in_cache_key,
+        verify_cache_signatures,
+        cache_signing_keyring,
+        cache_signing_key,
+        cache_player_lyric,
+        lyric_offset_ms,
+        auto_offset_detect,
+        auto_offset_threshold_ms,
+        reset_offset_on_track_change,
         enable_filter_regex,
         filter_regexies,
         ref length_toleration,
         triggers,
         lyric_search_source,
+        local_lyrics_dir,
+        local_lrc_root,
+        custom_lyric_command,
+        ranking_weights,
+        min_confidence_score,
+        title_strip_patterns,
+        language_preference,
         show_default_text_on_idle,
         show_lyric_on_pause,
+        gap_fill_text,
+        gap_fill_threshold_s,
+        lyric_line_timeout_ms,
+        lyric_line_min_duration_ms,
+        lyric_loop_threshold_ms,
+        no_timestamp_scroll_speed,
+        fallback_to_nosync,
+        override_duration_ms,
+        show_confidence_indicators,
+        confidence_warn_threshold,
+        lyric_spacing_above_ms,
+        lyric_spacing_below_ms,
+        lyrics_above_opacity,
+        lyrics_below_opacity,
+        translation_indent_px,
+        show_progress_bar,
+        show_position_in_title,
+        show_position_label,
+        show_line_indicator,
+        enable_repeat_animation,
+        animate_to_bpm,
+        font_size_auto_fit,
+        font_size_min,
+        lyrics_max_line_length,
+        lyrics_align,
+        lyrics_align_current,
+        lyrics_align_above,
+        lyrics_align_below,
+        lyrics_font,
+        lyrics_font_current,
+        lyrics_font_above,
+        lyrics_font_below,
+        lyric_font_weight_current,
+        lyric_font_weight_context,
+        lyrics_vertical_offset_pct,
+        double_tap_action,
+        snap_to_edge,
+        snap_threshold_px,
+        monitor_index,
+        #[cfg(feature = "transliterate-japanese")]
+        romaji_display,
         #[cfg(feature = "tray-icon")]
         show_tray_icon,
+        #[cfg(feature = "obs-integration")]
+        obs_enabled,
+        #[cfg(feature = "obs-integration")]
+        obs_host,
+        #[cfg(feature = "obs-integration")]
+        obs_port,
+        #[cfg(feature = "obs-integration")]
+        obs_password,
+        #[cfg(feature = "obs-integration")]
+        obs_text_source_name,
+        #[cfg(feature = "http-api")]
+        http_api_address,
+        debug_state_file,
         player_name_blacklist,
         player_identity_blacklist,
         enable_local_lyric,
@@ -142,34 +408,61 @@ fn build_ui(app: &Application) -> Result<()> {
         qqmusic,
         color_scheme,
         theme_dark_switch,
+        click_through,
+        window_decorated,
+        always_on_top,
+        window_opacity,
+        opacity_step,
+        shadow_color,
+        shadow_offset_x,
+        shadow_offset_y,
+        shadow_blur,
+        text_outline_width,
+        text_outline_color,
+        lyrics_color_current_gradient,
+        lyrics_background_color,
+        lyrics_background_radius,
+        lyric_spacing_px,
+        lyric_spacing_above_px,
+        lyric_spacing_below_px,
+        color_mode,
+        color_palette,
+        offline_mode,
+        providers,
+        per_player,
+        provider_genre_preference,
+        boost_rated_tracks,
+        preferred_providers_for_rated,
+        lyric_filters,
+        provider_group,
     } = config;
 
+    let player_sync_interval = parse_time(&player_sync_interval)?;
+    let player_sync_interval_battery = parse_time(&player_sync_interval_battery)?;
+    let lyric_update_interval = parse_time(&lyric_update_interval)?;
+
     #[cfg(feature = "tray-icon")]
     if show_tray_icon {
-        let result = start_tray_service();
+        let result = start_tray_service(player_sync_interval);
         log::info!("tray-icon status: {result:?}");
     }
 
-    let player_sync_interval = parse_time(&player_sync_interval)?;
-    let lyric_update_interval = parse_time(&lyric_update_interval)?;
-
-    let theme_file_name = format!("{theme}.css");
-    let user_theme = theme_dir.join(&theme_file_name);
-    let global_theme = THEME_PRESETS_DIR.map(|d| PathBuf::from(d).join(&theme_file_name));
+    #[cfg(feature = "obs-integration")]
+    if obs_enabled {
+        waylyrics::obs::init_obs(waylyrics::obs::ObsConfig {
+            host: obs_host,
+            port: obs_port,
+            password: obs_password,
+            text_source_name: obs_text_source_name,
+        });
+    }
 
-    let theme_path = if user_theme.exists() {
-        user_theme
-    } else {
-        let Some(global_theme) = global_theme else {
-            anyhow::bail!("theme {theme_file_name} not found");
-        };
-        global_theme
-    };
+    #[cfg(feature = "http-api")]
+    if !http_api_address.is_empty() {
+        waylyrics::http_api::start_http_api(http_api_address);
+    }
 
-    log::debug!("theme path: {:?}", theme_path);
-    let css_style = fs::read_to_string(&theme_path)?;
-    app::utils::merge_css(&css_style);
-    THEME_PATH.set(theme_path);
+    utils::load_theme(&theme_dir, &theme)?;
 
     #[cfg(not(windows))]
     utils::auto_theme_change(color_scheme, theme_dark_switch);
@@ -185,7 +478,129 @@ fn build_ui(app: &Application) -> Result<()> {
         show_lyric_on_pause,
     );
 
-    register_sync_task(ObjectExt::downgrade(&wind), player_sync_interval);
+    app::set_offline_mode(&wind, offline_mode || cli.offline);
+    if click_through {
+        app::input_region::set_click_through(&wind, true);
+    }
+    if !window_decorated {
+        wind.set_decorated(false);
+    }
+    if always_on_top {
+        app::stacking::set_always_on_top(&wind, true);
+    }
+    app::set_window_opacity(&wind, window_opacity);
+    wind.imp().opacity_step.set(opacity_step);
+    if snap_to_edge {
+        app::snap::watch_snap(&wind, snap_threshold_px);
+    }
+    app::monitor::watch_monitors(&wind, monitor_index);
+    wind.imp().double_tap_action.set(double_tap_action);
+    app::vertical_offset::set_lyrics_vertical_offset(&wind, lyrics_vertical_offset_pct);
+    app::set_lyric_shadow(
+        &wind,
+        Shadow::new(shadow_offset_x, shadow_offset_y, shadow_blur, &shadow_color)?,
+    );
+    app::set_lyric_outline(
+        &wind,
+        Outline::new(text_outline_width, &text_outline_color)?,
+    );
+    app::set_lyric_gradient(&wind, Gradient::new(&lyrics_color_current_gradient)?);
+    app::set_lyric_background(
+        &wind,
+        Background::new(&lyrics_background_color, lyrics_background_radius)?,
+    );
+    app::set_lyric_spacing(
+        &wind,
+        lyric_spacing_px,
+        lyric_spacing_above_px,
+        lyric_spacing_below_px,
+    );
+    wind.imp().gap_fill_text.replace(gap_fill_text);
+    wind.imp().debug_state_file.replace(debug_state_file);
+    app::set_progress_bar_visible(&wind, show_progress_bar);
+    app::position::set_position_display(&wind, show_position_in_title, show_position_label);
+    app::line_indicator::set_line_indicator_visible(&wind, show_line_indicator);
+    app::repeat_animation::set_repeat_animation(&wind, enable_repeat_animation);
+    app::bpm_pulse::set_animate_to_bpm(&wind, animate_to_bpm);
+    wind.imp().font_size_auto_fit.set(font_size_auto_fit);
+    wind.imp().font_size_min.set(font_size_min);
+    wind.imp()
+        .lyrics_max_line_length
+        .set(lyrics_max_line_length);
+    #[cfg(feature = "transliterate-japanese")]
+    wind.imp().romaji_display.set(romaji_display);
+    app::set_label_aligns(
+        &wind,
+        label_aligns(
+            lyrics_align,
+            lyrics_align_current,
+            lyrics_align_above,
+            lyrics_align_below,
+        ),
+    );
+    let fonts = label_fonts(
+        &lyrics_font,
+        &lyrics_font_current,
+        &lyrics_font_above,
+        &lyrics_font_below,
+    );
+    let [font_above, font_below] = fonts.map(app::fonts::parse_font);
+    let weight_current = app::fonts::parse_weight(&lyric_font_weight_current)?;
+    let weight_context = app::fonts::parse_weight(&lyric_font_weight_context)?;
+    app::fonts::set_label_fonts([
+        app::fonts::with_weight(font_above?, weight_current),
+        app::fonts::with_weight(font_below?, weight_context),
+    ]);
+    let gap_fill_threshold =
+        Duration::try_from_secs_f64(gap_fill_threshold_s).unwrap_or_else(|e| {
+            let default = Config::default().gap_fill_threshold_s;
+            log::warn!("invalid gap-fill-threshold-s {gap_fill_threshold_s}: {e}, using {default}");
+            Duration::from_secs_f64(default)
+        });
+    wind.imp().gap_fill_threshold.set(gap_fill_threshold);
+    wind.imp()
+        .lyric_line_timeout
+        .set(Duration::from_millis(lyric_line_timeout_ms));
+    wind.imp()
+        .lyric_line_min_duration
+        .set(Duration::from_millis(lyric_line_min_duration_ms));
+    set_loop_threshold(Duration::from_millis(lyric_loop_threshold_ms));
+    wind.imp()
+        .no_timestamp_scroll_speed
+        .set(no_timestamp_scroll_speed);
+    wind.imp()
+        .override_duration
+        .set(Duration::from_millis(override_duration_ms));
+    wind.imp()
+        .show_confidence_indicators
+        .set(show_confidence_indicators);
+    wind.imp()
+        .confidence_warn_threshold
+        .set(confidence_warn_threshold);
+    wind.imp()
+        .lyric_spacing_ms
+        .set([lyric_spacing_above_ms, lyric_spacing_below_ms]);
+    wind.imp()
+        .context_opacity
+        .set([lyrics_above_opacity, lyrics_below_opacity]);
+    wind.imp().translation_indent_px.set(translation_indent_px);
+    wind.imp().default_lyric_offset_ms.set(lyric_offset_ms);
+    wind.imp().lyric_offset_ms.set(lyric_offset_ms);
+    wind.imp()
+        .reset_offset_on_track_change
+        .set(reset_offset_on_track_change);
+    init_per_player(per_player, &wind);
+
+    waylyrics::config::persist::set_config_path(config_path.clone());
+    if let Err(e) = watch_config(config_path, theme_dir, ObjectExt::downgrade(&wind)) {
+        log::error!("cannot watch config: {e}");
+    }
+
+    register_sync_task_on_power(
+        ObjectExt::downgrade(&wind),
+        player_sync_interval,
+        player_sync_interval_battery,
+    );
     register_lyric_display(ObjectExt::downgrade(&wind), lyric_update_interval);
     register_actions(app, &wind, triggers);
 
@@ -198,9 +613,36 @@ fn build_ui(app: &Application) -> Result<()> {
         EXCLUDED_REGEXES.set(RegexSet::new(&filter_regexies)?);
     }
 
+    set_api_keys(&providers);
+    set_retry_policies(&providers)?;
     QQMusic.init(&serde_json::to_string(&qqmusic)?)?;
 
-    setup_providers(lyric_search_source);
+    set_custom_lyric_command(&custom_lyric_command);
+    #[cfg(feature = "subsonic-provider")]
+    set_subsonic_server(&providers.subsonic);
+    set_provider_groups(&provider_group, &lyric_search_source);
+    setup_providers(lyric_search_source, &providers)?;
+    set_local_lyrics_dir(&local_lyrics_dir);
+    set_local_lrc_root(&local_lrc_root);
+    set_language_preference(&language_preference);
+    set_ranking_weights(ranking_weights);
+    set_genre_preference(provider_genre_preference);
+    set_rated_preference(boost_rated_tracks, preferred_providers_for_rated);
+    set_min_confidence_score(min_confidence_score);
+    set_title_strip_patterns(&title_strip_patterns)?;
+    set_lyric_filters(&lyric_filters)?;
+    load_blacklist();
+    set_year_in_cache_key(year_in_cache_key);
+    migrate_legacy_caches(&waylyrics::history::load_history());
+    set_auto_offset(auto_offset_detect, auto_offset_threshold_ms);
+    set_cache_signing(SigningConfig {
+        verify: verify_cache_signatures,
+        keyring: cache_signing_keyring,
+        signing_key: cache_signing_key,
+    });
+    set_fallback_to_nosync(fallback_to_nosync);
+    set_cache_player_lyric(cache_player_lyric);
+    set_color_mode(color_mode, color_palette);
 
     #[cfg(target_os = "windows")]
     // * workaround for a GTK4 bug:
@@ -229,8 +671,13 @@ fn register_actions(
         switch_decoration,
         switch_passthrough,
         reload_theme,
+        reload_config,
+        save_config,
+        print_debug_state,
         search_lyric,
         refetch_lyric,
+        decrease_opacity,
+        increase_opacity,
     }: Triggers,
 ) {
     register_connect(app);
@@ -239,25 +686,70 @@ fn register_actions(
     register_set_display_mode(wind);
     register_switch_decoration(wind, &switch_decoration);
     register_switch_passthrough(wind, &switch_passthrough);
+    register_toggle_always_on_top(wind);
+    register_toggle_offline(wind);
+    register_copy_current_line(wind);
+    register_set_opacity(wind);
+    register_move_to_monitor(wind);
+    register_adjust_opacity(wind, &decrease_opacity, &increase_opacity);
     register_reload_theme(app, wind, &reload_theme);
     register_search_lyric(app, wind, &search_lyric);
+    register_clear_search_history(app);
+    register_compare_lyrics(app, wind);
+    register_open_editor(app, wind);
+    register_show_history(app);
+    register_show_track_info(app, wind);
     register_remove_lyric(app, wind);
+    register_swap_lyric_roles(app, wind);
+    register_unpin_lyric(app);
+    register_disable_auto_offset(app, wind);
+    register_import_netease_playlist(app, wind);
+    register_copy_lyric_as_lrc(app, wind);
+    register_random_lyric_line(app, wind);
+    register_clear_lyric_override(app, wind);
     register_reload_lyric(app);
+    register_reload_config(app, wind, &reload_config);
+    register_save_config(app, wind, &save_config);
+    register_print_debug_state(app, wind, &print_debug_state);
     register_refetch_lyric(app, wind, &refetch_lyric);
+    register_offset_reset(app, wind);
+    register_confess_wrong_lyric(app, wind);
+    register_open_provider_url(app, wind);
+    register_clear_blacklist(app);
     #[cfg(feature = "import-lyric")]
     register_import_lyric(app, wind);
+    #[cfg(feature = "import-lyric")]
+    register_import_original_lyric_from_clipboard(app, wind);
+    #[cfg(feature = "import-translation-from-srt")]
+    register_import_srt_translation(app, wind);
     #[cfg(feature = "export-lyric")]
     register_export_lyric(app, wind);
 }
 
-fn setup_providers(providers_enabled: Vec<String>) {
-    let mut providers = vec![];
-    for source in providers_enabled {
+/// scripts are loaded from `providers/` next to `config_path`
+#[cfg(feature = "lua-provider")]
+fn load_lua_providers(config_path: &std::path::Path) {
+    if let Some(config_dir) = config_path.parent() {
+        waylyrics::lyric_providers::lua::load_lua_providers(&config_dir.join("providers"));
+    }
+}
+
+fn setup_providers(providers_enabled: Vec<String>, config: &ProvidersConfig) -> Result<()> {
+    let mut providers: Vec<&'static dyn LyricProvider> = vec![];
+    for source in with_command_source(expand_sources(&providers_enabled)) {
         if let Some(provider) = get_provider(&source) {
-            providers.push(provider);
+            let cool_down = config
+                .get(provider.unique_name())
+                .map(|c| utils::parse_time(&c.circuit_cool_down))
+                .transpose()?
+                .unwrap_or(Duration::from_secs(60));
+            providers.push(Box::leak(Box::new(CircuitBreaker::new(
+                provider, cool_down,
+            ))));
         }
     }
     let _ = LYRIC_PROVIDERS.set(providers);
+    Ok(())
 }
 
 #[cfg(feature = "mimalloc")]
@@ -0,0 +1,204 @@
+//! Counters of lyric fetching, exposed in Prometheus text format 0.0.4 by `http_api`.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// upper bounds of `waylyrics_fetch_duration_seconds`, same as the default of Prometheus clients
+pub const DURATION_BUCKETS: [f64; 11] =
+    [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1., 2.5, 5., 10.];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FetchStatus {
+    /// found a likely matched song
+    Ok,
+    /// search succeeded without any matched song
+    NoResult,
+    Error,
+}
+
+impl FetchStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            FetchStatus::Ok => "ok",
+            FetchStatus::NoResult => "no_result",
+            FetchStatus::Error => "error",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Histogram {
+    /// not cumulative, one more than `DURATION_BUCKETS` for `+Inf`
+    buckets: [u64; DURATION_BUCKETS.len() + 1],
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    pub fn observe(&mut self, seconds: f64) {
+        let idx = DURATION_BUCKETS
+            .iter()
+            .position(|&bound| seconds <= bound)
+            .unwrap_or(DURATION_BUCKETS.len());
+        self.buckets[idx] += 1;
+        self.sum += seconds;
+        self.count += 1;
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Metrics {
+    fetch_total: BTreeMap<(String, FetchStatus), u64>,
+    fetch_duration: BTreeMap<String, Histogram>,
+    cache_hit: u64,
+    cache_miss: u64,
+    track_changes: u64,
+}
+
+static METRICS: Mutex<Metrics> = Mutex::new(Metrics {
+    fetch_total: BTreeMap::new(),
+    fetch_duration: BTreeMap::new(),
+    cache_hit: 0,
+    cache_miss: 0,
+    track_changes: 0,
+});
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', r"\\")
+        .replace('"', "\\\"")
+        .replace('\n', r"\n")
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+impl Metrics {
+    pub fn record_fetch(&mut self, provider: &str, status: FetchStatus, duration: Duration) {
+        *self
+            .fetch_total
+            .entry((provider.to_owned(), status))
+            .or_default() += 1;
+        self.fetch_duration
+            .entry(provider.to_owned())
+            .or_default()
+            .observe(duration.as_secs_f64());
+    }
+
+    pub fn record_cache_hit(&mut self) {
+        self.cache_hit += 1;
+    }
+
+    pub fn record_cache_miss(&mut self) {
+        self.cache_miss += 1;
+    }
+
+    pub fn record_track_change(&mut self) {
+        self.track_changes += 1;
+    }
+
+    /// in Prometheus text exposition format 0.0.4
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        header(
+            &mut out,
+            "waylyrics_fetch_total",
+            "counter",
+            "Lyric searches on each provider, by result.",
+        );
+        for ((provider, status), count) in &self.fetch_total {
+            let _ = writeln!(
+                out,
+                "waylyrics_fetch_total{{provider=\"{}\",status=\"{}\"}} {count}",
+                escape_label(provider),
+                status.as_str()
+            );
+        }
+
+        header(
+            &mut out,
+            "waylyrics_fetch_duration_seconds",
+            "histogram",
+            "Time taken by lyric searches on each provider.",
+        );
+        for (provider, histogram) in &self.fetch_duration {
+            let provider = escape_label(provider);
+            let mut cumulative = 0;
+            let bounds = DURATION_BUCKETS.iter().map(f64::to_string);
+            for (le, count) in bounds.chain(["+Inf".to_owned()]).zip(histogram.buckets) {
+                cumulative += count;
+                let _ = writeln!(
+                    out,
+                    "waylyrics_fetch_duration_seconds_bucket{{provider=\"{provider}\",le=\"{le}\"}} {cumulative}"
+                );
+            }
+            let _ = writeln!(
+                out,
+                "waylyrics_fetch_duration_seconds_sum{{provider=\"{provider}\"}} {}",
+                histogram.sum
+            );
+            let _ = writeln!(
+                out,
+                "waylyrics_fetch_duration_seconds_count{{provider=\"{provider}\"}} {}",
+                histogram.count
+            );
+        }
+
+        for (name, help, value) in [
+            (
+                "waylyrics_cache_hit_total",
+                "Tracks whose lyric was loaded from cache.",
+                self.cache_hit,
+            ),
+            (
+                "waylyrics_cache_miss_total",
+                "Tracks whose lyric was not in cache.",
+                self.cache_miss,
+            ),
+            (
+                "waylyrics_track_changes_total",
+                "Tracks played since start.",
+                self.track_changes,
+            ),
+        ] {
+            header(&mut out, name, "counter", help);
+            let _ = writeln!(out, "{name} {value}");
+        }
+        out
+    }
+}
+
+fn with_metrics(f: impl FnOnce(&mut Metrics)) {
+    if let Ok(mut metrics) = METRICS.lock() {
+        f(&mut metrics);
+    }
+}
+
+pub fn record_fetch(provider: &str, status: FetchStatus, duration: Duration) {
+    with_metrics(|metrics| metrics.record_fetch(provider, status, duration));
+}
+
+pub fn record_cache_hit() {
+    with_metrics(Metrics::record_cache_hit);
+}
+
+pub fn record_cache_miss() {
+    with_metrics(Metrics::record_cache_miss);
+}
+
+pub fn record_track_change() {
+    with_metrics(Metrics::record_track_change);
+}
+
+/// Metrics recorded since start, in Prometheus text exposition format 0.0.4
+pub fn render() -> String {
+    METRICS
+        .lock()
+        .map(|metrics| metrics.render())
+        .unwrap_or_default()
+}
@@ -0,0 +1,177 @@
+//! Show the current lyric line in an OBS text source, through OBS WebSocket (protocol v5).
+//!
+//! The connection is made when the first line is sent, and made again after any failure.
+
+use std::cell::RefCell;
+use std::sync::OnceLock;
+
+use anyhow::{anyhow, Result};
+use async_channel::{Receiver, Sender};
+use base64::Engine;
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use tokio::net::TcpStream;
+use tokio_tungstenite::{tungstenite::Message, MaybeTlsStream, WebSocketStream};
+
+use crate::log::{debug, info, warn};
+use crate::tokio_spawn;
+
+/// `rpcVersion` of OBS WebSocket v5
+const RPC_VERSION: u64 = 1;
+
+/// opcodes of OBS WebSocket v5
+mod op {
+    pub const HELLO: u64 = 0;
+    pub const IDENTIFY: u64 = 1;
+    pub const IDENTIFIED: u64 = 2;
+    pub const REQUEST: u64 = 6;
+    pub const REQUEST_RESPONSE: u64 = 7;
+}
+
+#[derive(Clone, Debug)]
+pub struct ObsConfig {
+    pub host: String,
+    pub port: u16,
+    /// empty if authentication is disabled in OBS
+    pub password: String,
+    pub text_source_name: String,
+}
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+static LINE_SENDER: OnceLock<Sender<String>> = OnceLock::new();
+
+thread_local! {
+    static LAST_LINE: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// `authentication` string for `Identify`, from `challenge` and `salt` in `Hello`
+pub fn authentication(password: &str, salt: &str, challenge: &str) -> String {
+    let base64 = base64::engine::general_purpose::STANDARD;
+    let secret = base64.encode(Sha256::digest(format!("{password}{salt}")));
+    base64.encode(Sha256::digest(format!("{secret}{challenge}")))
+}
+
+/// `SetInputSettings` request updating text of `source_name`
+pub fn set_text_request(source_name: &str, text: &str, request_id: u64) -> Value {
+    json!({
+        "op": op::REQUEST,
+        "d": {
+            "requestType": "SetInputSettings",
+            "requestId": request_id.to_string(),
+            "requestData": {
+                "inputName": source_name,
+                "inputSettings": { "text": text },
+            },
+        },
+    })
+}
+
+/// Start sending lines passed to `update_line` to OBS
+pub fn init_obs(config: ObsConfig) {
+    let (tx, rx) = async_channel::unbounded();
+    if LINE_SENDER.set(tx).is_err() {
+        return;
+    }
+    tokio_spawn!(run(config, rx));
+}
+
+/// Send `text` to OBS if it's not the line sent last time
+pub fn update_line(text: &str) {
+    let Some(sender) = LINE_SENDER.get() else {
+        return;
+    };
+    let changed = LAST_LINE.with_borrow_mut(|last| {
+        if last.as_deref() == Some(text) {
+            return false;
+        }
+        *last = Some(text.to_owned());
+        true
+    });
+    if changed {
+        let _ = sender.try_send(text.to_owned());
+    }
+}
+
+async fn run(config: ObsConfig, rx: Receiver<String>) {
+    let mut socket: Option<Socket> = None;
+    let mut request_id = 0;
+    while let Ok(mut text) = rx.recv().await {
+        // only the latest line matters if we fell behind
+        while let Ok(newer) = rx.try_recv() {
+            text = newer;
+        }
+
+        if socket.is_none() {
+            match connect(&config).await {
+                Ok(connected) => {
+                    info!("connected to OBS at {}:{}", config.host, config.port);
+                    socket = Some(connected);
+                }
+                Err(e) => {
+                    warn!("cannot connect to OBS: {e}");
+                    continue;
+                }
+            }
+        }
+        let Some(ws) = socket.as_mut() else {
+            continue;
+        };
+
+        request_id += 1;
+        if let Err(e) = set_text(ws, &config.text_source_name, &text, request_id).await {
+            warn!("lost connection to OBS: {e}");
+            socket = None;
+        }
+    }
+}
+
+async fn recv_op(ws: &mut Socket, expected: u64) -> Result<Value> {
+    while let Some(message) = ws.next().await {
+        let Message::Text(message) = message? else {
+            continue;
+        };
+        let message: Value = serde_json::from_str(&message)?;
+        if message["op"].as_u64() == Some(expected) {
+            return Ok(message["d"].clone());
+        }
+    }
+    Err(anyhow!("connection closed"))
+}
+
+async fn connect(config: &ObsConfig) -> Result<Socket> {
+    let url = format!("ws://{}:{}", config.host, config.port);
+    let (mut ws, _) = tokio_tungstenite::connect_async(url).await?;
+
+    let hello = recv_op(&mut ws, op::HELLO).await?;
+    let mut identify = json!({ "rpcVersion": RPC_VERSION });
+    if let (Some(challenge), Some(salt)) = (
+        hello["authentication"]["challenge"].as_str(),
+        hello["authentication"]["salt"].as_str(),
+    ) {
+        identify["authentication"] = authentication(&config.password, salt, challenge).into();
+    }
+    ws.send(Message::text(
+        json!({ "op": op::IDENTIFY, "d": identify }).to_string(),
+    ))
+    .await?;
+    recv_op(&mut ws, op::IDENTIFIED).await?;
+    Ok(ws)
+}
+
+async fn set_text(ws: &mut Socket, source_name: &str, text: &str, request_id: u64) -> Result<()> {
+    let request = set_text_request(source_name, text, request_id);
+    ws.send(Message::text(request.to_string())).await?;
+
+    let response = recv_op(ws, op::REQUEST_RESPONSE).await?;
+    let status = &response["requestStatus"];
+    if status["result"].as_bool() != Some(true) {
+        // the source may be missing for now, no need to reconnect
+        debug!(
+            "OBS refused to set text of {source_name}: {}",
+            status["comment"].as_str().unwrap_or_default()
+        );
+    }
+    Ok(())
+}
@@ -1,5 +1,7 @@
-use async_channel::Sender;
-use std::sync::OnceLock;
+use async_channel::{Receiver, Sender};
+use std::cell::Cell;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
 
 use gtk::{
     glib::{self, WeakRef},
@@ -8,6 +10,8 @@ use gtk::{
 };
 
 use crate::log::debug;
+use crate::lyric_providers::LyricLineOwned;
+use crate::sync::TrackMeta;
 
 #[derive(Clone, Debug)]
 pub enum PlayAction {
@@ -65,3 +69,62 @@ pub fn init_play_action_channel(app: WeakRef<Application>) {
 }
 
 pub static PLAY_ACTION: OnceLock<Sender<PlayAction>> = OnceLock::new();
+
+/// What happened to lyric and player, for code embedding waylyrics, see `subscribe_events`
+#[derive(Clone, Debug)]
+pub enum PlayEvent {
+    /// the current line, `translation` is empty if there's none
+    LyricLineChanged {
+        line: String,
+        translation: String,
+        timestamp: Duration,
+    },
+    TrackChanged {
+        meta: TrackMeta,
+    },
+    PlayerConnected {
+        identity: String,
+    },
+}
+
+static EVENT_SUBSCRIBERS: Mutex<Vec<Sender<PlayEvent>>> = Mutex::new(vec![]);
+
+thread_local! {
+    /// start time of the line sent last, so that each line is sent once
+    static LAST_LINE: Cell<Option<Duration>> = const { Cell::new(None) };
+}
+
+/// Receive every `PlayEvent` from now on, until the receiver is dropped
+pub fn subscribe_events() -> Receiver<PlayEvent> {
+    let (tx, rx) = async_channel::unbounded();
+    if let Ok(mut subscribers) = EVENT_SUBSCRIBERS.lock() {
+        subscribers.push(tx);
+    }
+    rx
+}
+
+pub fn emit_event(event: PlayEvent) {
+    let Ok(mut subscribers) = EVENT_SUBSCRIBERS.lock() else {
+        return;
+    };
+    // unbounded, so sending only fails once the receiver is dropped
+    subscribers.retain(|subscriber| subscriber.try_send(event.clone()).is_ok());
+}
+
+/// Send `LyricLineChanged` if `line` is not the one sent last
+pub fn emit_line_changed(line: Option<&LyricLineOwned>, translation: Option<&LyricLineOwned>) {
+    let timestamp = line.map(|line| line.start_time);
+    if LAST_LINE.replace(timestamp) == timestamp {
+        return;
+    }
+    let Some(line) = line else {
+        return;
+    };
+    emit_event(PlayEvent::LyricLineChanged {
+        line: line.text.clone(),
+        translation: translation
+            .map(|line| line.text.clone())
+            .unwrap_or_default(),
+        timestamp: line.start_time,
+    });
+}
@@ -1,12 +1,12 @@
 use crate::{
-    app::search_window,
+    app::{compare_window, editor_window, history_window, search_window, track_info_window},
     log::{info, warn},
     sync::{lyric::fetch::tricks::LYRIC_TAG_CACHE, LyricState},
     utils::bind_shortcut,
 };
 use glib_macros::clone;
 use gtk::{
-    gio::SimpleAction,
+    gio::{self, SimpleAction},
     glib::{self, VariantTy},
     prelude::*,
     subclass::prelude::ObjectSubclassIsExt,
@@ -14,11 +14,28 @@ use gtk::{
 };
 
 use crate::{
-    app::{self, dialog::show_dialog},
+    app::{
+        self,
+        dialog::{prompt_dialog, show_dialog},
+    },
     glib_spawn,
-    lyric_providers::LyricOwned,
-    sync::{interop::common::update_lyric, TrackState, LYRIC, TRACK_PLAYING_STATE},
-    utils::reset_lyric_labels,
+    lyric_providers::{
+        join_artists,
+        utils::http_cache::{self, LyricSource},
+        LyricOwned,
+    },
+    sync::{
+        get_lyric_cache_path, get_lyric_source,
+        interop::common::update_lyric,
+        lyric::{
+            blacklist,
+            cache::{set_auto_offset_disabled, set_lyric_pinned, swap_cached_lyric_roles},
+            scroll::{clear_lyric_override, refresh_lyric, set_lyric_override},
+            signature,
+        },
+        reset_lyric_offset, swap_lyric_roles, TrackState, LYRIC, TRACK_PLAYING_STATE,
+    },
+    utils::{gettext, reset_lyric_labels},
     MAIN_WINDOW,
 };
 
@@ -45,14 +62,8 @@ pub fn register_search_lyric(app: &Application, wind: &app::Window, trigger: &st
                 };
                 let artists = track
                     .artists
-                    .as_ref()
-                    .map(|artists| {
-                        artists
-                            .iter()
-                            .map(String::as_str)
-                            .collect::<Vec<&str>>()
-                            .join("/")
-                    })
+                    .as_deref()
+                    .map(join_artists)
                     .unwrap_or_default();
                 let title = track.title.as_deref().unwrap_or_default().to_string();
                 let album = track.album.as_deref().unwrap_or_default().to_string();
@@ -67,6 +78,62 @@ pub fn register_search_lyric(app: &Application, wind: &app::Window, trigger: &st
     bind_shortcut("app.search-lyric", wind, trigger);
 }
 
+/// fetch lyric of current track from all providers and show them side by side
+pub fn register_compare_lyrics(app: &Application, wind: &app::Window) {
+    let action = SimpleAction::new("compare-lyrics", None);
+    // read when activated, as they may be reloaded or overridden by player config
+    action.connect_activate(clone!(
+        #[weak]
+        wind,
+        move |_, _| {
+            let metainfo = TRACK_PLAYING_STATE
+                .with_borrow(|TrackState { metainfo, .. }| metainfo.as_ref().cloned());
+            let Some(metainfo) = metainfo else {
+                return;
+            };
+            compare_window::show_compare_window(
+                metainfo,
+                wind.imp().length_toleration_ms.get(),
+                wind.imp().cache_lyrics.get(),
+            );
+        }
+    ));
+    app.add_action(&action);
+}
+
+pub fn register_open_editor(app: &Application, wind: &app::Window) {
+    let action = SimpleAction::new("open-editor", None);
+    action.connect_activate(clone!(
+        #[weak]
+        wind,
+        move |_, _| {
+            let window = editor_window::Window::new(wind.imp().cache_lyrics.get());
+            window.present();
+        }
+    ));
+    app.add_action(&action);
+}
+
+pub fn register_show_history(app: &Application) {
+    let action = SimpleAction::new("show-history", None);
+    action.connect_activate(move |_, _| {
+        let window = history_window::Window::new();
+        window.present();
+    });
+    app.add_action(&action);
+}
+
+/// Show metadata, cache path and source of the playing track
+pub fn register_show_track_info(app: &Application, wind: &app::Window) {
+    let action = SimpleAction::new("show-track-info", None);
+    action.connect_activate(clone!(
+        #[weak(rename_to = window)]
+        wind,
+        move |_, _| track_info_window::show_track_info_window(&window)
+    ));
+    app.add_action(&action);
+}
+
 /// update lyric, but do not ignore cache
 pub fn register_reload_lyric(app: &Application) {
     let action = SimpleAction::new("reload-lyric", None);
@@ -96,6 +163,109 @@ pub fn register_reload_lyric(app: &Application) {
     app.add_action(&action);
 }
 
+/// dump global state for bug reports to stderr, and to `debug-state-file` if set
+pub fn register_print_debug_state(app: &Application, window: &app::Window, trigger: &str) {
+    use crate::sync::debug_state::{debug_state, dump_state};
+
+    let action = SimpleAction::new("print-debug-state", None);
+    action.connect_activate(clone!(
+        #[weak]
+        window,
+        move |_, _| {
+            let config = crate::config::watcher::loaded_config()
+                .and_then(|config| serde_json::to_value(config).ok());
+            let state = debug_state(Some(&window), config);
+            let file = window.imp().debug_state_file.borrow().clone();
+            match dump_state(&state, &file) {
+                Ok(()) => info!("printed debug state"),
+                Err(e) => warn!("cannot dump debug state: {e}"),
+            }
+        }
+    ));
+    app.add_action(&action);
+    // no shortcut by default
+    if !trigger.is_empty() {
+        bind_shortcut("app.print-debug-state", window, trigger);
+    }
+}
+
+/// config keys changed by runtime actions, with their values in `window`
+fn runtime_config_values(window: &app::Window) -> Vec<(&'static str, toml_edit::Value)> {
+    let imp = window.imp();
+    vec![
+        ("window-opacity", window.opacity().into()),
+        ("click-through", imp.clickthrough.get().into()),
+        ("window-decorated", window.is_decorated().into()),
+        ("always-on-top", imp.always_on_top.get().into()),
+        ("offline-mode", imp.offline_mode.get().into()),
+    ]
+}
+
+/// write opacity, click-through, decoration, always-on-top and offline mode back to config.
+///
+/// The lyric offset is left out, as it's the offset of the current track, not a default.
+pub fn register_save_config(app: &Application, window: &app::Window, trigger: &str) {
+    use crate::app::dialog::confirm_dialog;
+    use crate::config::persist::persist_config_values;
+
+    let action = SimpleAction::new("save-config", None);
+    action.connect_activate(clone!(
+        #[weak]
+        window,
+        move |_, _| {
+            confirm_dialog(
+                Some(&window),
+                &gettext("Overwrite config file with current settings?"),
+                clone!(
+                    #[weak]
+                    window,
+                    move || {
+                        let values = runtime_config_values(&window);
+                        match persist_config_values(values) {
+                            Ok(()) => info!("saved config"),
+                            Err(e) => show_dialog(
+                                Some(&window),
+                                &format!("{}\n{e}", gettext("Cannot save config:")),
+                                gtk::MessageType::Error,
+                            ),
+                        }
+                    }
+                ),
+            );
+        }
+    ));
+    app.add_action(&action);
+    bind_shortcut("app.save-config", window, trigger);
+}
+
+/// read config file again and apply it, unlike the watcher it reports parse errors
+pub fn register_reload_config(app: &Application, window: &app::Window, trigger: &str) {
+    use crate::config::watcher::{reload_config, Reloaded};
+
+    let action = SimpleAction::new("reload-config", None);
+    action.connect_activate(clone!(
+        #[weak]
+        window,
+        move |_, _| match reload_config(&window) {
+            Ok(Reloaded { changed, restart }) => {
+                if changed.is_empty() {
+                    info!("config not changed");
+                }
+                for key in restart {
+                    warn!("{key} cannot be changed without restarting, ignored");
+                }
+            }
+            Err(e) => show_dialog(
+                Some(&window),
+                &format!("{}\n{e}", gettext("Cannot reload config:")),
+                gtk::MessageType::Error,
+            ),
+        }
+    ));
+    app.add_action(&action);
+    bind_shortcut("app.reload-config", window, trigger);
+}
+
 pub fn register_refetch_lyric(app: &Application, window: &app::Window, trigger: &str) {
     let action = SimpleAction::new("refetch-lyric", None);
     action.connect_activate(move |_, _| {
@@ -126,6 +296,20 @@ pub fn register_refetch_lyric(app: &Application, window: &app::Window, trigger:
     bind_shortcut("app.refetch-lyric", window, trigger);
 }
 
+pub fn register_offset_reset(app: &Application, wind: &app::Window) {
+    let action = SimpleAction::new("offset-reset", None);
+    action.connect_activate(clone!(
+        #[weak(rename_to = window)]
+        wind,
+        move |_, _| {
+            reset_lyric_offset(&window);
+            // sync position now, so the new offset applies without waiting for next tick
+            let _ = OS::try_sync_track(&window);
+        }
+    ));
+    app.add_action(&action);
+}
+
 pub fn register_remove_lyric(app: &Application, wind: &app::Window) {
     let action = SimpleAction::new("remove-lyric", None);
     action.connect_activate(clone!(
@@ -138,6 +322,7 @@ pub fn register_remove_lyric(app: &Application, wind: &app::Window) {
             LYRIC.set(LyricState {
                 origin,
                 translation,
+                source_url: None,
             });
             let cache_lyrics = window.imp().cache_lyrics.get();
             // Update cache
@@ -152,6 +337,215 @@ pub fn register_remove_lyric(app: &Application, wind: &app::Window) {
     app.add_action(&action);
 }
 
+/// Swap origin and translation of current lyric, for providers returning them the other way round
+pub fn register_swap_lyric_roles(app: &Application, wind: &app::Window) {
+    let action = SimpleAction::new("swap-lyric-roles", None);
+    action.connect_activate(clone!(
+        #[weak(rename_to = window)]
+        wind,
+        move |_, _| {
+            if !LYRIC.with_borrow_mut(swap_lyric_roles) {
+                info!("no translation to swap with");
+                return;
+            }
+            if window.imp().cache_lyrics.get() {
+                if let Some(cache_path) = get_lyric_cache_path() {
+                    swap_cached_lyric_roles(&cache_path);
+                }
+            }
+            let paused = TRACK_PLAYING_STATE.with_borrow(|TrackState { paused, .. }| *paused);
+            refresh_lyric(&window, paused);
+            info!("swapped origin and translation");
+        }
+    ));
+    app.add_action(&action);
+}
+
+/// Let refetching the current track search providers again, after "Pin this lyric"
+pub fn register_unpin_lyric(app: &Application) {
+    let action = SimpleAction::new("unpin-lyric", None);
+    action.connect_activate(move |_, _| {
+        let Some(cache_path) = get_lyric_cache_path() else {
+            info!("no lyric cache to unpin");
+            return;
+        };
+        if set_lyric_pinned(&cache_path, false) {
+            info!("unpinned lyric of current track");
+        }
+    });
+    app.add_action(&action);
+}
+
+/// Stop `auto-offset-detect` for the current track, dropping the offset it detected
+pub fn register_disable_auto_offset(app: &Application, wind: &app::Window) {
+    let action = SimpleAction::new("disable-auto-offset", None);
+    action.connect_activate(clone!(
+        #[weak(rename_to = window)]
+        wind,
+        move |_, _| {
+            let Some(cache_path) = get_lyric_cache_path() else {
+                info!("no lyric cache to disable auto offset for");
+                return;
+            };
+            if set_auto_offset_disabled(&cache_path, true) {
+                reset_lyric_offset(&window);
+            }
+        }
+    ));
+    app.add_action(&action);
+}
+
+/// Cache lyrics of a whole NetEase playlist, enabled only if NetEase is in `lyric-search-source`
+pub fn register_import_netease_playlist(app: &Application, wind: &app::Window) {
+    use crate::sync::lyric::playlist::netease_configured;
+    use utils::import_netease_playlist;
+
+    let action = SimpleAction::new("import-netease-playlist", None);
+    action.set_enabled(netease_configured());
+    action.connect_activate(clone!(
+        #[weak(rename_to = window)]
+        wind,
+        move |_, _| {
+            if !window.imp().cache_lyrics.get() {
+                show_dialog(
+                    Some(&window),
+                    &gettext("cannot import playlist with cache-lyrics disabled"),
+                    gtk::MessageType::Error,
+                );
+                return;
+            }
+            prompt_dialog(
+                Some(&window),
+                &gettext("NetEase playlist ID"),
+                clone!(
+                    #[weak]
+                    window,
+                    move |id| {
+                        glib_spawn!(async move {
+                            import_netease_playlist(&window, &id).await;
+                        });
+                    }
+                ),
+            );
+        }
+    ));
+    app.add_action(&action);
+}
+
+/// Show a random line of current lyric for a while, for fun
+pub fn register_random_lyric_line(app: &Application, wind: &app::Window) {
+    let action = SimpleAction::new("random-lyric-line", None);
+    action.connect_activate(clone!(
+        #[weak(rename_to = window)]
+        wind,
+        move |_, _| {
+            let lines = LYRIC.with_borrow(|LyricState { origin, .. }| match origin {
+                LyricOwned::LineTimestamp(lines) => lines.len(),
+                _ => 0,
+            });
+            if lines == 0 {
+                info!("no line to pick from");
+                return;
+            }
+            let index = glib::random_int_range(0, lines.min(i32::MAX as usize) as i32) as usize;
+            set_lyric_override(&window, index);
+            let paused = TRACK_PLAYING_STATE.with_borrow(|TrackState { paused, .. }| *paused);
+            refresh_lyric(&window, paused);
+        }
+    ));
+    app.add_action(&action);
+}
+
+pub fn register_clear_lyric_override(app: &Application, wind: &app::Window) {
+    let action = SimpleAction::new("clear-lyric-override", None);
+    action.connect_activate(clone!(
+        #[weak(rename_to = window)]
+        wind,
+        move |_, _| {
+            clear_lyric_override(&window);
+            let paused = TRACK_PLAYING_STATE.with_borrow(|TrackState { paused, .. }| *paused);
+            refresh_lyric(&window, paused);
+        }
+    ));
+    app.add_action(&action);
+}
+
+/// Open the page of current lyric on its provider's website, to report errors there
+pub fn register_open_provider_url(app: &Application, wind: &app::Window) {
+    let action = SimpleAction::new("open-provider-url", None);
+    action.connect_activate(clone!(
+        #[weak(rename_to = window)]
+        wind,
+        move |_, _| {
+            let Some(source_url) = LYRIC.with_borrow(|lyric| lyric.source_url.clone()) else {
+                show_dialog(
+                    Some(&window),
+                    &gettext("current lyric has no source page"),
+                    gtk::MessageType::Info,
+                );
+                return;
+            };
+            info!("opening {source_url}");
+            #[allow(deprecated)]
+            gtk::show_uri(Some(&window), &source_url, gtk::gdk::CURRENT_TIME);
+        }
+    ));
+    app.add_action(&action);
+}
+
+/// Mark current lyric as wrong: blacklist its provider result, remove its cache and refetch
+pub fn register_confess_wrong_lyric(app: &Application, wind: &app::Window) {
+    let action = SimpleAction::new("confess-wrong-lyric", None);
+    action.connect_activate(clone!(
+        #[weak(rename_to = window)]
+        wind,
+        move |_, _| {
+            let Some(LyricSource { provider, id, .. }) = get_lyric_source() else {
+                show_dialog(
+                    Some(&window),
+                    &gettext("current lyric was not fetched from a provider"),
+                    gtk::MessageType::Error,
+                );
+                return;
+            };
+            let metainfo = TRACK_PLAYING_STATE
+                .with_borrow(|TrackState { metainfo, .. }| metainfo.as_ref().cloned());
+            let Some(metainfo) = metainfo else {
+                return;
+            };
+
+            blacklist::add_to_blacklist(provider, id);
+            if let Some(cache_path) = get_lyric_cache_path() {
+                let _ = std::fs::remove_file(&cache_path);
+                http_cache::write_sidecar(&cache_path, None);
+                signature::remove_signature(&cache_path);
+                info!("removed cache {cache_path:?}");
+            }
+
+            crate::log::debug!("spawned update_lyric from confess-wrong-lyric action");
+            glib_spawn!(async move {
+                reset_lyric_labels(&window, None);
+                if let Err(err) = update_lyric(&metainfo, &window, true).await {
+                    show_dialog(
+                        Some(&window),
+                        &format!("cannot refetch lyric: {err:?}"),
+                        gtk::MessageType::Error,
+                    );
+                }
+            });
+        }
+    ));
+    app.add_action(&action);
+}
+
+pub fn register_clear_blacklist(app: &Application) {
+    let action = SimpleAction::new("clear-blacklist", None);
+    action.connect_activate(|_, _| {
+        blacklist::clear_blacklist();
+    });
+    app.add_action(&action);
+}
+
 #[cfg(feature = "import-lyric")]
 pub fn register_import_lyric(app: &Application, wind: &app::Window) {
     use utils::import_lyric;
@@ -172,6 +566,60 @@ pub fn register_import_lyric(app: &Application, wind: &app::Window) {
     app.add_action(&action);
 }
 
+#[cfg(feature = "import-lyric")]
+pub fn register_import_original_lyric_from_clipboard(app: &Application, wind: &app::Window) {
+    use utils::import_lyric_from_clipboard;
+
+    let action = SimpleAction::new("import-lyric-from-clipboard", None);
+    action.connect_activate(clone!(
+        #[weak(rename_to = window)]
+        wind,
+        move |_, _| {
+            glib_spawn!(async move {
+                import_lyric_from_clipboard(&window).await;
+            });
+        }
+    ));
+    app.add_action(&action);
+}
+
+#[cfg(feature = "import-translation-from-srt")]
+pub fn register_import_srt_translation(app: &Application, wind: &app::Window) {
+    use utils::import_srt_translation;
+
+    let action = SimpleAction::new("import-srt-translation", None);
+    action.connect_activate(clone!(
+        #[weak(rename_to = window)]
+        wind,
+        move |_, _| {
+            glib_spawn!(async move {
+                import_srt_translation(&window).await;
+            });
+        }
+    ));
+    app.add_action(&action);
+}
+
+/// Copy the whole lyric to clipboard as LRC, for sharing
+pub fn register_copy_lyric_as_lrc(app: &Application, wind: &app::Window) {
+    let action = SimpleAction::new("copy-lyric-as-lrc", None);
+    action.connect_activate(clone!(
+        #[weak(rename_to = window)]
+        wind,
+        #[weak]
+        app,
+        move |_, _| {
+            if !utils::copy_lyric_as_lrc(&window) {
+                return;
+            }
+            let notification = gio::Notification::new(&gettext("Lyric copied"));
+            notification.set_body(Some(&gettext("Lyric was copied to clipboard as LRC")));
+            app.send_notification(Some("lyric-copied"), &notification);
+        }
+    ));
+    app.add_action(&action);
+}
+
 #[cfg(feature = "export-lyric")]
 pub fn register_export_lyric(app: &Application, wind: &app::Window) {
     use utils::export_lyric;
@@ -208,4 +656,7 @@ pub fn register_connect(app: &Application) {
 #[cfg(feature = "action-event")]
 mod event;
 #[cfg(feature = "action-event")]
-pub use event::{init_play_action_channel, PlayAction, PLAY_ACTION};
+pub use event::{
+    emit_event, emit_line_changed, init_play_action_channel, subscribe_events, PlayAction,
+    PlayEvent, PLAY_ACTION,
+};
@@ -9,8 +9,11 @@ use gtk::subclass::prelude::ObjectSubclassIsExt;
 use crate::{
     app::{dialog::show_dialog, Window},
     log::{error, info, warn},
-    lyric_providers::LyricOwned,
-    sync::{lyric::cache::update_lyric_cache, LyricState, TrackState, LYRIC, TRACK_PLAYING_STATE},
+    lyric_providers::{LyricLineOwned, LyricOwned},
+    sync::{
+        lyric::cache::update_lyric_cache, LyricState, TrackMeta, TrackState, LYRIC,
+        TRACK_PLAYING_STATE,
+    },
     utils::gettext,
 };
 
@@ -22,7 +25,6 @@ pub fn update_cache() {
     });
 }
 
-#[cfg(feature = "export-lyric")]
 pub fn make_lrc_line(text: impl Display, start_time: Duration) -> String {
     let mut ms = start_time.as_millis() as u64;
     let mut sec = ms / 1000;
@@ -33,40 +35,27 @@ pub fn make_lrc_line(text: impl Display, start_time: Duration) -> String {
     format!("[{min:02}:{sec:02}.{ms:03}]{text}")
 }
 
-#[cfg(feature = "export-lyric")]
-pub async fn export_lyric(window: &Window, is_original: bool) {
-    info!("spawned export-lyric: original={is_original}");
-
-    let meta = TRACK_PLAYING_STATE.with_borrow(|meta| meta.metainfo.clone());
-    let current_lyrics = LYRIC.with_borrow(|l| {
-        if is_original {
-            l.origin.clone()
-        } else {
-            l.translation.clone()
-        }
-    });
-    let offset = window.imp().lyric_offset_ms.get();
-
-    let LyricOwned::LineTimestamp(lines) = current_lyrics else {
-        let error_msg = gettext("lyric not exising!");
-        error!(error_msg);
-        show_dialog(gtk::Window::NONE, &error_msg, gtk::MessageType::Error);
-        return;
-    };
-
+/// LRC of `origin`, with each line of `translation` right after the origin line of the same
+/// timestamp, as `extract-translated-lyric` expects
+pub fn lyric_to_lrc(
+    meta: Option<&TrackMeta>,
+    offset: i64,
+    origin: &[LyricLineOwned],
+    translation: &[LyricLineOwned],
+) -> String {
     let mut output = String::default();
 
     let _ = output.write_str("[re:waylyrics]\n");
     let _ = output.write_str(concat!("[ve:", env!("CARGO_PKG_VERSION"), "]\n"));
     if let Some(meta) = meta {
-        if let Some(value) = meta.title {
+        if let Some(value) = &meta.title {
             let _ = output.write_fmt(format_args!("[ti:{value}]\n"));
         }
-        if let Some(value) = meta.artists {
+        if let Some(value) = &meta.artists {
             let value = value.join(", ");
             let _ = output.write_fmt(format_args!("[ar:{value}]\n"));
         }
-        if let Some(album) = meta.album {
+        if let Some(album) = &meta.album {
             let _ = output.write_fmt(format_args!("[al:{album}]\n"));
         }
     } else {
@@ -76,10 +65,79 @@ pub async fn export_lyric(window: &Window, is_original: bool) {
     let _ = output.write_fmt(format_args!("[offset:{offset}]\n"));
     let _ = output.write_char('\n');
 
-    for line in lines {
+    let translated = translation
+        .iter()
+        .filter(|line| !line.text.trim().is_empty())
+        .map(|line| (line, 1));
+    let mut lines: Vec<_> = origin
+        .iter()
+        .map(|line| (line, 0))
+        .chain(translated)
+        .collect();
+    // stable, so lines of the same kind keep their order
+    lines.sort_by_key(|(line, kind)| (line.start_time, *kind));
+    for (line, _) in lines {
         output += &make_lrc_line(&line.text, line.start_time);
         output += "\n";
     }
+    output
+}
+
+/// Copy current lyric to clipboard, as LRC with translation, or as plain text if it has no
+/// timestamp. `false` if there's no lyric
+pub fn copy_lyric_as_lrc(window: &Window) -> bool {
+    use gtk::prelude::*;
+
+    let meta = TRACK_PLAYING_STATE.with_borrow(|meta| meta.metainfo.clone());
+    let offset = window.imp().lyric_offset_ms.get();
+    let text = LYRIC.with_borrow(
+        |LyricState {
+             origin,
+             translation,
+             ..
+         }| match origin {
+            LyricOwned::LineTimestamp(lines) => {
+                let translation = match translation {
+                    LyricOwned::LineTimestamp(translation) => translation.as_slice(),
+                    _ => &[],
+                };
+                Some(lyric_to_lrc(meta.as_ref(), offset, lines, translation))
+            }
+            LyricOwned::NoTimestamp(lines) => Some(lines.join("\n")),
+            LyricOwned::None => None,
+        },
+    );
+    let Some(text) = text else {
+        info!("no lyric to copy");
+        return false;
+    };
+    window.clipboard().set_text(&text);
+    info!("copied lyric as LRC");
+    true
+}
+
+#[cfg(feature = "export-lyric")]
+pub async fn export_lyric(window: &Window, is_original: bool) {
+    info!("spawned export-lyric: original={is_original}");
+
+    let meta = TRACK_PLAYING_STATE.with_borrow(|meta| meta.metainfo.clone());
+    let current_lyrics = LYRIC.with_borrow(|l| {
+        if is_original {
+            l.origin.clone()
+        } else {
+            l.translation.clone()
+        }
+    });
+    let offset = window.imp().lyric_offset_ms.get();
+
+    let LyricOwned::LineTimestamp(lines) = current_lyrics else {
+        let error_msg = gettext("lyric not exising!");
+        error!(error_msg);
+        show_dialog(gtk::Window::NONE, &error_msg, gtk::MessageType::Error);
+        return;
+    };
+
+    let output = lyric_to_lrc(meta.as_ref(), offset, &lines, &[]);
 
     let Some(lrc_file) = rfd::AsyncFileDialog::new()
         .set_title(gettext("Export a lyrics file"))
@@ -101,15 +159,26 @@ pub async fn export_lyric(window: &Window, is_original: bool) {
 
 #[cfg(feature = "import-lyric")]
 pub async fn import_lyric(window: &Window, is_original: bool) {
-    use crate::lyric_providers::{utils::lrc_iter, Lyric};
+    use crate::lyric_providers::{
+        utils::{lrc_iter, ttml::parse_ttml},
+        Lyric,
+    };
 
     info!("spawned import-lyric: original={is_original}");
 
-    let lrc_file = rfd::AsyncFileDialog::new()
+    let extensions: &[&str] = if cfg!(feature = "import-ass") {
+        &["lrc", "ttml", "ass", "ssa"]
+    } else {
+        &["lrc", "ttml"]
+    };
+    let dialog = rfd::AsyncFileDialog::new()
         .set_title(gettext("Select a lyrics file"))
+        .add_filter(gettext("Lyrics"), extensions)
         .add_filter("Simple LRC", &["lrc"])
-        .pick_file()
-        .await;
+        .add_filter("TTML", &["ttml"]);
+    #[cfg(feature = "import-ass")]
+    let dialog = dialog.add_filter("ASS/SSA", &["ass", "ssa"]);
+    let lrc_file = dialog.pick_file().await;
 
     let Some(lrc_file) = lrc_file else {
         info!("user canceled selection");
@@ -125,6 +194,34 @@ pub async fn import_lyric(window: &Window, is_original: bool) {
             return;
         }
     };
+    let file_name = lrc_file.file_name().to_lowercase();
+    if file_name.ends_with(".ttml") {
+        match parse_ttml(&lrc) {
+            // second `<div>` is the translation
+            Ok(ttml) => set_imported_lyric(window, is_original, ttml.origin, ttml.translation),
+            Err(e) => {
+                let prompt = gettext("input TTML in unsupported format: ");
+                let error_msg = format!("{prompt}{e}");
+                error!(error_msg);
+                show_dialog(gtk::Window::NONE, &error_msg, gtk::MessageType::Error);
+            }
+        }
+        return;
+    }
+    #[cfg(feature = "import-ass")]
+    if file_name.ends_with(".ass") || file_name.ends_with(".ssa") {
+        match crate::lyric_providers::utils::ass::parse_ass(&lrc) {
+            Ok(ass) => set_imported_lyric(window, is_original, ass.origin, ass.translation),
+            Err(e) => {
+                let prompt = gettext("input ASS in unsupported format: ");
+                let error_msg = format!("{prompt}{e}");
+                error!(error_msg);
+                show_dialog(gtk::Window::NONE, &error_msg, gtk::MessageType::Error);
+            }
+        }
+        return;
+    }
+
     let lyric = match lrc_iter(lrc.lines()) {
         Ok(r) => r,
         Err(e) => {
@@ -139,6 +236,7 @@ pub async fn import_lyric(window: &Window, is_original: bool) {
         |LyricState {
              origin,
              translation,
+             ..
          }| {
             if is_original {
                 *origin = Lyric::LineTimestamp(lyric).into_owned();
@@ -152,3 +250,185 @@ pub async fn import_lyric(window: &Window, is_original: bool) {
         update_cache();
     }
 }
+
+/// Timestamped lines of LRC `text`, `None` if it has none so doesn't look like LRC
+#[cfg(feature = "import-lyric")]
+pub fn parse_clipboard_lrc(text: &str) -> Option<Vec<LyricLineOwned>> {
+    use crate::lyric_providers::{utils::lrc_iter, LyricLine};
+
+    let lines = lrc_iter(text.lines()).ok()?;
+    if lines.is_empty() {
+        return None;
+    }
+    Some(lines.into_iter().map(LyricLine::into_owned).collect())
+}
+
+/// Import LRC in clipboard as original lyric, for lyrics copied from a web page
+#[cfg(feature = "import-lyric")]
+pub async fn import_lyric_from_clipboard(window: &Window) {
+    use gtk::prelude::*;
+
+    info!("spawned import-lyric-from-clipboard");
+
+    let text = match window.clipboard().read_text_future().await {
+        Ok(Some(text)) => text,
+        Ok(None) => {
+            let error_msg = gettext("clipboard has no text");
+            error!(error_msg);
+            show_dialog(Some(window), &error_msg, gtk::MessageType::Error);
+            return;
+        }
+        Err(e) => {
+            let prompt = gettext("failed to read clipboard: ");
+            let error_msg = format!("{prompt}{e}");
+            error!(error_msg);
+            show_dialog(Some(window), &error_msg, gtk::MessageType::Error);
+            return;
+        }
+    };
+    let Some(lyric) = parse_clipboard_lrc(&text) else {
+        let error_msg = gettext("clipboard content doesn't look like LRC");
+        error!(error_msg);
+        show_dialog(Some(window), &error_msg, gtk::MessageType::Error);
+        return;
+    };
+    let count = lyric.len();
+    set_imported_lyric(window, true, lyric, vec![]);
+
+    let prompt = gettext("lines imported from clipboard: ");
+    show_dialog(
+        Some(window),
+        &format!("{prompt}{count}"),
+        gtk::MessageType::Info,
+    );
+}
+
+/// Import SRT subtitles as translation, keeping the original lyric
+#[cfg(feature = "import-translation-from-srt")]
+pub async fn import_srt_translation(window: &Window) {
+    use crate::lyric_providers::utils::srt::parse_srt;
+
+    info!("spawned import-srt-translation");
+
+    let srt_file = rfd::AsyncFileDialog::new()
+        .set_title(gettext("Select a subtitle file"))
+        .add_filter("SubRip", &["srt"])
+        .pick_file()
+        .await;
+    let Some(srt_file) = srt_file else {
+        info!("user canceled selection");
+        return;
+    };
+    let srt = match String::from_utf8(srt_file.read().await) {
+        Ok(srt) => srt,
+        Err(e) => {
+            let prompt = gettext("failed to read SRT in UTF-8: ");
+            let error_msg = format!("{prompt}{e}");
+            error!(error_msg);
+            show_dialog(gtk::Window::NONE, &error_msg, gtk::MessageType::Error);
+            return;
+        }
+    };
+    match parse_srt(&srt) {
+        Ok(translation) => set_imported_lyric(window, false, translation, vec![]),
+        Err(e) => {
+            let prompt = gettext("input SRT in unsupported format: ");
+            let error_msg = format!("{prompt}{e}");
+            error!(error_msg);
+            show_dialog(gtk::Window::NONE, &error_msg, gtk::MessageType::Error);
+        }
+    }
+}
+
+/// Set lyric imported from a file with an optional translation in it.
+///
+/// When importing a translation, `origin` of the file is used as translation.
+#[cfg(feature = "import-lyric")]
+fn set_imported_lyric(
+    window: &Window,
+    is_original: bool,
+    imported_origin: Vec<LyricLineOwned>,
+    imported_translation: Vec<LyricLineOwned>,
+) {
+    LYRIC.with_borrow_mut(
+        |LyricState {
+             origin,
+             translation,
+             ..
+         }| {
+            if !is_original {
+                *translation = LyricOwned::LineTimestamp(imported_origin);
+                return;
+            }
+            *origin = LyricOwned::LineTimestamp(imported_origin);
+            if !imported_translation.is_empty() {
+                *translation = LyricOwned::LineTimestamp(imported_translation);
+            }
+        },
+    );
+    if window.imp().cache_lyrics.get() {
+        update_cache();
+    }
+}
+
+/// Cache lyrics of all tracks in NetEase playlist `id`, stops early if the progress is closed
+pub async fn import_netease_playlist(window: &Window, id: &str) {
+    use crate::app::dialog::progress_dialog;
+    use crate::lyric_providers::netease::fetch_playlist_tracks;
+    use crate::sync::lyric::playlist::{import_track, ImportSummary};
+
+    info!("spawned import-netease-playlist: id={id}");
+
+    let tracks = match fetch_playlist_tracks(id).await {
+        Ok(tracks) => tracks,
+        Err(e) => {
+            let prompt = gettext("failed to fetch playlist: ");
+            show_dialog(
+                Some(window),
+                &format!("{prompt}{e}"),
+                gtk::MessageType::Error,
+            );
+            return;
+        }
+    };
+
+    let (progress_window, progress_bar) =
+        progress_dialog(window, &gettext("Importing NetEase playlist"));
+    let mut summary = ImportSummary::default();
+    for (idx, track) in tracks.iter().enumerate() {
+        if !progress_window.is_visible() {
+            info!("import-netease-playlist canceled after {idx} tracks");
+            break;
+        }
+        progress_bar.set_text(Some(&format!(
+            "{}/{} {}",
+            idx + 1,
+            tracks.len(),
+            track.title
+        )));
+        match import_track(track).await {
+            Ok(true) => summary.cached += 1,
+            Ok(false) => summary.skipped += 1,
+            Err(e) => {
+                warn!("cannot import lyric of {} ({}): {e}", track.title, track.id);
+                summary.failed += 1;
+            }
+        }
+        progress_bar.set_fraction((idx + 1) as f64 / tracks.len() as f64);
+    }
+    progress_window.close();
+
+    let ImportSummary {
+        cached,
+        skipped,
+        failed,
+    } = summary;
+    info!("imported NetEase playlist {id}: {cached} cached, {skipped} skipped, {failed} failed");
+    let msg = format!(
+        "{}: {cached}\n{}: {skipped}\n{}: {failed}",
+        gettext("Cached"),
+        gettext("Already cached"),
+        gettext("Failed"),
+    );
+    show_dialog(Some(window), &msg, gtk::MessageType::Info);
+}
@@ -0,0 +1,101 @@
+//! Dump global state as JSON for bug reports, by `print-debug-state` or `--dump-state`.
+//!
+//! Values of sensitive config keys are redacted, so are secrets registered for logging.
+
+use std::path::Path;
+
+use anyhow::Result;
+use gtk::prelude::*;
+use gtk::subclass::prelude::ObjectSubclassIsExt;
+use serde_json::{json, Value};
+
+use crate::app::{self, track_info_window::TrackInfo};
+use crate::log::redact;
+use crate::sync::{get_current_lyric, OsImp, TrackState, OS, TRACK_PLAYING_STATE};
+
+/// config keys whose values never appear in dumps, at any depth
+pub const SENSITIVE_KEYS: &[&str] = &["api-key", "cookies", "obs-password", "password"];
+
+const REDACTED: &str = "<redacted>";
+
+/// Replace non-empty values of `SENSITIVE_KEYS` in `value`
+pub fn redact_value(value: &mut Value) {
+    match value {
+        Value::Object(fields) => {
+            for (key, value) in fields.iter_mut() {
+                let empty = matches!(value, Value::Null) || value.as_str() == Some("");
+                if SENSITIVE_KEYS.contains(&key.as_str()) && !empty {
+                    *value = REDACTED.into();
+                } else {
+                    redact_value(value);
+                }
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(redact_value),
+        _ => (),
+    }
+}
+
+/// State without GTK, with player, lyric and window missing if `window` is `None`
+pub fn debug_state(window: Option<&app::Window>, config: Option<Value>) -> Value {
+    let mut config = config.unwrap_or(Value::Null);
+    redact_value(&mut config);
+    let mut state = json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "os": std::env::consts::OS,
+        "config": config,
+    });
+    let Some(window) = window else {
+        return state;
+    };
+
+    let (paused, loop_status, position) = TRACK_PLAYING_STATE.with_borrow(
+        |TrackState {
+             paused,
+             loop_status,
+             position,
+             ..
+         }| (*paused, *loop_status, *position),
+    );
+    let lyric = get_current_lyric();
+    let imp = window.imp();
+    state["player"] = json!({
+        "current": OS::current_player().map(|player| player.player_name),
+        "available": OS::list_players()
+            .into_iter()
+            .map(|player| player.player_name)
+            .collect::<Vec<_>>(),
+    });
+    state["track"] = TrackInfo::current(window).to_json();
+    state["track"]["paused"] = paused.into();
+    state["track"]["loop_status"] = format!("{loop_status:?}").into();
+    state["track"]["position_ms"] = position.map(|position| position.as_millis() as u64).into();
+    state["lyric"] = json!({
+        "origin": lyric.origin,
+        "translation": lyric.translation,
+        "source_url": lyric.source_url,
+    });
+    let (default_width, default_height) = window.default_size();
+    state["window"] = json!({
+        "width": window.width(),
+        "height": window.height(),
+        "default_width": default_width,
+        "default_height": default_height,
+        "decorated": window.is_decorated(),
+        "opacity": window.opacity(),
+        "clickthrough": imp.clickthrough.get(),
+        "offline_mode": imp.offline_mode.get(),
+    });
+    state
+}
+
+/// Write `state` to stderr, and to `file` if it's not empty
+pub fn dump_state(state: &Value, file: &str) -> Result<()> {
+    let json = serde_json::to_string_pretty(state)?;
+    let json = redact(&json);
+    eprintln!("{json}");
+    if !file.is_empty() {
+        std::fs::write(Path::new(file), json.as_bytes())?;
+    }
+    Ok(())
+}
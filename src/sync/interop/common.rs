@@ -2,11 +2,13 @@ use anyhow::Error;
 use gtk::subclass::prelude::*;
 use tokio::sync::Mutex;
 
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::OnceLock;
 
 use std::time::Duration;
 
-use gtk::glib::{self, WeakRef};
+use gtk::glib::{self, SourceId, WeakRef};
 
 use anyhow::Result;
 
@@ -15,16 +17,31 @@ use crate::{
     log::*,
     sync::{
         interop::{OsImp, PlayerStatus, OS},
+        is_track_loop,
         lyric::{
-            cache::{self, get_cache_path},
+            cache::{self, get_cache_path, migrate_legacy_cache},
             fetch,
+            scroll::restart_line_timeout,
         },
         utils::clean_lyric,
-        TrackMeta, TrackState, TRACK_PLAYING_STATE,
+        CancelToken, Cancelled, TrackMeta, TrackState, TRACK_PLAYING_STATE,
     },
-    utils::reset_lyric_labels,
+    utils::{on_battery, reset_lyric_labels, POWER_CHECK_INTERVAL},
 };
 
+/// see `lyric-loop-threshold-ms`
+static LOOP_THRESHOLD_MS: AtomicU64 = AtomicU64::new(500);
+
+pub fn set_loop_threshold(threshold: Duration) {
+    LOOP_THRESHOLD_MS.store(threshold.as_millis() as u64, Ordering::Relaxed);
+}
+
+thread_local! {
+    /// token of the `update_lyric` holding `UPDATE_LYRIC_LOCK`
+    static RUNNING_FETCH: RefCell<Option<CancelToken>> = const { RefCell::new(None) };
+}
+
+/// Fetch lyric of the track playing, giving up once another track starts playing
 pub async fn update_lyric(
     track_meta: &TrackMeta,
     window: &app::Window,
@@ -32,23 +49,76 @@ pub async fn update_lyric(
 ) -> Result<(), Error> {
     static UPDATE_LYRIC_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
     let lock = UPDATE_LYRIC_LOCK.get_or_init(|| Mutex::new(()));
-    let Ok(_gaurd) = lock.try_lock() else {
-        return Err(anyhow::anyhow!("update_lyric already in queue"));
+    let cancel =
+        TRACK_PLAYING_STATE.with_borrow(|TrackState { fetch_cancel, .. }| fetch_cancel.clone());
+    let _gaurd = match lock.try_lock() {
+        Ok(guard) => guard,
+        // the running one is for a previous track, it returns at its next check
+        Err(_)
+            if RUNNING_FETCH
+                .with_borrow(|running| running.as_ref().is_some_and(CancelToken::is_cancelled)) =>
+        {
+            lock.lock().await
+        }
+        Err(_) => return Err(anyhow::anyhow!("update_lyric already in queue")),
     };
+    if cancel.is_cancelled() {
+        return Ok(());
+    }
+    RUNNING_FETCH.set(Some(cancel.clone()));
 
     crate::sync::utils::clean_lyric(window);
 
-    if window.imp().cache_lyrics.get() {
-        cache::fetch_lyric_cached(track_meta, ignore_cache, window).await?
+    // last resort for tracks without metadata
+    #[cfg(feature = "acoustid")]
+    let track_meta = &crate::lyric_providers::acoustid::identify_if_untagged(track_meta).await;
+
+    let result = if window.imp().cache_lyrics.get() {
+        cache::fetch_lyric_cached(track_meta, ignore_cache, window, &cancel).await
     } else {
-        fetch::fetch_lyric(track_meta, window).await?
+        fetch::fetch_lyric(track_meta, window, &cancel)
+            .await
+            .map(|_| ())
     };
+    RUNNING_FETCH.take();
 
-    drop(_gaurd);
-    Ok(())
+    match result {
+        Err(e) if e.is::<Cancelled>() => {
+            debug!("{e}: {:?}", track_meta.title);
+            Ok(())
+        }
+        result => result,
+    }
+}
+
+/// `register_sync_task` with `battery_interval` while running on battery, and `interval` otherwise.
+///
+/// The power state is checked every `POWER_CHECK_INTERVAL`, and the task is registered again
+/// with the other interval once it changed.
+pub fn register_sync_task_on_power(
+    wind: WeakRef<Window>,
+    interval: Duration,
+    battery_interval: Duration,
+) {
+    let interval_of = move |battery: bool| if battery { battery_interval } else { interval };
+    let mut battery = on_battery();
+    let mut source = register_sync_task(wind.clone(), interval_of(battery));
+    if interval == battery_interval {
+        return;
+    }
+
+    glib::timeout_add_local(POWER_CHECK_INTERVAL, move || {
+        if on_battery() != battery {
+            battery = !battery;
+            let interval = interval_of(battery);
+            info!("switched player sync interval to {interval:?}, on battery: {battery}");
+            std::mem::replace(&mut source, register_sync_task(wind.clone(), interval)).remove();
+        }
+        glib::ControlFlow::Continue
+    });
 }
 
-pub fn register_sync_task(wind: WeakRef<Window>, interval: Duration) {
+pub fn register_sync_task(wind: WeakRef<Window>, interval: Duration) -> SourceId {
     glib::timeout_add_local(interval, move || {
         let Some(window) = wind.upgrade() else {
             return glib::ControlFlow::Continue;
@@ -59,7 +129,7 @@ pub fn register_sync_task(wind: WeakRef<Window>, interval: Duration) {
                 OS::reconnect_player();
                 reset_lyric_labels(&window, None);
                 clean_lyric(&window);
-                TRACK_PLAYING_STATE.take();
+                TRACK_PLAYING_STATE.take().fetch_cancel.cancel();
             }
             Err(PlayerStatus::Unsupported(kind)) => {
                 app::get_label(&window, "above").set_label("Unsupported Player");
@@ -69,18 +139,22 @@ pub fn register_sync_task(wind: WeakRef<Window>, interval: Duration) {
                 error!(kind);
             }
             Err(PlayerStatus::Paused) => {
+                restart_line_timeout(&window);
                 TRACK_PLAYING_STATE.with_borrow_mut(|TrackState { paused, .. }| *paused = true)
             }
             Err(PlayerStatus::Stopped) => {
                 reset_lyric_labels(&window, None);
                 clean_lyric(&window);
-                TRACK_PLAYING_STATE.take();
+                TRACK_PLAYING_STATE.take().fetch_cancel.cancel();
+            }
+            _ => {
+                restart_line_timeout(&window);
+                TRACK_PLAYING_STATE.with_borrow_mut(|TrackState { paused, .. }| *paused = false)
             }
-            _ => TRACK_PLAYING_STATE.with_borrow_mut(|TrackState { paused, .. }| *paused = false),
         }
 
         glib::ControlFlow::Continue
-    });
+    })
 }
 
 pub fn need_fetch_lyric(track_meta: &TrackMeta) -> bool {
@@ -88,24 +162,58 @@ pub fn need_fetch_lyric(track_meta: &TrackMeta) -> bool {
         |TrackState {
              metainfo,
              cache_path,
+             fetch_cancel,
+             loop_status,
+             position,
+             previous_position,
              ..
          }| {
-            let track_meta_playing = metainfo.as_ref().cloned();
             trace!("got track_id: {track_meta:#?}");
 
-            // ? issue [#109](https://github.com/waylyrics/waylyrics/issues/109)
-            // skip comparing length
-            let need = !track_meta_playing.is_some_and(|p| {
-                TrackMeta { length: None, ..p }
-                    == TrackMeta {
-                        length: None,
-                        ..track_meta.clone()
-                    }
+            // `TrackMeta` never compares length, see issue [#109](https://github.com/waylyrics/waylyrics/issues/109)
+            let need = metainfo.as_ref() != Some(track_meta);
+
+            let threshold = Duration::from_millis(LOOP_THRESHOLD_MS.load(Ordering::Relaxed));
+            let looped = position.is_some_and(|position| {
+                is_track_loop(*previous_position, position, *loop_status, threshold)
+            });
+            let same_tags = metainfo.as_ref().is_some_and(|metainfo| {
+                let retagged = TrackMeta {
+                    unique_song_id: metainfo.unique_song_id.clone(),
+                    ..track_meta.clone()
+                };
+                *metainfo == retagged
             });
+            if need && looped && same_tags {
+                // the lyric stays, `lyric_start` was synced to the restarted track already
+                info!("track restarted on repeat, skipped fetching its lyric");
+                *metainfo = Some(track_meta.clone());
+                return false;
+            }
+            let retagged = metainfo.as_ref().is_some_and(|metainfo| {
+                cache::is_retagged_track(metainfo, cache_path.as_deref(), track_meta)
+            });
+            if need && retagged {
+                // e.g. a typo in title fixed by the player, the lyric is still the right one
+                info!(
+                    "track metadata changed without changing its cache, skipped fetching its lyric"
+                );
+                *metainfo = Some(track_meta.clone());
+                return false;
+            }
 
             if need {
+                crate::metrics::record_track_change();
+                fetch_cancel.cancel();
+                *fetch_cancel = CancelToken::default();
                 *metainfo = Some(track_meta.clone());
+                migrate_legacy_cache(track_meta);
                 *cache_path = get_cache_path(track_meta);
+                crate::history::record_track(track_meta, cache_path.clone());
+                #[cfg(feature = "action-event")]
+                crate::sync::actions::emit_event(crate::sync::PlayEvent::TrackChanged {
+                    meta: track_meta.clone(),
+                });
             }
             need
         },
@@ -1,7 +1,9 @@
+use serde::Serialize;
+
 use crate::sync::lyric::fetch::LyricHint;
 
 pub(crate) mod common;
-pub use common::register_sync_task;
+pub use common::{register_sync_task, register_sync_task_on_power, set_loop_threshold};
 
 #[cfg(unix)]
 mod mpris;
@@ -17,7 +19,13 @@ pub trait OsImp {
     fn clean_player();
     fn connect_player_with_id(player_id: impl AsRef<str>);
     fn hint_from_player() -> Option<LyricHint>;
+    /// local file of the playing track, if the player exposes one
+    fn playing_file() -> Option<std::path::PathBuf>;
     fn list_players() -> Vec<PlayerId>;
+    /// players with their bus name and playback status, for `--list-players`
+    fn player_summaries() -> Vec<PlayerSummary>;
+    /// the player connected now
+    fn current_player() -> Option<PlayerId>;
     fn reconnect_player() -> bool;
     /// This function should:
     ///     call `update_lyric` when fetched new metadata
@@ -39,3 +47,33 @@ pub struct PlayerId {
     pub player_name: String,
     pub inner_id: String,
 }
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlayerSummary {
+    /// what `app.connect` takes
+    pub identity: String,
+    /// D-Bus name on Linux, AppUserModelId on Windows
+    pub bus_name: String,
+    /// `Playing`, `Paused`, `Stopped` or `Unknown`
+    pub status: String,
+}
+
+/// `players` one per line, or as a JSON array if `json`
+pub fn format_player_summaries(players: &[PlayerSummary], json: bool) -> String {
+    if json {
+        return serde_json::to_string_pretty(players).expect("cannot serialize players!");
+    }
+    if players.is_empty() {
+        return "no player found".into();
+    }
+    players
+        .iter()
+        .map(|player| {
+            format!(
+                "{}\t{}\t{}",
+                player.identity, player.bus_name, player.status
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
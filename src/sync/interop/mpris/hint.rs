@@ -1,5 +1,6 @@
 use std::str::FromStr;
 
+use crate::lyric_providers::mpris_native::AS_TEXT;
 use crate::lyric_providers::netease::Netease;
 use crate::lyric_providers::qqmusic::QQMusic;
 
@@ -17,6 +18,14 @@ pub fn hint_from_player() -> Option<LyricHint> {
         let player_bus_name = player.bus_name_player_name_part();
         let meta = player.get_metadata().ok()?;
 
+        if let Some(text) = meta
+            .get(AS_TEXT)
+            .and_then(mpris::MetadataValue::as_str)
+            .filter(|text| !text.trim().is_empty())
+        {
+            return Some(LyricHint::LyricText(text.to_owned()));
+        }
+
         match (player_name, player_bus_name) {
             ("ElectronNCM" | "Qcm", _) | (_, "musicfox" | "NeteaseCloudMusicGtk4") => meta
                 .get("mpris:trackid")
@@ -7,12 +7,15 @@ use sync_task::{reconnect_player, try_sync_track};
 mod hint;
 use hint::hint_from_player;
 
-use mpris::{Metadata, Player, PlayerFinder};
+#[cfg(feature = "prefetch")]
+mod prefetch;
+
+use mpris::{Metadata, MetadataValue, Player, PlayerFinder};
 
 use crate::log::error;
 
-use crate::sync::interop::PlayerStatus;
-use crate::sync::TrackMeta;
+use crate::sync::interop::{PlayerStatus, PlayerSummary};
+use crate::sync::{TrackId, TrackMeta};
 
 use super::PlayerId;
 
@@ -28,6 +31,10 @@ impl super::OsImp for MPRIS {
 
         PLAYER_FINDER.with_borrow(|player_finder| {
             if let Ok(player) = player_finder.find_by_name(player_id) {
+                #[cfg(feature = "action-event")]
+                crate::sync::actions::emit_event(crate::sync::PlayEvent::PlayerConnected {
+                    identity: player.identity().to_owned(),
+                });
                 PLAYER.set(Some(player));
             } else {
                 error!("cannot connect to: {player_id}");
@@ -39,6 +46,16 @@ impl super::OsImp for MPRIS {
         hint_from_player()
     }
 
+    fn playing_file() -> Option<std::path::PathBuf> {
+        PLAYER.with_borrow(|player| {
+            let meta = player.as_ref()?.get_metadata().ok()?;
+            let url = url::Url::parse(meta.url()?).ok()?;
+            (url.scheme() == "file")
+                .then(|| url.to_file_path().ok())
+                .flatten()
+        })
+    }
+
     fn list_players() -> Vec<PlayerId> {
         find_players()
             .iter()
@@ -49,6 +66,30 @@ impl super::OsImp for MPRIS {
             .collect()
     }
 
+    fn player_summaries() -> Vec<PlayerSummary> {
+        find_players()
+            .iter()
+            .map(|p| PlayerSummary {
+                identity: p.identity().to_owned(),
+                bus_name: p.bus_name().to_owned(),
+                status: p
+                    .get_playback_status()
+                    .map(|status| format!("{status:?}"))
+                    .unwrap_or_else(|_| "Unknown".into()),
+            })
+            .collect()
+    }
+
+    fn current_player() -> Option<PlayerId> {
+        PLAYER.with_borrow(|player| {
+            let player = player.as_ref()?;
+            Some(PlayerId {
+                player_name: player.identity().to_owned(),
+                inner_id: player.identity().to_owned(),
+            })
+        })
+    }
+
     fn reconnect_player() -> bool {
         reconnect_player()
     }
@@ -77,20 +118,69 @@ impl TryFrom<Metadata> for TrackMeta {
     type Error = Infallible;
 
     fn try_from(meta: Metadata) -> Result<Self, Self::Error> {
-        let track_id = meta.track_id();
+        // MusicBrainz id is the same across players, unlike `mpris:trackid`
+        let musicbrainz_id = match meta.get("xesam:musicBrainzTrackID") {
+            Some(MetadataValue::String(id)) => TrackId::from_musicbrainz(id),
+            Some(MetadataValue::Array(ids)) => ids.iter().find_map(|id| match id {
+                MetadataValue::String(id) => TrackId::from_musicbrainz(id),
+                _ => None,
+            }),
+            _ => None,
+        };
+        let track_id = musicbrainz_id.or_else(|| {
+            meta.track_id()
+                .and_then(|id| TrackId::from_mpris_path(&id.to_string()))
+        });
         let title = meta.title().map(str::to_string);
         let album = meta.album_name().map(ToOwned::to_owned);
         let artists: Option<Vec<_>> = meta
             .artists()
             .map(|v| v.iter().map(ToString::to_string).collect());
         let length = meta.length();
+        let year = meta
+            .get("xesam:contentCreated")
+            .and_then(|created| created.as_str())
+            .and_then(TrackMeta::parse_year);
+        let track_number = meta.track_number().and_then(|n| u32::try_from(n).ok());
+        let disc_number = meta.disc_number().and_then(|n| u32::try_from(n).ok());
+        // a list by spec, though some players send a single string
+        let genre = match meta.get("xesam:genre") {
+            Some(MetadataValue::String(genre)) => Some(vec![genre.clone()]),
+            Some(MetadataValue::Array(genres)) => Some(
+                genres
+                    .iter()
+                    .filter_map(|genre| genre.as_str().map(ToOwned::to_owned))
+                    .collect(),
+            ),
+            _ => None,
+        }
+        .filter(|genres: &Vec<String>| !genres.is_empty());
+        // an integer by spec, 0 for unknown
+        let bpm = match meta.get("xesam:audioBPM") {
+            Some(MetadataValue::SignedInt(bpm)) => u32::try_from(*bpm).ok(),
+            Some(MetadataValue::UnsignedInt(bpm)) => u32::try_from(*bpm).ok(),
+            Some(MetadataValue::Float(bpm)) if bpm.is_finite() => Some(bpm.round() as u32),
+            _ => None,
+        }
+        .filter(|&bpm| bpm != 0);
+        let user_rating = match meta.get("xesam:userRating") {
+            Some(MetadataValue::Float(rating)) => Some(*rating),
+            _ => None,
+        }
+        .filter(|rating| (0. ..=1.).contains(rating));
 
         Ok(Self {
-            unique_song_id: track_id.map(|id| id.to_string()),
+            unique_song_id: track_id,
             title,
             album,
             artists,
             length,
+            year,
+            track_number,
+            disc_number,
+            genre,
+            bpm,
+            user_rating,
         })
     }
 }
@@ -0,0 +1,88 @@
+use std::cell::RefCell;
+
+use gtk::subclass::prelude::ObjectSubclassIsExt;
+use mpris::{Player, TrackID};
+
+use crate::log::*;
+use crate::sync::{interop::mpris::PLAYER, lyric::cache, TrackMeta};
+use crate::{app, glib_spawn};
+
+/// how many upcoming tracks to prefetch
+const PREFETCH_COUNT: usize = 3;
+
+thread_local! {
+    /// the track we last prefetched for, so that we only prefetch once per track change
+    static LAST_PREFETCHED: RefCell<Option<TrackID>> = const { RefCell::new(None) };
+}
+
+/// Prefetch lyrics of the next tracks into cache, if the player supports `TrackList`.
+///
+/// Prefetched lyrics are written to cache only, and never shown in the window.
+pub fn prefetch_next_tracks(window: &app::Window) {
+    if !window.imp().cache_lyrics.get() || window.imp().offline_mode.get() {
+        return;
+    }
+
+    let Some(tracks) = PLAYER.with_borrow(|player| player.as_ref().and_then(next_tracks)) else {
+        return;
+    };
+
+    let tracks: Vec<TrackMeta> = tracks
+        .into_iter()
+        .inspect(|meta| {
+            cache::migrate_legacy_cache(meta);
+        })
+        .filter(|meta| cache::get_cache_path(meta).is_some_and(|path| !path.exists()))
+        .collect();
+    if tracks.is_empty() {
+        return;
+    }
+
+    let length_toleration_ms = window.imp().length_toleration_ms.get();
+    glib_spawn!(async move {
+        // fetch one by one to avoid hammering providers
+        for meta in tracks {
+            let title = meta.title.as_deref().unwrap_or_default();
+            match cache::prefetch_lyric_cache(&meta, length_toleration_ms).await {
+                Ok(true) => info!("prefetched lyric for {title}"),
+                Ok(false) => (),
+                Err(e) => debug!("{e} occurs prefetching lyric for {title}"),
+            }
+        }
+    });
+}
+
+/// metadata of the next `PREFETCH_COUNT` tracks,
+/// or `None` if we already prefetched for the current track
+fn next_tracks(player: &Player) -> Option<Vec<TrackMeta>> {
+    if !player.supports_track_lists() {
+        return None;
+    }
+
+    let current = player.get_metadata().ok()?.track_id()?;
+    if LAST_PREFETCHED.with_borrow(|last| last.as_ref() == Some(&current)) {
+        return None;
+    }
+    LAST_PREFETCHED.set(Some(current.clone()));
+
+    let track_list = player.get_track_list().ok()?;
+    let idx = (0..track_list.len()).find(|&i| track_list.get(i) == Some(&current))?;
+    let next_ids: Vec<TrackID> = (idx + 1..track_list.len())
+        .take(PREFETCH_COUNT)
+        .filter_map(|i| track_list.get(i).cloned())
+        .collect();
+    if next_ids.is_empty() {
+        return None;
+    }
+
+    let metadata = player
+        .get_tracks_metadata(&next_ids)
+        .inspect_err(|e| warn!("cannot get metadata of next tracks: {e}"))
+        .ok()?;
+    Some(
+        metadata
+            .into_iter()
+            .filter_map(|meta| TrackMeta::try_from(meta).ok())
+            .collect(),
+    )
+}
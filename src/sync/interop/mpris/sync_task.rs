@@ -12,14 +12,16 @@ use utils::find_next_player;
 
 use crate::{
     app, glib_spawn,
-    sync::TrackMeta,
     sync::{
         interop::common::need_fetch_lyric,
         interop::common::update_lyric,
         interop::mpris::{PLAYER, PLAYER_FINDER},
         interop::PlayerStatus,
         lyric::scroll::refresh_lyric,
+        per_player::apply_player_config,
+        reset_lyric_offset,
     },
+    sync::{LoopStatus, TrackMeta, TrackState, TRACK_PLAYING_STATE},
     utils::reset_lyric_labels,
 };
 
@@ -33,6 +35,10 @@ pub fn reconnect_player() -> bool {
         };
 
         info!("connected to player: {}", player.identity());
+        #[cfg(feature = "action-event")]
+        crate::sync::actions::emit_event(crate::sync::PlayEvent::PlayerConnected {
+            identity: player.identity().to_owned(),
+        });
         PLAYER.set(Some(player));
         true
     })
@@ -60,7 +66,7 @@ fn sync_position(player: &Player, window: &app::Window) -> Result<(), PlayerStat
         trace!("diff (expected - actual): {}", diff);
     }
 
-    let offset = window.imp().lyric_offset_ms.get();
+    let offset = window.imp().lyric_offset_ms.get() + window.imp().player_offset_ms.get();
     let start = if offset.is_negative() {
         start.checked_sub(Duration::from_millis(offset.unsigned_abs()))
     } else {
@@ -69,18 +75,32 @@ fn sync_position(player: &Player, window: &app::Window) -> Result<(), PlayerStat
     .expect("infinite offset time");
 
     window.imp().lyric_start.set(Some(start));
+    TRACK_PLAYING_STATE.with_borrow_mut(|state| state.update_position(position));
     Ok(())
 }
 
+fn loop_status(player: &Player) -> LoopStatus {
+    match player.get_loop_status() {
+        Ok(mpris::LoopStatus::Track) => LoopStatus::Track,
+        Ok(mpris::LoopStatus::Playlist) => LoopStatus::Playlist,
+        _ => LoopStatus::None,
+    }
+}
+
 /// call `update_lyric` when we fetched new metadata
 pub fn try_sync_track(window: &crate::app::Window) -> Result<(), PlayerStatus> {
     let meta = PLAYER.with_borrow(|player| {
-        let player = player.as_ref().ok_or(PlayerStatus::Missing)?;
+        let Some(player) = player.as_ref() else {
+            apply_player_config(window, None);
+            return Err(PlayerStatus::Missing);
+        };
 
         if !player.is_running() {
             info!("disconnected from player: {}", player.identity());
+            apply_player_config(window, None);
             return Err(PlayerStatus::Missing);
         }
+        apply_player_config(window, Some(player.identity()));
 
         let mut progress_tracker = ProgressTracker::new(player, 0)
             .map_err(|_| PlayerStatus::Unsupported("cannot fetch progress"))?;
@@ -93,6 +113,13 @@ pub fn try_sync_track(window: &crate::app::Window) -> Result<(), PlayerStatus> {
         }
 
         sync_position(player, window)?;
+        let loop_status = loop_status(player);
+        TRACK_PLAYING_STATE.with_borrow_mut(
+            |TrackState {
+                 loop_status: status,
+                 ..
+             }| *status = loop_status,
+        );
 
         if let Some(LyricHint::Metadata(meta)) = hint_from_player() {
             return Ok(meta);
@@ -112,6 +139,9 @@ pub fn try_sync_track(window: &crate::app::Window) -> Result<(), PlayerStatus> {
     })?;
 
     if need_fetch_lyric(&meta) {
+        #[cfg(feature = "prefetch")]
+        super::prefetch::prefetch_next_tracks(window);
+
         let window = gtk::prelude::ObjectExt::downgrade(window);
         crate::log::debug!("spawned update_lyric from try_sync_track");
         glib_spawn!(async move {
@@ -122,6 +152,9 @@ pub fn try_sync_track(window: &crate::app::Window) -> Result<(), PlayerStatus> {
             if let Err(e) = update_lyric(&meta, &window, false).await {
                 error!("{e} occurs fetching lyric")
             }
+            if window.imp().reset_offset_on_track_change.get() {
+                reset_lyric_offset(&window);
+            }
         });
     }
 
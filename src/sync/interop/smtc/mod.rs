@@ -16,9 +16,9 @@ use windows::Media::Control::GlobalSystemMediaTransportControlsSessionTimelinePr
 use crate::glib_spawn;
 use crate::log::*;
 use crate::sync::interop::common::{need_fetch_lyric, update_lyric};
-use crate::sync::interop::{OsImp, PlayerId, PlayerStatus};
+use crate::sync::interop::{OsImp, PlayerId, PlayerStatus, PlayerSummary};
 use crate::sync::lyric::scroll::refresh_lyric;
-use crate::sync::TrackMeta;
+use crate::sync::{reset_lyric_offset, TrackId, TrackMeta};
 use crate::utils::reset_lyric_labels;
 
 pub struct GSMTC;
@@ -64,6 +64,10 @@ impl OsImp for GSMTC {
         };
         if let Ok(mut guard) = SESSION.write() {
             info!("connected to {}", player_id.as_ref());
+            #[cfg(feature = "action-event")]
+            crate::sync::actions::emit_event(crate::sync::PlayEvent::PlayerConnected {
+                identity: player_id.as_ref().to_owned(),
+            });
             guard.replace(session);
         }
     }
@@ -72,6 +76,10 @@ impl OsImp for GSMTC {
         None
     }
 
+    fn playing_file() -> Option<std::path::PathBuf> {
+        None
+    }
+
     fn list_players() -> Vec<PlayerId> {
         let Some(sessions) = list_sessions() else {
             return vec![];
@@ -88,6 +96,42 @@ impl OsImp for GSMTC {
             .collect()
     }
 
+    fn player_summaries() -> Vec<PlayerSummary> {
+        let Some(sessions) = list_sessions() else {
+            return vec![];
+        };
+
+        sessions
+            .filter_map(|s| {
+                let app_user_model_id = s.SourceAppUserModelId().ok()?.to_string();
+                let status = s
+                    .GetPlaybackInfo()
+                    .and_then(|info| info.PlaybackStatus())
+                    .map(|GSMTCSessionPlaybackStatus(status)| match status {
+                        4 => "Playing",
+                        1 | 2 | 5 => "Paused",
+                        0 | 3 => "Stopped",
+                        _ => "Unknown",
+                    })
+                    .unwrap_or("Unknown");
+                Some(PlayerSummary {
+                    identity: app_user_model_id.clone(),
+                    bus_name: app_user_model_id,
+                    status: status.into(),
+                })
+            })
+            .collect()
+    }
+
+    fn current_player() -> Option<PlayerId> {
+        let session = SESSION.read().ok()?;
+        let app_user_model_id = session.as_ref()?.SourceAppUserModelId().ok()?;
+        Some(PlayerId {
+            player_name: app_user_model_id.to_string(),
+            inner_id: app_user_model_id.to_string(),
+        })
+    }
+
     fn reconnect_player() -> bool {
         let session_manager = session_manager();
         if let Ok(session) = session_manager.GetCurrentSession() {
@@ -150,6 +194,23 @@ impl OsImp for GSMTC {
         let album = media_properties.AlbumTitle().ok().map(|t| t.to_string());
         let title = media_properties.Title().ok().map(|t| t.to_string());
         let artist = media_properties.Artist().ok().map(|t| t.to_string());
+        // 0 if unknown
+        let track_number = media_properties
+            .TrackNumber()
+            .ok()
+            .and_then(|n| u32::try_from(n).ok())
+            .filter(|&n| n != 0);
+
+        let genre = media_properties
+            .Genres()
+            .ok()
+            .map(|genres| {
+                genres
+                    .into_iter()
+                    .map(|g| g.to_string())
+                    .collect::<Vec<_>>()
+            })
+            .filter(|genres| !genres.is_empty());
 
         let mut length = timeline_properties.EndTime().ok().map(Duration::from);
 
@@ -158,12 +219,22 @@ impl OsImp for GSMTC {
             length = None;
         }
 
+        let artists = artist.map(|a| vec![a]);
         let new_trackmeta = TrackMeta {
-            unique_song_id: None,
+            // SMTC gives no id of the track
+            unique_song_id: Some(TrackId::synthetic(title.as_deref(), artists.as_deref())),
             title,
             album,
-            artists: artist.map(|a| vec![a]),
+            artists,
             length,
+            year: None,
+            track_number,
+            // SMTC gives no disc number
+            disc_number: None,
+            genre,
+            // SMTC gives no tempo
+            bpm: None,
+            user_rating: None,
         };
 
         if need_fetch_lyric(&new_trackmeta) {
@@ -177,6 +248,9 @@ impl OsImp for GSMTC {
                 if let Err(e) = update_lyric(&new_trackmeta, &window, false).await {
                     error!("{e} occurs fetching lyric")
                 }
+                if window.imp().reset_offset_on_track_change.get() {
+                    reset_lyric_offset(&window);
+                }
             });
         }
 
@@ -229,7 +303,7 @@ fn update_position(
         start.duration_since(SystemTime::UNIX_EPOCH)
     );
 
-    let offset = window.imp().lyric_offset_ms.get();
+    let offset = window.imp().lyric_offset_ms.get() + window.imp().player_offset_ms.get();
     let start_time = if offset.is_negative() {
         start.checked_sub(Duration::from_millis(offset.unsigned_abs()))
     } else {
@@ -1,3 +1,9 @@
+pub mod auto_offset;
+pub mod blacklist;
 pub mod cache;
+pub mod color;
 pub mod fetch;
+pub mod playlist;
+pub mod rebuild;
 pub mod scroll;
+pub mod signature;
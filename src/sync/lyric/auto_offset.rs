@@ -0,0 +1,159 @@
+//! Detect lyrics off by a constant amount, see `auto-offset-detect`.
+//!
+//! Lyrics of a provider are often timed for another release of the track, so every line is
+//! early or late by the same amount. After a lyric is fetched into the cache, lines with the
+//! same text are looked up in lyrics the other providers found in the same search, and the
+//! median of the differences of their start times is taken as the offset of the lyric,
+//! if all the providers roughly agree on it.
+
+use std::path::Path;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use gtk::subclass::prelude::ObjectSubclassIsExt;
+
+use crate::app;
+use crate::log::{debug, info};
+use crate::lyric_providers::{LyricLineOwned, LyricOwned};
+use crate::sync::lyric::cache::{
+    is_auto_offset_disabled, is_lyric_pinned, write_lyric_cache_with_offset,
+};
+use crate::sync::{LyricState, LYRIC};
+
+/// lines with the same text farther apart than this are different lines, like a repeated chorus
+const MAX_LINE_DISTANCE: Duration = Duration::from_secs(5);
+/// fewer matched lines than this tell nothing about the offset
+const MIN_MATCHED_LINES: usize = 3;
+/// estimates of providers farther apart than this disagree, no offset is applied then
+const MAX_DISAGREEMENT_MS: i64 = 300;
+
+#[derive(Clone, Copy, Debug)]
+struct AutoOffset {
+    threshold_ms: i64,
+}
+
+static AUTO_OFFSET: OnceLock<AutoOffset> = OnceLock::new();
+
+/// Offsets below `threshold_ms` are not applied, as lyrics are rarely timed more precisely
+pub fn set_auto_offset(enabled: bool, threshold_ms: u64) {
+    if enabled {
+        let _ = AUTO_OFFSET.set(AutoOffset {
+            threshold_ms: threshold_ms as i64,
+        });
+    }
+}
+
+fn normalized(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+fn median(values: &mut [i64]) -> Option<i64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_unstable();
+    let mid = values.len() / 2;
+    Some(if values.len() % 2 == 0 {
+        (values[mid - 1] + values[mid]) / 2
+    } else {
+        values[mid]
+    })
+}
+
+/// Milliseconds `lyric` is early by compared to `reference`, negative if it's late.
+///
+/// `None` if fewer than `MIN_MATCHED_LINES` lines are found in both.
+pub fn estimate_offset(lyric: &[LyricLineOwned], reference: &[LyricLineOwned]) -> Option<i64> {
+    let mut diffs = lyric
+        .iter()
+        .filter_map(|line| {
+            let text = normalized(&line.text);
+            if text.is_empty() {
+                return None;
+            }
+            let start = line.start_time.as_millis() as i64;
+            reference
+                .iter()
+                .filter(|other| normalized(&other.text) == text)
+                .map(|other| other.start_time.as_millis() as i64 - start)
+                .filter(|diff| diff.unsigned_abs() <= MAX_LINE_DISTANCE.as_millis() as u64)
+                .min_by_key(|diff| diff.unsigned_abs())
+        })
+        .collect::<Vec<_>>();
+    if diffs.len() < MIN_MATCHED_LINES {
+        return None;
+    }
+    median(&mut diffs)
+}
+
+/// Offset of `lyric` all `references` agree on, within `MAX_DISAGREEMENT_MS`
+pub fn consensus_offset(lyric: &[LyricLineOwned], references: &[&[LyricLineOwned]]) -> Option<i64> {
+    let mut estimates = references
+        .iter()
+        .filter_map(|reference| estimate_offset(lyric, reference))
+        .collect::<Vec<_>>();
+    let (min, max) = (*estimates.iter().min()?, *estimates.iter().max()?);
+    if max - min > MAX_DISAGREEMENT_MS {
+        debug!("providers disagree on lyric offset: {estimates:?}");
+        return None;
+    }
+    median(&mut estimates)
+}
+
+fn timed_lines(lyric: &LyricOwned) -> Option<&[LyricLineOwned]> {
+    match lyric {
+        LyricOwned::LineTimestamp(lines) if !lines.is_empty() => Some(lines),
+        _ => None,
+    }
+}
+
+/// Whether the offset of the cache at `cache_path` may be changed by detection
+pub fn is_offset_detectable(cache_path: &Path) -> bool {
+    if is_auto_offset_disabled(cache_path) {
+        debug!("auto offset is disabled for {cache_path:?}");
+        return false;
+    }
+    if is_lyric_pinned(cache_path) {
+        debug!("skipped auto offset of pinned {cache_path:?}");
+        return false;
+    }
+    true
+}
+
+/// Detect offset of the lyric just fetched and cached at `cache_path`, by the `others` found
+/// along with it, applying and persisting it if it's beyond `auto-offset-threshold-ms`.
+///
+/// Caches pinned or with `disable-auto-offset` are left alone.
+pub fn detect_lyric_offset(window: &app::Window, others: &[LyricState], cache_path: &Path) {
+    let Some(AutoOffset { threshold_ms }) = AUTO_OFFSET.get().copied() else {
+        return;
+    };
+    if !is_offset_detectable(cache_path) {
+        return;
+    }
+    let references = others
+        .iter()
+        .filter_map(|lyric| timed_lines(&lyric.origin))
+        .collect::<Vec<_>>();
+    let offset = LYRIC.with_borrow(|LyricState { origin, .. }| {
+        consensus_offset(timed_lines(origin)?, &references)
+    });
+    let Some(offset) = offset else {
+        return;
+    };
+    if offset.abs() < threshold_ms {
+        debug!("detected lyric offset {offset}ms is below threshold");
+        return;
+    }
+
+    let imp = window.imp();
+    let offset = imp.default_lyric_offset_ms.get() + offset;
+    imp.lyric_offset_ms.set(offset);
+    info!("detected lyric offset, set offset to {offset}ms");
+    LYRIC.with_borrow(|lyric| {
+        write_lyric_cache_with_offset(&cache_path.to_owned(), lyric, Some(offset))
+    });
+}
@@ -0,0 +1,75 @@
+//! Provider results marked as wrong by the user.
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use anyhow::Result;
+
+use crate::log::{error, info};
+
+/// `LyricProvider::unique_name`
+pub type ProviderName = String;
+/// song id on the provider
+pub type TrackId = String;
+
+static BLACKLIST: Mutex<Option<HashSet<(ProviderName, TrackId)>>> = Mutex::new(None);
+
+/// `~/.config/waylyrics/blacklist.json`
+pub fn blacklist_path() -> Option<PathBuf> {
+    let proj_dirs = directories::ProjectDirs::from("io", "poly000", "waylyrics")?;
+    Some(proj_dirs.config_dir().join("blacklist.json"))
+}
+
+/// Load blacklist from `blacklist_path`, missing or broken file is treated as empty
+pub fn load_blacklist() {
+    let blacklist = blacklist_path()
+        .and_then(|path| {
+            let content = std::fs::read_to_string(&path).ok()?;
+            serde_json::from_str(&content)
+                .inspect_err(|e| error!("blacklist parse error: {e} from {path:?}"))
+                .ok()
+        })
+        .unwrap_or_default();
+    *BLACKLIST.lock().unwrap() = Some(blacklist);
+}
+
+fn save_blacklist(blacklist: &HashSet<(ProviderName, TrackId)>) -> Result<()> {
+    let Some(path) = blacklist_path() else {
+        return Ok(());
+    };
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, serde_json::to_string(blacklist)?)?;
+    Ok(())
+}
+
+pub fn is_blacklisted(provider: &str, id: &str) -> bool {
+    BLACKLIST
+        .lock()
+        .unwrap()
+        .as_ref()
+        .is_some_and(|blacklist| blacklist.contains(&(provider.to_owned(), id.to_owned())))
+}
+
+/// Never use `id` from `provider` again
+pub fn add_to_blacklist(provider: ProviderName, id: TrackId) {
+    let mut blacklist = BLACKLIST.lock().unwrap();
+    let blacklist = blacklist.get_or_insert_with(HashSet::default);
+    info!("blacklisted {id} on {provider}");
+    blacklist.insert((provider, id));
+    if let Err(e) = save_blacklist(blacklist) {
+        error!("cannot save blacklist: {e}");
+    }
+}
+
+pub fn clear_blacklist() {
+    let mut blacklist = BLACKLIST.lock().unwrap();
+    let blacklist = blacklist.get_or_insert_with(HashSet::default);
+    blacklist.clear();
+    if let Err(e) = save_blacklist(blacklist) {
+        error!("cannot save blacklist: {e}");
+    }
+    info!("cleared blacklist");
+}
@@ -1,159 +1,631 @@
 use anyhow::Result;
 use gtk::glib::Variant;
-use std::path::PathBuf;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
 
+use crate::history::PlayRecord;
 use crate::log::{debug, error, info, warn};
 use crate::GTK_DBUS_CONNECTION;
 use gtk::prelude::*;
 use gtk::subclass::prelude::ObjectSubclassIsExt;
 use serde::{Deserialize, Serialize};
 
-use crate::sync::{lyric::fetch::fetch_lyric, LYRIC};
-use crate::sync::{LyricState, TrackMeta};
-use crate::{app, lyric_providers::LyricOwned, CACHE_DIR};
+use crate::lyric_providers::utils::http_cache::{self, Conditional, LyricSource, Validator};
+use crate::lyric_providers::utils::lyric_filter::filter_lyric;
+use crate::lyric_providers::{local_files, mpris_native};
+use crate::metrics;
+use crate::sync::lyric::auto_offset::detect_lyric_offset;
+use crate::sync::lyric::fetch::LyricHint;
+use crate::sync::lyric::rebuild::track_of_record;
+use crate::sync::lyric::signature;
+use crate::sync::{
+    lyric::fetch::{fetch_lyric, search_lyric, Found},
+    LYRIC,
+};
+use crate::sync::{
+    set_lyric_source, swap_lyric_roles, CancelToken, LyricState, OsImp, TrackId, TrackMeta, OS,
+};
+use crate::{app, lyric_providers::LyricOwned, CACHE_DIR, LYRIC_PROVIDERS};
 
-/// This will not create cache dir for you -- you should create it yourself.
-///
-/// Note that window.imp().cache_lyrics controls whether to cache lyrics.
-///
-/// When `track_meta.title == None`, this returns `None` as well,
-///
-/// because we should not cache lyric for an unknown song
-pub fn get_cache_path(track_meta: &TrackMeta) -> Option<PathBuf> {
+static YEAR_IN_CACHE_KEY: AtomicBool = AtomicBool::new(false);
+
+/// Whether `get_cache_path` keys tracks by year too, so that remasters get their own cache
+pub fn set_year_in_cache_key(enabled: bool) {
+    YEAR_IN_CACHE_KEY.store(enabled, Ordering::Relaxed);
+}
+
+/// leading articles dropped from cache keys, English ones are always dropped
+const ARTICLES: &[(&str, &[&str])] = &[
+    ("en", &["the", "a", "an"]),
+    ("de", &["der", "die", "das"]),
+    ("es", &["el", "la", "los", "las"]),
+    ("fr", &["le", "la", "les"]),
+    ("it", &["il", "lo", "la", "gli", "le"]),
+];
+
+/// language part of the locale, like `de` of `de_DE.UTF-8`
+fn locale_language() -> Option<&'static str> {
+    static LANGUAGE: OnceLock<Option<String>> = OnceLock::new();
+    LANGUAGE
+        .get_or_init(|| {
+            ["LC_ALL", "LC_MESSAGES", "LANG"]
+                .into_iter()
+                .filter_map(|var| std::env::var(var).ok())
+                .find(|locale| !locale.is_empty())
+                .and_then(|locale| {
+                    let language = locale.split(['_', '-', '.', '@']).next()?;
+                    Some(language.to_lowercase())
+                })
+        })
+        .as_deref()
+}
+
+/// Case-fold `s` and drop its leading article in English or `language`,
+/// so "The Beatles" and "the beatles" share their cache
+pub fn canonical_key_part(s: &str, language: Option<&str>) -> String {
+    let folded = s
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+    let Some((first, rest)) = folded.split_once(' ') else {
+        return folded;
+    };
+    let is_article = ARTICLES
+        .iter()
+        .filter(|(lang, _)| *lang == "en" || Some(*lang) == language)
+        .any(|(_, articles)| articles.contains(&first));
+    if is_article {
+        rest.to_owned()
+    } else {
+        folded
+    }
+}
+
+fn digest_cache_path(cache_key: &str) -> PathBuf {
+    debug!("get_cache_path: received {cache_key}");
+    let digest = md5::compute(cache_key);
+
+    let cache_dir =
+        CACHE_DIR.with_borrow(|cache_home| PathBuf::from(cache_home).join(md5_cache_dir(digest)));
+    cache_dir.join(format!("{digest:x}.json"))
+}
+
+fn title_cache_path(track_meta: &TrackMeta, canonical: bool) -> Option<PathBuf> {
     match track_meta {
         TrackMeta {
             title: Some(title),
             album,
             artists,
             length,
+            year,
+            track_number,
+            disc_number,
             ..
         } => {
-            let cache_key = format!("{title}-{artists:?}-{album:?}-{length:?}");
-            debug!("get_cache_path: received {cache_key}");
-            let digest = md5::compute(&cache_key);
-
-            let cache_dir = CACHE_DIR
-                .with_borrow(|cache_home| PathBuf::from(cache_home).join(md5_cache_dir(digest)));
-            Some(cache_dir.join(format!("{digest:x}.json")))
+            let mut cache_key = if canonical {
+                let language = locale_language();
+                let title = canonical_key_part(title, language);
+                let artists = artists.as_ref().map(|artists| {
+                    artists
+                        .iter()
+                        .map(|artist| canonical_key_part(artist, language))
+                        .collect::<Vec<_>>()
+                });
+                format!("{title}-{artists:?}-{album:?}-{length:?}")
+            } else {
+                format!("{title}-{artists:?}-{album:?}-{length:?}")
+            };
+            if let Some(year) = year.filter(|_| YEAR_IN_CACHE_KEY.load(Ordering::Relaxed)) {
+                cache_key += &format!("-{year}");
+            }
+            // only in canonical keys, so that legacy caches are still found
+            if canonical {
+                if let Some(disc_number) = disc_number {
+                    cache_key += &format!("-disc{disc_number}");
+                }
+                if let Some(track_number) = track_number {
+                    cache_key += &format!("-track{track_number}");
+                }
+            }
+            Some(digest_cache_path(&cache_key))
         }
 
         _ => None,
     }
 }
 
+/// This will not create cache dir for you -- you should create it yourself.
+///
+/// Note that window.imp().cache_lyrics controls whether to cache lyrics.
+///
+/// When `track_meta.title == None`, this returns `None` as well,
+///
+/// because we should not cache lyric for an unknown song
+///
+/// Keyed by MusicBrainz id if there's one, otherwise by tags with title and artists keyed by
+/// `canonical_key_part`, and disc and track numbers if known.
+///
+/// MPRIS track ids are never used, as players reuse them for other tracks in later sessions.
+pub fn get_cache_path(track_meta: &TrackMeta) -> Option<PathBuf> {
+    if let Some(TrackId::MusicBrainz(id)) = &track_meta.unique_song_id {
+        return Some(digest_cache_path(&format!("musicbrainz-{id}")));
+    }
+    title_cache_path(track_meta, true)
+}
+
+/// Whether `new` is the track `old` with tags edited while playing, keyed to the same cache.
+///
+/// The player must not have moved on to another track, so MPRIS track ids must be the same.
+/// Synthetic ids are derived from tags, and change along with them.
+pub fn is_retagged_track(old: &TrackMeta, old_cache_path: Option<&Path>, new: &TrackMeta) -> bool {
+    let same_track = match (&old.unique_song_id, &new.unique_song_id) {
+        (Some(TrackId::Synthetic(_)), Some(TrackId::Synthetic(_))) => true,
+        (old_id, new_id) => old_id == new_id,
+    };
+    same_track && old_cache_path.is_some() && get_cache_path(new).as_deref() == old_cache_path
+}
+
+/// Path of `track_meta` before cache keys were canonicalized
+pub fn get_legacy_cache_path(track_meta: &TrackMeta) -> Option<PathBuf> {
+    title_cache_path(track_meta, false)
+}
+
+/// Move the cache of `track_meta` from its legacy path, or its tags if it has a MusicBrainz id,
+/// to `get_cache_path`, with its sidecar.
+///
+/// Nothing is moved if the canonical cache exists already, so calling it again is harmless.
+/// Names of legacy caches are digests, so tracks are migrated when they are played,
+/// or at startup by `migrate_legacy_caches` if they are in play history.
+pub fn migrate_legacy_cache(track_meta: &TrackMeta) -> bool {
+    let Some(cache_path) = get_cache_path(track_meta) else {
+        return false;
+    };
+    if cache_path.exists() {
+        return false;
+    }
+    let unnumbered = TrackMeta {
+        track_number: None,
+        disc_number: None,
+        ..track_meta.clone()
+    };
+    let legacy_path = [
+        title_cache_path(track_meta, true),
+        title_cache_path(&unnumbered, true),
+        get_legacy_cache_path(track_meta),
+    ]
+    .into_iter()
+    .flatten()
+    .find(|path| *path != cache_path && path.exists());
+    let Some(legacy_path) = legacy_path else {
+        return false;
+    };
+
+    if let Some(cache_dir) = cache_path.parent() {
+        if let Err(e) = std::fs::create_dir_all(cache_dir) {
+            error!("cannot create cache dir {cache_dir:?}: {e}");
+            return false;
+        }
+    }
+    if let Err(e) = std::fs::rename(&legacy_path, &cache_path) {
+        error!("cannot migrate cache {legacy_path:?}: {e}");
+        return false;
+    }
+    let legacy_sidecar = http_cache::sidecar_path(&legacy_path);
+    if legacy_sidecar.exists() {
+        let _ = std::fs::rename(&legacy_sidecar, http_cache::sidecar_path(&cache_path));
+    }
+    let legacy_signature = signature::signature_path(&legacy_path);
+    if legacy_signature.exists() {
+        let _ = std::fs::rename(&legacy_signature, signature::signature_path(&cache_path));
+    }
+    info!("migrated cache {legacy_path:?} to {cache_path:?}");
+    true
+}
+
+/// Migrate caches of tracks in `records` with `migrate_legacy_cache`, run once at startup.
+///
+/// Migrated caches are skipped, so running it again is harmless. Play history has no track
+/// length, so tracks whose player reported one are left to be migrated when played.
+pub fn migrate_legacy_caches(records: &[PlayRecord]) -> usize {
+    let mut seen = HashSet::new();
+    let migrated = records
+        .iter()
+        .map(track_of_record)
+        .filter(|track| seen.insert(get_cache_path(track)))
+        .filter(migrate_legacy_cache)
+        .count();
+    if migrated > 0 {
+        info!("migrated {migrated} legacy lyric caches of played tracks");
+    } else {
+        debug!("no legacy lyric cache to migrate");
+    }
+    migrated
+}
+
 pub async fn fetch_lyric_cached(
     track_meta: &TrackMeta,
     ignore_cache: bool,
     window: &app::Window,
+    cancel: &CancelToken,
 ) -> Result<()> {
     let Some(cache_path) = get_cache_path(track_meta) else {
         warn!("cannot cache lyric due to missing title");
-        return fetch_lyric(track_meta, window).await;
+        return fetch_lyric(track_meta, window, cancel).await.map(|_| ());
     };
 
+    // lyric set by the player is always up to date, don't let a cached one shadow it
+    if !mpris_native::cache_player_lyric()
+        && matches!(OS::hint_from_player(), Some(LyricHint::LyricText(_)))
+    {
+        info!("skipped lyric cache as the player provides lyric");
+        return fetch_lyric(track_meta, window, cancel).await.map(|_| ());
+    }
+    // same for lyrics in `local-lyrics-dir` or `local-lrc-root`, which may be edited any time
+    if local_files::lyric_file_for(track_meta).is_some() {
+        info!("skipped lyric cache as the lyric is a local file");
+        return fetch_lyric(track_meta, window, cancel).await.map(|_| ());
+    }
+
     info!(
         "cache_path for {}: {cache_path:?}",
         track_meta.title.as_deref().unwrap()
     );
 
+    // the lyric was chosen by hand, don't let a refetch replace it
+    if is_lyric_pinned(&cache_path) && load_lyric_cache(&cache_path, window).await? {
+        info!("lyric is pinned, skipped providers");
+        metrics::record_cache_hit();
+        return Ok(());
+    }
+
     if !ignore_cache {
-        if let Ok(lyric) = std::fs::read_to_string(&cache_path) {
-            let cached_lyric: Result<LyricCache, _> = serde_json::from_str(&lyric);
-            match cached_lyric {
-                Ok(LyricCache {
-                    olyric: origin,
-                    tlyric: translation,
-                    offset,
-                }) => {
-                    let dbus_conn = GTK_DBUS_CONNECTION
-                        .with_borrow(|conn| conn.as_ref().cloned())
-                        .expect("GApplication was not set");
-                    let _ = dbus_conn.emit_signal(
-                        None,
-                        "/io/github/waylyrics/Waylyrics",
-                        crate::INSTANCE_NAME
-                            .get()
-                            .ok_or(anyhow::anyhow!("Failed to read app_id"))?,
-                        "LoadLyricCache",
-                        Some(&Variant::tuple_from_iter([cache_path
-                            .to_string_lossy()
-                            .to_variant()])),
-                    );
-                    LYRIC.set(LyricState {
-                        origin,
-                        translation,
-                    });
-                    window.imp().lyric_offset_ms.set(offset);
-                    info!("set offset: {offset}ms");
-                    return Ok(());
-                }
-                Err(e) => error!("cache parse error: {e} from {cache_path:?}"),
+        if load_lyric_cache(&cache_path, window).await? {
+            metrics::record_cache_hit();
+            return Ok(());
+        }
+        metrics::record_cache_miss();
+    }
+
+    // refetching is asked for when the cached lyric is wrong, so it's never revalidated
+    let result = fetch_lyric(track_meta, window, cancel).await;
+    if let Ok(fetched) = &result {
+        // a pinned cache that failed to load is still not replaced
+        if !is_lyric_pinned(&cache_path) && update_lyric_cache(&cache_path) {
+            http_cache::write_sidecar(&cache_path, fetched.as_ref().map(|(source, _)| source));
+            // only lyrics new to the cache, a refetch keeps the offset set for the old one
+            if let (false, Some((_, others))) = (ignore_cache, fetched) {
+                detect_lyric_offset(window, others, &cache_path);
+            }
+            let dbus_conn = GTK_DBUS_CONNECTION
+                .with_borrow(|conn| conn.as_ref().cloned())
+                .expect("GApplication was not set");
+            let _ = dbus_conn.emit_signal(
+                None,
+                "/io/github/waylyrics/Waylyrics",
+                crate::INSTANCE_NAME
+                    .get()
+                    .ok_or(anyhow::anyhow!("Failed to read app_id"))?,
+                "NewLyricCache",
+                Some(&Variant::tuple_from_iter([cache_path
+                    .to_string_lossy()
+                    .to_variant()])),
+            );
+        }
+    }
+    result.map(|_| ())
+}
+
+/// Load lyric and offset from `cache_path` into `LYRIC` and `window`.
+///
+/// Returns `Ok(false)` if the cache is missing or broken.
+pub async fn load_lyric_cache(cache_path: &PathBuf, window: &app::Window) -> Result<bool> {
+    let Ok(lyric) = std::fs::read_to_string(cache_path) else {
+        return Ok(false);
+    };
+    if !signature::verify_cache(cache_path).await {
+        return Ok(false);
+    }
+    let cached_lyric: Result<LyricCache, _> = serde_json::from_str(&lyric);
+    match cached_lyric {
+        Ok(LyricCache {
+            olyric: origin,
+            tlyric: translation,
+            offset,
+            source_url,
+            ..
+        }) => {
+            let dbus_conn = GTK_DBUS_CONNECTION
+                .with_borrow(|conn| conn.as_ref().cloned())
+                .expect("GApplication was not set");
+            let _ = dbus_conn.emit_signal(
+                None,
+                "/io/github/waylyrics/Waylyrics",
+                crate::INSTANCE_NAME
+                    .get()
+                    .ok_or(anyhow::anyhow!("Failed to read app_id"))?,
+                "LoadLyricCache",
+                Some(&Variant::tuple_from_iter([cache_path
+                    .to_string_lossy()
+                    .to_variant()])),
+            );
+            LYRIC.set(LyricState {
+                origin: filter_lyric(origin),
+                translation: filter_lyric(translation),
+                source_url,
+            });
+            set_lyric_source(http_cache::read_sidecar(cache_path));
+            // keep `lyric-offset-ms` if no offset was persisted for this track
+            if let Some(offset) = offset {
+                window.imp().lyric_offset_ms.set(offset);
+                info!("set offset: {offset}ms");
             }
+            Ok(true)
+        }
+        Err(e) => {
+            error!("cache parse error: {e} from {cache_path:?}");
+            Ok(false)
         }
     }
+}
+
+/// Send the validator stored next to `cache_path` to the provider of the cached lyric.
+///
+/// Returns `true` if the provider replied with 304 Not Modified.
+pub async fn revalidate_lyric_cache(cache_path: &PathBuf) -> bool {
+    if !cache_path.exists() {
+        return false;
+    }
+    let Some(LyricSource {
+        provider,
+        id,
+        validator,
+    }) = http_cache::read_sidecar(cache_path)
+    else {
+        return false;
+    };
+    if validator == Validator::default() {
+        return false;
+    }
+    let Some(provider) = LYRIC_PROVIDERS
+        .get()
+        .and_then(|providers| providers.iter().find(|p| p.unique_name() == provider))
+    else {
+        return false;
+    };
 
-    let result = fetch_lyric(track_meta, window).await;
-    if result.is_ok() && update_lyric_cache(&cache_path) {
-        let dbus_conn = GTK_DBUS_CONNECTION
-            .with_borrow(|conn| conn.as_ref().cloned())
-            .expect("GApplication was not set");
-        let _ = dbus_conn.emit_signal(
-            None,
-            "/io/github/waylyrics/Waylyrics",
-            crate::INSTANCE_NAME
-                .get()
-                .ok_or(anyhow::anyhow!("Failed to read app_id"))?,
-            "NewLyricCache",
-            Some(&Variant::tuple_from_iter([cache_path
-                .to_string_lossy()
-                .to_variant()])),
-        );
+    match provider
+        .query_lyric_conditional(&id, Some(&validator))
+        .await
+    {
+        Ok(Conditional::NotModified) => {
+            info!("{id} on {provider:?} was not modified, use cached lyric");
+            true
+        }
+        Ok(Conditional::Modified { .. }) => false,
+        Err(e) => {
+            warn!("{e} when revalidating {id} on {provider:?}");
+            false
+        }
     }
-    result
 }
 
 /// Using olyric and tlyric inside LYRIC to update corresponding cache file.
 pub fn update_lyric_cache(cache_path: &PathBuf) -> bool {
+    LYRIC.with_borrow(|lyric| write_lyric_cache(cache_path, lyric))
+}
+
+/// Write `lyric` to `cache_path`, creating parent dirs if needed.
+pub fn write_lyric_cache(cache_path: &PathBuf, lyric: &LyricState) -> bool {
+    write_lyric_cache_with_offset(cache_path, lyric, None)
+}
+
+/// Offset persisted in the cache at `cache_path`, if any
+pub fn read_cached_offset(cache_path: &Path) -> Option<i64> {
+    std::fs::read_to_string(cache_path)
+        .ok()
+        .and_then(|cached| serde_json::from_str::<LyricCache>(&cached).ok())
+        .and_then(|cache| cache.offset)
+}
+
+/// Like `write_lyric_cache`, persisting `offset` along with the lyric
+pub fn write_lyric_cache_with_offset(
+    cache_path: &PathBuf,
+    lyric: &LyricState,
+    offset: Option<i64>,
+) -> bool {
     let cache_dir = cache_path.parent().unwrap();
     if let Err(e) = std::fs::create_dir_all(cache_dir) {
         error!("cannot create cache dir {cache_dir:?}: {e}");
         return false;
     }
 
-    LYRIC.with_borrow(
-        |LyricState {
-             origin,
-             translation,
-         }| {
-            // do not cache empty lyric
-            if origin.is_none() && translation.is_none() {
-                return false;
-            }
+    let LyricState {
+        origin,
+        translation,
+        source_url,
+    } = lyric;
+    // do not cache empty lyric
+    if origin.is_none() && translation.is_none() {
+        return false;
+    }
 
-            let Err(e) = std::fs::write(
-                cache_path,
-                serde_json::to_string(&LyricCache {
-                    olyric: origin.clone(),
-                    tlyric: translation.clone(),
-                    offset: 0,
-                })
-                .expect("cannot serialize lyrics!"),
-            ) else {
-                info!("cached to {cache_path:?}");
-                return true;
-            };
+    let Err(e) = std::fs::write(
+        cache_path,
+        serde_json::to_string(&LyricCache {
+            olyric: origin.clone(),
+            tlyric: translation.clone(),
+            offset,
+            source_url: source_url.clone(),
+            // rewriting the lyric, e.g. to persist the offset, keeps the pin
+            pinned: is_lyric_pinned(cache_path),
+            no_auto_offset: is_auto_offset_disabled(cache_path),
+        })
+        .expect("cannot serialize lyrics!"),
+    ) else {
+        info!("cached to {cache_path:?}");
+        signature::sign_cache(cache_path);
+        return true;
+    };
 
-            error!("cannot write cache {cache_path:?}: {e}");
-            false
-        },
-    )
+    error!("cannot write cache {cache_path:?}: {e}");
+    false
+}
+
+/// Swap origin and translation in the cache at `cache_path`, keeping its offset.
+///
+/// Returns `false` if there's no such cache, or it has no translation.
+pub fn swap_cached_lyric_roles(cache_path: &Path) -> bool {
+    let Ok(cached) = std::fs::read_to_string(cache_path) else {
+        return false;
+    };
+    let LyricCache {
+        olyric,
+        tlyric,
+        offset,
+        source_url,
+        pinned,
+        no_auto_offset,
+    } = match serde_json::from_str(&cached) {
+        Ok(cache) => cache,
+        Err(e) => {
+            error!("cannot parse cache {cache_path:?}: {e}");
+            return false;
+        }
+    };
+
+    let mut lyric = LyricState {
+        origin: olyric,
+        translation: tlyric,
+        source_url,
+    };
+    if !swap_lyric_roles(&mut lyric) {
+        return false;
+    }
+    let cache = LyricCache {
+        olyric: lyric.origin,
+        tlyric: lyric.translation,
+        offset,
+        source_url: lyric.source_url,
+        pinned,
+        no_auto_offset,
+    };
+    if let Err(e) = std::fs::write(
+        cache_path,
+        serde_json::to_string(&cache).expect("cannot serialize lyrics!"),
+    ) {
+        error!("cannot write cache {cache_path:?}: {e}");
+        return false;
+    }
+    signature::sign_cache(cache_path);
+    info!("swapped lyric roles in {cache_path:?}");
+    true
+}
+
+fn read_lyric_cache(cache_path: &Path) -> Option<LyricCache> {
+    std::fs::read_to_string(cache_path)
+        .ok()
+        .and_then(|cached| serde_json::from_str(&cached).ok())
+}
+
+/// Whether the cache at `cache_path` was pinned in the search window
+pub fn is_lyric_pinned(cache_path: &Path) -> bool {
+    read_lyric_cache(cache_path).is_some_and(|cache| cache.pinned)
+}
+
+/// Whether `disable-auto-offset` was used on the cache at `cache_path`
+pub fn is_auto_offset_disabled(cache_path: &Path) -> bool {
+    read_lyric_cache(cache_path).is_some_and(|cache| cache.no_auto_offset)
+}
+
+/// Set or remove the `pinned` marker of the cache at `cache_path`.
+///
+/// Returns `false` if there's no such cache.
+pub fn set_lyric_pinned(cache_path: &Path, pinned: bool) -> bool {
+    let updated = update_cache_markers(cache_path, |cache| LyricCache { pinned, ..cache });
+    if updated {
+        info!("set pinned={pinned} in {cache_path:?}");
+    }
+    updated
+}
+
+/// Set or remove the `no_auto_offset` marker of the cache at `cache_path`.
+///
+/// Returns `false` if there's no such cache.
+pub fn set_auto_offset_disabled(cache_path: &Path, disabled: bool) -> bool {
+    let updated = update_cache_markers(cache_path, |cache| LyricCache {
+        no_auto_offset: disabled,
+        ..cache
+    });
+    if updated {
+        info!("set no_auto_offset={disabled} in {cache_path:?}");
+    }
+    updated
+}
+
+fn update_cache_markers(cache_path: &Path, update: impl FnOnce(LyricCache) -> LyricCache) -> bool {
+    let Ok(cached) = std::fs::read_to_string(cache_path) else {
+        return false;
+    };
+    let cache = match serde_json::from_str::<LyricCache>(&cached) {
+        Ok(cache) => update(cache),
+        Err(e) => {
+            error!("cannot parse cache {cache_path:?}: {e}");
+            return false;
+        }
+    };
+    if let Err(e) = std::fs::write(
+        cache_path,
+        serde_json::to_string(&cache).expect("cannot serialize lyrics!"),
+    ) {
+        error!("cannot write cache {cache_path:?}: {e}");
+        return false;
+    }
+    signature::sign_cache(cache_path);
+    true
+}
+
+/// Fetch lyric for `track_meta` into cache only, leaving `LYRIC` untouched.
+///
+/// Returns `Ok(false)` if the track is already cached or cannot be cached.
+pub async fn prefetch_lyric_cache(
+    track_meta: &TrackMeta,
+    length_toleration_ms: u128,
+) -> Result<bool> {
+    let Some(cache_path) = get_cache_path(track_meta) else {
+        return Ok(false);
+    };
+    if cache_path.exists() {
+        return Ok(false);
+    }
+
+    let Found { lyric, source, .. } =
+        search_lyric(track_meta, length_toleration_ms, &CancelToken::default()).await?;
+    let written = write_lyric_cache(&cache_path, &lyric);
+    if written {
+        http_cache::write_sidecar(&cache_path, Some(&source));
+    }
+    Ok(written)
 }
 
 #[derive(Deserialize, Serialize)]
 struct LyricCache {
     olyric: LyricOwned,
     tlyric: LyricOwned,
-    offset: i64,
+    /// `lyric-offset-ms` is used if missing, `Some(0)` still overrides it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    offset: Option<i64>,
+    /// missing in caches written by older versions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source_url: Option<String>,
+    /// skip providers on refetch, set by "Pin this lyric" of the search window
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pinned: bool,
+    /// never detect offset of this lyric, see `auto_offset`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    no_auto_offset: bool,
 }
 
 fn md5_cache_dir(digest: md5::Digest) -> PathBuf {
@@ -0,0 +1,105 @@
+//! Per-line colors of lyric labels, see `color-mode`.
+
+use std::cell::RefCell;
+
+use ahash::HashMap;
+use gtk::CssProvider;
+
+use crate::config::ColorMode;
+use crate::lyric_providers::LyricLineOwned;
+
+#[derive(Default)]
+struct ColorState {
+    mode: ColorMode,
+    palette: Vec<String>,
+    /// per label
+    labels: HashMap<String, LabelColor>,
+}
+
+#[derive(Default)]
+struct LabelColor {
+    line: Option<LyricLineOwned>,
+    cycle: usize,
+    provider: Option<CssProvider>,
+}
+
+thread_local! {
+    static COLOR_STATE: RefCell<ColorState> = RefCell::new(ColorState::default());
+}
+
+/// A color derived from `text`, the same text always gets the same color
+pub fn hash_color(text: &str) -> String {
+    let digest = md5::compute(text.trim());
+    let hue = u16::from_le_bytes([digest[0], digest[1]]) % 360;
+    format!("hsl({hue}, 80%, 70%)")
+}
+
+/// `index`-th color of `palette`, wrapping around
+pub fn cycle_color(palette: &[String], index: usize) -> Option<&str> {
+    (!palette.is_empty()).then(|| palette[index % palette.len()].as_str())
+}
+
+pub fn set_color_mode(mode: ColorMode, palette: Vec<String>) {
+    COLOR_STATE.with_borrow_mut(|state| {
+        state.mode = mode;
+        state.palette = palette;
+        for (_, label) in state.labels.drain() {
+            remove_provider(label.provider);
+        }
+    });
+}
+
+/// Color label `position` for `line`, does nothing if the line did not change
+pub fn update_line_color(position: &str, line: Option<&LyricLineOwned>) {
+    COLOR_STATE.with_borrow_mut(|state| {
+        if state.mode == ColorMode::Fixed {
+            return;
+        }
+        let label = state.labels.entry(position.to_owned()).or_default();
+        if label.line.as_ref() == line {
+            return;
+        }
+        label.line = line.cloned();
+
+        let color = match line.filter(|line| !line.text.trim().is_empty()) {
+            None => None,
+            Some(line) => match state.mode {
+                ColorMode::Fixed => None,
+                ColorMode::Random => Some(hash_color(&line.text)),
+                ColorMode::Cycle => {
+                    label.cycle = label.cycle.wrapping_add(1);
+                    cycle_color(&state.palette, label.cycle).map(str::to_owned)
+                }
+            },
+        };
+        set_label_color(label, position, color.as_deref());
+    });
+}
+
+fn set_label_color(label: &mut LabelColor, position: &str, color: Option<&str>) {
+    let Some(color) = color else {
+        remove_provider(label.provider.take());
+        return;
+    };
+    let Some(display) = gtk::gdk::Display::default() else {
+        return;
+    };
+    let provider = label.provider.get_or_insert_with(|| {
+        let provider = CssProvider::new();
+        gtk::style_context_add_provider_for_display(
+            &display,
+            &provider,
+            // above the theme
+            gtk::STYLE_PROVIDER_PRIORITY_USER + 1,
+        );
+        provider
+    });
+    provider.load_from_data(&format!("label#{position} {{ color: {color}; }}"));
+}
+
+fn remove_provider(provider: Option<CssProvider>) {
+    let (Some(provider), Some(display)) = (provider, gtk::gdk::Display::default()) else {
+        return;
+    };
+    gtk::style_context_remove_provider_for_display(&display, &provider);
+}
@@ -2,71 +2,361 @@ pub mod tricks;
 
 use anyhow::Result;
 use std::borrow::Cow;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::Instant;
 use tokio::task::JoinSet;
 
 use crate::log::{debug, error, info};
 use gtk::subclass::prelude::ObjectSubclassIsExt;
 
-use crate::lyric_providers::LyricOwned;
-use crate::sync::{LyricState, TrackMeta, LYRIC};
+use crate::lyric_providers::utils::{
+    http_cache::{Conditional, LyricSource},
+    lyric_filter::filter_lyric,
+    normalise::normalize_title,
+    ranking,
+};
+use crate::lyric_providers::{group, join_artists, local_files, router, LyricOwned};
+use crate::metrics::{self, FetchStatus};
+use crate::sync::lyric::blacklist::is_blacklisted;
+use crate::sync::per_player::player_providers;
+use crate::sync::{CancelToken, LyricState, TrackMeta, LYRIC};
 use crate::{app, tokio_spawn, LYRIC_PROVIDERS};
 
 use crate::sync::utils::{self, match_likely_lyric};
 
 pub(crate) use tricks::LyricHint;
 
+static FALLBACK_TO_NOSYNC: AtomicBool = AtomicBool::new(false);
+
+/// Whether lyrics without timestamp are used when no provider has a synced one
+pub fn set_fallback_to_nosync(enabled: bool) {
+    FALLBACK_TO_NOSYNC.store(enabled, Ordering::Relaxed);
+}
+
 use self::tricks::LyricHintResult;
 
-pub async fn fetch_lyric(track_meta: &TrackMeta, window: &app::Window) -> Result<()> {
-    utils::clean_lyric(window);
+/// Sort `matches` of `(song id, weight, provider index)` by weight, lower first, then by
+/// the order of providers, so that results finishing in a different order don't matter
+pub fn sort_matches(matches: &mut [(String, u8, usize)]) {
+    matches.sort_by_key(|(_, weight, idx)| (*weight, *idx));
+}
+
+/// Lyric picked by `search_lyric`
+pub struct Found {
+    pub lyric: LyricState,
+    pub source: LyricSource,
+    /// lyrics of the other matches queried along with it, in ranked order
+    pub others: Vec<LyricState>,
+}
 
-    let title = Arc::new(track_meta.title.as_deref().unwrap_or("Unknown").to_owned());
-    let album = Arc::new(track_meta.album.as_ref().map(|album| album.to_owned()));
-    let artists = &track_meta.artists;
-    let length = track_meta.length;
+/// Returns where the lyric came from and the other lyrics found along with it,
+/// if it was fetched from a provider.
+///
+/// Fails with `Cancelled` without touching `LYRIC` once `cancel` is cancelled.
+pub async fn fetch_lyric(
+    track_meta: &TrackMeta,
+    window: &app::Window,
+    cancel: &CancelToken,
+) -> Result<Option<(LyricSource, Vec<LyricState>)>> {
+    utils::clean_lyric(window);
 
-    let artists_str = artists
+    let title = track_meta.title.as_deref().unwrap_or("Unknown");
+    let artists_str = track_meta
+        .artists
         .as_ref()
-        .map(|s| Cow::Owned(s.join(",")))
+        .map(|s| Cow::Owned(join_artists(s)))
         .unwrap_or_else(|| Cow::Borrowed("Unknown"));
 
     if let Some(LyricHintResult::Lyric { olyric, tlyric }) =
         tricks::get_lyric_hint_from_player().await
     {
+        cancel.check()?;
         info!("fetched lyrics by player hint");
-        set_lyric(olyric, tlyric, &title, &artists_str);
-        return Ok(());
+        set_lyric(olyric, tlyric, None, title, &artists_str);
+        return Ok(None);
+    }
+
+    if let Some(path) = local_files::lyric_file_for(track_meta) {
+        match local_files::read_lyric_file(&path) {
+            Ok(olyric) => {
+                info!("read lyric from {path:?}");
+                set_lyric(olyric, LyricOwned::None, None, title, &artists_str);
+                utils::set_lyric_source(Some(LyricSource {
+                    provider: local_files::NAME.to_owned(),
+                    id: path.to_string_lossy().into_owned(),
+                    validator: Default::default(),
+                }));
+                // not cached, like lyrics from player
+                return Ok(None);
+            }
+            Err(e) => error!("cannot read lyric file {path:?}: {e}"),
+        }
+    }
+
+    if window.imp().offline_mode.get() {
+        info!("offline mode: skipped searching for {artists_str} - {title}");
+        return Ok(None);
     }
 
+    let Found {
+        lyric:
+            LyricState {
+                origin,
+                translation,
+                source_url,
+            },
+        source,
+        others,
+    } = search_lyric(track_meta, window.imp().length_toleration_ms.get(), cancel).await?;
+    cancel.check()?;
+    set_lyric(origin, translation, source_url, title, &artists_str);
+    utils::set_lyric_source(Some(source.clone()));
+    Ok(Some((source, others)))
+}
+
+/// Search all providers for `track_meta` and return the best matched lyric with its source,
+/// without touching `LYRIC` or the window.
+///
+/// If nothing was found with the raw title, retry with `normalize_title`.
+pub async fn search_lyric(
+    track_meta: &TrackMeta,
+    length_toleration_ms: u128,
+    cancel: &CancelToken,
+) -> Result<Found> {
+    let result = search_lyric_by_title(track_meta, length_toleration_ms, cancel).await;
+
+    let Some(title) = track_meta.title.as_deref() else {
+        return result;
+    };
+    let no_result =
+        matches!(&result, Err(e) if e.downcast_ref::<crate::lyric_providers::Error>().is_some());
+    let normalized = normalize_title(title);
+    if !no_result || normalized == title {
+        return result;
+    }
+    cancel.check()?;
+
+    info!("retry searching with normalized title {normalized}");
+    let track_meta = TrackMeta {
+        title: Some(normalized),
+        ..track_meta.clone()
+    };
+    search_lyric_by_title(&track_meta, length_toleration_ms, cancel).await
+}
+
+async fn search_lyric_by_title(
+    track_meta: &TrackMeta,
+    length_toleration_ms: u128,
+    cancel: &CancelToken,
+) -> Result<Found> {
+    let title = track_meta.title.as_deref().unwrap_or("Unknown");
+    let artists_str = track_meta
+        .artists
+        .as_ref()
+        .map(|s| Cow::Owned(join_artists(s)))
+        .unwrap_or_else(|| Cow::Borrowed("Unknown"));
+
+    let providers = LYRIC_PROVIDERS
+        .get()
+        .expect("lyric providers should be initialized");
+
+    let track = Arc::new(track_meta.clone());
+    let player_providers = player_providers().map(|sources| group::expand_sources(&sources));
+    let mut results = vec![];
+    let tiers = router::route(
+        providers,
+        track_meta.genre.as_deref(),
+        track_meta.user_rating,
+    );
+    for group in group::split(providers, tiers) {
+        // a later group is only searched if the earlier ones found nothing, which takes a while
+        cancel.check()?;
+        results = search_matches(
+            group,
+            track.clone(),
+            player_providers.clone(),
+            length_toleration_ms,
+        )
+        .await?;
+        if !results.is_empty() {
+            break;
+        }
+    }
+
+    if results.is_empty() {
+        info!("Failed searching for {artists_str} - {title}",);
+        Err(crate::lyric_providers::Error::NoResult)?;
+    };
+
+    sort_matches(&mut results);
+
+    let mut lyrics = query_matches(results, title).await;
+    cancel.check()?;
+    let picked = pick_lyric(&lyrics).ok_or(crate::lyric_providers::Error::NoResult)?;
+    let (lyric, source) = lyrics.remove(picked);
+    Ok(Found {
+        lyric,
+        source,
+        others: lyrics.into_iter().map(|(lyric, _)| lyric).collect(),
+    })
+}
+
+/// Index of the lyric to use in ranked `lyrics`, preferring synced ones
+pub fn pick_lyric(lyrics: &[(LyricState, LyricSource)]) -> Option<usize> {
+    let preference = ranking::language_preference();
+    let fallback_to_nosync = FALLBACK_TO_NOSYNC.load(Ordering::Relaxed);
+
+    let synced = lyrics
+        .iter()
+        .enumerate()
+        .filter(|(_, (lyric, source))| {
+            if lyric.origin.is_no_timestamp() && !fallback_to_nosync {
+                info!(
+                    "skipped {} from {} as it has no timestamp",
+                    source.id, source.provider
+                );
+            }
+            matches!(&lyric.origin, LyricOwned::LineTimestamp(lines) if !lines.is_empty())
+        })
+        .map(|(idx, _)| idx)
+        .collect::<Vec<_>>();
+    if preference.is_empty() {
+        if let Some(&idx) = synced.first() {
+            return Some(idx);
+        }
+    } else if let Some(idx) =
+        ranking::pick_by_language(synced.iter().map(|&idx| &lyrics[idx].0.origin), preference)
+    {
+        return Some(synced[idx]);
+    }
+
+    if !fallback_to_nosync {
+        return None;
+    }
+    // the first one found, if no synced lyric is found
+    let idx = lyrics.iter().position(
+        |(lyric, _)| matches!(&lyric.origin, LyricOwned::NoTimestamp(lines) if !lines.is_empty()),
+    )?;
+    let source = &lyrics[idx].1;
+    info!(
+        "no synced lyric found, using {} from {} without timestamp",
+        source.id, source.provider
+    );
+    Some(idx)
+}
+
+/// Lyric of each of `matches` with its source, queried in parallel and kept in the order
+/// of `matches`. Matches whose lyric cannot be queried are left out.
+async fn query_matches(
+    matches: Vec<(String, u8, usize)>,
+    title: &str,
+) -> Vec<(LyricState, LyricSource)> {
     let providers = LYRIC_PROVIDERS
         .get()
         .expect("lyric providers should be initialized");
 
-    let artists = Arc::new(artists.as_ref().cloned().unwrap_or_else(std::vec::Vec::new).clone());
+    let queries = matches
+        .into_iter()
+        .map(|(song_id, weight, idx)| {
+            let provider = providers[idx];
+            let query = tokio_spawn!(async move {
+                let result = provider.query_lyric_conditional(&song_id, None).await;
+                (song_id, result)
+            });
+            (provider, weight, query)
+        })
+        .collect::<Vec<_>>();
 
-    let length_toleration_ms = window.imp().length_toleration_ms.get();
+    let mut lyrics = vec![];
+    for (provider, weight, query) in queries {
+        let Ok((song_id, result)) = query.await else {
+            continue;
+        };
+        match result {
+            Ok(Conditional::Modified {
+                body: lyric,
+                validator,
+            }) => {
+                info!(
+                    "fetched {song_id} from {} with weight {weight}",
+                    provider.unique_name()
+                );
+                let lyric = LyricState {
+                    origin: provider.parse_lyric(&lyric),
+                    translation: provider.parse_translated_lyric(&lyric),
+                    source_url: provider.source_url(&song_id),
+                };
+                let source = LyricSource {
+                    provider: provider.unique_name().to_owned(),
+                    id: song_id,
+                    validator: validator.unwrap_or_default(),
+                };
+                lyrics.push((lyric, source));
+            }
+            Ok(Conditional::NotModified) => {
+                error!(
+                    "unexpected 304 when get lyric for {title} on {}",
+                    provider.unique_name()
+                );
+            }
+            Err(e) => {
+                error!(
+                    "{e} when get lyric for {title} on {}",
+                    provider.unique_name()
+                );
+            }
+        }
+    }
+    lyrics
+}
+
+/// Best match of each provider in `group` for `track`, searched in parallel.
+///
+/// Providers whose circuit is open, or not in `player_providers` if given, are skipped.
+async fn search_matches(
+    group: Vec<usize>,
+    track: Arc<TrackMeta>,
+    player_providers: Option<Vec<String>>,
+    length_toleration_ms: u128,
+) -> Result<Vec<(String, u8, usize)>> {
+    let providers = LYRIC_PROVIDERS
+        .get()
+        .expect("lyric providers should be initialized");
 
-    let (mut results, artists_str, title) = tokio_spawn!(async move {
+    let results = tokio_spawn!(async move {
         let mut set = JoinSet::new();
-        for (idx, provider) in providers.iter().enumerate() {
-            let title = title.clone();
-            let artists = artists.clone();
-            let album = album.clone();
+        for idx in group {
+            let provider = providers[idx];
+            if !provider.is_available() {
+                debug!("skipped {} as its circuit is open", provider.unique_name());
+                continue;
+            }
+            if player_providers
+                .as_ref()
+                .is_some_and(|names| !names.iter().any(|n| n == provider.unique_name()))
+            {
+                continue;
+            }
+            let track = track.clone();
 
             set.spawn(async move {
-                let artists = artists.iter().map(|s| s.as_str()).collect::<Vec<&str>>();
-                let title = title.as_ref();
-                let album = album.as_deref();
-                let singer = if artists.is_empty() {
-                    None
-                } else {
-                    Some(artists.join(","))
-                };
+                let title = track.title.as_deref().unwrap_or("Unknown");
+                let album = track.album.as_deref();
+                let length = track.length;
+                let artists = track
+                    .artists
+                    .iter()
+                    .flatten()
+                    .map(|s| s.as_str())
+                    .collect::<Vec<&str>>();
+                let singer = (!artists.is_empty()).then(|| artists.join(","));
+                let started = Instant::now();
                 let search_result = provider
-                    .search_song_detailed(album.unwrap_or_default(), &artists, title)
+                    .search_song_with_length(album.unwrap_or_default(), &artists, title, length)
                     .await;
-                search_result.map(|songs| {
+                let matched = search_result.map(|mut songs| {
+                    songs.retain(|song| !is_blacklisted(provider.unique_name(), &song.id));
                     match_likely_lyric(
                         album,
                         title,
@@ -76,12 +366,17 @@ pub async fn fetch_lyric(track_meta: &TrackMeta, window: &app::Window) -> Result
                         length_toleration_ms,
                     )
                     .map(|(id, weight)| (id.to_owned(), weight, idx))
-                })
+                });
+                let status = match &matched {
+                    Ok(Some(_)) => FetchStatus::Ok,
+                    Ok(None) => FetchStatus::NoResult,
+                    Err(_) => FetchStatus::Error,
+                };
+                metrics::record_fetch(provider.unique_name(), status, started.elapsed());
+                matched
             });
         }
 
-        let artists_str = artists_str.to_string();
-        let title = title.clone();
         let mut results = vec![];
         while let Some(Ok(re)) = set.join_next().await {
             let Ok(Some((id, weight, idx))) = re else {
@@ -89,47 +384,37 @@ pub async fn fetch_lyric(track_meta: &TrackMeta, window: &app::Window) -> Result
             };
             results.push((id, weight, idx));
         }
-        (results, artists_str, title)
+        results
     })
     .await?;
+    Ok(results)
+}
 
-    if results.is_empty() {
-        info!("Failed searching for {artists_str} - {title}",);
-        Err(crate::lyric_providers::Error::NoResult)?;
-    };
-
+/// Search every available provider for `track_meta` in parallel, and fetch the best matched
+/// lyric of each one, in order of `LYRIC_PROVIDERS`. Providers without result are left out.
+pub async fn search_each_provider(
+    track_meta: &TrackMeta,
+    length_toleration_ms: u128,
+) -> Result<Vec<(LyricState, LyricSource)>> {
     let providers = LYRIC_PROVIDERS
         .get()
         .expect("lyric providers should be initialized");
 
-    results.sort_by_key(|(_, _, weight)| *weight);
-
-    for (song_id, weight, platform_idx) in results {
-        let provider = &providers[platform_idx];
-        match provider.query_lyric(&song_id).await {
-            Ok(lyric) => {
-                let olyric = provider.parse_lyric(&lyric);
-                let tlyric = provider.parse_translated_lyric(&lyric);
-
-                info!(
-                    "fetched {song_id} from {} with weight {weight}",
-                    provider.unique_name()
-                );
-                set_lyric(olyric, tlyric, &title, &artists_str);
-                return Ok(());
-            }
-            Err(e) => {
-                error!(
-                    "{e} when get lyric for {title} on {}",
-                    provider.unique_name()
-                );
-            }
-        };
-    }
-    Err(crate::lyric_providers::Error::NoResult)?
+    let group = (0..providers.len()).collect();
+    let track = Arc::new(track_meta.clone());
+    let mut matches = search_matches(group, track, None, length_toleration_ms).await?;
+    matches.sort_by_key(|(_, _, idx)| *idx);
+    let title = track_meta.title.as_deref().unwrap_or("Unknown");
+    Ok(query_matches(matches, title).await)
 }
 
-fn set_lyric(origin: LyricOwned, translation: LyricOwned, title: &str, artists: &str) {
+fn set_lyric(
+    origin: LyricOwned,
+    translation: LyricOwned,
+    source_url: Option<String>,
+    title: &str,
+    artists: &str,
+) {
     debug!("original lyric: {origin:?}");
     debug!("translated lyric: {translation:?}");
 
@@ -145,7 +430,8 @@ fn set_lyric(origin: LyricOwned, translation: LyricOwned, title: &str, artists:
         info!("No translated lyric for {} - {title}", artists,);
     }
     LYRIC.set(LyricState {
-        origin,
-        translation,
+        origin: filter_lyric(origin),
+        translation: filter_lyric(translation),
+        source_url,
     });
 }
@@ -1,5 +1,5 @@
 use crate::log::{debug, error, warn};
-use crate::lyric_providers::{Lyric, LyricOwned, LyricProvider};
+use crate::lyric_providers::{mpris_native, Lyric, LyricOwned, LyricProvider};
 use crate::sync::interop::{OsImp, OS};
 use crate::sync::utils::extract_translated_lyric;
 use crate::sync::{filter_original_lyric, TrackMeta};
@@ -20,6 +20,8 @@ pub enum LyricHint {
         provider: &'static dyn LyricProvider,
     },
     LyricFile(PathBuf),
+    /// lyric text set by the player, see `mpris_native`
+    LyricText(String),
     LyricMetadata(PathBuf),
     Metadata(TrackMeta),
 }
@@ -58,6 +60,13 @@ pub async fn get_lyric_hint_from_player() -> Option<LyricHintResult> {
 
             Some(LyricHintResult::Lyric { olyric, tlyric })
         }
+        Some(LyricHint::LyricText(text)) => {
+            let olyric = mpris_native::parse_as_text(&text)?;
+            Some(LyricHintResult::Lyric {
+                olyric,
+                tlyric: LyricOwned::None,
+            })
+        }
         Some(LyricHint::LyricFile(path)) => {
             let (olyric, tlyric) = load_local_lyric(&path)?;
             Some(LyricHintResult::Lyric { olyric, tlyric })
@@ -161,7 +170,7 @@ fn load_local_lyric<P: AsRef<Path>>(path: P) -> Option<(LyricOwned, LyricOwned)>
             .ok()
             .as_ref()
             .and_then(|lyric| parse_local_lyric(lyric))
-            .map(|(tlyric,_)| tlyric)
+            .map(|(tlyric, _)| tlyric)
             .unwrap_or_default()
     };
     #[cfg(not(feature = "i18n-local-lyric"))]
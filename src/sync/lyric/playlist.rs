@@ -0,0 +1,79 @@
+//! Cache lyrics of every track in a NetEase playlist before playing them,
+//! see `app.import-netease-playlist`.
+//!
+//! Tracks are queried by their NetEase id, and cached by the tags they have in the playlist,
+//! which are the same as what the player reports for the same track in most cases.
+
+use anyhow::{anyhow, Result};
+
+use crate::lyric_providers::netease::{self, PlaylistTrack};
+use crate::lyric_providers::utils::http_cache::{self, LyricSource};
+use crate::sync::lyric::cache::{get_cache_path, write_lyric_cache};
+use crate::sync::{LyricState, TrackMeta};
+use crate::LYRIC_PROVIDERS;
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ImportSummary {
+    pub cached: usize,
+    pub skipped: usize,
+    pub failed: usize,
+}
+
+pub fn track_of_playlist(track: &PlaylistTrack) -> TrackMeta {
+    TrackMeta {
+        unique_song_id: None,
+        title: Some(track.title.clone()).filter(|title| !title.is_empty()),
+        album: track.album.clone().filter(|album| !album.is_empty()),
+        artists: (!track.artists.is_empty()).then(|| track.artists.clone()),
+        length: (!track.length.is_zero()).then_some(track.length),
+        year: None,
+        track_number: None,
+        disc_number: None,
+        genre: None,
+        bpm: None,
+        user_rating: None,
+    }
+}
+
+/// Whether `netease` is in `lyric-search-source`
+pub fn netease_configured() -> bool {
+    LYRIC_PROVIDERS.get().is_some_and(|providers| {
+        providers
+            .iter()
+            .any(|provider| provider.unique_name() == netease::NAME)
+    })
+}
+
+/// Query and cache lyric of `track`, returns `Ok(false)` if it's already cached
+pub async fn import_track(track: &PlaylistTrack) -> Result<bool> {
+    let cache_path = get_cache_path(&track_of_playlist(track))
+        .ok_or_else(|| anyhow!("track {} has no title", track.id))?;
+    if cache_path.exists() {
+        return Ok(false);
+    }
+    let provider = LYRIC_PROVIDERS
+        .get()
+        .and_then(|providers| {
+            providers
+                .iter()
+                .find(|provider| provider.unique_name() == netease::NAME)
+        })
+        .ok_or_else(|| anyhow!("{} is not in lyric-search-source", netease::NAME))?;
+
+    let store = provider.query_lyric(&track.id).await?;
+    let lyric = LyricState {
+        origin: provider.parse_lyric(&store),
+        translation: provider.parse_translated_lyric(&store),
+        source_url: provider.source_url(&track.id),
+    };
+    if !write_lyric_cache(&cache_path, &lyric) {
+        return Err(anyhow!("no lyric for track {}", track.id));
+    }
+    let source = LyricSource {
+        provider: netease::NAME.to_owned(),
+        id: track.id.clone(),
+        validator: Default::default(),
+    };
+    http_cache::write_sidecar(&cache_path, Some(&source));
+    Ok(true)
+}
@@ -0,0 +1,240 @@
+//! Fetch every cached lyric again from providers configured now, used by `--rebuild-cache`.
+//!
+//! Cache files are named by digests of tags, so tracks are looked up in play history.
+//! Caches of tracks missing in history are queried again by the id in their sidecar,
+//! sending the validator stored in it so unchanged lyrics aren't downloaded again.
+//! Pinned caches were chosen by hand and are never refreshed.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+
+use crate::history::PlayRecord;
+use crate::lyric_providers::split_artists;
+use crate::lyric_providers::utils::http_cache::{self, Conditional, LyricSource};
+use crate::sync::lyric::cache::{
+    get_cache_path, is_lyric_pinned, read_cached_offset, revalidate_lyric_cache,
+    write_lyric_cache_with_offset,
+};
+use crate::sync::lyric::fetch::search_lyric;
+use crate::sync::{CancelToken, LyricState, TrackMeta};
+use crate::LYRIC_PROVIDERS;
+
+/// How a cache is fetched again
+#[derive(Clone, Debug, PartialEq)]
+pub enum Refresh {
+    /// searched on all providers, like when the track is played
+    Search(TrackMeta),
+    /// queried again on the provider it came from
+    Query(LyricSource),
+    /// neither the track nor where the lyric came from is known
+    Skip,
+    /// the lyric is pinned, so kept as it is
+    Pinned,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct RebuildSummary {
+    pub refreshed: usize,
+    pub failed: usize,
+    pub skipped: usize,
+}
+
+/// Lyric caches under `cache_dir`, without sidecars
+pub fn cache_entries(cache_dir: &Path) -> Vec<PathBuf> {
+    let mut entries = vec![];
+    let mut dirs = vec![cache_dir.to_owned()];
+    while let Some(dir) = dirs.pop() {
+        let Ok(read_dir) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for path in read_dir.flatten().map(|entry| entry.path()) {
+            if path.is_dir() {
+                dirs.push(path);
+            } else if path.extension().is_some_and(|ext| ext == "json")
+                && !path.to_string_lossy().ends_with(".http.json")
+            {
+                entries.push(path);
+            }
+        }
+    }
+    entries.sort();
+    entries
+}
+
+/// Track of a play record, without length which is not recorded
+pub fn track_of_record(record: &PlayRecord) -> TrackMeta {
+    let artists = split_artists(&record.artist);
+    TrackMeta {
+        unique_song_id: None,
+        title: Some(record.title.clone()).filter(|title| !title.is_empty()),
+        album: Some(record.album.clone()).filter(|album| !album.is_empty()),
+        artists: (!artists.is_empty()).then_some(artists),
+        length: None,
+        year: None,
+        track_number: None,
+        disc_number: None,
+        genre: None,
+        bpm: None,
+        user_rating: None,
+    }
+}
+
+/// Tracks by their cache path, both recorded and as computed now, newest record wins
+pub fn tracks_by_cache_path(records: &[PlayRecord]) -> HashMap<PathBuf, TrackMeta> {
+    let mut tracks = HashMap::new();
+    // records are newest first, so newer ones overwrite
+    for record in records.iter().rev() {
+        let track = track_of_record(record);
+        let paths = record
+            .lyric_cache_path
+            .clone()
+            .into_iter()
+            .chain(get_cache_path(&track));
+        for path in paths {
+            tracks.insert(path, track.clone());
+        }
+    }
+    tracks
+}
+
+pub fn plan_refresh(cache_path: &Path, tracks: &HashMap<PathBuf, TrackMeta>) -> Refresh {
+    if is_lyric_pinned(cache_path) {
+        return Refresh::Pinned;
+    }
+    if let Some(track) = tracks.get(cache_path).filter(|track| track.title.is_some()) {
+        return Refresh::Search(track.clone());
+    }
+    match http_cache::read_sidecar(cache_path) {
+        Some(source) => Refresh::Query(source),
+        None => Refresh::Skip,
+    }
+}
+
+pub fn describe(refresh: &Refresh) -> String {
+    match refresh {
+        Refresh::Search(track) => format!(
+            "search {} - {}",
+            track
+                .artists
+                .as_deref()
+                .map(crate::lyric_providers::join_artists)
+                .unwrap_or_default(),
+            track.title.as_deref().unwrap_or_default()
+        ),
+        Refresh::Query(source) => format!("query {} on {}", source.id, source.provider),
+        Refresh::Skip => "skip, track is unknown".to_owned(),
+        Refresh::Pinned => "skip, lyric is pinned".to_owned(),
+    }
+}
+
+async fn query_source(source: &LyricSource) -> Result<(LyricState, LyricSource)> {
+    let provider = LYRIC_PROVIDERS
+        .get()
+        .and_then(|providers| {
+            providers
+                .iter()
+                .find(|provider| provider.unique_name() == source.provider)
+        })
+        .ok_or_else(|| anyhow!("{} is not in lyric-search-source", source.provider))?;
+    let Conditional::Modified {
+        body: store,
+        validator,
+    } = provider.query_lyric_conditional(&source.id, None).await?
+    else {
+        return Err(anyhow!("unexpected 304 without a validator"));
+    };
+    let lyric = LyricState {
+        origin: provider.parse_lyric(&store),
+        translation: provider.parse_translated_lyric(&store),
+        source_url: provider.source_url(&source.id),
+    };
+    let source = LyricSource {
+        validator: validator.unwrap_or_default(),
+        ..source.clone()
+    };
+    Ok((lyric, source))
+}
+
+/// Fetch the lyric of `cache_path` again and overwrite it, keeping its offset.
+///
+/// Fails without fetching if the lyric is pinned.
+pub async fn refresh_entry(
+    cache_path: &PathBuf,
+    refresh: &Refresh,
+    length_toleration_ms: u128,
+) -> Result<()> {
+    // the plan may be outdated
+    if is_lyric_pinned(cache_path) {
+        return Err(anyhow!("lyric is pinned"));
+    }
+    let (lyric, source) = match refresh {
+        Refresh::Search(track) => {
+            let found = search_lyric(track, length_toleration_ms, &CancelToken::default()).await?;
+            (found.lyric, found.source)
+        }
+        Refresh::Query(_) if revalidate_lyric_cache(cache_path).await => return Ok(()),
+        Refresh::Query(source) => query_source(source).await?,
+        Refresh::Skip => return Err(anyhow!("track is unknown")),
+        Refresh::Pinned => return Err(anyhow!("lyric is pinned")),
+    };
+    let offset = read_cached_offset(cache_path);
+    if !write_lyric_cache_with_offset(cache_path, &lyric, offset) {
+        return Err(anyhow!("cannot write {cache_path:?}"));
+    }
+    http_cache::write_sidecar(cache_path, Some(&source));
+    Ok(())
+}
+
+/// Refresh every cache under `cache_dir`, printing progress. Nothing is fetched if `dry_run`
+pub async fn rebuild_cache(
+    cache_dir: &Path,
+    records: &[PlayRecord],
+    length_toleration_ms: u128,
+    dry_run: bool,
+) -> RebuildSummary {
+    let entries = cache_entries(cache_dir);
+    let tracks = tracks_by_cache_path(records);
+    let total = entries.len();
+    println!("found {total} cached lyrics in {cache_dir:?}");
+
+    let mut summary = RebuildSummary::default();
+    for (idx, cache_path) in entries.iter().enumerate() {
+        let refresh = plan_refresh(cache_path, &tracks);
+        let progress = format!("[{}/{total}]", idx + 1);
+        if matches!(refresh, Refresh::Skip | Refresh::Pinned) {
+            println!("{progress} skipped {cache_path:?}: {}", describe(&refresh));
+            summary.skipped += 1;
+            continue;
+        }
+        if dry_run {
+            println!(
+                "{progress} would refresh {cache_path:?}: {}",
+                describe(&refresh)
+            );
+            continue;
+        }
+        match refresh_entry(cache_path, &refresh, length_toleration_ms).await {
+            Ok(()) => {
+                println!(
+                    "{progress} refreshed {cache_path:?}: {}",
+                    describe(&refresh)
+                );
+                summary.refreshed += 1;
+            }
+            Err(e) => {
+                println!(
+                    "{progress} failed {cache_path:?}: {}: {e}",
+                    describe(&refresh)
+                );
+                summary.failed += 1;
+            }
+        }
+    }
+    println!(
+        "refreshed {}, failed {}, skipped {} of {total}",
+        summary.refreshed, summary.failed, summary.skipped
+    );
+    summary
+}
@@ -2,13 +2,18 @@ use std::time::Duration;
 
 use gtk::glib::{self, WeakRef};
 use gtk::glib::{ControlFlow, Priority};
+use gtk::prelude::WidgetExt;
 use gtk::subclass::prelude::ObjectSubclassIsExt;
 
-use crate::app::{self, get_label};
+use crate::app::{self, font_fit::fit_label, get_label, line_indicator::update_line_indicator};
 use crate::config::LyricDisplayMode;
 use crate::log::*;
+use crate::lyric_providers::utils::{
+    find_gap, find_next_long_lyric, find_next_lyric, line_progress, plain_line_at, truncate_line,
+};
 use crate::lyric_providers::{LyricLineOwned, LyricOwned};
 
+use crate::sync::lyric::color::update_line_color;
 use crate::sync::{LyricState, TrackState, LYRIC, TRACK_PLAYING_STATE};
 use crate::utils::reset_lyric_labels;
 
@@ -35,40 +40,319 @@ pub fn register_lyric_display(app: WeakRef<app::Window>, interval: Duration) {
     });
 }
 
-fn set_lyric_with_mode(
-    window: &app::Window,
-    translation: Option<&LyricLineOwned>,
-    origin: Option<&LyricLineOwned>,
-) {
-    match window.imp().lyric_display_mode.get() {
+/// `(translation, origin)` lines playing at some moment
+pub type Lines<'a> = (Option<&'a LyricLineOwned>, Option<&'a LyricLineOwned>);
+
+/// Whether `above` and `below` label show translation in `mode`, given which have one
+pub fn translation_labels(mode: LyricDisplayMode, has_translation: [bool; 2]) -> [bool; 2] {
+    let [above, below] = has_translation;
+    match mode {
+        LyricDisplayMode::ShowBoth | LyricDisplayMode::PreferTranslation => [above, false],
+        LyricDisplayMode::ShowBothRev => [false, below],
+        LyricDisplayMode::Origin => [false, false],
+    }
+}
+
+/// Indent labels showing translation by `translation-indent-px`
+fn indent_translation(window: &app::Window, mode: LyricDisplayMode, above: Lines, below: Lines) {
+    let indent = window.imp().translation_indent_px.get() as i32;
+    let translated = translation_labels(mode, [above.0.is_some(), below.0.is_some()]);
+    for (position, translated) in ["above", "below"].into_iter().zip(translated) {
+        get_label(window, position).set_margin_start(if translated { indent } else { 0 });
+    }
+}
+
+/// `above` and `below` are lines playing at the moment of each label, see `lyric_spacing_ms`
+fn set_lyric_with_mode(window: &app::Window, above: Lines, below: Lines) {
+    let mode = window.imp().lyric_display_mode.get();
+    indent_translation(window, mode, above, below);
+    #[cfg(feature = "action-event")]
+    crate::sync::actions::emit_line_changed(above.1, above.0);
+    match mode {
         LyricDisplayMode::ShowBoth => {
-            set_lyric(window, translation.or(origin), "above");
-            set_lyric(window, translation.and(origin), "below");
+            set_lyric(window, above.0.or(above.1), "above");
+            set_lyric(window, below.0.and(below.1), "below");
         }
         LyricDisplayMode::ShowBothRev => {
-            set_lyric(window, origin, "above");
-            set_lyric(window, translation, "below");
+            set_lyric(window, above.1, "above");
+            set_lyric(window, below.0, "below");
         }
         LyricDisplayMode::Origin => {
-            set_lyric(window, origin, "above");
+            set_lyric(window, above.1, "above");
             set_lyric(window, None, "below");
         }
         LyricDisplayMode::PreferTranslation => {
-            set_lyric(window, translation.or(origin), "above");
+            set_lyric(window, above.0.or(above.1), "above");
             set_lyric(window, None, "below");
         }
     }
 }
 
-fn set_lyric(window: &app::Window, text: Option<&LyricLineOwned>, position: &str) {
-    let text = text
+/// `elapsed` moved forward by `shift_ms`, or backward if negative, not before zero
+pub fn shifted_elapsed(elapsed: Duration, shift_ms: i64) -> Duration {
+    let shift = Duration::from_millis(shift_ms.unsigned_abs());
+    if shift_ms.is_negative() {
+        elapsed.saturating_sub(shift)
+    } else {
+        elapsed + shift
+    }
+}
+
+/// Opacity of a label shifted by `spacing_ms`, dimmed to `opacity` only if it's not the current line
+pub fn context_opacity(opacity: f32, spacing_ms: i64) -> f64 {
+    if spacing_ms == 0 {
+        return 1.;
+    }
+    opacity.clamp(0., 1.) as f64
+}
+
+/// Dim `above` and `below` label showing lines shifted by `spacings`, see `context_opacity`
+fn dim_context_labels(window: &app::Window, spacings: [i64; 2]) {
+    let opacities = window.imp().context_opacity.get();
+    for ((position, spacing_ms), opacity) in
+        ["above", "below"].into_iter().zip(spacings).zip(opacities)
+    {
+        let label = get_label(window, position);
+        // fading out by `fill_gap`
+        if label.has_css_class("gap-fill") {
+            continue;
+        }
+        label.set_opacity(context_opacity(opacity, spacing_ms));
+    }
+}
+
+/// how long the gap filler takes to fade out before the next line
+const GAP_FILL_FADE: Duration = Duration::from_millis(500);
+
+/// Show `gap-fill-text` in `above` label if `origin_lyric` has a long gap at `elapsed`
+fn fill_gap(window: &app::Window, elapsed: &Duration, origin_lyric: &[LyricLineOwned]) {
+    let imp = window.imp();
+    let label = get_label(window, "above");
+    let gap_fill_text = imp.gap_fill_text.borrow();
+
+    let left = (!gap_fill_text.is_empty())
+        .then(|| find_gap(elapsed, origin_lyric, imp.gap_fill_threshold.get()))
+        .flatten();
+    let Some(left) = left else {
+        if label.has_css_class("gap-fill") {
+            label.remove_css_class("gap-fill");
+            label.remove_css_class("dim-label");
+            let spacing_ms = imp.lyric_spacing_ms.get()[0];
+            label.set_opacity(context_opacity(imp.context_opacity.get()[0], spacing_ms));
+        }
+        return;
+    };
+
+    label.add_css_class("gap-fill");
+    label.add_css_class("dim-label");
+    label.set_label(&gap_fill_text);
+    label.set_opacity((left.as_secs_f64() / GAP_FILL_FADE.as_secs_f64()).min(1.));
+}
+
+/// Move the progress bar under `above` label, if it's shown
+fn update_progress(window: &app::Window, elapsed: &Duration, origin_lyric: &[LyricLineOwned]) {
+    let Some(progress_bar) = app::get_progress_bar(window) else {
+        return;
+    };
+    if !progress_bar.is_visible() {
+        return;
+    }
+    progress_bar.set_fraction(line_progress(elapsed, origin_lyric).unwrap_or_default());
+}
+
+/// Blank lyric labels once `lyric-line-timeout-ms` passed, unless `restart_line_timeout` is called
+fn arm_line_timeout(window: &app::Window) {
+    let imp = window.imp();
+    let timeout = imp.lyric_line_timeout.get();
+    if timeout.is_zero() || imp.line_timeout_source.borrow().is_some() {
+        return;
+    }
+    let source = glib::timeout_add_local_once(
+        timeout,
+        glib::clone!(
+            #[weak]
+            window,
+            move || {
+                let imp = window.imp();
+                imp.line_timeout_source.take();
+                imp.line_timed_out.set(true);
+                warn!("no update from player in {timeout:?}, blanked lyric");
+                reset_lyric_labels(&window, Some(""));
+            }
+        ),
+    );
+    imp.line_timeout_source.replace(Some(source));
+}
+
+/// Called when the player is synced, so that lines are shown again if they were blanked
+pub fn restart_line_timeout(window: &app::Window) {
+    let imp = window.imp();
+    if let Some(source) = imp.line_timeout_source.take() {
+        source.remove();
+    }
+    imp.line_timed_out.set(false);
+}
+
+/// Line `index` of `origin_lyric` and the translation shown along with it,
+/// `None` if there's no such line
+pub fn override_lines<'a>(
+    origin_lyric: &'a [LyricLineOwned],
+    translation_lyric: Option<&'a [LyricLineOwned]>,
+    index: usize,
+) -> Option<Lines<'a>> {
+    let origin = origin_lyric.get(index)?;
+    let translation =
+        translation_lyric.and_then(|lyric| find_next_lyric(&origin.start_time, lyric));
+    Some((translation, Some(origin)))
+}
+
+/// Show line `index` in place of the playing one, until `clear_lyric_override` is called
+/// or `override_duration` passed
+pub fn set_lyric_override(window: &app::Window, index: usize) {
+    let imp = window.imp();
+    if let Some(source) = imp.lyric_override_source.take() {
+        source.remove();
+    }
+    imp.lyric_override_index.set(Some(index));
+
+    let duration = imp.override_duration.get();
+    if !duration.is_zero() {
+        let source = glib::timeout_add_local_once(
+            duration,
+            glib::clone!(
+                #[weak]
+                window,
+                move || {
+                    window.imp().lyric_override_source.take();
+                    clear_lyric_override(&window);
+                }
+            ),
+        );
+        imp.lyric_override_source.replace(Some(source));
+    }
+}
+
+pub fn clear_lyric_override(window: &app::Window) {
+    let imp = window.imp();
+    if let Some(source) = imp.lyric_override_source.take() {
+        source.remove();
+    }
+    imp.lyric_override_index.set(None);
+}
+
+/// css class marking a line with `confidence` of its provider
+pub const UNCERTAIN_CSS_CLASS: &str = "uncertain";
+/// css class marking a line with `confidence` below `confidence-warn-threshold`
+pub const LOW_CONFIDENCE_CSS_CLASS: &str = "low-confidence";
+
+/// css class for a line with `confidence`, `None` for lines its provider is sure about
+pub fn confidence_css_class(confidence: Option<f32>, warn_threshold: f32) -> Option<&'static str> {
+    match confidence? {
+        confidence if confidence < warn_threshold => Some(LOW_CONFIDENCE_CSS_CLASS),
+        confidence if confidence < 1. => Some(UNCERTAIN_CSS_CLASS),
+        _ => None,
+    }
+}
+
+fn update_confidence_indicator(
+    window: &app::Window,
+    label: &gtk::Label,
+    line: Option<&LyricLineOwned>,
+) {
+    let imp = window.imp();
+    let class = imp
+        .show_confidence_indicators
+        .get()
+        .then(|| confidence_css_class(line?.confidence, imp.confidence_warn_threshold.get()))
+        .flatten();
+    for css_class in [UNCERTAIN_CSS_CLASS, LOW_CONFIDENCE_CSS_CLASS] {
+        if class == Some(css_class) {
+            label.add_css_class(css_class);
+        } else {
+            label.remove_css_class(css_class);
+        }
+    }
+}
+
+fn set_lyric(window: &app::Window, line: Option<&LyricLineOwned>, position: &str) {
+    update_line_color(position, line);
+    let text = line
         .map(|LyricLineOwned { text, .. }| text.as_str().trim())
         .unwrap_or_default();
+    if !text.is_empty() {
+        arm_line_timeout(window);
+    }
+
+    #[cfg(feature = "obs-integration")]
+    if position == "above" {
+        crate::obs::update_line(text);
+    }
+
+    let label = get_label(window, position);
+    update_confidence_indicator(window, &label, line);
+    let imp = window.imp();
+    let shown = truncate_line(text, imp.lyrics_max_line_length.get());
+    // full line is kept for hovering and `copy-current-line`
+    label.set_tooltip_text(Some(text).filter(|_| shown != text));
+    if position == "above" {
+        app::repeat_animation::on_line_shown(window, &label, line);
+    }
+    #[cfg(feature = "accessibility")]
+    if label.label().as_str() != shown {
+        app::accessibility::update_line(&label, position, text);
+    }
+    #[cfg(feature = "transliterate-japanese")]
+    set_label_with_romaji(&label, &shown, imp.romaji_display.get());
+    #[cfg(not(feature = "transliterate-japanese"))]
+    label.set_label(&shown);
 
-    get_label(window, position).set_label(text);
+    if imp.font_size_auto_fit.get() {
+        fit_label(&label, window.width(), imp.font_size_min.get());
+    }
+}
+
+#[cfg(feature = "transliterate-japanese")]
+fn set_label_with_romaji(label: &gtk::Label, text: &str, display: crate::config::RomajiDisplay) {
+    use crate::lyric_providers::utils::romaji::{transliterate, Transliterated};
+
+    match transliterate(text, display) {
+        Some(Transliterated::Above(markup)) => label.set_markup(&markup),
+        Some(Transliterated::Replace(romaji)) => {
+            label.set_use_markup(false);
+            label.set_label(&romaji);
+        }
+        None => {
+            label.set_use_markup(false);
+            label.set_label(text);
+        }
+    }
+}
+
+/// Scroll through lyric without timestamp, translation is not shown as it can't be matched
+fn refresh_plain_lyric(window: &app::Window, elapsed: Duration, lines: &[String]) {
+    let imp = window.imp();
+    let length = TRACK_PLAYING_STATE
+        .with_borrow(|TrackState { metainfo, .. }| metainfo.as_ref().and_then(|meta| meta.length));
+    let line_at = |shift_ms: i64| {
+        plain_line_at(
+            &shifted_elapsed(elapsed, shift_ms),
+            lines,
+            length,
+            imp.no_timestamp_scroll_speed.get(),
+        )
+    };
+    let [above_ms, below_ms] = imp.lyric_spacing_ms.get();
+    let (above, below) = (line_at(above_ms), line_at(below_ms));
+    set_lyric_with_mode(window, (None, above.as_ref()), (None, below.as_ref()));
+    dim_context_labels(window, [above_ms, below_ms]);
 }
 
 pub fn refresh_lyric(window: &app::Window, paused: bool) {
+    // blanked by `lyric-line-timeout-ms` until the player is synced again
+    if window.imp().line_timed_out.get() {
+        return;
+    }
+    app::bpm_pulse::update_pulse(window, paused);
     if paused {
         if !window.imp().show_lyric_on_pause.get() {
             reset_lyric_labels(window, Some(""));
@@ -80,30 +364,54 @@ pub fn refresh_lyric(window: &app::Window, paused: bool) {
         |LyricState {
              origin,
              translation,
+             ..
          }| {
             let system_time = window.imp().lyric_start.get().unwrap();
             let elapsed = system_time.elapsed().ok();
             let Some(elapsed) = elapsed else {
                 return;
             };
-            match (origin, translation) {
-                (
-                    LyricOwned::LineTimestamp(origin_lyric),
-                    LyricOwned::LineTimestamp(translation_lyric),
-                ) => {
-                    let translation =
-                        crate::lyric_providers::utils::find_next_lyric(&elapsed, translation_lyric);
-                    let origin =
-                        crate::lyric_providers::utils::find_next_lyric(&elapsed, origin_lyric);
-                    set_lyric_with_mode(window, translation, origin);
+            let origin_lyric = match origin {
+                LyricOwned::LineTimestamp(origin_lyric) => origin_lyric,
+                LyricOwned::NoTimestamp(lines) => {
+                    refresh_plain_lyric(window, elapsed, lines);
+                    return;
                 }
-                (LyricOwned::LineTimestamp(origin_lyric), _) => {
-                    let origin =
-                        crate::lyric_providers::utils::find_next_lyric(&elapsed, origin_lyric);
-                    set_lyric_with_mode(window, None, origin);
+                LyricOwned::None => return,
+            };
+            let translation_lyric = match translation {
+                LyricOwned::LineTimestamp(translation_lyric) => Some(translation_lyric),
+                _ => None,
+            };
+            let overridden = window.imp().lyric_override_index.get().and_then(|index| {
+                override_lines(origin_lyric, translation_lyric.map(Vec::as_slice), index)
+            });
+            if let Some(lines) = overridden {
+                set_lyric_with_mode(window, lines, lines);
+                dim_context_labels(window, [0, 0]);
+                if let Some(start_time) = lines.1.map(|line| line.start_time) {
+                    fill_gap(window, &start_time, origin_lyric);
+                    update_progress(window, &start_time, origin_lyric);
+                    update_line_indicator(window, &start_time, origin_lyric);
                 }
-                _ => (),
+                return;
             }
+            let min_duration = window.imp().lyric_line_min_duration.get();
+            // only which lines are shown is shifted, timestamps in `LYRIC` are untouched
+            let lines_at = |shift_ms: i64| {
+                let elapsed = shifted_elapsed(elapsed, shift_ms);
+                (
+                    translation_lyric
+                        .and_then(|lyric| find_next_long_lyric(&elapsed, lyric, min_duration)),
+                    find_next_long_lyric(&elapsed, origin_lyric, min_duration),
+                )
+            };
+            let [above_ms, below_ms] = window.imp().lyric_spacing_ms.get();
+            set_lyric_with_mode(window, lines_at(above_ms), lines_at(below_ms));
+            dim_context_labels(window, [above_ms, below_ms]);
+            fill_gap(window, &elapsed, origin_lyric);
+            update_progress(window, &elapsed, origin_lyric);
+            update_line_indicator(window, &elapsed, origin_lyric);
         },
     );
 }
@@ -0,0 +1,131 @@
+//! Detached GnuPG signatures of lyric caches, see `verify-cache-signatures`.
+//!
+//! For caches in directories shared with others, where a cache may be corrupted or replaced.
+//! A cache is signed into `<digest>.sig` next to it whenever it's written, if
+//! `cache-signing-key` is set, and checked by `gpgv` against `cache-signing-keyring` on loading.
+//! Both run on the tokio runtime and are killed after `GPG_TIMEOUT`, as a pinentry prompt or a
+//! slow keyring would otherwise block the caller.
+
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use crate::log::{debug, warn};
+use crate::tokio_spawn;
+
+/// `gpg` or `gpgv` running longer than this is killed, and the cache treated as unsigned
+pub const GPG_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SigningConfig {
+    pub verify: bool,
+    /// keyring `gpgv` checks signatures against
+    pub keyring: String,
+    /// key id `gpg` signs new caches with, empty to not sign
+    pub signing_key: String,
+}
+
+static SIGNING_CONFIG: OnceLock<SigningConfig> = OnceLock::new();
+
+pub fn set_cache_signing(config: SigningConfig) {
+    let _ = SIGNING_CONFIG.set(config);
+}
+
+pub fn signature_path(cache_path: &Path) -> PathBuf {
+    cache_path.with_extension("sig")
+}
+
+/// Arguments of `gpgv` verifying `signature` over `data` with `keyring` only
+pub fn verify_args(keyring: &str, signature: &Path, data: &Path) -> Vec<OsString> {
+    vec![
+        "--keyring".into(),
+        keyring.into(),
+        signature.into(),
+        data.into(),
+    ]
+}
+
+/// Arguments of `gpg` writing a detached signature of `data` by `key` to `signature`
+pub fn sign_args(key: &str, signature: &Path, data: &Path) -> Vec<OsString> {
+    vec![
+        "--batch".into(),
+        "--yes".into(),
+        "--local-user".into(),
+        key.into(),
+        "--output".into(),
+        signature.into(),
+        "--detach-sign".into(),
+        data.into(),
+    ]
+}
+
+async fn run(program: &'static str, args: Vec<OsString>) -> Result<(), String> {
+    let mut command = tokio::process::Command::new(program);
+    command
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    let output =
+        tokio_spawn!(async move { tokio::time::timeout(GPG_TIMEOUT, command.output()).await })
+            .await
+            .map_err(|e| format!("cannot run {program}: {e}"))?
+            .map_err(|_| format!("{program} timed out after {GPG_TIMEOUT:?}"))?
+            .map_err(|e| format!("cannot run {program}: {e}"))?;
+    if output.status.success() {
+        return Ok(());
+    }
+    Err(String::from_utf8_lossy(&output.stderr).trim().to_owned())
+}
+
+/// Whether the cache at `cache_path` may be loaded, always if verification is disabled
+pub async fn verify_cache(cache_path: &Path) -> bool {
+    let Some(config) = SIGNING_CONFIG.get().filter(|config| config.verify) else {
+        return true;
+    };
+    let signature = signature_path(cache_path);
+    if !signature.exists() {
+        warn!("lyric cache {cache_path:?} is not signed, ignored");
+        return false;
+    }
+    match run("gpgv", verify_args(&config.keyring, &signature, cache_path)).await {
+        Ok(()) => {
+            debug!("verified signature of {cache_path:?}");
+            true
+        }
+        Err(e) => {
+            warn!("bad signature of lyric cache {cache_path:?}, ignored: {e}");
+            false
+        }
+    }
+}
+
+/// Sign the cache just written to `cache_path` in the background.
+///
+/// The signature of its old content is removed right away, as it cannot match.
+pub fn sign_cache(cache_path: &Path) {
+    let signature = signature_path(cache_path);
+    let _ = std::fs::remove_file(&signature);
+    let Some(config) = SIGNING_CONFIG
+        .get()
+        .filter(|config| !config.signing_key.is_empty())
+    else {
+        return;
+    };
+    let args = sign_args(&config.signing_key, &signature, cache_path);
+    let cache_path = cache_path.to_owned();
+    tokio_spawn!(async move {
+        if let Err(e) = run("gpg", args).await {
+            warn!("cannot sign lyric cache {cache_path:?}: {e}");
+            let _ = std::fs::remove_file(&signature);
+        }
+    });
+}
+
+/// Remove the signature of removed cache `cache_path`
+pub fn remove_signature(cache_path: &Path) {
+    let _ = std::fs::remove_file(signature_path(cache_path));
+}
@@ -1,25 +1,83 @@
 use std::cell::RefCell;
 use std::path::PathBuf;
-use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
 use std::time::Duration;
 
+use crate::lyric_providers::utils::http_cache::LyricSource;
 use crate::lyric_providers::LyricOwned;
 
+pub mod debug_state;
 mod interop;
 pub mod lyric;
+pub mod per_player;
+mod track_id;
 mod utils;
 
 pub use interop::{OsImp, OS};
 pub use lyric::scroll::register_lyric_display;
+pub use track_id::{TrackId, MPRIS_NO_TRACK};
 
 /// metadata from connected player
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug)]
 pub struct TrackMeta {
-    pub unique_song_id: Option<String>,
+    pub unique_song_id: Option<TrackId>,
     pub title: Option<String>,
     pub album: Option<String>,
     pub artists: Option<Vec<String>>,
     pub length: Option<Duration>,
+    /// from `xesam:contentCreated`
+    pub year: Option<u32>,
+    /// from `xesam:trackNumber`, to tell apart tracks of the same title on an album
+    pub track_number: Option<u32>,
+    /// from `xesam:discNumber`
+    pub disc_number: Option<u32>,
+    /// from `xesam:genre`, see `router` for how it picks providers
+    pub genre: Option<Vec<String>>,
+    /// from `xesam:audioBPM`, see `animate-to-bpm`
+    pub bpm: Option<u32>,
+    /// from `xesam:userRating`, 0 to 1, see `boost-rated-tracks`
+    pub user_rating: Option<f64>,
+}
+
+/// Same track if MusicBrainz ids are the same, or else if neither MPRIS track ids (if any)
+/// nor tags differ.
+///
+/// `length` is never compared, as players may correct it while playing (issue #109)
+impl PartialEq for TrackMeta {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.unique_song_id, &other.unique_song_id) {
+            (Some(id @ TrackId::MusicBrainz(_)), Some(other_id @ TrackId::MusicBrainz(_))) => {
+                return id == other_id;
+            }
+            (Some(TrackId::Mpris(path)), Some(TrackId::Mpris(other_path)))
+                if path != other_path =>
+            {
+                return false;
+            }
+            _ => (),
+        }
+        // some players reuse one track id, tags still tell tracks apart
+        self.title == other.title
+            && self.artists == other.artists
+            && self.album == other.album
+            && self.year == other.year
+            && self.track_number == other.track_number
+            && self.disc_number == other.disc_number
+    }
+}
+
+// `user_rating` is never compared
+impl Eq for TrackMeta {}
+
+impl TrackMeta {
+    /// year from a date like `2009-09-09T00:00:00`, reading the first four characters
+    pub fn parse_year(date: &str) -> Option<u32> {
+        date.get(..4)
+            .filter(|year| year.bytes().all(|b| b.is_ascii_digit()))?
+            .parse()
+            .ok()
+    }
 }
 
 #[derive(Clone, Debug, Default)]
@@ -27,19 +85,86 @@ pub struct TrackState {
     pub metainfo: Option<TrackMeta>,
     pub paused: bool,
     pub cache_path: Option<PathBuf>,
+    /// cancelled once another track starts playing
+    pub fetch_cancel: CancelToken,
+    pub loop_status: LoopStatus,
+    /// playback position at the last and the one before, to tell when a track restarts
+    pub position: Option<Duration>,
+    pub previous_position: Option<Duration>,
+}
+
+/// `LoopStatus` of MPRIS, `None` if the player doesn't tell
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LoopStatus {
+    #[default]
+    None,
+    Track,
+    Playlist,
+}
+
+impl TrackState {
+    /// Called on each sync with the player
+    pub fn update_position(&mut self, position: Duration) {
+        self.previous_position = self.position.replace(position);
+    }
+}
+
+/// Whether a track repeated by the player restarted, instead of a new track being played.
+///
+/// Players may give the track a new MPRIS track id on each repeat.
+pub fn is_track_loop(
+    previous_position: Option<Duration>,
+    position: Duration,
+    loop_status: LoopStatus,
+    threshold: Duration,
+) -> bool {
+    loop_status == LoopStatus::Track
+        && previous_position.is_some_and(|previous| previous.saturating_sub(position) > threshold)
+}
+
+/// Shared by a lyric fetch and the track it was started for, to abort the fetch once
+/// the track is no longer playing
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+#[derive(Debug, thiserror::Error)]
+#[error("lyric fetch cancelled as the track changed")]
+pub struct Cancelled;
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// `Err(Cancelled)` if cancelled, to return early with `?`
+    pub fn check(&self) -> Result<(), Cancelled> {
+        if self.is_cancelled() {
+            Err(Cancelled)
+        } else {
+            Ok(())
+        }
+    }
 }
 
 #[derive(Clone, Default)]
 pub struct LyricState {
     pub origin: LyricOwned,
     pub translation: LyricOwned,
+    /// page of the lyric on its provider's website, see `LyricProvider::source_url`
+    pub source_url: Option<String>,
 }
 
 thread_local! {
-    static LYRIC: RefCell<LyricState> = const { RefCell::new(LyricState { origin: LyricOwned::None, translation: LyricOwned::None }) };
+    static LYRIC: RefCell<LyricState> = const { RefCell::new(LyricState { origin: LyricOwned::None, translation: LyricOwned::None, source_url: None }) };
     /// A global variable that contains current playing state (excluding lyrics)
     /// including: track_id, paused, cache_path
     pub static TRACK_PLAYING_STATE: RefCell<TrackState> = RefCell::new(TrackState::default());
+    /// where current lyric came from, `None` for lyrics from player hints or local files
+    static LYRIC_SOURCE: RefCell<Option<LyricSource>> = const { RefCell::new(None) };
 }
 
 pub static ENABLE_LOCAL_LYRIC: OnceLock<bool> = OnceLock::new();
@@ -49,17 +174,29 @@ pub(crate) mod actions;
 pub use actions::register_export_lyric;
 #[cfg(feature = "import-lyric")]
 pub use actions::register_import_lyric;
+#[cfg(feature = "import-lyric")]
+pub use actions::register_import_original_lyric_from_clipboard;
+#[cfg(feature = "import-translation-from-srt")]
+pub use actions::register_import_srt_translation;
 pub use actions::{
-    register_connect, register_disconnect, register_refetch_lyric, register_reload_lyric,
-    register_remove_lyric, register_search_lyric,
+    register_clear_blacklist, register_clear_lyric_override, register_compare_lyrics,
+    register_confess_wrong_lyric, register_connect, register_copy_lyric_as_lrc,
+    register_disable_auto_offset, register_disconnect, register_import_netease_playlist,
+    register_offset_reset, register_open_editor, register_open_provider_url,
+    register_print_debug_state, register_random_lyric_line, register_refetch_lyric,
+    register_reload_config, register_reload_lyric, register_remove_lyric, register_save_config,
+    register_search_lyric, register_show_history, register_show_track_info,
+    register_swap_lyric_roles, register_unpin_lyric,
 };
 
 #[cfg(feature = "action-event")]
-pub use actions::{init_play_action_channel, PlayAction, PLAY_ACTION};
+pub use actions::{init_play_action_channel, subscribe_events, PlayAction, PlayEvent, PLAY_ACTION};
 
-pub use interop::register_sync_task;
 pub use interop::PlayerId;
+pub use interop::{format_player_summaries, PlayerSummary};
+pub use interop::{register_sync_task, register_sync_task_on_power, set_loop_threshold};
 pub use utils::{
-    extract_translated_lyric, filter_original_lyric, fuzzy_match_song, get_lyric_cache_path,
-    set_current_lyric,
+    adjust_lyric_offset, extract_translated_lyric, filter_original_lyric, fuzzy_match_song,
+    get_current_lyric, get_lyric_cache_path, get_lyric_source, reset_lyric_offset,
+    set_current_lyric, set_lyric_source, set_original_lyric_line, sort_lyric, swap_lyric_roles,
 };
@@ -0,0 +1,106 @@
+//! Settings overridden for specific players.
+
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use gtk::subclass::prelude::*;
+use gtk::CssProvider;
+
+use crate::app;
+use crate::config::PlayerConfig;
+use crate::log::info;
+
+thread_local! {
+    static PER_PLAYER: RefCell<BTreeMap<String, PlayerConfig>> = const { RefCell::new(BTreeMap::new()) };
+    /// global `cache-lyrics`, restored when no player config applies
+    static DEFAULT_CACHE_LYRICS: Cell<bool> = const { Cell::new(true) };
+    /// key of the applied player config, `Some(None)` for global defaults,
+    /// `None` if configs were reloaded since
+    static ACTIVE_PLAYER: RefCell<Option<Option<String>>> = const { RefCell::new(Some(None)) };
+    static FONT_SIZE_PROVIDER: RefCell<Option<CssProvider>> = const { RefCell::new(None) };
+}
+
+/// providers of the applied player config, read by `search_lyric` from any thread
+static PLAYER_PROVIDERS: Mutex<Option<Vec<String>>> = Mutex::new(None);
+
+/// Remember global defaults from `window` and set player configs
+pub fn init_per_player(configs: BTreeMap<String, PlayerConfig>, window: &app::Window) {
+    DEFAULT_CACHE_LYRICS.set(window.imp().cache_lyrics.get());
+    PER_PLAYER.set(configs);
+}
+
+/// Replace player configs and global `cache-lyrics` on config reload, applied on next sync
+pub fn reload_per_player(configs: BTreeMap<String, PlayerConfig>, cache_lyrics: bool) {
+    DEFAULT_CACHE_LYRICS.set(cache_lyrics);
+    PER_PLAYER.set(configs);
+    ACTIVE_PLAYER.set(None);
+}
+
+/// Find config for `identity` by the longest matched prefix
+pub fn find_player_config<'a>(
+    configs: &'a BTreeMap<String, PlayerConfig>,
+    identity: &str,
+) -> Option<(&'a String, &'a PlayerConfig)> {
+    configs
+        .iter()
+        .filter(|(prefix, _)| identity.starts_with(prefix.as_str()))
+        .max_by_key(|(prefix, _)| prefix.len())
+}
+
+/// Apply config matching `identity`, or global defaults if `None` or nothing matches
+pub fn apply_player_config(window: &app::Window, identity: Option<&str>) {
+    let found = PER_PLAYER.with_borrow(|configs| {
+        identity
+            .and_then(|identity| find_player_config(configs, identity))
+            .map(|(key, config)| (key.clone(), config.clone()))
+    });
+    let key = found.as_ref().map(|(key, _)| key.clone());
+    if ACTIVE_PLAYER.with_borrow(|active| active.as_ref() == Some(&key)) {
+        return;
+    }
+    match &key {
+        Some(key) => info!("applied player config for {key}"),
+        None => info!("restored global config"),
+    }
+    ACTIVE_PLAYER.set(Some(key));
+
+    let config = found.map(|(_, config)| config).unwrap_or_default();
+    let imp = window.imp();
+    imp.cache_lyrics
+        .set(config.cache_lyrics.unwrap_or(DEFAULT_CACHE_LYRICS.get()));
+    imp.player_offset_ms
+        .set(config.lyric_offset_ms.unwrap_or_default());
+    *PLAYER_PROVIDERS.lock().unwrap() = config.providers;
+    set_font_size(config.font_size);
+}
+
+/// Providers to search for current player, `None` for all enabled ones
+pub fn player_providers() -> Option<Vec<String>> {
+    PLAYER_PROVIDERS.lock().unwrap().clone()
+}
+
+fn set_font_size(font_size: Option<f64>) {
+    use gtk::gdk::Display as GdkDisplay;
+
+    let Some(display) = GdkDisplay::default() else {
+        return;
+    };
+    FONT_SIZE_PROVIDER.with_borrow_mut(|provider| {
+        if let Some(provider) = provider.take() {
+            gtk::style_context_remove_provider_for_display(&display, &provider);
+        }
+        let Some(font_size) = font_size else {
+            return;
+        };
+        let css_provider = CssProvider::new();
+        css_provider.load_from_data(&format!("#above, #below {{ font-size: {font_size}pt; }}"));
+        gtk::style_context_add_provider_for_display(
+            &display,
+            &css_provider,
+            // above the theme
+            gtk::STYLE_PROVIDER_PRIORITY_USER + 2,
+        );
+        *provider = Some(css_provider);
+    });
+}
@@ -0,0 +1,43 @@
+use std::fmt;
+
+use uuid::Uuid;
+
+/// `mpris:trackid` of players without a track, as defined by MPRIS
+pub const MPRIS_NO_TRACK: &str = "/org/mpris/MediaPlayer2/TrackList/NoTrack";
+
+/// Identifier of a track, from the most to the least specific variant
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TrackId {
+    /// MusicBrainz recording, from `xesam:musicBrainzTrackID` or AcoustID
+    MusicBrainz(Uuid),
+    /// `mpris:trackid` object path, only unique among tracks of one player
+    Mpris(String),
+    /// digest of title and artists, for players without any id
+    Synthetic(String),
+}
+
+impl TrackId {
+    /// `None` for `MPRIS_NO_TRACK` or an empty path
+    pub fn from_mpris_path(path: &str) -> Option<Self> {
+        (!path.is_empty() && path != MPRIS_NO_TRACK).then(|| Self::Mpris(path.to_owned()))
+    }
+
+    pub fn from_musicbrainz(id: &str) -> Option<Self> {
+        Uuid::parse_str(id.trim()).ok().map(Self::MusicBrainz)
+    }
+
+    pub fn synthetic(title: Option<&str>, artists: Option<&[String]>) -> Self {
+        let key = format!("{title:?}-{artists:?}");
+        Self::Synthetic(format!("{:x}", md5::compute(key)))
+    }
+}
+
+impl fmt::Display for TrackId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrackId::MusicBrainz(id) => write!(f, "musicbrainz:{id}"),
+            TrackId::Mpris(path) => f.write_str(path),
+            TrackId::Synthetic(digest) => write!(f, "synthetic:{digest}"),
+        }
+    }
+}
@@ -5,17 +5,52 @@ use gtk::subclass::prelude::*;
 use sorensen::distance;
 
 use crate::log::*;
-use crate::lyric_providers::LyricLineOwned;
+use crate::lyric_providers::utils::http_cache::LyricSource;
+use crate::lyric_providers::utils::lyric_filter::filter_lyric;
+use crate::lyric_providers::utils::ranking::{self, TrackInfo};
+use crate::lyric_providers::{LyricLineOwned, LyricOwned};
 use crate::{app, lyric_providers::SongInfo};
 
-use super::{LyricState, TrackState, LYRIC, TRACK_PLAYING_STATE};
+use super::lyric::cache::update_lyric_cache;
+use super::{LyricState, TrackState, LYRIC, LYRIC_SOURCE, TRACK_PLAYING_STATE};
 
 pub fn clean_lyric(window: &app::Window) {
     LYRIC.set(LyricState::default());
-    window.imp().lyric_offset_ms.set(0);
+    LYRIC_SOURCE.set(None);
+    window
+        .imp()
+        .lyric_offset_ms
+        .set(window.imp().default_lyric_offset_ms.get());
+}
+
+/// Restore `lyric-offset-ms` from config, dropping the offset persisted in cache
+pub fn reset_lyric_offset(window: &app::Window) {
+    let imp = window.imp();
+    imp.lyric_offset_ms.set(imp.default_lyric_offset_ms.get());
+    info!("reset offset to {}ms", imp.default_lyric_offset_ms.get());
+
+    if !imp.cache_lyrics.get() {
+        return;
+    }
+    let Some(cache_path) = get_lyric_cache_path().filter(|path| path.exists()) else {
+        return;
+    };
+    // lyric cache is written without offset
+    update_lyric_cache(&cache_path);
+}
+
+/// Shift lyric by `delta_ms` until the next track, it applies on next sync of position
+pub fn adjust_lyric_offset(window: &app::Window, delta_ms: i64) {
+    let imp = window.imp();
+    let offset = imp.lyric_offset_ms.get() + delta_ms;
+    imp.lyric_offset_ms.set(offset);
+    info!("adjusted offset to {offset}ms");
 }
 
 /// both singer and album are optional non-empty string
+///
+/// candidates are ranked by `ranking::score`, the weight returned is `0` if
+/// the best one is within `length_toleration_ms`, `2` if it was picked blindly
 pub fn match_likely_lyric<'a>(
     album: Option<&str>,
     title: &str,
@@ -24,98 +59,152 @@ pub fn match_likely_lyric<'a>(
     search_result: &'a [SongInfo],
     length_toleration_ms: u128,
 ) -> Option<(&'a str, u8)> {
-    length
-        .and_then(|leng| {
-            search_result
-                .iter()
-                .find(|SongInfo { length, .. }| {
-                    length.as_millis().abs_diff(leng.as_millis()) <= length_toleration_ms
-                })
-                .map(|song| (song, 1))
-        })
-        .or_else(|| {
-            // if we get only title, it is likely
-            // the player is giving messy title, which is not applicable
-            // to fuzzy-match. so we skip fuzzy-match and use
-            // the first result instead
-            if album.is_none() && singer.is_none() {
-                return None;
-            }
+    // if we get only title, it is likely
+    // the player is giving messy title, which is not applicable
+    // to fuzzy-match. so we skip fuzzy-match and use
+    // the first result instead
+    if length.is_none() && album.is_none() && singer.is_none() {
+        let song = search_result.first()?;
+        let track = TrackInfo {
+            title: title.to_owned(),
+            ..Default::default()
+        };
+        let candidate = TrackInfo {
+            title: song.title.clone(),
+            ..Default::default()
+        };
+        return is_confident(&track, &candidate, song).then_some((song.id.as_str(), 2));
+    }
 
-            #[cfg(feature = "opencc")]
-            let Ok(opencc) = opencc_rust::OpenCC::new("t2s.json") else {
-                error!("opencc enabled but missing t2s.json dictionary");
-                return None;
-            };
-
-            let o_title: Vec<char>;
-            let o_album: Option<Vec<char>>;
-            let o_singer: Option<Vec<char>>;
-
-            #[cfg(feature = "opencc")]
-            {
-                o_title = opencc.convert(title).chars().collect();
-                o_album = album.map(|a| opencc.convert(a).chars().collect());
-                o_singer = singer.map(|s| opencc.convert(s).chars().collect());
-            }
-            #[cfg(not(feature = "opencc"))]
-            {
-                o_title = title.chars().collect();
-                o_album = album.map(|a| a.chars().collect());
-                o_singer = singer.map(|s| s.chars().collect());
-            }
+    #[cfg(feature = "opencc")]
+    let Ok(opencc) = opencc_rust::OpenCC::new("t2s.json") else {
+        error!("opencc enabled but missing t2s.json dictionary");
+        return search_result.first().map(|song| (song.id.as_str(), 2));
+    };
+    let convert = |s: &str| -> String {
+        #[cfg(feature = "opencc")]
+        return opencc.convert(s);
+        #[cfg(not(feature = "opencc"))]
+        s.to_owned()
+    };
+
+    let track = TrackInfo {
+        title: convert(title),
+        artists: singer.map(convert),
+        album: album.map(convert),
+        length,
+    };
+    let candidates = search_result.iter().map(|song| TrackInfo {
+        title: convert(&song.title),
+        artists: Some(convert(&song.singer)),
+        album: song.album.as_deref().map(convert),
+        length: Some(song.length),
+    });
+
+    let ranked = ranking::rank(&track, candidates, &ranking::ranking_weights());
+    let &(idx, score) = ranked.first()?;
+    let song = &search_result[idx];
+    trace!("score={score} for {song:?}");
+    let candidate = TrackInfo {
+        title: convert(&song.title),
+        artists: Some(convert(&song.singer)),
+        ..Default::default()
+    };
+    if !is_confident(&track, &candidate, song) {
+        return None;
+    }
+
+    let weight = match length {
+        Some(leng)
+            if song.length.as_millis().abs_diff(leng.as_millis()) <= length_toleration_ms =>
+        {
+            0
+        }
+        _ => 1,
+    };
+    Some((song.id.as_str(), weight))
+}
+
+/// Whether `candidate` of `song` reaches `min-confidence-score`
+fn is_confident(track: &TrackInfo, candidate: &TrackInfo, song: &SongInfo) -> bool {
+    let min_score = ranking::min_confidence_score();
+    if min_score <= 0. {
+        return true;
+    }
+    let confidence = ranking::confidence(track, candidate);
+    if confidence < min_score {
+        debug!(
+            "discarded {} - {} ({}): confidence {confidence:.2} is below {min_score}",
+            song.singer, song.title, song.id
+        );
+        return false;
+    }
+    true
+}
 
-            search_result
-                .iter()
-                .map(
-                    |s @ SongInfo {
-                         title: _title,
-                         singer: _singer,
-                         album: _album,
-                         ..
-                     }| {
-                        let r_title;
-                        let r_album;
-                        let r_singer;
-
-                        #[cfg(feature = "opencc")]
-                        {
-                            r_title = opencc.convert(_title).chars().collect::<Vec<_>>();
-                            r_album = _album
-                                .as_ref()
-                                .map(|a| opencc.convert(a).chars().collect::<Vec<_>>());
-                            r_singer = opencc.convert(_singer).chars().collect::<Vec<_>>();
-                        }
-                        #[cfg(not(feature = "opencc"))]
-                        {
-                            r_title = _title.chars().collect::<Vec<_>>();
-                            r_album = _album.as_ref().map(|a| a.chars().collect::<Vec<_>>());
-                            r_singer = _singer.chars().collect::<Vec<_>>();
-                        }
-
-                        let likelihood = fuzzy_match_song(
-                            &o_title,
-                            o_album.as_deref(),
-                            o_singer.as_deref(),
-                            &r_title,
-                            r_album.as_deref(),
-                            &r_singer,
-                        );
-                        trace!("p={likelihood} for {s:?}");
-                        (s, likelihood)
-                    },
-                )
-                .max_by_key(|(_, likelihood)| (likelihood * 1024.) as u32)
-                .map(|(s, _)| (s, 0))
-        })
-        .or(search_result.first().map(|song| (song, 2)))
-        .map(|(song, weight)| (song.id.as_str(), weight))
+/// Swap origin and translation of `lyric`, calling it again undoes the swap.
+///
+/// Returns `false` and keeps `lyric` as is if there's no translation.
+pub fn swap_lyric_roles(lyric: &mut LyricState) -> bool {
+    if lyric.translation.is_none() {
+        return false;
+    }
+    std::mem::swap(&mut lyric.origin, &mut lyric.translation);
+    true
 }
 
 pub fn set_current_lyric(lyric: LyricState) {
+    let lyric = LyricState {
+        origin: filter_lyric(lyric.origin),
+        translation: filter_lyric(lyric.translation),
+        ..lyric
+    };
     LYRIC.set(lyric);
 }
 
+pub fn get_current_lyric() -> LyricState {
+    LYRIC.with_borrow(Clone::clone)
+}
+
+pub fn set_lyric_source(source: Option<LyricSource>) {
+    LYRIC_SOURCE.set(source);
+}
+
+pub fn get_lyric_source() -> Option<LyricSource> {
+    LYRIC_SOURCE.with_borrow(Clone::clone)
+}
+
+/// Replace the `idx`-th line of original lyric, does nothing if it's out of range
+pub fn set_original_lyric_line(idx: usize, line: LyricLineOwned) {
+    LYRIC.with_borrow_mut(|LyricState { origin, .. }| {
+        if let LyricOwned::LineTimestamp(lines) = origin {
+            if let Some(l) = lines.get_mut(idx) {
+                *l = line;
+            }
+        }
+    });
+}
+
+/// Sort lines of current lyric by start time, returns whether original lines were out of order
+pub fn sort_lyric() -> bool {
+    LYRIC.with_borrow_mut(
+        |LyricState {
+             origin,
+             translation,
+             ..
+         }| {
+            let unsorted = matches!(origin, LyricOwned::LineTimestamp(lines)
+                if lines.windows(2).any(|w| w[0].start_time > w[1].start_time));
+            for lyric in [origin, translation] {
+                if let LyricOwned::LineTimestamp(lines) = lyric {
+                    lines.sort_by_key(|l| l.start_time);
+                }
+            }
+            unsorted
+        },
+    )
+}
+
 pub fn get_lyric_cache_path() -> Option<PathBuf> {
     TRACK_PLAYING_STATE.with_borrow(|TrackState { cache_path, .. }| cache_path.as_ref().cloned())
 }
@@ -161,14 +250,20 @@ pub fn filter_original_lyric(
     let tlyric = tlyric
         .as_ref()
         .iter()
-        .map(|LyricLineOwned { text, start_time }| (start_time, text))
+        .map(
+            |LyricLineOwned {
+                 text, start_time, ..
+             }| (start_time, text),
+        )
         .collect::<HashMap<_, _>>();
     let tlyric_lines = lyric
         .as_ref()
         .iter()
-        .filter(|LyricLineOwned { text, start_time }| {
-            !tlyric.contains_key(start_time) || tlyric[start_time] != text
-        })
+        .filter(
+            |LyricLineOwned {
+                 text, start_time, ..
+             }| { !tlyric.contains_key(start_time) || tlyric[start_time] != text },
+        )
         .cloned()
         .collect::<Vec<_>>();
     tlyric_lines
@@ -0,0 +1,38 @@
+use std::time::Duration;
+
+use crate::lyric_providers::acoustid::{best_recording, LookupResponse};
+use crate::sync::TrackId;
+
+#[test]
+fn best_scored_recording_with_title() {
+    let response: LookupResponse = serde_json::from_str(
+        r#"{
+  "status": "ok",
+  "results": [
+    {"id": "a", "score": 0.5, "recordings": [{"id": "low", "title": "Wrong"}]},
+    {"id": "b", "score": 0.9, "recordings": [
+      {"id": "untitled"},
+      {"id": "c2d0ab5e-5f4d-4b8d-9f33-5a7e8d6e2a1b", "title": "Yesterday", "duration": 125.5,
+       "artists": [{"id": "x", "name": "The Beatles"}]}
+    ]}
+  ]
+}"#,
+    )
+    .unwrap();
+    let track = best_recording(response).unwrap();
+    assert_eq!(
+        track.unique_song_id,
+        TrackId::from_musicbrainz("c2d0ab5e-5f4d-4b8d-9f33-5a7e8d6e2a1b")
+    );
+    assert_eq!(track.title.as_deref(), Some("Yesterday"));
+    assert_eq!(track.artists, Some(vec!["The Beatles".to_owned()]));
+    assert_eq!(track.length, Some(Duration::from_millis(125500)));
+}
+
+#[test]
+fn no_recording() {
+    let response: LookupResponse =
+        serde_json::from_str(r#"{"status": "ok", "results": [{"id": "a", "score": 1.0}]}"#)
+            .unwrap();
+    assert!(best_recording(response).is_none());
+}
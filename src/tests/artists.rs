@@ -0,0 +1,32 @@
+use crate::lyric_providers::{default_search_query, join_artists, split_artists};
+
+#[test]
+fn slash_in_artist_name_is_kept() {
+    let artists = ["AC/DC", "Brian Johnson"];
+    let joined = join_artists(&artists);
+    assert_eq!(joined, "AC/DC & Brian Johnson");
+    assert_eq!(split_artists(&joined), artists);
+}
+
+#[test]
+fn split_drops_empty_names() {
+    assert_eq!(split_artists(" A &  & B & "), ["A", "B"]);
+    assert!(split_artists("").is_empty());
+}
+
+#[test]
+fn ampersand_in_artist_name_is_kept() {
+    assert_eq!(split_artists("Simon&Garfunkel"), ["Simon&Garfunkel"]);
+    assert_eq!(
+        split_artists("Earth, Wind&Fire & AT&T"),
+        ["Earth, Wind&Fire", "AT&T"]
+    );
+}
+
+#[test]
+fn artists_are_separate_search_terms() {
+    assert_eq!(
+        default_search_query("Album", &["A", "B"], "Title"),
+        "Title Album A B"
+    );
+}
@@ -0,0 +1,53 @@
+use std::time::Duration;
+
+use super::line;
+use crate::lyric_providers::utils::ass::{parse_ass, parse_timestamp, strip_tags, AssError};
+
+#[test]
+fn timestamp() {
+    assert_eq!(
+        parse_timestamp("0:01:02.50"),
+        Some(Duration::from_millis(62500))
+    );
+    assert_eq!(
+        parse_timestamp("1:00:00.00"),
+        Some(Duration::from_secs(3600))
+    );
+    assert_eq!(parse_timestamp("01:02.50"), None);
+}
+
+#[test]
+fn override_tags_are_stripped() {
+    assert_eq!(
+        strip_tags(r"{\an8\c&H00FFFF&}Hello{\i1}\Nworld"),
+        "Hello world"
+    );
+}
+
+#[test]
+fn translation_styles() {
+    let ass = r"[Script Info]
+Title: test
+
+[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Dialogue: 0,0:00:05.00,0:00:07.00,Default,,0,0,0,,second, with comma
+Dialogue: 0,0:00:01.00,0:00:03.00,Default,,0,0,0,,{\k20}first
+Dialogue: 0,0:00:01.00,0:00:03.00,romaji,,0,0,0,,faasuto
+Comment: 0,0:00:09.00,0:00:10.00,Default,,0,0,0,,ignored
+";
+    let lyric = parse_ass(ass).unwrap();
+    assert_eq!(
+        lyric.origin,
+        [line(1000, "first"), line(5000, "second, with comma")]
+    );
+    assert_eq!(lyric.translation, [line(1000, "faasuto")]);
+}
+
+#[test]
+fn missing_events() {
+    assert!(matches!(
+        parse_ass("[Script Info]\n"),
+        Err(AssError::NoEvents)
+    ));
+}
@@ -0,0 +1,60 @@
+use std::time::Duration;
+
+use crate::lyric_providers::LyricLineOwned;
+use crate::sync::lyric::auto_offset::{consensus_offset, estimate_offset};
+
+fn lyric(lines: &[(u64, &str)]) -> Vec<LyricLineOwned> {
+    lines
+        .iter()
+        .map(|&(millis, text)| LyricLineOwned {
+            text: text.into(),
+            start_time: Duration::from_millis(millis),
+            confidence: None,
+        })
+        .collect()
+}
+
+fn shifted(lines: &[(u64, &str)], shift_ms: u64) -> Vec<LyricLineOwned> {
+    let lines = lines
+        .iter()
+        .map(|&(millis, text)| (millis + shift_ms, text))
+        .collect::<Vec<_>>();
+    lyric(&lines)
+}
+
+const LINES: &[(u64, &str)] = &[
+    (1000, "Is this the real life?"),
+    (4000, "Is this just fantasy?"),
+    (7000, "Caught in a landslide"),
+    (10000, "No escape from reality"),
+    (13000, ""),
+];
+
+#[test]
+fn early_lyric() {
+    let early = lyric(LINES);
+    let reference = shifted(LINES, 800);
+    assert_eq!(estimate_offset(&early, &reference), Some(800));
+    assert_eq!(estimate_offset(&reference, &early), Some(-800));
+    assert_eq!(estimate_offset(&early, &early), Some(0));
+}
+
+#[test]
+fn matched_by_text_only() {
+    let lyric_ = lyric(LINES);
+    let other = lyric(&[(1000, "another song"), (4000, "entirely"), (7000, "here")]);
+    assert_eq!(estimate_offset(&lyric_, &other), None);
+    // lines too far apart are not the same line
+    assert_eq!(estimate_offset(&lyric_, &shifted(LINES, 6000)), None);
+}
+
+#[test]
+fn providers_must_agree() {
+    let lyric_ = lyric(LINES);
+    let a = shifted(LINES, 800);
+    let b = shifted(LINES, 900);
+    assert_eq!(consensus_offset(&lyric_, &[&a, &b]), Some(850));
+    let c = shifted(LINES, 2000);
+    assert_eq!(consensus_offset(&lyric_, &[&a, &c]), None);
+    assert_eq!(consensus_offset(&lyric_, &[]), None);
+}
@@ -0,0 +1,25 @@
+use std::time::Duration;
+
+use crate::app::bpm_pulse::{beat_interval, until_next_beat};
+
+#[test]
+fn interval_of_tempo() {
+    assert_eq!(beat_interval(120), Some(Duration::from_millis(500)));
+    assert_eq!(beat_interval(90), Some(Duration::from_nanos(666_666_666)));
+    assert_eq!(beat_interval(0), None);
+    assert_eq!(beat_interval(1000), None);
+}
+
+#[test]
+fn aligned_to_first_beat() {
+    let beat = Duration::from_millis(500);
+    assert_eq!(until_next_beat(Duration::ZERO, beat), Duration::ZERO);
+    assert_eq!(
+        until_next_beat(Duration::from_millis(1200), beat),
+        Duration::from_millis(300)
+    );
+    assert_eq!(
+        until_next_beat(Duration::from_secs(3), beat),
+        Duration::ZERO
+    );
+}
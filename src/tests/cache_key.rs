@@ -0,0 +1,157 @@
+use crate::history::PlayRecord;
+use crate::sync::lyric::cache::{
+    canonical_key_part, get_cache_path, get_legacy_cache_path, is_retagged_track,
+    migrate_legacy_cache, migrate_legacy_caches,
+};
+use crate::sync::{TrackId, TrackMeta};
+use crate::CACHE_DIR;
+
+fn meta(title: &str, artist: &str) -> TrackMeta {
+    TrackMeta {
+        unique_song_id: None,
+        title: Some(title.into()),
+        album: None,
+        artists: Some(vec![artist.into()]),
+        length: None,
+        year: None,
+        track_number: None,
+        disc_number: None,
+        genre: None,
+        bpm: None,
+        user_rating: None,
+    }
+}
+
+#[test]
+fn canonical_key_drops_article_and_case() {
+    assert_eq!(canonical_key_part("The Beatles", None), "beatles");
+    assert_eq!(canonical_key_part("  the   beatles ", None), "beatles");
+    assert_eq!(canonical_key_part("An Ending", Some("de")), "ending");
+    assert_eq!(canonical_key_part("Die Ärzte", Some("de")), "ärzte");
+    assert_eq!(canonical_key_part("Die Ärzte", Some("fr")), "die ärzte");
+    // a lone article is kept
+    assert_eq!(canonical_key_part("The", None), "the");
+    assert_eq!(canonical_key_part("Theory", None), "theory");
+}
+
+#[test]
+fn case_variants_share_cache() {
+    assert_eq!(
+        get_cache_path(&meta("Something", "The Beatles")),
+        get_cache_path(&meta("something", "the beatles"))
+    );
+    assert_ne!(
+        get_cache_path(&meta("Something", "The Beatles")),
+        get_cache_path(&meta("Something", "The Rolling Stones"))
+    );
+}
+
+#[test]
+fn track_numbers_tell_apart_discs() {
+    let track = |disc_number, track_number| TrackMeta {
+        disc_number,
+        track_number,
+        ..meta("Intro", "Someone")
+    };
+    assert_ne!(
+        get_cache_path(&track(Some(1), Some(1))),
+        get_cache_path(&track(Some(2), Some(1)))
+    );
+    assert_ne!(
+        get_cache_path(&track(None, Some(1))),
+        get_cache_path(&meta("Intro", "Someone"))
+    );
+    assert_eq!(
+        get_cache_path(&track(None, None)),
+        get_cache_path(&meta("Intro", "Someone"))
+    );
+}
+
+#[test]
+fn migrate_legacy_cache_once() {
+    let cache_home =
+        std::env::temp_dir().join(format!("waylyrics-cache-key-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&cache_home);
+    CACHE_DIR.set(cache_home.to_string_lossy().into_owned());
+
+    let track = meta("Something", "The Beatles");
+    let legacy_path = get_legacy_cache_path(&track).unwrap();
+    let cache_path = get_cache_path(&track).unwrap();
+    assert_ne!(legacy_path, cache_path);
+
+    std::fs::create_dir_all(legacy_path.parent().unwrap()).unwrap();
+    std::fs::write(&legacy_path, "{}").unwrap();
+
+    assert!(migrate_legacy_cache(&track));
+    assert!(!legacy_path.exists());
+    assert_eq!(std::fs::read_to_string(&cache_path).unwrap(), "{}");
+    assert!(!migrate_legacy_cache(&track));
+
+    CACHE_DIR.set(String::new());
+    let _ = std::fs::remove_dir_all(&cache_home);
+}
+
+#[test]
+fn migrate_played_caches_at_startup() {
+    let cache_home =
+        std::env::temp_dir().join(format!("waylyrics-cache-scan-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&cache_home);
+    CACHE_DIR.set(cache_home.to_string_lossy().into_owned());
+
+    let track = meta("Yesterday", "The Beatles");
+    let legacy_path = get_legacy_cache_path(&track).unwrap();
+    std::fs::create_dir_all(legacy_path.parent().unwrap()).unwrap();
+    std::fs::write(&legacy_path, "{}").unwrap();
+    let record = PlayRecord {
+        timestamp: 0,
+        title: "Yesterday".into(),
+        artist: "The Beatles".into(),
+        album: String::new(),
+        lyric_cache_path: Some(legacy_path.clone()),
+    };
+
+    // played twice, migrated once
+    let records = [record.clone(), record];
+    assert_eq!(migrate_legacy_caches(&records), 1);
+    assert!(!legacy_path.exists());
+    assert!(get_cache_path(&track).unwrap().exists());
+    assert_eq!(migrate_legacy_caches(&records), 0);
+
+    CACHE_DIR.set(String::new());
+    let _ = std::fs::remove_dir_all(&cache_home);
+}
+
+#[test]
+fn retagged_track_keeps_cache() {
+    let playing = TrackMeta {
+        unique_song_id: TrackId::from_mpris_path("/org/mpris/track/1"),
+        ..meta("Something", "The Beatles")
+    };
+    let cache_path = get_cache_path(&playing);
+    let retagged = TrackMeta {
+        title: Some("something ".into()),
+        ..playing.clone()
+    };
+    assert!(is_retagged_track(
+        &playing,
+        cache_path.as_deref(),
+        &retagged
+    ));
+
+    let renamed = TrackMeta {
+        title: Some("Somethin'".into()),
+        ..playing.clone()
+    };
+    assert!(!is_retagged_track(
+        &playing,
+        cache_path.as_deref(),
+        &renamed
+    ));
+
+    // same tags, but the player moved on to another track in its queue
+    let next = TrackMeta {
+        unique_song_id: TrackId::from_mpris_path("/org/mpris/track/2"),
+        ..retagged
+    };
+    assert!(!is_retagged_track(&playing, cache_path.as_deref(), &next));
+}
@@ -0,0 +1,46 @@
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use crate::sync::lyric::signature::{sign_args, signature_path, verify_args};
+
+fn strings(args: Vec<OsString>) -> Vec<String> {
+    args.into_iter()
+        .map(|arg| arg.into_string().unwrap())
+        .collect()
+}
+
+#[test]
+fn signature_next_to_cache() {
+    assert_eq!(
+        signature_path(Path::new("/cache/ab/abcdef.json")),
+        PathBuf::from("/cache/ab/abcdef.sig")
+    );
+}
+
+#[test]
+fn gpg_arguments() {
+    let cache = Path::new("/cache/ab/abcdef.json");
+    let signature = signature_path(cache);
+    assert_eq!(
+        strings(verify_args("/keys/shared.kbx", &signature, cache)),
+        [
+            "--keyring",
+            "/keys/shared.kbx",
+            "/cache/ab/abcdef.sig",
+            "/cache/ab/abcdef.json"
+        ]
+    );
+    assert_eq!(
+        strings(sign_args("0xDEADBEEF", &signature, cache)),
+        [
+            "--batch",
+            "--yes",
+            "--local-user",
+            "0xDEADBEEF",
+            "--output",
+            "/cache/ab/abcdef.sig",
+            "--detach-sign",
+            "/cache/ab/abcdef.json"
+        ]
+    );
+}
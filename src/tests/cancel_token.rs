@@ -0,0 +1,29 @@
+use crate::sync::{CancelToken, Cancelled};
+
+#[test]
+fn clones_share_cancellation() {
+    let token = CancelToken::default();
+    let fetching = token.clone();
+    assert!(!fetching.is_cancelled());
+    assert!(fetching.check().is_ok());
+
+    token.cancel();
+    assert!(fetching.is_cancelled());
+    assert!(matches!(fetching.check(), Err(Cancelled)));
+}
+
+#[test]
+fn new_token_not_cancelled() {
+    let mut token = CancelToken::default();
+    let previous = token.clone();
+    token.cancel();
+    token = CancelToken::default();
+    assert!(previous.is_cancelled());
+    assert!(!token.is_cancelled());
+}
+
+#[test]
+fn cancelled_survives_anyhow() {
+    let e: anyhow::Error = Cancelled.into();
+    assert!(e.is::<Cancelled>());
+}
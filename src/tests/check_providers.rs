@@ -0,0 +1,27 @@
+use std::time::Duration;
+
+use crate::lyric_providers::check::{format_table, CheckResult};
+
+#[test]
+fn table_is_aligned() {
+    let results = [
+        CheckResult {
+            provider: "netease".into(),
+            latency: Duration::from_millis(120),
+            outcome: Ok(12),
+        },
+        CheckResult {
+            provider: "lrclib".into(),
+            latency: Duration::from_millis(3),
+            outcome: Err("timeout".into()),
+        },
+    ];
+    assert!(results[0].passed());
+    assert!(!results[1].passed());
+    assert_eq!(
+        format_table(&results),
+        "provider  latency  results  status\n\
+         netease   120ms    12       pass\n\
+         lrclib    3ms      -        fail: timeout\n"
+    );
+}
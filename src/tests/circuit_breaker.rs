@@ -0,0 +1,63 @@
+use std::time::Duration;
+
+use anyhow::Result;
+
+use crate::lyric_providers::utils::circuit_breaker::{
+    CircuitBreaker, CircuitState, FAILURE_THRESHOLD,
+};
+use crate::lyric_providers::{LyricOwned, LyricParse, LyricProvider, LyricStore, SongInfo};
+
+struct Broken;
+
+#[async_trait::async_trait]
+impl LyricProvider for Broken {
+    async fn query_lyric(&self, _id: &str) -> Result<LyricStore> {
+        anyhow::bail!("broken")
+    }
+    async fn search_song_detailed(
+        &self,
+        _album: &str,
+        _artists: &[&str],
+        _title: &str,
+    ) -> Result<Vec<SongInfo>> {
+        anyhow::bail!("broken")
+    }
+    async fn search_song(&self, _keyword: &str) -> Result<Vec<SongInfo>> {
+        anyhow::bail!("broken")
+    }
+    fn unique_name(&self) -> &'static str {
+        "Broken"
+    }
+    fn init(self, _config: &str) -> Result<()> {
+        Ok(())
+    }
+    fn is_likely_songid(&self, _s: &str) -> bool {
+        false
+    }
+}
+
+impl LyricParse for Broken {
+    fn parse_lyric(&self, _store: &LyricStore) -> LyricOwned {
+        LyricOwned::None
+    }
+    fn parse_translated_lyric(&self, _store: &LyricStore) -> LyricOwned {
+        LyricOwned::None
+    }
+}
+
+#[tokio::test]
+async fn open_after_consecutive_failures() {
+    let breaker = CircuitBreaker::new(&Broken, Duration::ZERO);
+    for _ in 0..FAILURE_THRESHOLD {
+        assert!(breaker.is_available());
+        let _ = breaker.search_song("").await;
+    }
+    assert!(matches!(breaker.state(), CircuitState::Open { .. }));
+
+    // cool down is zero, so it should be half-opened immediately
+    assert!(breaker.is_available());
+    assert_eq!(breaker.state(), CircuitState::HalfOpen);
+
+    let _ = breaker.search_song("").await;
+    assert!(matches!(breaker.state(), CircuitState::Open { .. }));
+}
@@ -0,0 +1,46 @@
+use crate::cli::Cli;
+
+#[test]
+fn pass_unknown_args_to_gtk() {
+    let args = ["waylyrics", "--offline", "--gapplication-service"].map(str::to_string);
+    let (cli, rest) = Cli::parse(args);
+    assert!(cli.offline);
+    assert!(!cli.check_auth);
+    assert_eq!(rest, ["waylyrics", "--gapplication-service"]);
+}
+
+#[test]
+fn check_providers_flag() {
+    let args = ["waylyrics", "--check-providers"].map(str::to_string);
+    let (cli, rest) = Cli::parse(args);
+    assert!(cli.check_providers);
+    assert!(!cli.check_auth);
+    assert_eq!(rest, ["waylyrics"]);
+}
+
+#[test]
+fn rebuild_cache_flags() {
+    let args = ["waylyrics", "--rebuild-cache", "--dry-run"].map(str::to_string);
+    let (cli, rest) = Cli::parse(args);
+    assert!(cli.rebuild_cache);
+    assert!(cli.dry_run);
+    assert_eq!(rest, ["waylyrics"]);
+}
+
+#[test]
+fn dump_state_flag() {
+    let args = ["waylyrics", "--dump-state"].map(str::to_string);
+    let (cli, rest) = Cli::parse(args);
+    assert!(cli.dump_state);
+    assert!(!cli.rebuild_cache);
+    assert_eq!(rest, ["waylyrics"]);
+}
+
+#[test]
+fn list_players_flags() {
+    let args = ["waylyrics", "--list-players", "--json"].map(str::to_string);
+    let (cli, rest) = Cli::parse(args);
+    assert!(cli.list_players);
+    assert!(cli.json);
+    assert_eq!(rest, ["waylyrics"]);
+}
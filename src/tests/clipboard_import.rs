@@ -0,0 +1,20 @@
+use std::time::Duration;
+
+use crate::sync::actions::utils::parse_clipboard_lrc;
+
+#[test]
+fn parses_lrc_in_clipboard() {
+    let text = "[ti:Song]\n[00:12.00]second\n[00:01.50]first\n";
+    let lines = parse_clipboard_lrc(text).unwrap();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0].text, "first");
+    assert_eq!(lines[0].start_time, Duration::from_millis(1500));
+    assert_eq!(lines[1].text, "second");
+}
+
+#[test]
+fn rejects_text_without_timestamps() {
+    assert!(parse_clipboard_lrc("just some words\nfrom a web page").is_none());
+    assert!(parse_clipboard_lrc("[ar:Artist]\n[ti:Song]").is_none());
+    assert!(parse_clipboard_lrc("").is_none());
+}
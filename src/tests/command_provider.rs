@@ -0,0 +1,71 @@
+use std::time::Duration;
+
+use crate::lyric_providers::command::{expand_template, CommandProvider};
+use crate::lyric_providers::{LyricOwned, LyricParse, LyricProvider};
+
+#[test]
+#[cfg(unix)]
+fn placeholders_quoted() {
+    let command = expand_template(
+        "lyrics {title} --by {artist} {album} {duration}",
+        "Don't Stop",
+        "Queen",
+        "",
+        Some(Duration::from_millis(209_500)),
+    );
+    assert_eq!(command, r"lyrics 'Don'\''t Stop' --by 'Queen' '' '209'");
+}
+
+#[test]
+#[cfg(unix)]
+fn placeholders_in_values_not_expanded() {
+    let command = expand_template(
+        "lyrics {title} {artist}",
+        "{artist}'; touch x; '",
+        "Queen",
+        "",
+        None,
+    );
+    assert_eq!(command, r"lyrics '{artist}'\''; touch x; '\''' 'Queen'");
+}
+
+#[test]
+#[cfg(windows)]
+fn percent_not_expanded_by_cmd() {
+    let command = expand_template("lyrics {title}", "100% %PATH%", "", "", None);
+    assert_eq!(command, r#"lyrics "100"^%" "^%"PATH"^%"""#);
+}
+
+#[test]
+#[cfg(unix)]
+fn search_and_query() {
+    let provider = CommandProvider::new("printf '[00:01.00]%s %s' {artist} {duration}");
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let songs = runtime
+        .block_on(provider.search_song_with_length(
+            "",
+            &["Beatles"],
+            "Yesterday",
+            Some(Duration::from_secs(125)),
+        ))
+        .unwrap();
+    assert_eq!(songs.len(), 1);
+
+    let store = runtime
+        .block_on(provider.query_lyric(&songs[0].id))
+        .unwrap();
+    let LyricOwned::LineTimestamp(lines) = provider.parse_lyric(&store) else {
+        panic!("expected timestamped lyric");
+    };
+    assert_eq!(lines[0].text, "Beatles 125");
+    assert_eq!(lines[0].start_time, Duration::from_secs(1));
+}
+
+#[test]
+#[cfg(unix)]
+fn non_zero_exit_fails() {
+    let provider = CommandProvider::new("echo '[00:01.00]partial'; echo oops >&2; exit 1");
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let result = runtime.block_on(provider.search_song_with_length("", &[], "title", None));
+    assert!(result.is_err());
+}
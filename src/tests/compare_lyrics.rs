@@ -0,0 +1,40 @@
+use std::time::Duration;
+
+use super::line;
+use crate::app::compare_window::{timing_class, timing_diffs};
+
+#[test]
+fn diff_to_closest_line_of_each_provider() {
+    let lyric = [line(1000, "a"), line(5000, "a")];
+    let near = [line(1100, "a"), line(4000, "a")];
+    let far = [line(3000, "a")];
+    assert_eq!(
+        timing_diffs(&lyric, &[&near, &far]),
+        [
+            Some(Duration::from_millis(2000)),
+            Some(Duration::from_millis(2000))
+        ]
+    );
+    assert_eq!(
+        timing_diffs(&lyric, &[&near]),
+        [
+            Some(Duration::from_millis(100)),
+            Some(Duration::from_millis(1000))
+        ]
+    );
+    assert_eq!(timing_diffs(&lyric, &[]), [None, None]);
+}
+
+#[test]
+fn class_by_diff() {
+    assert_eq!(timing_class(None), None);
+    assert_eq!(
+        timing_class(Some(Duration::from_millis(100))),
+        Some("success")
+    );
+    assert_eq!(
+        timing_class(Some(Duration::from_millis(800))),
+        Some("warning")
+    );
+    assert_eq!(timing_class(Some(Duration::from_secs(3))), Some("error"));
+}
@@ -0,0 +1,41 @@
+use crate::config::persist::{with_config_value, with_config_values};
+
+#[test]
+fn replaced_value_keeps_comments() {
+    let config =
+        "# the interval\nplayer-sync-interval = \"2s\"\n\n# opacity\nwindow-opacity = 1.0\n";
+    assert_eq!(
+        with_config_value(config, "window-opacity", 0.6).unwrap(),
+        "# the interval\nplayer-sync-interval = \"2s\"\n\n# opacity\nwindow-opacity = 0.6\n"
+    );
+}
+
+#[test]
+fn missing_key_is_added() {
+    let config = "theme = \"default\"\n";
+    let written = with_config_value(config, "window-opacity", 0.5).unwrap();
+    assert_eq!(written, "theme = \"default\"\nwindow-opacity = 0.5\n");
+}
+
+#[test]
+fn broken_config_not_touched() {
+    assert!(with_config_value("theme = ", "window-opacity", 0.5).is_err());
+}
+
+#[test]
+fn several_values_written_at_once() {
+    let config = "# opacity\nwindow-opacity = 1.0\nclick-through = false\n";
+    let written = with_config_values(
+        config,
+        vec![
+            ("window-opacity", 0.8.into()),
+            ("click-through", true.into()),
+            ("lyric-offset-ms", 300i64.into()),
+        ],
+    )
+    .unwrap();
+    assert_eq!(
+        written,
+        "# opacity\nwindow-opacity = 0.8\nclick-through = true\nlyric-offset-ms = 300\n"
+    );
+}
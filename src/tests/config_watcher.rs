@@ -0,0 +1,18 @@
+use crate::config::{watcher::changed_keys, Config};
+
+#[test]
+fn detect_changed_keys() {
+    let old = Config::default();
+    let mut new = Config::default();
+    new.theme = "dark".into();
+    new.cache_lyrics = !old.cache_lyrics;
+    new.triggers.search_lyric = "<Control>f".into();
+
+    let old = toml::Table::try_from(&old).unwrap();
+    let new = toml::Table::try_from(&new).unwrap();
+    assert_eq!(
+        changed_keys(&old, &new),
+        ["cache-lyrics", "theme", "triggers"]
+    );
+    assert!(changed_keys(&old, &old).is_empty());
+}
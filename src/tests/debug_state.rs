@@ -0,0 +1,33 @@
+use serde_json::json;
+
+use crate::sync::debug_state::{debug_state, redact_value};
+
+#[test]
+fn redact_sensitive_keys() {
+    let mut config = json!({
+        "theme": "default",
+        "obs-password": "hunter2",
+        "providers": {
+            "genius": { "api-key": "secret", "circuit-cool-down": "60s" },
+            "musixmatch": { "api-key": "" },
+        },
+        "qqmusic": { "cookies": ["uin=1"] },
+    });
+    redact_value(&mut config);
+    assert_eq!(config["theme"], "default");
+    assert_eq!(config["obs-password"], "<redacted>");
+    assert_eq!(config["providers"]["genius"]["api-key"], "<redacted>");
+    assert_eq!(config["providers"]["genius"]["circuit-cool-down"], "60s");
+    // nothing to hide, and useful to know it's not set
+    assert_eq!(config["providers"]["musixmatch"]["api-key"], "");
+    assert_eq!(config["qqmusic"]["cookies"], "<redacted>");
+}
+
+#[test]
+fn state_without_window() {
+    let state = debug_state(None, Some(json!({ "obs-password": "hunter2" })));
+    assert_eq!(state["version"], env!("CARGO_PKG_VERSION"));
+    assert_eq!(state["config"]["obs-password"], "<redacted>");
+    assert!(state.get("player").is_none());
+    assert!(state.get("window").is_none());
+}
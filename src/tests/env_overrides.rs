@@ -0,0 +1,32 @@
+use crate::config::{Config, LYRIC_TIMEOUT_ENV, REFRESH_INTERVAL_ENV};
+
+#[test]
+fn overrides_named_fields() {
+    let mut config = Config::default();
+    let player_sync_interval_battery = config.player_sync_interval_battery.clone();
+    config.apply_env_overrides(|name| match name {
+        LYRIC_TIMEOUT_ENV => Some("150".into()),
+        REFRESH_INTERVAL_ENV => Some(" 50 ".into()),
+        _ => None,
+    });
+    assert_eq!(config.lyric_line_timeout_ms, 150);
+    assert_eq!(config.player_sync_interval, "50ms");
+    assert_eq!(
+        config.player_sync_interval_battery,
+        player_sync_interval_battery
+    );
+}
+
+#[test]
+fn invalid_overrides_ignored() {
+    let mut config = Config::default();
+    config.apply_env_overrides(|name| (name == LYRIC_TIMEOUT_ENV).then(|| "soon".into()));
+    assert_eq!(
+        config.lyric_line_timeout_ms,
+        Config::default().lyric_line_timeout_ms
+    );
+    assert_eq!(
+        config.player_sync_interval,
+        Config::default().player_sync_interval
+    );
+}
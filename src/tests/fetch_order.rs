@@ -0,0 +1,45 @@
+use crate::lyric_providers::lrclib::LRCLib;
+use crate::lyric_providers::utils::http_cache::{LyricSource, Validator};
+use crate::lyric_providers::{LyricOwned, LyricParse, LyricStore};
+use crate::sync::lyric::fetch::{pick_lyric, set_fallback_to_nosync, sort_matches};
+use crate::sync::LyricState;
+
+#[test]
+fn equal_weights_keep_provider_order() {
+    // as if the second provider answered first
+    let mut matches = vec![
+        ("b".to_owned(), 0, 1),
+        ("c".to_owned(), 1, 0),
+        ("a".to_owned(), 0, 0),
+    ];
+    sort_matches(&mut matches);
+    let ids: Vec<&str> = matches.iter().map(|(id, ..)| id.as_str()).collect();
+    assert_eq!(ids, ["a", "b", "c"]);
+}
+
+#[test]
+fn fallback_picks_plain_lyric() {
+    let state = |origin| {
+        let source = LyricSource {
+            provider: "LRCLib".to_owned(),
+            id: "1".to_owned(),
+            validator: Validator::default(),
+        };
+        let state = LyricState {
+            origin,
+            translation: LyricOwned::None,
+            source_url: None,
+        };
+        (state, source)
+    };
+    // a provider answering with only `plain_lyrics`
+    let plain = LRCLib.parse_lyric(&LyricStore::new(Some("first\nsecond".into()), None));
+    assert!(matches!(&plain, LyricOwned::NoTimestamp(lines) if lines.len() == 2));
+    let lyrics = vec![state(LyricOwned::None), state(plain)];
+
+    set_fallback_to_nosync(false);
+    assert_eq!(pick_lyric(&lyrics), None);
+    set_fallback_to_nosync(true);
+    assert_eq!(pick_lyric(&lyrics), Some(1));
+    set_fallback_to_nosync(false);
+}
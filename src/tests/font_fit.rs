@@ -0,0 +1,26 @@
+use std::cell::Cell;
+
+use crate::app::font_fit::{fit_font_size, FONT_SIZE_PRECISION};
+
+#[test]
+fn largest_size_that_fits() {
+    let size = fit_font_size(8., 32., |size| size * 10. <= 205.);
+    assert!(size <= 20.5);
+    assert!(20.5 - size <= FONT_SIZE_PRECISION);
+}
+
+#[test]
+fn bounds() {
+    assert_eq!(fit_font_size(8., 32., |_| true), 32.);
+    assert_eq!(fit_font_size(8., 32., |_| false), 8.);
+}
+
+#[test]
+fn logarithmic_steps() {
+    let steps = Cell::new(0);
+    fit_font_size(1., 1025., |size| {
+        steps.set(steps.get() + 1);
+        size < 100.
+    });
+    assert!(steps.get() <= 13, "took {} steps", steps.get());
+}
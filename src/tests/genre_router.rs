@@ -0,0 +1,79 @@
+use std::collections::BTreeMap;
+
+use crate::lyric_providers::lrclib::LRCLib;
+use crate::lyric_providers::netease::Netease;
+use crate::lyric_providers::qqmusic::QQMusic;
+use crate::lyric_providers::router::{glob_match, is_highly_rated, route_rated_with, route_with};
+use crate::lyric_providers::LyricProvider;
+
+#[test]
+fn glob_ignores_case() {
+    assert!(glob_match("classical", "Classical"));
+    assert!(glob_match("*rock*", "Progressive Rock"));
+    assert!(glob_match("j-?op", "J-Pop"));
+    assert!(glob_match("*", ""));
+    assert!(!glob_match("pop", "K-Pop"));
+    assert!(!glob_match("j-?op", "J-op"));
+}
+
+#[test]
+fn preferred_searched_first() {
+    let providers: [&dyn LyricProvider; 3] = [&Netease, &QQMusic, &LRCLib];
+    let preference = BTreeMap::from([(
+        "*classical*".to_owned(),
+        vec![LRCLib.unique_name().to_owned()],
+    )]);
+    let genres = ["Modern Classical".to_owned()];
+    assert_eq!(
+        route_with(&preference, &providers, Some(&genres)),
+        [vec![2], vec![0, 1]]
+    );
+}
+
+#[test]
+fn unchanged_without_match() {
+    let providers: [&dyn LyricProvider; 2] = [&Netease, &LRCLib];
+    let preference = BTreeMap::from([("classical".to_owned(), vec!["unknown".to_owned()])]);
+    let genres = ["Classical".to_owned()];
+    // a provider not in `lyric-search-source` is not searched at all
+    assert_eq!(
+        route_with(&preference, &providers, Some(&genres)),
+        [vec![0, 1]]
+    );
+    assert_eq!(route_with(&preference, &providers, None), [vec![0, 1]]);
+}
+
+#[test]
+fn five_stars_highly_rated() {
+    assert!(is_highly_rated(Some(1.)));
+    assert!(is_highly_rated(Some(0.8)));
+    assert!(!is_highly_rated(Some(0.6)));
+    assert!(!is_highly_rated(None));
+}
+
+#[test]
+fn rated_searched_before_genre() {
+    let providers: [&dyn LyricProvider; 3] = [&Netease, &QQMusic, &LRCLib];
+    let preference = BTreeMap::from([(
+        "*classical*".to_owned(),
+        vec![
+            LRCLib.unique_name().to_owned(),
+            QQMusic.unique_name().to_owned(),
+        ],
+    )]);
+    let rated = [QQMusic.unique_name().to_owned()];
+    let genres = ["Classical".to_owned()];
+    // a provider is only searched in the first group it is preferred in
+    assert_eq!(
+        route_rated_with(&rated, &preference, &providers, Some(&genres)),
+        [vec![1], vec![2], vec![0]]
+    );
+    assert_eq!(
+        route_rated_with(&rated, &preference, &providers, None),
+        [vec![1], vec![0, 2]]
+    );
+    assert_eq!(
+        route_rated_with(&[], &BTreeMap::new(), &providers, None),
+        [vec![0, 1, 2]]
+    );
+}
@@ -0,0 +1,68 @@
+use crate::app::gestures::{swipe_action, SwipeAction, FONT_SIZE_STEP, OFFSET_STEP_MS};
+
+#[test]
+fn slow_swipe_ignored() {
+    assert_eq!(swipe_action(100., 50.), None);
+}
+
+#[test]
+fn horizontal_swipe_shifts_offset() {
+    assert_eq!(
+        swipe_action(1000., 200.),
+        Some(SwipeAction::Offset(OFFSET_STEP_MS as i64))
+    );
+    assert_eq!(
+        swipe_action(-1000., 0.),
+        Some(SwipeAction::Offset(-OFFSET_STEP_MS as i64))
+    );
+}
+
+#[test]
+fn vertical_swipe_resizes_font() {
+    assert_eq!(
+        swipe_action(0., -1000.),
+        Some(SwipeAction::FontSize(FONT_SIZE_STEP))
+    );
+    assert_eq!(
+        swipe_action(100., 1000.),
+        Some(SwipeAction::FontSize(-FONT_SIZE_STEP))
+    );
+}
+
+#[test]
+fn velocity_scales_step() {
+    assert_eq!(
+        swipe_action(2000., 0.),
+        Some(SwipeAction::Offset(2 * OFFSET_STEP_MS as i64))
+    );
+    // clamped on both ends
+    assert_eq!(
+        swipe_action(400., 0.),
+        Some(SwipeAction::Offset(OFFSET_STEP_MS as i64 / 2))
+    );
+    assert_eq!(
+        swipe_action(100_000., 0.),
+        Some(SwipeAction::Offset(5 * OFFSET_STEP_MS as i64))
+    );
+}
+
+#[test]
+fn double_tap_actions() {
+    use crate::config::DoubleTapAction;
+
+    assert_eq!(DoubleTapAction::None.action_name(), None);
+    assert_eq!(
+        DoubleTapAction::Search.action_name(),
+        Some("app.search-lyric")
+    );
+    assert_eq!(
+        DoubleTapAction::Copy.action_name(),
+        Some("win.copy-current-line")
+    );
+    #[derive(serde::Deserialize)]
+    struct Config {
+        action: DoubleTapAction,
+    }
+    let config: Config = toml::from_str(r#"action = "reload""#).unwrap();
+    assert_eq!(config.action, DoubleTapAction::Reload);
+}
@@ -0,0 +1,46 @@
+use std::path::Path;
+
+use reqwest::header::{HeaderMap, HeaderValue, ETAG, LAST_MODIFIED};
+
+use crate::lyric_providers::utils::http_cache::{sidecar_path, LyricSource, Validator};
+
+#[test]
+fn validator_from_headers() {
+    let mut headers = HeaderMap::new();
+    assert_eq!(Validator::from_headers(&headers), None);
+
+    headers.insert(ETAG, HeaderValue::from_static("\"abc\""));
+    headers.insert(
+        LAST_MODIFIED,
+        HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+    );
+    assert_eq!(
+        Validator::from_headers(&headers),
+        Some(Validator {
+            etag: Some("\"abc\"".into()),
+            last_modified: Some("Wed, 21 Oct 2015 07:28:00 GMT".into()),
+        })
+    );
+}
+
+#[test]
+fn sidecar_next_to_cache() {
+    assert_eq!(
+        sidecar_path(Path::new("/cache/ab/cd/ef/abcdef.json")),
+        Path::new("/cache/ab/cd/ef/abcdef.http.json")
+    );
+}
+
+#[test]
+fn sidecar_roundtrip() {
+    let source = LyricSource {
+        provider: "LRCLib".into(),
+        id: "42".into(),
+        validator: Validator {
+            etag: Some("W/\"1\"".into()),
+            last_modified: None,
+        },
+    };
+    let json = serde_json::to_string(&source).unwrap();
+    assert_eq!(serde_json::from_str::<LyricSource>(&json).unwrap(), source);
+}
@@ -0,0 +1,24 @@
+use crate::config::{label_aligns, Config, LabelAlign};
+
+#[test]
+fn specific_align_wins() {
+    use LabelAlign::*;
+    assert_eq!(label_aligns(Auto, Auto, Auto, Auto), [Auto, Auto]);
+    assert_eq!(label_aligns(Left, Auto, Auto, Auto), [Left, Left]);
+    assert_eq!(label_aligns(Left, Center, Auto, Auto), [Center, Center]);
+    assert_eq!(label_aligns(Left, Center, Auto, Right), [Center, Right]);
+}
+
+#[test]
+fn parse_label_aligns() {
+    let config: Config = toml_edit::de::from_str(
+        r#"
+lyrics-align = "left"
+lyrics-align-below = "right"
+"#,
+    )
+    .unwrap();
+    assert_eq!(config.lyrics_align, LabelAlign::Left);
+    assert_eq!(config.lyrics_align_current, LabelAlign::Auto);
+    assert_eq!(config.lyrics_align_below, LabelAlign::Right);
+}
@@ -0,0 +1,60 @@
+use gtk::pango;
+
+use crate::app::fonts::{font_css, parse_font, parse_weight, with_weight};
+use crate::config::label_fonts;
+
+#[test]
+fn specific_font_wins() {
+    assert_eq!(label_fonts("", "", "", ""), ["", ""]);
+    assert_eq!(label_fonts("Sans 20", "", "", ""), ["Sans 20", "Sans 20"]);
+    assert_eq!(
+        label_fonts("Sans 20", "Sans Bold 20", "", "Serif Italic 16"),
+        ["Sans Bold 20", "Serif Italic 16"]
+    );
+}
+
+#[test]
+fn empty_font_keeps_theme() {
+    assert!(parse_font("").unwrap().is_none());
+    assert!(parse_font("  ").unwrap().is_none());
+}
+
+#[test]
+fn font_to_css() {
+    let desc = parse_font("Noto Serif Bold Italic 24").unwrap().unwrap();
+    assert_eq!(
+        font_css(&desc),
+        "font-family: \"Noto Serif\"; font-style: italic; font-weight: 700; font-size: 24pt;"
+    );
+    // size is kept from the theme
+    let desc = parse_font("Sans Bold").unwrap().unwrap();
+    assert_eq!(
+        font_css(&desc),
+        "font-family: \"Sans\"; font-style: normal; font-weight: 700;"
+    );
+}
+
+#[test]
+fn parse_weights() {
+    assert_eq!(parse_weight(" ").unwrap(), None);
+    assert_eq!(parse_weight("Bold").unwrap(), Some(pango::Weight::Bold));
+    assert_eq!(parse_weight("normal").unwrap(), Some(pango::Weight::Normal));
+    assert_eq!(parse_weight("Light").unwrap(), Some(pango::Weight::Light));
+    assert!(parse_weight("Sans Bold").is_err());
+    assert!(parse_weight("Bold 20").is_err());
+    assert!(parse_weight("Italic").is_err());
+}
+
+#[test]
+fn weight_keeps_family_and_size() {
+    let desc = with_weight(None, Some(pango::Weight::Bold)).unwrap();
+    assert_eq!(font_css(&desc), "font-weight: 700;");
+
+    let font = parse_font("Noto Serif 24").unwrap();
+    let desc = with_weight(font, Some(pango::Weight::Bold)).unwrap();
+    assert_eq!(desc.family().as_deref(), Some("Noto Serif"));
+    assert_eq!(desc.weight(), pango::Weight::Bold);
+    assert_eq!(desc.size(), 24 * pango::SCALE);
+
+    assert!(with_weight(None, None).is_none());
+}
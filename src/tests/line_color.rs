@@ -0,0 +1,16 @@
+use crate::sync::lyric::color::{cycle_color, hash_color};
+
+#[test]
+fn same_line_same_color() {
+    assert_eq!(hash_color("hello"), hash_color("hello"));
+    assert_eq!(hash_color(" hello "), hash_color("hello"));
+    assert!(hash_color("hello").starts_with("hsl("));
+}
+
+#[test]
+fn cycle_wraps_around() {
+    let palette = ["red", "green"].map(String::from);
+    assert_eq!(cycle_color(&palette, 0), Some("red"));
+    assert_eq!(cycle_color(&palette, 3), Some("green"));
+    assert_eq!(cycle_color(&[], 1), None);
+}
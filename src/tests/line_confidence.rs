@@ -0,0 +1,38 @@
+use super::line;
+use crate::lyric_providers::utils::with_confidence;
+use crate::lyric_providers::{LyricLineOwned, LyricOwned};
+use crate::sync::lyric::scroll::{
+    confidence_css_class, LOW_CONFIDENCE_CSS_CLASS, UNCERTAIN_CSS_CLASS,
+};
+
+#[test]
+fn confidence_set_in_order() {
+    let lyric = LyricOwned::LineTimestamp(vec![line(0, "a"), line(1000, "b"), line(2000, "c")]);
+    let LyricOwned::LineTimestamp(lines) = with_confidence(lyric, &[0.9, 1.5]) else {
+        panic!("not line timestamp");
+    };
+    let confidence: Vec<_> = lines.iter().map(|line| line.confidence).collect();
+    assert_eq!(confidence, [Some(0.9), Some(1.), None]);
+}
+
+#[test]
+fn cache_without_confidence() {
+    let cached = r#"{"text":"Hi","start_time":{"secs":1,"nanos":0}}"#;
+    let parsed: LyricLineOwned = serde_json::from_str(cached).unwrap();
+    assert_eq!(parsed.confidence, None);
+    assert_eq!(serde_json::to_string(&parsed).unwrap(), cached);
+}
+
+#[test]
+fn confidence_css_classes() {
+    assert_eq!(confidence_css_class(None, 0.5), None);
+    assert_eq!(confidence_css_class(Some(1.), 0.5), None);
+    assert_eq!(
+        confidence_css_class(Some(0.8), 0.5),
+        Some(UNCERTAIN_CSS_CLASS)
+    );
+    assert_eq!(
+        confidence_css_class(Some(0.3), 0.5),
+        Some(LOW_CONFIDENCE_CSS_CLASS)
+    );
+}
@@ -0,0 +1,21 @@
+use std::time::Duration;
+
+use super::line;
+use crate::app::line_indicator::{format_line_indicator, line_index};
+
+#[test]
+fn index_of_current_line() {
+    let lyric = [line(5000, "a"), line(10000, "b"), line(15000, "c")];
+    let at = |secs| line_index(&Duration::from_secs(secs), &lyric);
+    assert_eq!(at(0), (0, 3));
+    assert_eq!(at(5), (1, 3));
+    assert_eq!(at(12), (2, 3));
+    assert_eq!(at(600), (3, 3));
+    assert_eq!(line_index(&Duration::ZERO, &[]), (0, 0));
+}
+
+#[test]
+fn indicator_text() {
+    assert_eq!(format_line_indicator(12, 48), "12 / 48");
+    assert_eq!(format_line_indicator(0, 48), "0 / 48");
+}
@@ -0,0 +1,47 @@
+use std::time::Duration;
+
+use crate::lyric_providers::utils::find_next_long_lyric;
+use crate::lyric_providers::LyricLineOwned;
+
+fn lyric(lines: &[(u64, &str)]) -> Vec<LyricLineOwned> {
+    lines
+        .iter()
+        .map(|&(millis, text)| LyricLineOwned {
+            text: text.into(),
+            start_time: Duration::from_millis(millis),
+            confidence: None,
+        })
+        .collect()
+}
+
+fn text_at(lyric: &[LyricLineOwned], millis: u64, min_ms: u64) -> Option<&str> {
+    let elapsed = Duration::from_millis(millis);
+    find_next_long_lyric(&elapsed, lyric, Duration::from_millis(min_ms))
+        .map(|line| line.text.as_str())
+}
+
+#[test]
+fn short_line_keeps_previous() {
+    let lyric = lyric(&[(0, "first"), (2000, "Oh"), (2150, "second")]);
+    assert_eq!(text_at(&lyric, 2100, 200), Some("first"));
+    assert_eq!(text_at(&lyric, 2200, 200), Some("second"));
+}
+
+#[test]
+fn zero_shows_all() {
+    let lyric = lyric(&[(0, "first"), (2000, "Oh"), (2150, "second")]);
+    assert_eq!(text_at(&lyric, 2100, 0), Some("Oh"));
+}
+
+#[test]
+fn last_line_never_skipped() {
+    let lyric = lyric(&[(0, "first"), (2000, "Yeah")]);
+    assert_eq!(text_at(&lyric, 2010, 5000), Some("Yeah"));
+}
+
+#[test]
+fn short_first_line_shows_nothing() {
+    let lyric = lyric(&[(0, "Oh"), (100, "first")]);
+    assert_eq!(text_at(&lyric, 50, 200), None);
+    assert_eq!(text_at(&lyric, 150, 200), Some("first"));
+}
@@ -0,0 +1,19 @@
+use std::time::Duration;
+
+use super::line;
+use crate::lyric_providers::utils::line_progress;
+
+#[test]
+fn progress_within_line() {
+    let lyric = [line(2000, "a"), line(6000, "b")];
+    assert_eq!(line_progress(&Duration::from_secs(1), &lyric), None);
+    assert_eq!(line_progress(&Duration::from_secs(2), &lyric), Some(0.));
+    assert_eq!(line_progress(&Duration::from_secs(3), &lyric), Some(0.25));
+}
+
+#[test]
+fn last_line_stays_full() {
+    let lyric = [line(0, "a"), line(4000, "b")];
+    assert_eq!(line_progress(&Duration::from_secs(4), &lyric), Some(1.));
+    assert_eq!(line_progress(&Duration::from_secs(60), &lyric), Some(1.));
+}
@@ -0,0 +1,31 @@
+use crate::lyric_providers::utils::truncate_line;
+
+#[test]
+fn short_lines_kept() {
+    assert_eq!(truncate_line("hello world", 0), "hello world");
+    assert_eq!(truncate_line("hello world", 11), "hello world");
+}
+
+#[test]
+fn cut_at_word() {
+    assert_eq!(
+        truncate_line("hello wonderful world", 18),
+        "hello wonderful…"
+    );
+    assert_eq!(
+        truncate_line("hello wonderful world", 16),
+        "hello wonderful…"
+    );
+}
+
+#[test]
+fn cut_mid_word_if_too_short() {
+    assert_eq!(truncate_line("hello wonderful world", 12), "hello wonde…");
+    assert_eq!(truncate_line("a supercalifragilistic", 10), "a superca…");
+    assert_eq!(truncate_line("我们一起唱这首长长的歌", 6), "我们一起唱…");
+}
+
+#[test]
+fn cut_between_graphemes() {
+    assert_eq!(truncate_line("e\u{301}e\u{301}e\u{301}", 2), "e\u{301}…");
+}
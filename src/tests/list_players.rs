@@ -0,0 +1,35 @@
+use crate::sync::{format_player_summaries, PlayerSummary};
+
+fn players() -> Vec<PlayerSummary> {
+    vec![
+        PlayerSummary {
+            identity: "Spotify".into(),
+            bus_name: "org.mpris.MediaPlayer2.spotify".into(),
+            status: "Playing".into(),
+        },
+        PlayerSummary {
+            identity: "mpv".into(),
+            bus_name: "org.mpris.MediaPlayer2.mpv".into(),
+            status: "Paused".into(),
+        },
+    ]
+}
+
+#[test]
+fn human_readable() {
+    assert_eq!(
+        format_player_summaries(&players(), false),
+        "Spotify\torg.mpris.MediaPlayer2.spotify\tPlaying\nmpv\torg.mpris.MediaPlayer2.mpv\tPaused"
+    );
+    assert_eq!(format_player_summaries(&[], false), "no player found");
+}
+
+#[test]
+fn json() {
+    let json: serde_json::Value =
+        serde_json::from_str(&format_player_summaries(&players(), true)).unwrap();
+    assert_eq!(json[1]["identity"], "mpv");
+    assert_eq!(json[0]["bus_name"], "org.mpris.MediaPlayer2.spotify");
+    assert_eq!(json[0]["status"], "Playing");
+    assert_eq!(format_player_summaries(&[], true), "[]");
+}
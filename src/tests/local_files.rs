@@ -0,0 +1,111 @@
+use std::path::PathBuf;
+
+use crate::lyric_providers::local_files::{find_lyric_file, fuzzy_key};
+
+fn library(name: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!(
+        "waylyrics-local-files-{name}-{}",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&root);
+    for file in [
+        "The Beatles/Abbey Road/Something.lrc",
+        "AC_DC/Back in Black/Hells Bells.lrc",
+        "Queen/A Night at the Opera/Bohemian Rhapsody.LRC",
+    ] {
+        let path = root.join(file);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, "[00:01.00]line").unwrap();
+    }
+    root
+}
+
+#[test]
+fn fuzzy_key_ignores_case_and_symbols() {
+    assert_eq!(fuzzy_key("AC/DC"), "acdc");
+    assert_eq!(fuzzy_key("Don't Stop Me Now!"), "dontstopmenow");
+}
+
+#[test]
+fn exact_and_fuzzy_match() {
+    let root = library("match");
+    let artists = |name: &str| vec![name.to_owned()];
+
+    assert_eq!(
+        find_lyric_file(
+            &root,
+            &artists("The Beatles"),
+            Some("Abbey Road"),
+            "Something"
+        ),
+        Some(root.join("The Beatles/Abbey Road/Something.lrc"))
+    );
+    assert_eq!(
+        find_lyric_file(
+            &root,
+            &artists("AC/DC"),
+            Some("Back In Black"),
+            "Hell's Bells"
+        ),
+        Some(root.join("AC_DC/Back in Black/Hells Bells.lrc"))
+    );
+    // album unknown, and extension in other case
+    assert_eq!(
+        find_lyric_file(&root, &artists("queen"), None, "Bohemian Rhapsody"),
+        Some(root.join("Queen/A Night at the Opera/Bohemian Rhapsody.LRC"))
+    );
+    assert_eq!(
+        find_lyric_file(&root, &artists("The Beatles"), None, "Yesterday"),
+        None
+    );
+    assert_eq!(find_lyric_file(&root, &[], None, "Something"), None);
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn flat_directory_fallback() {
+    let root = library("flat");
+    std::fs::write(root.join("Daft Punk - One More Time.lrc"), "[00:01.00]line").unwrap();
+    let artists = vec!["Daft Punk".to_owned()];
+
+    assert_eq!(
+        find_lyric_file(&root, &artists, Some("Discovery"), "One More Time"),
+        Some(root.join("Daft Punk - One More Time.lrc"))
+    );
+    assert_eq!(
+        find_lyric_file(&root, &["daft punk".to_owned()], None, "One more time!"),
+        Some(root.join("Daft Punk - One More Time.lrc"))
+    );
+    // the library layout is preferred
+    std::fs::write(root.join("The Beatles - Something.lrc"), "[00:01.00]line").unwrap();
+    assert_eq!(
+        find_lyric_file(&root, &["The Beatles".to_owned()], None, "Something"),
+        Some(root.join("The Beatles/Abbey Road/Something.lrc"))
+    );
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn names_stay_in_library() {
+    let root = library("traversal");
+    std::fs::write(root.join("The Beatles/Yesterday.lrc"), "[00:01.00]line").unwrap();
+
+    // would be `Queen/../The Beatles/Yesterday.lrc`
+    assert_eq!(
+        find_lyric_file(
+            &root.join("Queen"),
+            &["..".to_owned()],
+            Some("The Beatles"),
+            "Yesterday"
+        ),
+        None
+    );
+    assert_eq!(
+        find_lyric_file(&root, &["The Beatles".to_owned()], Some(".."), "Something"),
+        Some(root.join("The Beatles/Abbey Road/Something.lrc"))
+    );
+
+    let _ = std::fs::remove_dir_all(&root);
+}
@@ -0,0 +1,36 @@
+use std::io::Write;
+
+use tracing_subscriber::fmt::MakeWriter;
+
+use crate::log::RotatingFile;
+
+#[test]
+fn rotate_when_exceeding_max_size() {
+    let path = std::env::temp_dir().join(format!("waylyrics-log-{}.log", std::process::id()));
+    let mut rotated = path.as_os_str().to_owned();
+    rotated.push(".1");
+    let _ = std::fs::remove_file(&path);
+    let _ = std::fs::remove_file(&rotated);
+
+    let file = RotatingFile::open(&path, 8).unwrap();
+    file.make_writer().write_all(b"first\n").unwrap();
+    file.make_writer().write_all(b"second\n").unwrap();
+
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "second\n");
+    assert_eq!(std::fs::read_to_string(&rotated).unwrap(), "first\n");
+    let _ = std::fs::remove_file(&path);
+    let _ = std::fs::remove_file(&rotated);
+}
+
+#[test]
+fn append_to_existing_file() {
+    let path =
+        std::env::temp_dir().join(format!("waylyrics-log-append-{}.log", std::process::id()));
+    std::fs::write(&path, "old\n").unwrap();
+
+    let file = RotatingFile::open(&path, u64::MAX).unwrap();
+    file.make_writer().write_all(b"new\n").unwrap();
+
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "old\nnew\n");
+    let _ = std::fs::remove_file(&path);
+}
@@ -0,0 +1,83 @@
+use std::time::Duration;
+
+use crate::lyric_providers::lua::LuaProvider;
+use crate::lyric_providers::{LyricOwned, LyricParse, LyricProvider};
+
+const SCRIPT: &str = r#"
+function search(title, artist, album, duration_ms)
+    if title ~= "Yesterday" then
+        return nil
+    end
+    return {
+        origin = "[00:01.00]" .. artist .. " " .. tostring(duration_ms),
+        confidence = { 0.25 },
+    }
+end
+"#;
+
+#[test]
+fn search_and_query() {
+    let path = std::env::temp_dir().join(format!("waylyrics-{}.lua", std::process::id()));
+    std::fs::write(&path, SCRIPT).unwrap();
+    let provider = LuaProvider::load(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+    assert!(provider.unique_name().starts_with("lua:waylyrics-"));
+
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let songs = runtime
+        .block_on(provider.search_song_with_length(
+            "",
+            &["Beatles"],
+            "Yesterday",
+            Some(Duration::from_secs(1)),
+        ))
+        .unwrap();
+    assert_eq!(songs.len(), 1);
+
+    let store = runtime
+        .block_on(provider.query_lyric(&songs[0].id))
+        .unwrap();
+    let LyricOwned::LineTimestamp(lines) = provider.parse_lyric(&store) else {
+        panic!("lyric should have timestamps");
+    };
+    assert_eq!(lines[0].text, "Beatles 1000");
+    assert_eq!(lines[0].confidence, Some(0.25));
+    assert!(provider.parse_translated_lyric(&store).is_none());
+
+    assert!(runtime
+        .block_on(provider.search_song_detailed("", &[], "Tomorrow"))
+        .is_err());
+}
+
+#[test]
+fn script_without_search_is_rejected() {
+    let path = std::env::temp_dir().join(format!("waylyrics-empty-{}.lua", std::process::id()));
+    std::fs::write(&path, "x = 1").unwrap();
+    assert!(LuaProvider::load(&path).is_err());
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn plain_text_origin_has_no_timestamp() {
+    let script = r#"
+function search(title, artist, album, duration_ms)
+    return { origin = "first line\nsecond line" }
+end
+"#;
+    let path = std::env::temp_dir().join(format!("waylyrics-plain-{}.lua", std::process::id()));
+    std::fs::write(&path, script).unwrap();
+    let provider = LuaProvider::load(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let songs = runtime
+        .block_on(provider.search_song_with_length("", &[], "Yesterday", None))
+        .unwrap();
+    let store = runtime
+        .block_on(provider.query_lyric(&songs[0].id))
+        .unwrap();
+    let LyricOwned::NoTimestamp(lines) = provider.parse_lyric(&store) else {
+        panic!("plain text should have no timestamp");
+    };
+    assert_eq!(lines, ["first line", "second line"]);
+}
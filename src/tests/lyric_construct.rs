@@ -18,3 +18,33 @@ fn make_lrc_line() {
         "[02:03.570]遗忘我".to_string()
     );
 }
+
+#[test]
+fn lyric_to_lrc_with_translation() {
+    use std::time::Duration;
+
+    use crate::lyric_providers::LyricLineOwned;
+    use crate::sync::actions::utils::lyric_to_lrc;
+
+    let line = |secs, text: &str| LyricLineOwned {
+        text: text.into(),
+        start_time: Duration::from_secs(secs),
+        confidence: None,
+    };
+    let origin = [line(1, "你好"), line(2, "世界")];
+    let translation = [line(1, "hello"), line(2, ""), line(3, "world")];
+
+    let lrc = lyric_to_lrc(None, -100, &origin, &translation);
+    let lines: Vec<_> = lrc.lines().skip_while(|line| !line.is_empty()).collect();
+    assert_eq!(
+        lines,
+        [
+            "",
+            "[00:01.000]你好",
+            "[00:01.000]hello",
+            "[00:02.000]世界",
+            "[00:03.000]world"
+        ]
+    );
+    assert!(lrc.contains("[offset:-100]\n"));
+}
@@ -0,0 +1,66 @@
+use std::time::Duration;
+
+use crate::config::LyricFilter;
+use crate::lyric_providers::utils::lyric_filter::{build_lyric_filters, filter_lyric_with};
+use crate::lyric_providers::{LyricLineOwned, LyricOwned};
+
+fn lines(texts: &[&str]) -> LyricOwned {
+    LyricOwned::LineTimestamp(
+        texts
+            .iter()
+            .enumerate()
+            .map(|(i, text)| LyricLineOwned {
+                text: text.to_string(),
+                start_time: Duration::from_secs(i as u64),
+                confidence: None,
+            })
+            .collect(),
+    )
+}
+
+fn filter(pattern: &str, replacement: &str, drop_line: bool) -> LyricFilter {
+    LyricFilter {
+        pattern: pattern.into(),
+        replacement: replacement.into(),
+        drop_line,
+    }
+}
+
+#[test]
+fn replace_with_capture_groups() {
+    let filters = build_lyric_filters(&[
+        filter(r"\s*\(www\.[^)]*\)", "", false),
+        filter(r"^(\w+): (.*)$", "$2 ($1)", false),
+    ])
+    .unwrap();
+    assert_eq!(
+        filter_lyric_with(lines(&["hello (www.example.com)", "Alice: hi"]), &filters),
+        lines(&["hello", "hi (Alice)"])
+    );
+}
+
+#[test]
+fn drop_matching_lines() {
+    let filters = build_lyric_filters(&[filter("^Lyrics provided by", "", true)]).unwrap();
+    let LyricOwned::LineTimestamp(filtered) = filter_lyric_with(
+        lines(&["Lyrics provided by XXX", "first", "second"]),
+        &filters,
+    ) else {
+        panic!("lines are kept timestamped");
+    };
+    let texts: Vec<_> = filtered.iter().map(|line| line.text.as_str()).collect();
+    assert_eq!(texts, ["first", "second"]);
+    assert_eq!(filtered[0].start_time, Duration::from_secs(1));
+
+    assert_eq!(
+        filter_lyric_with(LyricOwned::NoTimestamp(vec!["first".into()]), &filters),
+        LyricOwned::NoTimestamp(vec!["first".into()])
+    );
+}
+
+#[test]
+fn invalid_filter_pattern() {
+    let err = build_lyric_filters(&[filter("ok", "", false), filter("(", "", false)]).unwrap_err();
+    assert_eq!(err.index, 1);
+    assert!(err.to_string().contains("lyric-filters[1]"));
+}
@@ -0,0 +1,42 @@
+use std::time::Duration;
+
+use super::line;
+use crate::lyric_providers::utils::find_gap;
+
+const THRESHOLD: Duration = Duration::from_secs(5);
+
+#[test]
+fn gap_after_blank_lines() {
+    let lyric = [
+        line(0, "a"),
+        line(2000, ""),
+        line(3000, " "),
+        line(10000, "b"),
+    ];
+    assert_eq!(find_gap(&Duration::from_secs(1), &lyric, THRESHOLD), None);
+    assert_eq!(
+        find_gap(&Duration::from_secs(4), &lyric, THRESHOLD),
+        Some(Duration::from_secs(6))
+    );
+}
+
+#[test]
+fn gap_before_first_line() {
+    let lyric = [line(8000, "a")];
+    assert_eq!(
+        find_gap(&Duration::from_secs(1), &lyric, THRESHOLD),
+        Some(Duration::from_secs(7))
+    );
+}
+
+#[test]
+fn short_or_trailing_gap_is_ignored() {
+    let lyric = [
+        line(0, "a"),
+        line(2000, ""),
+        line(5000, "b"),
+        line(6000, ""),
+    ];
+    assert_eq!(find_gap(&Duration::from_secs(3), &lyric, THRESHOLD), None);
+    assert_eq!(find_gap(&Duration::from_secs(60), &lyric, THRESHOLD), None);
+}
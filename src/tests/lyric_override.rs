@@ -0,0 +1,38 @@
+use std::time::Duration;
+
+use crate::lyric_providers::LyricLineOwned;
+use crate::sync::lyric::scroll::override_lines;
+
+fn lyric(lines: &[(u64, &str)]) -> Vec<LyricLineOwned> {
+    lines
+        .iter()
+        .map(|&(secs, text)| LyricLineOwned {
+            text: text.into(),
+            start_time: Duration::from_secs(secs),
+            confidence: None,
+        })
+        .collect()
+}
+
+#[test]
+fn overridden_line_comes_with_its_translation() {
+    let origin = lyric(&[(0, "one"), (5, "two"), (10, "three")]);
+    let translation = lyric(&[(0, "eins"), (5, "zwei"), (10, "drei")]);
+    let (translated, line) = override_lines(&origin, Some(&translation), 1).unwrap();
+    assert_eq!(line.unwrap().text, "two");
+    assert_eq!(translated.unwrap().text, "zwei");
+}
+
+#[test]
+fn override_without_translation() {
+    let origin = lyric(&[(0, "one"), (5, "two")]);
+    let (translated, line) = override_lines(&origin, None, 0).unwrap();
+    assert_eq!(line.unwrap().text, "one");
+    assert_eq!(translated, None);
+}
+
+#[test]
+fn override_out_of_range_is_ignored() {
+    let origin = lyric(&[(0, "one")]);
+    assert_eq!(override_lines(&origin, None, 3), None);
+}
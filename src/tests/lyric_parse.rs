@@ -21,6 +21,7 @@ mod lrc {
             Some(LyricLineOwned {
                 text: "Hi friend...".into(),
                 start_time: Duration::from_millis(1000 + 50),
+                confidence: None,
             })
         );
         assert_eq!(
@@ -28,6 +29,7 @@ mod lrc {
             Some(LyricLineOwned {
                 text: "Can you hear me?...".into(),
                 start_time: Duration::from_millis(640 + (45 * 60 + 5) * 1000),
+                confidence: None,
             })
         );
 
@@ -47,6 +49,7 @@ mod lrc {
             Some(LyricLineOwned {
                 text: "Hi friend...".into(),
                 start_time: Duration::from_millis(1000 + 50),
+                confidence: None,
             })
         );
         assert_eq!(
@@ -54,6 +57,7 @@ mod lrc {
             Some(LyricLineOwned {
                 text: "Can you hear me?...".into(),
                 start_time: Duration::from_millis(640 + (45 * 60 + 5) * 1000),
+                confidence: None,
             })
         );
 
@@ -72,6 +76,7 @@ mod lrc {
             Some(LyricLineOwned {
                 text: "Hi friend...".into(),
                 start_time: Duration::from_millis(1000 + 50),
+                confidence: None,
             })
         );
         Ok(())
@@ -91,6 +96,7 @@ Author: poly000
             Some(LyricLineOwned {
                 text: "Hi friend...".into(),
                 start_time: Duration::from_millis(1000 + 50),
+                confidence: None,
             })
         );
         Ok(())
@@ -118,6 +124,7 @@ Author: poly000
             Some(LyricLineOwned {
                 text: "Hi friend...".into(),
                 start_time: Duration::from_millis(1014),
+                confidence: None,
             })
         );
 
@@ -126,6 +133,7 @@ Author: poly000
             Some(LyricLineOwned {
                 text: "Hi friend...".into(),
                 start_time: Duration::from_millis(2062),
+                confidence: None,
             })
         );
 
@@ -145,6 +153,7 @@ Author: poly000
             Some(LyricLineOwned {
                 text: "Hi friend...".into(),
                 start_time: Duration::from_millis(1014),
+                confidence: None,
             })
         );
 
@@ -153,6 +162,7 @@ Author: poly000
             Some(LyricLineOwned {
                 text: "".into(),
                 start_time: Duration::from_millis(2062),
+                confidence: None,
             })
         );
 
@@ -175,7 +185,8 @@ Author: poly000
             tlyric,
             vec![LyricLineOwned {
                 text: "请不要说“你很懒”".into(),
-                start_time: Duration::from_millis(1770)
+                start_time: Duration::from_millis(1770),
+                confidence: None,
             },]
         );
         assert_eq!(
@@ -183,11 +194,13 @@ Author: poly000
             vec![
                 LyricLineOwned {
                     text: "Please don't say \"You are lazy\"".into(),
-                    start_time: Duration::from_millis(1770)
+                    start_time: Duration::from_millis(1770),
+                    confidence: None,
                 },
                 LyricLineOwned {
                     text: "だって本当はcrazy".into(),
-                    start_time: Duration::from_millis(4380)
+                    start_time: Duration::from_millis(4380),
+                    confidence: None,
                 }
             ]
         );
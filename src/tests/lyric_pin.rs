@@ -0,0 +1,119 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use super::temp_path;
+use crate::lyric_providers::{LyricLineOwned, LyricOwned};
+use crate::sync::lyric::cache::{
+    is_lyric_pinned, read_cached_offset, set_lyric_pinned, swap_cached_lyric_roles,
+    write_lyric_cache_with_offset,
+};
+use crate::sync::LyricState;
+
+fn lyric() -> LyricState {
+    let line = |text: &str| {
+        LyricOwned::LineTimestamp(vec![LyricLineOwned {
+            text: text.into(),
+            start_time: Duration::from_secs(1),
+            confidence: None,
+        }])
+    };
+    LyricState {
+        origin: line("origin"),
+        translation: line("translation"),
+        source_url: None,
+    }
+}
+
+fn read_cache(path: &PathBuf) -> serde_json::Value {
+    serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+}
+
+#[test]
+fn pin_and_unpin() {
+    let path = temp_path("pin-roundtrip.json");
+    assert!(write_lyric_cache_with_offset(&path, &lyric(), None));
+    assert!(!is_lyric_pinned(&path));
+    assert!(read_cache(&path).get("pinned").is_none());
+
+    assert!(set_lyric_pinned(&path, true));
+    assert!(is_lyric_pinned(&path));
+    assert_eq!(read_cache(&path)["pinned"], true);
+
+    assert!(set_lyric_pinned(&path, false));
+    assert!(!is_lyric_pinned(&path));
+    assert!(read_cache(&path).get("pinned").is_none());
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn rewriting_cache_keeps_pin() {
+    let path = temp_path("pin-rewrite.json");
+    assert!(write_lyric_cache_with_offset(&path, &lyric(), None));
+    assert!(set_lyric_pinned(&path, true));
+
+    assert!(write_lyric_cache_with_offset(&path, &lyric(), Some(300)));
+    assert!(is_lyric_pinned(&path));
+    assert_eq!(read_cache(&path)["offset"], 300);
+
+    assert!(swap_cached_lyric_roles(&path));
+    assert!(is_lyric_pinned(&path));
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn zero_offset_is_kept() {
+    let path = temp_path("pin-zero-offset.json");
+    assert!(write_lyric_cache_with_offset(&path, &lyric(), None));
+    assert!(read_cache(&path).get("offset").is_none());
+    assert_eq!(read_cached_offset(&path), None);
+
+    assert!(write_lyric_cache_with_offset(&path, &lyric(), Some(0)));
+    assert_eq!(read_cached_offset(&path), Some(0));
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn pin_missing_cache() {
+    let path = temp_path("pin-missing.json");
+    assert!(!set_lyric_pinned(&path, true));
+    assert!(!is_lyric_pinned(&path));
+}
+
+#[test]
+fn rebuild_keeps_pinned() {
+    use crate::lyric_providers::utils::http_cache::LyricSource;
+    use crate::sync::lyric::rebuild::{refresh_entry, Refresh};
+
+    let path = temp_path("pin-rebuild.json");
+    assert!(write_lyric_cache_with_offset(&path, &lyric(), None));
+    assert!(set_lyric_pinned(&path, true));
+    let cached = std::fs::read_to_string(&path).unwrap();
+
+    let source = LyricSource {
+        provider: "LRCLib".into(),
+        id: "42".into(),
+        validator: Default::default(),
+    };
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let refreshed = runtime.block_on(refresh_entry(&path, &Refresh::Query(source), 0));
+    assert!(refreshed.is_err());
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), cached);
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn auto_offset_skips_pinned() {
+    use crate::sync::lyric::auto_offset::is_offset_detectable;
+
+    let path = temp_path("pin-auto-offset.json");
+    assert!(write_lyric_cache_with_offset(&path, &lyric(), None));
+    assert!(is_offset_detectable(&path));
+    assert!(set_lyric_pinned(&path, true));
+    assert!(!is_offset_detectable(&path));
+
+    let _ = std::fs::remove_file(&path);
+}
@@ -0,0 +1,65 @@
+use std::time::Duration;
+
+use crate::lyric_providers::utils::find_next_lyric;
+use crate::lyric_providers::LyricLineOwned;
+use crate::sync::lyric::scroll::{context_opacity, shifted_elapsed};
+
+#[test]
+fn shifted_both_ways() {
+    let elapsed = Duration::from_millis(1000);
+    assert_eq!(shifted_elapsed(elapsed, 0), elapsed);
+    assert_eq!(shifted_elapsed(elapsed, 500), Duration::from_millis(1500));
+    assert_eq!(shifted_elapsed(elapsed, -300), Duration::from_millis(700));
+    assert_eq!(shifted_elapsed(elapsed, -3000), Duration::ZERO);
+}
+
+#[test]
+fn next_line_shown_early() {
+    let lyric = [0, 2000]
+        .map(|millis| LyricLineOwned {
+            text: millis.to_string(),
+            start_time: Duration::from_millis(millis),
+            confidence: None,
+        })
+        .to_vec();
+    let elapsed = Duration::from_millis(1600);
+    assert_eq!(find_next_lyric(&elapsed, &lyric).unwrap().text, "0");
+    let early = shifted_elapsed(elapsed, 500);
+    assert_eq!(find_next_lyric(&early, &lyric).unwrap().text, "2000");
+}
+
+#[test]
+fn only_shifted_labels_dimmed() {
+    assert_eq!(context_opacity(0.5, 0), 1.);
+    assert_eq!(context_opacity(0.5, 500), 0.5);
+    assert_eq!(context_opacity(0.5, -300), 0.5);
+    assert_eq!(context_opacity(1.5, 500), 1.);
+    assert_eq!(context_opacity(-1., 500), 0.);
+}
+
+#[test]
+fn labels_showing_translation() {
+    use crate::config::LyricDisplayMode;
+    use crate::sync::lyric::scroll::translation_labels;
+
+    assert_eq!(
+        translation_labels(LyricDisplayMode::ShowBoth, [true, true]),
+        [true, false]
+    );
+    assert_eq!(
+        translation_labels(LyricDisplayMode::ShowBoth, [false, false]),
+        [false, false]
+    );
+    assert_eq!(
+        translation_labels(LyricDisplayMode::ShowBothRev, [true, true]),
+        [false, true]
+    );
+    assert_eq!(
+        translation_labels(LyricDisplayMode::PreferTranslation, [true, false]),
+        [true, false]
+    );
+    assert_eq!(
+        translation_labels(LyricDisplayMode::Origin, [true, true]),
+        [false, false]
+    );
+}
@@ -0,0 +1,57 @@
+use std::time::Duration;
+
+use crate::metrics::{FetchStatus, Metrics};
+
+#[test]
+fn empty_metrics_have_counters() {
+    let rendered = Metrics::default().render();
+    assert!(rendered.contains("# TYPE waylyrics_fetch_total counter\n"));
+    assert!(rendered.contains("# TYPE waylyrics_fetch_duration_seconds histogram\n"));
+    assert!(rendered.contains("\nwaylyrics_cache_hit_total 0\n"));
+    assert!(rendered.contains("\nwaylyrics_cache_miss_total 0\n"));
+    assert!(rendered.contains("\nwaylyrics_track_changes_total 0\n"));
+}
+
+#[test]
+fn fetch_counted_by_provider_and_status() {
+    let mut metrics = Metrics::default();
+    metrics.record_fetch("LRCLib", FetchStatus::Ok, Duration::from_millis(30));
+    metrics.record_fetch("LRCLib", FetchStatus::Ok, Duration::from_millis(300));
+    metrics.record_fetch("netease", FetchStatus::Error, Duration::from_secs(20));
+    metrics.record_cache_hit();
+    metrics.record_track_change();
+
+    let rendered = metrics.render();
+    assert!(rendered.contains("waylyrics_fetch_total{provider=\"LRCLib\",status=\"ok\"} 2\n"));
+    assert!(rendered.contains("waylyrics_fetch_total{provider=\"netease\",status=\"error\"} 1\n"));
+    assert!(rendered.contains("\nwaylyrics_cache_hit_total 1\n"));
+    assert!(rendered.contains("\nwaylyrics_track_changes_total 1\n"));
+}
+
+#[test]
+fn histogram_buckets_are_cumulative() {
+    let mut metrics = Metrics::default();
+    metrics.record_fetch("LRCLib", FetchStatus::Ok, Duration::from_millis(30));
+    metrics.record_fetch("LRCLib", FetchStatus::NoResult, Duration::from_millis(300));
+    metrics.record_fetch("LRCLib", FetchStatus::Ok, Duration::from_secs(20));
+
+    let rendered = metrics.render();
+    let bucket = |le: &str| {
+        format!("waylyrics_fetch_duration_seconds_bucket{{provider=\"LRCLib\",le=\"{le}\"}} ")
+    };
+    assert!(rendered.contains(&format!("{}0\n", bucket("0.025"))));
+    assert!(rendered.contains(&format!("{}1\n", bucket("0.05"))));
+    assert!(rendered.contains(&format!("{}2\n", bucket("0.5"))));
+    assert!(rendered.contains(&format!("{}2\n", bucket("10"))));
+    assert!(rendered.contains(&format!("{}3\n", bucket("+Inf"))));
+    assert!(rendered.contains("waylyrics_fetch_duration_seconds_count{provider=\"LRCLib\"} 3\n"));
+}
+
+#[test]
+fn label_value_escaped() {
+    let mut metrics = Metrics::default();
+    metrics.record_fetch("a\"b\\c", FetchStatus::Ok, Duration::ZERO);
+    assert!(metrics
+        .render()
+        .contains(r#"waylyrics_fetch_total{provider="a\"b\\c",status="ok"} 1"#));
+}
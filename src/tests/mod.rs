@@ -1,7 +1,106 @@
+#[cfg(feature = "acoustid")]
+mod acoustid;
+mod artists;
+#[cfg(feature = "import-ass")]
+mod ass_import;
+mod auto_offset;
+mod bpm_pulse;
+mod cache_key;
+mod cache_signature;
+mod cancel_token;
+mod check_providers;
+mod circuit_breaker;
+mod cli;
+#[cfg(feature = "import-lyric")]
+mod clipboard_import;
+mod command_provider;
+mod compare_lyrics;
+mod config_persist;
+mod config_watcher;
+mod debug_state;
+mod env_overrides;
+mod fetch_order;
+mod font_fit;
+mod genre_router;
+mod gestures;
+mod http_cache;
 mod is_likely_songid;
+mod label_align;
+mod label_fonts;
+mod line_color;
+mod line_confidence;
+mod line_indicator;
+mod line_min_duration;
+mod line_progress;
+mod line_truncate;
+mod list_players;
+mod local_files;
+mod log_file;
 mod lrclib_lyric;
+#[cfg(feature = "lua-provider")]
+mod lua_provider;
 mod lyric_construct;
+mod lyric_filter;
+mod lyric_gap;
+mod lyric_override;
 mod lyric_parse;
 mod lyric_path;
+mod lyric_pin;
+mod lyric_spacing;
+mod metrics;
+mod monitor;
+mod mpris_native;
 mod netease_lyric;
+mod netease_playlist;
+mod no_timestamp_scroll;
+mod normalise;
+#[cfg(feature = "obs-integration")]
+mod obs;
+mod per_player;
+#[cfg(feature = "action-event")]
+mod play_event;
+mod play_history;
+mod position;
+mod power;
+mod provider_group;
 mod qqmusic_init;
+mod ranking;
+mod rebuild_cache;
+mod redact;
+mod repeat_animation;
+mod retry;
+#[cfg(feature = "transliterate-japanese")]
+mod romaji;
+mod search_history;
+mod shadow;
+mod snap;
+mod source_url;
+#[cfg(feature = "import-translation-from-srt")]
+mod srt_import;
+#[cfg(feature = "subsonic-provider")]
+mod subsonic_provider;
+mod swap_lyric_roles;
+mod track_id;
+mod track_info;
+mod track_loop;
+mod track_year;
+mod ttml;
+mod vertical_offset;
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::lyric_providers::LyricLineOwned;
+
+fn line(millis: u64, text: &str) -> LyricLineOwned {
+    LyricLineOwned {
+        text: text.into(),
+        start_time: Duration::from_millis(millis),
+        confidence: None,
+    }
+}
+
+/// `name` in the temp directory, unique to this test run
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("waylyrics-{}-{name}", std::process::id()))
+}
@@ -0,0 +1,31 @@
+use crate::app::monitor::{centered_in, monitor_or_fallback};
+use crate::app::snap::Rect;
+
+#[test]
+fn connected_monitor_is_used() {
+    assert_eq!(monitor_or_fallback(1, 2), Some(1));
+    assert_eq!(monitor_or_fallback(0, 1), Some(0));
+}
+
+#[test]
+fn unplugged_monitor_falls_back_to_first() {
+    assert_eq!(monitor_or_fallback(2, 2), Some(0));
+    assert_eq!(monitor_or_fallback(5, 0), None);
+}
+
+#[test]
+fn centered_on_second_monitor() {
+    let window = Rect {
+        x: 100,
+        y: 100,
+        width: 400,
+        height: 100,
+    };
+    let monitor = Rect {
+        x: 1920,
+        y: 0,
+        width: 2560,
+        height: 1440,
+    };
+    assert_eq!(centered_in(window, monitor), (1920 + 1080, 670));
+}
@@ -0,0 +1,36 @@
+use std::time::Duration;
+
+use crate::lyric_providers::mpris_native::parse_as_text;
+use crate::lyric_providers::{LyricLineOwned, LyricOwned};
+
+#[test]
+fn parse_lrc_as_text() {
+    let text = "[ar:Someone]\n[00:01.00]first\n[00:02.50]second\n";
+    assert_eq!(
+        parse_as_text(text),
+        Some(LyricOwned::LineTimestamp(vec![
+            LyricLineOwned {
+                text: "first".into(),
+                start_time: Duration::from_secs(1),
+                confidence: None,
+            },
+            LyricLineOwned {
+                text: "second".into(),
+                start_time: Duration::from_millis(2500),
+                confidence: None,
+            },
+        ]))
+    );
+}
+
+#[test]
+fn plain_text_has_no_timestamp() {
+    assert_eq!(
+        parse_as_text("first line\nsecond line"),
+        Some(LyricOwned::NoTimestamp(vec![
+            "first line".into(),
+            "second line".into()
+        ]))
+    );
+    assert_eq!(parse_as_text(" \n"), None);
+}
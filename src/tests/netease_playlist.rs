@@ -0,0 +1,70 @@
+use std::time::Duration;
+
+use serde_json::json;
+
+use crate::lyric_providers::netease::{parse_playlist_tracks, PlaylistTrack};
+use crate::sync::lyric::playlist::track_of_playlist;
+
+#[test]
+fn parse_tracks_of_playlist() {
+    let detail = json!({
+        "code": 200,
+        "playlist": {
+            "id": 24381616,
+            "tracks": [
+                {
+                    "id": 708965,
+                    "name": "Sincerely",
+                    "ar": [{"id": 1, "name": "TRUE"}],
+                    "al": {"id": 2, "name": "Sincerely"},
+                    "dt": 251000
+                },
+                {
+                    "id": 1,
+                    "name": "No Tags",
+                    "ar": []
+                },
+                {
+                    "name": "No Id"
+                }
+            ]
+        }
+    });
+    let tracks = parse_playlist_tracks(&detail).unwrap();
+    assert_eq!(
+        tracks,
+        vec![
+            PlaylistTrack {
+                id: "708965".into(),
+                title: "Sincerely".into(),
+                artists: vec!["TRUE".into()],
+                album: Some("Sincerely".into()),
+                length: Duration::from_millis(251000),
+            },
+            PlaylistTrack {
+                id: "1".into(),
+                title: "No Tags".into(),
+                artists: vec![],
+                album: None,
+                length: Duration::ZERO,
+            },
+        ]
+    );
+
+    assert!(parse_playlist_tracks(&json!({"code": 404})).is_err());
+}
+
+#[test]
+fn playlist_track_meta() {
+    let track = PlaylistTrack {
+        id: "1".into(),
+        title: "No Tags".into(),
+        artists: vec![],
+        album: None,
+        length: Duration::ZERO,
+    };
+    let meta = track_of_playlist(&track);
+    assert_eq!(meta.title.as_deref(), Some("No Tags"));
+    assert!(meta.artists.is_none());
+    assert!(meta.length.is_none());
+}
@@ -0,0 +1,62 @@
+use std::time::Duration;
+
+use crate::lyric_providers::lrclib::LRCLib;
+use crate::lyric_providers::netease::Netease;
+use crate::lyric_providers::qqmusic::QQMusic;
+use crate::lyric_providers::utils::{plain_line_at, plain_lines};
+use crate::lyric_providers::{LyricOwned, LyricParse, LyricStore};
+
+fn lines() -> Vec<String> {
+    plain_lines("first\n\n  second \nthird\nfourth\n")
+        .into_iter()
+        .map(Into::into)
+        .collect()
+}
+
+#[test]
+fn blank_lines_are_dropped() {
+    assert_eq!(lines(), ["first", "second", "third", "fourth"]);
+}
+
+#[test]
+fn lines_spread_over_track_length() {
+    let length = Some(Duration::from_secs(40));
+    let at = |secs| plain_line_at(&Duration::from_secs(secs), &lines(), length, 1.).unwrap();
+    assert_eq!(at(0).text, "first");
+    assert_eq!(at(15).text, "second");
+    assert_eq!(at(15).start_time, Duration::from_secs(10));
+    assert_eq!(at(39).text, "fourth");
+    // stays at the last line once the track should have ended
+    assert_eq!(at(100).text, "fourth");
+}
+
+#[test]
+fn speed_is_used_without_track_length() {
+    let line = plain_line_at(&Duration::from_millis(2500), &lines(), None, 0.5).unwrap();
+    assert_eq!(line.text, "second");
+}
+
+#[test]
+fn zero_speed_disables_scrolling() {
+    let length = Some(Duration::from_secs(40));
+    assert_eq!(plain_line_at(&Duration::ZERO, &lines(), length, 0.), None);
+    assert_eq!(plain_line_at(&Duration::ZERO, &[], length, 1.), None);
+    for speed in [f64::NAN, f64::INFINITY] {
+        assert_eq!(
+            plain_line_at(&Duration::from_secs(5), &lines(), None, speed),
+            None
+        );
+    }
+}
+
+#[test]
+fn providers_parse_plain_text_as_no_timestamp() {
+    let store = LyricStore::new(Some("first\n\nsecond\n".into()), None);
+    let parsers: [&dyn LyricParse; 3] = [&Netease, &QQMusic, &LRCLib];
+    for parser in parsers {
+        let LyricOwned::NoTimestamp(lines) = parser.parse_lyric(&store) else {
+            panic!("plain text should be parsed as lyric without timestamp");
+        };
+        assert_eq!(lines, ["first", "second"]);
+    }
+}
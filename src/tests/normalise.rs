@@ -0,0 +1,111 @@
+use crate::lyric_providers::utils::normalise::{
+    alternate_romanizations, build_title_strip_patterns, fuzzy_queries, normalize_title,
+    normalize_title_with, remove_feat, strip_parenthetical,
+};
+
+#[test]
+fn strip_remastered_suffix() {
+    assert_eq!(
+        strip_parenthetical("Yesterday (Remastered 2009)"),
+        "Yesterday"
+    );
+    assert_eq!(
+        strip_parenthetical("Let It Be - Remastered 2009"),
+        "Let It Be"
+    );
+    assert_eq!(strip_parenthetical("Song [Live] (Bonus Track)"), "Song");
+    // title entirely in brackets is kept
+    assert_eq!(
+        strip_parenthetical("(What's the Story)"),
+        "(What's the Story)"
+    );
+}
+
+#[test]
+fn strip_jpop_suffix() {
+    assert_eq!(
+        strip_parenthetical("First Love（2014 Remastered）"),
+        "First Love"
+    );
+    assert_eq!(strip_parenthetical("紅蓮華【TV Size】"), "紅蓮華");
+    assert_eq!(strip_parenthetical("Lemon - TV ver."), "Lemon");
+}
+
+#[test]
+fn strip_classical_suffix() {
+    assert_eq!(
+        strip_parenthetical("Piano Sonata No. 14 in C-Sharp Minor, Op. 27 No. 2 (Moonlight)"),
+        "Piano Sonata No. 14 in C-Sharp Minor, Op. 27 No. 2"
+    );
+}
+
+#[test]
+fn remove_featured_artists() {
+    assert_eq!(remove_feat("Stay (feat. Justin Bieber)"), "Stay");
+    assert_eq!(remove_feat("IU Feat. SUGA"), "IU");
+    assert_eq!(remove_feat("Eight ft. SUGA"), "Eight");
+    assert_eq!(remove_feat("Defeat"), "Defeat");
+}
+
+#[test]
+fn romanize_jpop_artists() {
+    let alts = alternate_romanizations("Ryūichi Sakamoto");
+    assert!(alts.contains(&"Ryuuichi Sakamoto".to_owned()));
+    assert!(alts.contains(&"Ryuichi Sakamoto".to_owned()));
+
+    let alts = alternate_romanizations("Hikaru Utada");
+    assert!(alts.contains(&"Utada Hikaru".to_owned()));
+
+    let alts = alternate_romanizations("Yousuke Yamashita");
+    assert!(alts.contains(&"Yosuke Yamashita".to_owned()));
+}
+
+#[test]
+fn romanize_kpop_artists() {
+    let alts = alternate_romanizations("Lee Ji-eun");
+    assert!(alts.contains(&"Lee Jieun".to_owned()));
+}
+
+#[test]
+fn romanize_classical_artists() {
+    let alts = alternate_romanizations("Beethoven, Ludwig van");
+    assert!(alts.contains(&"Ludwig van Beethoven".to_owned()));
+}
+
+#[test]
+fn fuzzy_queries_exclude_raw() {
+    let artists = vec!["Hikaru Utada".to_owned()];
+    let queries = fuzzy_queries("First Love (Remastered)", &artists);
+    assert_eq!(queries[0], ("First Love".to_owned(), artists.clone()));
+    assert!(queries.contains(&("First Love".to_owned(), vec!["Utada Hikaru".to_owned()])));
+    assert!(!queries.contains(&("First Love (Remastered)".to_owned(), artists)));
+}
+
+#[test]
+fn normalize_featured_and_version_suffixes() {
+    assert_eq!(normalize_title("Song feat. Artist B"), "Song");
+    assert_eq!(
+        normalize_title("Song (feat. Artist B) [2023 Remaster]"),
+        "Song"
+    );
+    assert_eq!(normalize_title("Song (2009)"), "Song");
+    assert_eq!(normalize_title("Song - Radio Edit"), "Song");
+}
+
+#[test]
+fn normalize_keeps_plain_titles() {
+    assert_eq!(normalize_title("Live Forever"), "Live Forever");
+    assert_eq!(normalize_title("1999"), "1999");
+    // do not strip into nothing
+    assert_eq!(normalize_title("(Live)"), "(Live)");
+}
+
+#[test]
+fn user_title_strip_patterns() {
+    let patterns = build_title_strip_patterns(&[r"\s+TV Size$".into()]).unwrap();
+    assert_eq!(
+        normalize_title_with("Song [Live] TV Size", &patterns),
+        "Song"
+    );
+    assert!(build_title_strip_patterns(&["(".into()]).is_err());
+}
@@ -0,0 +1,26 @@
+use crate::obs::{authentication, set_text_request};
+
+#[test]
+fn obs_authentication() {
+    assert_eq!(
+        authentication(
+            "supersecretpassword",
+            "lM1GncleQOaCu9lT1yeUZhFYnqhsLLP1G5lAGo3ixaI=",
+            "+IxH4CnCiqpX1rM9scsNynZzbOe4KhDeYcTNS3PDaeY="
+        ),
+        "1Ct943GAT+6YQUUX47Ia/ncufilbe6+oD6lY+5kaCu4="
+    );
+}
+
+#[test]
+fn obs_set_text_request() {
+    let request = set_text_request("Lyrics", "some line", 3);
+    assert_eq!(request["op"], 6);
+    assert_eq!(request["d"]["requestType"], "SetInputSettings");
+    assert_eq!(request["d"]["requestId"], "3");
+    assert_eq!(request["d"]["requestData"]["inputName"], "Lyrics");
+    assert_eq!(
+        request["d"]["requestData"]["inputSettings"]["text"],
+        "some line"
+    );
+}
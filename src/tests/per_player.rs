@@ -0,0 +1,43 @@
+use std::collections::BTreeMap;
+
+use crate::config::{Config, PlayerConfig};
+use crate::sync::per_player::find_player_config;
+
+#[test]
+fn longest_prefix_wins() {
+    let configs = BTreeMap::from([
+        ("Spotify".to_owned(), PlayerConfig::default()),
+        (
+            "Spotify Premium".to_owned(),
+            PlayerConfig {
+                font_size: Some(32.),
+                ..Default::default()
+            },
+        ),
+    ]);
+
+    let (key, _) = find_player_config(&configs, "Spotify").unwrap();
+    assert_eq!(key, "Spotify");
+    let (key, config) = find_player_config(&configs, "Spotify Premium Edition").unwrap();
+    assert_eq!(key, "Spotify Premium");
+    assert_eq!(config.font_size, Some(32.));
+    assert!(find_player_config(&configs, "mpd").is_none());
+}
+
+#[test]
+fn parse_per_player_table() {
+    let config: Config = toml_edit::de::from_str(
+        r#"
+        [per_player."Music Player Daemon"]
+        font-size = 12
+        lyric-offset-ms = -300
+        providers = ["LRCLib"]
+        "#,
+    )
+    .unwrap();
+    let mpd = &config.per_player["Music Player Daemon"];
+    assert_eq!(mpd.font_size, Some(12.));
+    assert_eq!(mpd.lyric_offset_ms, Some(-300));
+    assert_eq!(mpd.providers.as_deref(), Some(&["LRCLib".to_owned()][..]));
+    assert_eq!(mpd.cache_lyrics, None);
+}
@@ -0,0 +1,38 @@
+use std::time::Duration;
+
+use super::line;
+use crate::sync::actions::{emit_event, emit_line_changed};
+use crate::sync::{subscribe_events, PlayEvent};
+
+#[test]
+fn subscribers_receive_events() {
+    let events = subscribe_events();
+    let dropped = subscribe_events();
+    drop(dropped);
+
+    emit_event(PlayEvent::PlayerConnected {
+        identity: "mpv".into(),
+    });
+    let Ok(PlayEvent::PlayerConnected { identity }) = events.try_recv() else {
+        panic!("no PlayerConnected event");
+    };
+    assert_eq!(identity, "mpv");
+
+    let (origin, translation) = (line(3000, "hello"), line(3000, "你好"));
+    emit_line_changed(Some(&origin), Some(&translation));
+    // refreshed again while the same line is playing
+    emit_line_changed(Some(&origin), Some(&translation));
+    let Ok(PlayEvent::LyricLineChanged {
+        line,
+        translation,
+        timestamp,
+    }) = events.try_recv()
+    else {
+        panic!("no LyricLineChanged event");
+    };
+    assert_eq!(
+        (line.as_str(), translation.as_str(), timestamp),
+        ("hello", "你好", Duration::from_secs(3))
+    );
+    assert!(events.try_recv().is_err());
+}
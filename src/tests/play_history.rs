@@ -0,0 +1,51 @@
+use std::path::{Path, PathBuf};
+
+use super::temp_path;
+use crate::history::{append_record, load_records, rotate_history, PlayRecord};
+
+fn record(title: &str, timestamp: u64) -> PlayRecord {
+    PlayRecord {
+        timestamp,
+        title: title.into(),
+        artist: "Artist".into(),
+        album: String::new(),
+        lyric_cache_path: Some(PathBuf::from(format!("/tmp/{title}.json"))),
+    }
+}
+
+#[test]
+fn newest_first() {
+    let path = temp_path("play-history-order.jsonl");
+    let _ = std::fs::remove_file(&path);
+    append_record(&path, &record("a", 1)).unwrap();
+    append_record(&path, &record("b", 2)).unwrap();
+    append_record(&path, &record("c", 2)).unwrap();
+
+    let titles: Vec<_> = load_records(&path).into_iter().map(|r| r.title).collect();
+    assert_eq!(titles, ["c", "b", "a"]);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn broken_lines_are_skipped() {
+    let path = temp_path("play-history-broken.jsonl");
+    std::fs::write(&path, "not json\n").unwrap();
+    append_record(&path, &record("a", 1)).unwrap();
+
+    assert_eq!(load_records(&path), [record("a", 1)]);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn rotate_when_too_large() {
+    let path = temp_path("play-history-rotate.jsonl");
+    let mut rotated = path.as_os_str().to_owned();
+    rotated.push(".1");
+    append_record(&path, &record("a", 1)).unwrap();
+
+    assert!(!rotate_history(&path, u64::MAX).unwrap());
+    assert!(rotate_history(&path, 0).unwrap());
+    assert!(!path.exists());
+    assert_eq!(load_records(Path::new(&rotated)), [record("a", 1)]);
+    let _ = std::fs::remove_file(&rotated);
+}
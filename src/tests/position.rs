@@ -0,0 +1,28 @@
+use std::time::Duration;
+
+use crate::app::position::{format_duration, format_position};
+use crate::app::window_title;
+
+#[test]
+fn position_format() {
+    assert_eq!(format_duration(Duration::from_millis(83_900)), "01:23");
+    assert_eq!(format_duration(Duration::from_secs(3725)), "62:05");
+    assert_eq!(
+        format_position(Duration::from_secs(83), Some(Duration::from_secs(296))),
+        "01:23 / 04:56"
+    );
+    assert_eq!(format_position(Duration::from_secs(83), None), "01:23");
+    assert_eq!(
+        format_position(Duration::from_secs(83), Some(Duration::ZERO)),
+        "01:23"
+    );
+}
+
+#[test]
+fn title_with_position() {
+    assert_eq!(window_title(false, None), "Waylyrics");
+    assert_eq!(
+        window_title(true, Some("01:23 / 04:56")),
+        "Waylyrics (offline) [01:23 / 04:56]"
+    );
+}
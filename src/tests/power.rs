@@ -0,0 +1,59 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::utils::on_battery_in;
+
+fn supply_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("waylyrics-power-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn add_supply(dir: &Path, name: &str, attrs: &[(&str, &str)]) {
+    let supply = dir.join(name);
+    fs::create_dir_all(&supply).unwrap();
+    for (attr, value) in attrs {
+        fs::write(supply.join(attr), format!("{value}\n")).unwrap();
+    }
+}
+
+#[test]
+fn discharging_battery() {
+    let dir = supply_dir("discharging");
+    add_supply(&dir, "AC", &[("type", "Mains"), ("online", "0")]);
+    add_supply(
+        &dir,
+        "BAT0",
+        &[("type", "Battery"), ("status", "Discharging")],
+    );
+    assert!(on_battery_in(&dir));
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn ac_online() {
+    let dir = supply_dir("ac-online");
+    add_supply(&dir, "AC", &[("type", "Mains"), ("online", "1")]);
+    add_supply(
+        &dir,
+        "BAT0",
+        &[("type", "Battery"), ("status", "Discharging")],
+    );
+    assert!(!on_battery_in(&dir));
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn no_battery() {
+    let dir = supply_dir("no-battery");
+    assert!(!on_battery_in(&dir));
+    add_supply(
+        &dir,
+        "hidpp_battery_0",
+        &[("type", "Battery"), ("status", "Charging")],
+    );
+    assert!(!on_battery_in(&dir));
+    fs::remove_dir_all(dir).unwrap();
+    assert!(!on_battery_in(&dir));
+}
@@ -0,0 +1,88 @@
+use crate::config::ProviderGroup;
+use crate::lyric_providers::group::{expand_sources_with, search_units, split_with};
+use crate::lyric_providers::lrclib::LRCLib;
+use crate::lyric_providers::netease::Netease;
+use crate::lyric_providers::qqmusic::QQMusic;
+use crate::lyric_providers::LyricProvider;
+
+fn names(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| name.to_string()).collect()
+}
+
+fn groups() -> Vec<ProviderGroup> {
+    vec![
+        ProviderGroup {
+            name: "local".into(),
+            members: names(&[LRCLib.unique_name()]),
+            sequential: false,
+        },
+        ProviderGroup {
+            name: "cloud".into(),
+            members: names(&[QQMusic.unique_name(), Netease.unique_name()]),
+            sequential: true,
+        },
+    ]
+}
+
+#[test]
+fn groups_expanded_in_place() {
+    let sources = names(&["local", "cloud", LRCLib.unique_name()]);
+    assert_eq!(
+        expand_sources_with(&groups(), &sources),
+        names(&[
+            LRCLib.unique_name(),
+            QQMusic.unique_name(),
+            Netease.unique_name()
+        ])
+    );
+    // not a group
+    assert_eq!(expand_sources_with(&[], &sources), sources);
+}
+
+#[test]
+fn ungrouped_providers_make_one_unit() {
+    let sources = names(&[Netease.unique_name(), QQMusic.unique_name(), "local"]);
+    assert_eq!(
+        search_units(&groups(), &sources),
+        [
+            names(&[Netease.unique_name(), QQMusic.unique_name()]),
+            names(&[LRCLib.unique_name()]),
+        ]
+    );
+    // without groups everything is searched at once
+    assert_eq!(search_units(&[], &sources), [sources.clone()]);
+}
+
+#[test]
+fn sequential_group_one_unit_per_member() {
+    let sources = names(&["local", "cloud"]);
+    assert_eq!(
+        search_units(&groups(), &sources),
+        [
+            names(&[LRCLib.unique_name()]),
+            names(&[QQMusic.unique_name()]),
+            names(&[Netease.unique_name()]),
+        ]
+    );
+}
+
+#[test]
+fn tiers_split_by_units() {
+    let providers: [&dyn LyricProvider; 3] = [&LRCLib, &QQMusic, &Netease];
+    let units = search_units(&groups(), &names(&["local", "cloud"]));
+    assert_eq!(
+        split_with(&units, &providers, vec![vec![0, 1, 2]]),
+        [vec![0], vec![1], vec![2]]
+    );
+    // genre preferred providers stay first
+    assert_eq!(
+        split_with(&units, &providers, vec![vec![2], vec![0, 1]]),
+        [vec![2], vec![0], vec![1]]
+    );
+
+    let units = search_units(&[], &names(&[LRCLib.unique_name(), QQMusic.unique_name()]));
+    assert_eq!(
+        split_with(&units, &providers, vec![vec![0, 1, 2]]),
+        [vec![0, 1, 2]]
+    );
+}
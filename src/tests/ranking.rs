@@ -0,0 +1,114 @@
+use std::time::Duration;
+
+use whatlang::Lang;
+
+use crate::config::RankingWeights;
+use crate::lyric_providers::utils::ranking::{
+    confidence, levenshtein, parse_languages, pick_by_language, rank, similarity, TrackInfo,
+};
+use crate::lyric_providers::{LyricLineOwned, LyricOwned};
+
+fn lyric(lines: &[&str]) -> LyricOwned {
+    LyricOwned::LineTimestamp(
+        lines
+            .iter()
+            .map(|text| LyricLineOwned {
+                text: text.to_string(),
+                start_time: Duration::ZERO,
+                confidence: None,
+            })
+            .collect(),
+    )
+}
+
+#[test]
+fn parse_language_codes() {
+    let codes = ["zh", "JA", "eng", "xx"].map(String::from);
+    assert_eq!(parse_languages(&codes), [Lang::Cmn, Lang::Jpn, Lang::Eng]);
+}
+
+#[test]
+fn prefer_language() {
+    let english = lyric(&["Yesterday, all my troubles seemed so far away"]);
+    let japanese = lyric(&["夜空に輝く星たちが私を見つめている"]);
+
+    let candidates = [LyricOwned::None, english, japanese];
+    assert_eq!(pick_by_language(&candidates, &[Lang::Jpn]), Some(2));
+    assert_eq!(
+        pick_by_language(&candidates, &[Lang::Eng, Lang::Jpn]),
+        Some(1)
+    );
+    // soft rank: fallback to the first non-empty lyric
+    assert_eq!(pick_by_language(&candidates, &[Lang::Kor]), Some(1));
+    assert_eq!(pick_by_language(&[], &[Lang::Kor]), None);
+}
+
+fn track(title: &str, artists: &str, album: Option<&str>, secs: u64) -> TrackInfo {
+    TrackInfo {
+        title: title.into(),
+        artists: Some(artists.into()),
+        album: album.map(str::to_owned),
+        length: Some(Duration::from_secs(secs)),
+    }
+}
+
+#[test]
+fn levenshtein_distance() {
+    let chars = |s: &str| s.chars().collect::<Vec<_>>();
+    assert_eq!(levenshtein(&chars("kitten"), &chars("sitting")), 3);
+    assert_eq!(levenshtein(&chars(""), &chars("abc")), 3);
+    assert_eq!(similarity("Yesterday", "yesterday"), 1.);
+}
+
+#[test]
+fn confidence_of_title_and_artist() {
+    let playing = track("Yesterday", "The Beatles", None, 125);
+    assert_eq!(confidence(&playing, &playing), 1.);
+    let other = track("Hey Jude", "Someone Else", None, 125);
+    assert!(confidence(&playing, &other) < 0.3);
+
+    let no_artist = TrackInfo {
+        artists: None,
+        ..playing.clone()
+    };
+    assert_eq!(confidence(&playing, &no_artist), 1.);
+}
+
+#[test]
+fn title_and_duration_disagree() {
+    let playing = track("Yesterday", "The Beatles", None, 125);
+    // title matches exactly, but 15s longer
+    let same_title = track("Yesterday", "The Beatles", None, 140);
+    // same length, but a different title
+    let same_length = track("Yesterday (Live)", "The Beatles", None, 125);
+    let candidates = || [same_title.clone(), same_length.clone()];
+
+    let ranked = rank(&playing, candidates(), &RankingWeights::default());
+    assert_eq!(ranked[0].0, 1);
+
+    let weights = RankingWeights {
+        duration: 0.2,
+        ..Default::default()
+    };
+    let ranked = rank(&playing, candidates(), &weights);
+    assert_eq!(ranked[0].0, 0);
+}
+
+#[test]
+fn album_breaks_ties() {
+    let playing = track("Song", "Artist", Some("Album"), 200);
+    let candidates = [
+        track("Song", "Artist", Some("Best Of"), 200),
+        track("Song", "Artist", Some("album"), 200),
+    ];
+    let ranked = rank(&playing, candidates, &RankingWeights::default());
+    assert_eq!(ranked[0].0, 1);
+
+    // same score keeps the order of providers
+    let candidates = [
+        track("Song", "Artist", None, 200),
+        track("Song", "Artist", None, 200),
+    ];
+    let ranked = rank(&playing, candidates, &RankingWeights::default());
+    assert_eq!(ranked.iter().map(|(i, _)| *i).collect::<Vec<_>>(), [0, 1]);
+}
@@ -0,0 +1,90 @@
+use crate::history::PlayRecord;
+use crate::lyric_providers::utils::http_cache::{write_sidecar, LyricSource};
+use std::time::Duration;
+
+use crate::lyric_providers::{LyricLineOwned, LyricOwned};
+use crate::sync::lyric::cache::{get_cache_path, set_lyric_pinned, write_lyric_cache};
+use crate::sync::lyric::rebuild::{
+    cache_entries, plan_refresh, track_of_record, tracks_by_cache_path, Refresh,
+};
+use crate::sync::LyricState;
+use crate::CACHE_DIR;
+
+fn record(timestamp: u64, title: &str, artist: &str) -> PlayRecord {
+    PlayRecord {
+        timestamp,
+        title: title.into(),
+        artist: artist.into(),
+        album: String::new(),
+        lyric_cache_path: None,
+    }
+}
+
+#[test]
+fn track_of_record_splits_artists() {
+    let track = track_of_record(&record(0, "Song", "A & B"));
+    assert_eq!(track.title.as_deref(), Some("Song"));
+    assert_eq!(track.artists, Some(vec!["A".to_owned(), "B".to_owned()]));
+    assert_eq!(track.album, None);
+}
+
+#[test]
+fn rebuild_plan() {
+    let cache_home =
+        std::env::temp_dir().join(format!("waylyrics-rebuild-cache-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&cache_home);
+    CACHE_DIR.set(cache_home.to_string_lossy().into_owned());
+
+    let played = record(2, "Played", "Someone");
+    let played_path = get_cache_path(&track_of_record(&played)).unwrap();
+    let queried_path = cache_home.join("lyrics").join("queried.json");
+    let unknown_path = cache_home.join("unknown.json");
+    for path in [&played_path, &queried_path, &unknown_path] {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, "{}").unwrap();
+    }
+    let source = LyricSource {
+        provider: "LRCLib".into(),
+        id: "42".into(),
+        validator: Default::default(),
+    };
+    write_sidecar(&queried_path, Some(&source));
+    // would be queried by its sidecar, if not pinned
+    let pinned_path = cache_home.join("lyrics").join("pinned.json");
+    let lyric = LyricState {
+        origin: LyricOwned::LineTimestamp(vec![LyricLineOwned {
+            text: "edited".into(),
+            start_time: Duration::from_secs(1),
+            confidence: None,
+        }]),
+        translation: LyricOwned::None,
+        source_url: None,
+    };
+    assert!(write_lyric_cache(&pinned_path, &lyric));
+    assert!(set_lyric_pinned(&pinned_path, true));
+    write_sidecar(&pinned_path, Some(&source));
+
+    let mut entries = cache_entries(&cache_home);
+    entries.sort();
+    let mut expected = vec![
+        played_path.clone(),
+        queried_path.clone(),
+        unknown_path.clone(),
+        pinned_path.clone(),
+    ];
+    expected.sort();
+    assert_eq!(entries, expected);
+
+    let records = [played.clone(), record(1, "Played", "Someone Else")];
+    let tracks = tracks_by_cache_path(&records);
+    assert_eq!(
+        plan_refresh(&played_path, &tracks),
+        Refresh::Search(track_of_record(&played))
+    );
+    assert_eq!(plan_refresh(&queried_path, &tracks), Refresh::Query(source));
+    assert_eq!(plan_refresh(&unknown_path, &tracks), Refresh::Skip);
+    assert_eq!(plan_refresh(&pinned_path, &tracks), Refresh::Pinned);
+
+    CACHE_DIR.set(String::new());
+    let _ = std::fs::remove_dir_all(&cache_home);
+}
@@ -0,0 +1,47 @@
+#[cfg(test)]
+mod redact {
+    use crate::log::{redact, register_secret};
+
+    #[test]
+    fn redact_sensitive_headers() {
+        let msg = r#"headers: {"authorization": "Bearer abc123", "accept": "*/*"}"#;
+        let redacted = redact(msg);
+        assert!(!redacted.contains("abc123"));
+        assert!(redacted.contains(r#""accept": "*/*""#));
+    }
+
+    #[test]
+    fn redact_registered_secret() {
+        register_secret("s3cr3t-key");
+        assert_eq!(redact("key=s3cr3t-key&q=1"), "key=<redacted>&q=1");
+    }
+
+    #[test]
+    fn drop_events_to_redact() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        use tracing_subscriber::layer::{Context, SubscriberExt};
+        use tracing_subscriber::{Layer, Registry};
+
+        use crate::log::DropUnredacted;
+
+        struct Count(Arc<AtomicUsize>);
+
+        impl<S: tracing::Subscriber> Layer<S> for Count {
+            fn on_event(&self, _event: &tracing::Event<'_>, _cx: Context<'_, S>) {
+                self.0.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        register_secret("journald-s3cr3t");
+        let count = Arc::new(AtomicUsize::new(0));
+        let subscriber = Registry::default().with(Count(count.clone()).with_filter(DropUnredacted));
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!("playing a track");
+            tracing::info!("token is journald-s3cr3t");
+            tracing::info!(token = "journald-s3cr3t", "authorized");
+        });
+        assert_eq!(count.load(Ordering::Relaxed), 1);
+    }
+}
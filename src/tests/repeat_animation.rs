@@ -0,0 +1,15 @@
+use std::time::Duration;
+
+use crate::app::repeat_animation::is_repeated_line;
+
+#[test]
+fn repeated_line() {
+    let line = |text, secs| Some((text, Duration::from_secs(secs)));
+    assert!(is_repeated_line(line("la la", 1), line("la la", 3)));
+    // same line synced again
+    assert!(!is_repeated_line(line("la la", 1), line("la la", 1)));
+    assert!(!is_repeated_line(line("la la", 1), line("oh", 3)));
+    assert!(!is_repeated_line(line("", 1), line("", 3)));
+    assert!(!is_repeated_line(None, line("la la", 3)));
+    assert!(!is_repeated_line(line("la la", 1), None));
+}
@@ -0,0 +1,24 @@
+use std::time::Duration;
+
+use crate::lyric_providers::utils::retry::{should_retry, Error, RetryPolicy};
+
+#[test]
+fn exponential_backoff() {
+    let policy = RetryPolicy::default();
+    assert_eq!(policy.backoff(0), Duration::from_millis(500));
+    assert_eq!(policy.backoff(1), Duration::from_millis(1000));
+    assert_eq!(policy.backoff(2), Duration::from_millis(2000));
+}
+
+#[test]
+fn retry_after_too_many_requests() {
+    let retry_after = Some(Duration::from_secs(3));
+    let e = anyhow::Error::from(Error::TooManyRequests { retry_after });
+    assert_eq!(should_retry(&e), Some(retry_after));
+}
+
+#[test]
+fn do_not_retry_unknown_error() {
+    let e = anyhow::Error::from(crate::lyric_providers::Error::NoResult);
+    assert_eq!(should_retry(&e), None);
+}
@@ -0,0 +1,26 @@
+use crate::config::RomajiDisplay;
+use crate::lyric_providers::utils::romaji::{has_kana, transliterate, Transliterated};
+
+#[test]
+fn detect_kana() {
+    assert!(has_kana("夜に駆ける"));
+    assert!(has_kana("カタカナ"));
+    assert!(!has_kana("漢字"));
+    assert!(!has_kana("English"));
+}
+
+#[test]
+fn display_modes() {
+    assert_eq!(
+        transliterate("さくら", RomajiDisplay::Replace),
+        Some(Transliterated::Replace("sakura".into()))
+    );
+    assert_eq!(
+        transliterate("さくら & <", RomajiDisplay::Above),
+        Some(Transliterated::Above(
+            "<span size=\"small\">sakura &amp; &lt;</span>\nさくら &amp; &lt;".into()
+        ))
+    );
+    assert_eq!(transliterate("さくら", RomajiDisplay::Off), None);
+    assert_eq!(transliterate("English", RomajiDisplay::Above), None);
+}
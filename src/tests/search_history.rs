@@ -0,0 +1,55 @@
+use crate::app::search_window::history::{SearchHistory, SearchQuery, MAX_HISTORY};
+
+fn query(title: &str, found: bool) -> SearchQuery {
+    SearchQuery::new(title.into(), String::new(), "Artist".into(), found)
+}
+
+#[test]
+fn most_recent_first_without_duplicates() {
+    let mut history = SearchHistory::default();
+    history.push(query("a", true));
+    history.push(query("b", false));
+    history.push(query("a", false));
+
+    let titles: Vec<_> = history
+        .iter()
+        .map(|q| (q.title.as_str(), q.found))
+        .collect();
+    assert_eq!(titles, [("a", false), ("b", false)]);
+}
+
+#[test]
+fn history_is_bounded() {
+    let mut history = SearchHistory::default();
+    for i in 0..MAX_HISTORY + 10 {
+        history.push(query(&i.to_string(), true));
+    }
+    assert_eq!(history.len(), MAX_HISTORY);
+    assert_eq!(
+        history.iter().next().unwrap().title,
+        (MAX_HISTORY + 9).to_string()
+    );
+}
+
+#[test]
+fn query_string() {
+    assert_eq!(query("Song", true).query(), "Song - Artist");
+}
+
+#[test]
+fn history_roundtrip() {
+    let path = std::env::temp_dir().join(format!(
+        "waylyrics-search-history-{}.json",
+        std::process::id()
+    ));
+    let mut history = SearchHistory::default();
+    history.push(query("a", true));
+    history.save(&path).unwrap();
+
+    let loaded = SearchHistory::load(&path);
+    assert_eq!(
+        loaded.iter().collect::<Vec<_>>(),
+        history.iter().collect::<Vec<_>>()
+    );
+    let _ = std::fs::remove_file(&path);
+}
@@ -0,0 +1,77 @@
+use crate::app::shadow::{approach_size, Background, Gradient, Outline, Shadow};
+
+#[test]
+fn empty_color_disables_shadow() {
+    assert_eq!(Shadow::new(2., 2., 2., "  ").unwrap(), None);
+}
+
+#[test]
+fn parse_shadow() {
+    let shadow = Shadow::new(1., -1., -3., "rgba(0, 0, 0, 0.5)")
+        .unwrap()
+        .unwrap();
+    assert_eq!(shadow.blur, 0.);
+    assert_eq!(shadow.color.alpha(), 0.5);
+    assert!(Shadow::new(0., 0., 0., "not a color").is_err());
+}
+
+#[test]
+fn outline_needs_width_and_color() {
+    assert_eq!(Outline::new(0., "black").unwrap(), None);
+    assert_eq!(Outline::new(1.5, "").unwrap(), None);
+    let outline = Outline::new(0.5, "white").unwrap().unwrap();
+    assert_eq!(outline.width, 0.5);
+    assert_eq!(outline.color.red(), 1.);
+    assert!(Outline::new(1., "not a color").is_err());
+}
+
+#[test]
+fn gradient_stops_spread_evenly() {
+    assert_eq!(Gradient::new(&[]).unwrap(), None);
+    assert_eq!(Gradient::new(&[" ".into()]).unwrap(), None);
+    assert!(Gradient::new(&["red".into(), "not a color".into()]).is_err());
+
+    let colors = ["#ff0000", "lime", "#0000ff"].map(str::to_owned);
+    let gradient = Gradient::new(&colors).unwrap().unwrap();
+    let stops: Vec<_> = gradient.stops().collect();
+    assert_eq!(stops.len(), 3);
+    assert_eq!(stops[0].0, 0.);
+    assert_eq!(stops[1].0, 0.5);
+    assert_eq!(stops[2].0, 1.);
+    assert_eq!(stops[2].1.blue(), 1.);
+
+    // a single color fills the text with it
+    let solid = Gradient::new(&["red".into()]).unwrap().unwrap();
+    assert_eq!(
+        solid.stops().map(|(offset, _)| offset).collect::<Vec<_>>(),
+        [0.]
+    );
+}
+
+#[test]
+fn parse_background() {
+    assert_eq!(Background::new("", 12).unwrap(), None);
+    let background = Background::new("rgba(255, 255, 255, 0.2)", 8)
+        .unwrap()
+        .unwrap();
+    assert_eq!(background.radius, 8.);
+    assert!(Background::new("not a color", 8).is_err());
+}
+
+#[test]
+fn background_size_eases_to_target() {
+    let target = (200., 40.);
+    let mut size = (100., 40.);
+    let mut frames = 0;
+    while size != target {
+        let next = approach_size(size, target);
+        assert!(next.0 > size.0 && next.0 <= target.0);
+        assert_eq!(next.1, target.1);
+        size = next;
+        frames += 1;
+        assert!(frames < 60, "never reached {target:?}");
+    }
+    assert_eq!(approach_size(target, target), target);
+    // shrinks as well
+    assert!(approach_size((200., 40.), (50., 20.)).0 < 200.);
+}
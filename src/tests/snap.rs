@@ -0,0 +1,40 @@
+use crate::app::snap::{snap_position, Rect};
+
+const MONITOR: Rect = Rect {
+    x: 0,
+    y: 0,
+    width: 1920,
+    height: 1080,
+};
+
+fn window(x: i32, y: i32) -> Rect {
+    Rect {
+        x,
+        y,
+        width: 400,
+        height: 100,
+    }
+}
+
+#[test]
+fn snap_to_nearest_edge() {
+    assert_eq!(snap_position(window(15, 500), MONITOR, 20), Some((0, 500)));
+    assert_eq!(
+        snap_position(window(1510, 990), MONITOR, 20),
+        Some((1520, 980))
+    );
+}
+
+#[test]
+fn snap_to_center_axis() {
+    assert_eq!(
+        snap_position(window(765, 300), MONITOR, 20),
+        Some((760, 300))
+    );
+}
+
+#[test]
+fn far_from_edges() {
+    assert_eq!(snap_position(window(300, 300), MONITOR, 20), None);
+    assert_eq!(snap_position(window(0, 300), MONITOR, 20), Some((0, 300)));
+}
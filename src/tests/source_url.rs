@@ -0,0 +1,21 @@
+use crate::lyric_providers::lrclib::LRCLib;
+use crate::lyric_providers::netease::Netease;
+use crate::lyric_providers::qqmusic::QQMusic;
+use crate::lyric_providers::LyricProvider;
+
+#[test]
+fn provider_source_urls() {
+    assert_eq!(
+        LRCLib.source_url("3396226").as_deref(),
+        Some("https://lrclib.net/lyrics/3396226")
+    );
+    assert_eq!(
+        Netease.source_url("1901371647").as_deref(),
+        Some("https://music.163.com/#/song?id=1901371647")
+    );
+    assert_eq!(
+        QQMusic.source_url("004Knor205SbZb").as_deref(),
+        Some("https://y.qq.com/n/ryqq/songDetail/004Knor205SbZb")
+    );
+    assert_eq!(QQMusic.source_url("1145141919"), None);
+}
@@ -0,0 +1,70 @@
+use std::time::Duration;
+
+use super::line;
+use crate::lyric_providers::utils::srt::{parse_srt, parse_timestamp, strip_tags, SrtError};
+
+#[test]
+fn timestamp() {
+    assert_eq!(
+        parse_timestamp("00:01:02,500"),
+        Some(Duration::from_millis(62500))
+    );
+    assert_eq!(
+        parse_timestamp("01:00:00.000"),
+        Some(Duration::from_secs(3600))
+    );
+    assert_eq!(parse_timestamp("01:02,500"), None);
+}
+
+#[test]
+fn tags_are_stripped() {
+    assert_eq!(
+        strip_tags("{\\an8}<i>Hello</i>  <b>world</b>"),
+        "Hello world"
+    );
+}
+
+#[test]
+fn subtitles_with_gaps() {
+    let srt = "\u{feff}1\r\n\
+        00:00:01,000 --> 00:00:03,000\r\n\
+        First line\r\n\
+        continued\r\n\
+        \r\n\
+        2\r\n\
+        00:00:03,000 --> 00:00:04,500 X1:100 X2:200\r\n\
+        <i>Second</i>\r\n\
+        \r\n\
+        3\r\n\
+        00:00:06,000 --> 00:00:07,000\r\n\
+        Third\r\n";
+    assert_eq!(
+        parse_srt(srt).unwrap(),
+        vec![
+            line(1000, "First line continued"),
+            line(3000, "Second"),
+            line(4500, ""),
+            line(6000, "Third"),
+            line(7000, ""),
+        ]
+    );
+}
+
+#[test]
+fn unordered_subtitles_are_sorted() {
+    let srt =
+        "2\n00:00:05,000 --> 00:00:06,000\nLater\n\n1\n00:00:01,000 --> 00:00:05,000\nEarlier\n";
+    assert_eq!(
+        parse_srt(srt).unwrap(),
+        vec![line(1000, "Earlier"), line(5000, "Later"), line(6000, "")]
+    );
+}
+
+#[test]
+fn invalid_srt() {
+    assert!(matches!(parse_srt("not a subtitle"), Err(SrtError::Empty)));
+    assert!(matches!(
+        parse_srt("1\n00:00:01 --> 00:00:02,000\ntext\n"),
+        Err(SrtError::InvalidTiming(_, 2))
+    ));
+}
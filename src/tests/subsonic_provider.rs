@@ -0,0 +1,82 @@
+use std::time::Duration;
+
+use serde_json::json;
+
+use crate::lyric_providers::subsonic::{
+    auth_token, into_response, parse_search_result, parse_structured_lyrics,
+};
+
+#[test]
+fn token_of_subsonic_docs() {
+    // example from http://www.subsonic.org/pages/api.jsp
+    assert_eq!(
+        auth_token("sesame", "c19b2d"),
+        "26719a1196d2a940705a59634eb18eab"
+    );
+}
+
+#[test]
+fn failed_response() {
+    let failed = json!({ "subsonic-response": {
+        "status": "failed",
+        "error": { "code": 40, "message": "Wrong username or password" },
+    }});
+    let e = into_response(failed).unwrap_err();
+    assert_eq!(
+        e.to_string(),
+        "subsonic error 40: Wrong username or password"
+    );
+    assert!(into_response(json!({ "error": "not subsonic" })).is_err());
+}
+
+#[test]
+fn parse_search3() {
+    let body = into_response(json!({ "subsonic-response": {
+        "status": "ok",
+        "searchResult3": { "song": [
+            { "id": "a1", "title": "Yesterday", "artist": "The Beatles", "album": "Help!", "duration": 125 },
+            { "id": "a2", "title": "Yesterday" },
+        ]},
+    }}))
+    .unwrap();
+    let songs = parse_search_result(&body);
+    assert_eq!(songs.len(), 2);
+    assert_eq!(songs[0].id, "a1");
+    assert_eq!(songs[0].singer, "The Beatles");
+    assert_eq!(songs[0].album.as_deref(), Some("Help!"));
+    assert_eq!(songs[0].length, Duration::from_secs(125));
+    assert_eq!(songs[1].singer, "");
+
+    assert!(parse_search_result(&json!({ "searchResult3": {} })).is_empty());
+}
+
+#[test]
+fn synced_lyric_preferred() {
+    let body = json!({ "lyricsList": { "structuredLyrics": [
+        { "lang": "und", "synced": false, "line": [{ "value": "plain" }] },
+        { "lang": "en", "synced": true, "line": [
+            { "start": 1500, "value": "first" },
+            { "start": 61000, "value": "second" },
+        ]},
+        { "lang": "zh", "synced": true, "line": [{ "start": 1500, "value": "第一" }] },
+    ]}});
+    let store = parse_structured_lyrics(&body);
+    assert_eq!(
+        store.lyric.as_deref(),
+        Some("[00:01.500]first\n[01:01.000]second")
+    );
+    assert_eq!(store.tlyric.as_deref(), Some("[00:01.500]第一"));
+}
+
+#[test]
+fn unsynced_lyric_as_text() {
+    let body = json!({ "lyricsList": { "structuredLyrics": [
+        { "synced": false, "line": [{ "value": "one" }, { "value": "two" }] },
+    ]}});
+    let store = parse_structured_lyrics(&body);
+    assert_eq!(store.lyric.as_deref(), Some("one\ntwo"));
+    assert_eq!(store.tlyric, None);
+
+    let empty = parse_structured_lyrics(&json!({ "lyricsList": {} }));
+    assert_eq!(empty.lyric, None);
+}
@@ -0,0 +1,104 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use super::temp_path;
+use crate::lyric_providers::{LyricLineOwned, LyricOwned};
+use crate::sync::lyric::cache::swap_cached_lyric_roles;
+use crate::sync::{swap_lyric_roles, LyricState};
+
+fn timestamped(text: &str) -> LyricOwned {
+    LyricOwned::LineTimestamp(vec![LyricLineOwned {
+        text: text.into(),
+        start_time: Duration::from_secs(1),
+        confidence: None,
+    }])
+}
+
+fn variants() -> [LyricOwned; 3] {
+    [
+        LyricOwned::None,
+        LyricOwned::NoTimestamp(vec!["line".into()]),
+        timestamped("line"),
+    ]
+}
+
+#[test]
+fn swap_every_combination() {
+    for origin in variants() {
+        for translation in variants() {
+            let mut lyric = LyricState {
+                origin: origin.clone(),
+                translation: translation.clone(),
+                source_url: None,
+            };
+            let swapped = swap_lyric_roles(&mut lyric);
+
+            if translation.is_none() {
+                assert!(!swapped);
+                assert_eq!(lyric.origin, origin);
+                assert_eq!(lyric.translation, translation);
+            } else {
+                assert!(swapped);
+                assert_eq!(lyric.origin, translation);
+                assert_eq!(lyric.translation, origin);
+            }
+        }
+    }
+}
+
+#[test]
+fn swap_twice_restores() {
+    let mut lyric = LyricState {
+        origin: timestamped("origin"),
+        translation: timestamped("translation"),
+        source_url: None,
+    };
+    assert!(swap_lyric_roles(&mut lyric));
+    assert!(swap_lyric_roles(&mut lyric));
+    assert_eq!(lyric.origin, timestamped("origin"));
+    assert_eq!(lyric.translation, timestamped("translation"));
+}
+
+fn write_cache(path: &PathBuf, olyric: &LyricOwned, tlyric: &LyricOwned, offset: i64) {
+    let cache = serde_json::json!({ "olyric": olyric, "tlyric": tlyric, "offset": offset });
+    std::fs::write(path, cache.to_string()).unwrap();
+}
+
+fn read_cache(path: &PathBuf) -> serde_json::Value {
+    serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+}
+
+#[test]
+fn swap_cache_keeps_offset() {
+    let path = temp_path("swap-offset.json");
+    write_cache(
+        &path,
+        &timestamped("origin"),
+        &timestamped("translation"),
+        250,
+    );
+
+    assert!(swap_cached_lyric_roles(&path));
+    let cache = read_cache(&path);
+    assert_eq!(cache["offset"], 250);
+    assert_eq!(
+        cache["olyric"],
+        serde_json::json!(timestamped("translation"))
+    );
+    assert_eq!(cache["tlyric"], serde_json::json!(timestamped("origin")));
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn swap_cache_without_translation() {
+    let path = temp_path("swap-untranslated.json");
+    write_cache(&path, &timestamped("origin"), &LyricOwned::None, 0);
+
+    assert!(!swap_cached_lyric_roles(&path));
+    let cache = read_cache(&path);
+    assert_eq!(cache["olyric"], serde_json::json!(timestamped("origin")));
+
+    let _ = std::fs::remove_file(&path);
+    assert!(!swap_cached_lyric_roles(&path));
+}
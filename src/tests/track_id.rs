@@ -0,0 +1,83 @@
+use std::time::Duration;
+
+use crate::sync::lyric::cache::get_cache_path;
+use crate::sync::{TrackId, TrackMeta, MPRIS_NO_TRACK};
+
+const MBID: &str = "c2d0ab5e-5f4d-4b8d-9f33-5a7e8d6e2a1b";
+const OTHER_MBID: &str = "0f1e2d3c-4b5a-4968-8776-a5b4c3d2e1f0";
+
+fn meta(id: Option<TrackId>, title: &str) -> TrackMeta {
+    TrackMeta {
+        unique_song_id: id,
+        title: Some(title.into()),
+        album: None,
+        artists: Some(vec!["The Beatles".into()]),
+        length: None,
+        year: None,
+        track_number: None,
+        disc_number: None,
+        genre: None,
+        bpm: None,
+        user_rating: None,
+    }
+}
+
+#[test]
+fn parse_ids() {
+    assert_eq!(TrackId::from_mpris_path(MPRIS_NO_TRACK), None);
+    assert_eq!(TrackId::from_mpris_path(""), None);
+    assert_eq!(
+        TrackId::from_mpris_path("/org/mpris/1"),
+        Some(TrackId::Mpris("/org/mpris/1".into()))
+    );
+    assert!(matches!(
+        TrackId::from_musicbrainz(MBID),
+        Some(TrackId::MusicBrainz(_))
+    ));
+    assert_eq!(TrackId::from_musicbrainz("mbid"), None);
+    assert_eq!(
+        TrackId::synthetic(Some("Yesterday"), None),
+        TrackId::synthetic(Some("Yesterday"), None)
+    );
+}
+
+#[test]
+fn musicbrainz_id_decides() {
+    let a = meta(TrackId::from_musicbrainz(MBID), "Yesterday");
+    let retagged = meta(TrackId::from_musicbrainz(MBID), "Yesterday (Remastered)");
+    let other = meta(TrackId::from_musicbrainz(OTHER_MBID), "Yesterday");
+    assert_eq!(a, retagged);
+    assert_ne!(a, other);
+}
+
+#[test]
+fn mpris_id_and_tags() {
+    let a = meta(TrackId::from_mpris_path("/org/mpris/1"), "Yesterday");
+    let b = meta(TrackId::from_mpris_path("/org/mpris/2"), "Yesterday");
+    let reused = meta(TrackId::from_mpris_path("/org/mpris/1"), "Something");
+    assert_ne!(a, b);
+    assert_ne!(a, reused);
+    assert_eq!(a, meta(None, "Yesterday"));
+}
+
+#[test]
+fn length_not_compared() {
+    let a = meta(None, "Yesterday");
+    let corrected = TrackMeta {
+        length: Some(Duration::from_secs(125)),
+        ..a.clone()
+    };
+    assert_eq!(a, corrected);
+}
+
+#[test]
+fn cache_keyed_by_musicbrainz_id() {
+    let a = meta(TrackId::from_musicbrainz(MBID), "Yesterday");
+    let retagged = meta(TrackId::from_musicbrainz(MBID), "Yesterday (Remastered)");
+    assert_eq!(get_cache_path(&a), get_cache_path(&retagged));
+    // MPRIS ids are ignored
+    assert_eq!(
+        get_cache_path(&meta(TrackId::from_mpris_path("/org/mpris/1"), "Yesterday")),
+        get_cache_path(&meta(None, "Yesterday"))
+    );
+}
@@ -0,0 +1,55 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::app::track_info_window::TrackInfo;
+use crate::lyric_providers::utils::http_cache::LyricSource;
+use crate::sync::{TrackId, TrackMeta};
+
+#[test]
+fn track_info_json() {
+    let info = TrackInfo {
+        meta: Some(TrackMeta {
+            unique_song_id: TrackId::from_mpris_path("/org/mpris/1"),
+            title: Some("Something".into()),
+            album: None,
+            artists: Some(vec!["The Beatles".into()]),
+            length: Some(Duration::from_millis(182_500)),
+            year: Some(1969),
+            track_number: Some(2),
+            disc_number: None,
+            genre: None,
+            bpm: None,
+            user_rating: None,
+        }),
+        file: Some(PathBuf::from("/music/something.flac")),
+        cache_path: None,
+        source: Some(LyricSource {
+            provider: "LRCLib".into(),
+            id: "42".into(),
+            validator: Default::default(),
+        }),
+        source_url: None,
+        offset_ms: -200,
+    };
+    let json = info.to_json();
+    assert_eq!(json["title"], "Something");
+    assert_eq!(json["artists"][0], "The Beatles");
+    assert!(json["album"].is_null());
+    assert_eq!(json["length_ms"], 182_500);
+    assert_eq!(json["year"], 1969);
+    assert_eq!(json["track_number"], 2);
+    assert!(json["disc_number"].is_null());
+    assert_eq!(json["track_id"], "/org/mpris/1");
+    assert_eq!(json["file"], "/music/something.flac");
+    assert_eq!(json["provider"], "LRCLib");
+    assert_eq!(json["provider_song_id"], "42");
+    assert_eq!(json["offset_ms"], -200);
+}
+
+#[test]
+fn unknown_track_info() {
+    let json = TrackInfo::default().to_json();
+    assert!(json["title"].is_null());
+    assert!(json["provider"].is_null());
+    assert_eq!(json["offset_ms"], 0);
+}
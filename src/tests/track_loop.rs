@@ -0,0 +1,53 @@
+use std::time::Duration;
+
+use crate::sync::{is_track_loop, LoopStatus, TrackState};
+
+const THRESHOLD: Duration = Duration::from_millis(500);
+
+#[test]
+fn restart_on_repeat_is_a_loop() {
+    let mut state = TrackState::default();
+    state.update_position(Duration::from_secs(180));
+    state.update_position(Duration::from_millis(200));
+    assert!(is_track_loop(
+        state.previous_position,
+        state.position.unwrap(),
+        LoopStatus::Track,
+        THRESHOLD
+    ));
+}
+
+#[test]
+fn no_loop_without_repeating_track() {
+    let (previous, position) = (Some(Duration::from_secs(180)), Duration::ZERO);
+    assert!(!is_track_loop(
+        previous,
+        position,
+        LoopStatus::None,
+        THRESHOLD
+    ));
+    assert!(!is_track_loop(
+        previous,
+        position,
+        LoopStatus::Playlist,
+        THRESHOLD
+    ));
+}
+
+#[test]
+fn small_jitter_is_not_a_loop() {
+    let position = Duration::from_secs(60);
+    assert!(!is_track_loop(
+        Some(position + Duration::from_millis(300)),
+        position,
+        LoopStatus::Track,
+        THRESHOLD
+    ));
+    assert!(!is_track_loop(None, position, LoopStatus::Track, THRESHOLD));
+    assert!(!is_track_loop(
+        Some(Duration::from_secs(59)),
+        position,
+        LoopStatus::Track,
+        THRESHOLD
+    ));
+}
@@ -0,0 +1,44 @@
+use crate::sync::lyric::cache::{get_cache_path, set_year_in_cache_key};
+use crate::sync::TrackMeta;
+
+#[test]
+fn parse_year_from_content_created() {
+    assert_eq!(TrackMeta::parse_year("2009-09-09T00:00:00"), Some(2009));
+    assert_eq!(TrackMeta::parse_year("1969"), Some(1969));
+    assert_eq!(TrackMeta::parse_year("69"), None);
+    assert_eq!(TrackMeta::parse_year("-123-01-01"), None);
+}
+
+#[test]
+fn year_in_cache_key_is_opt_in() {
+    let meta = |year| TrackMeta {
+        unique_song_id: None,
+        title: Some("Something".into()),
+        album: Some("Abbey Road".into()),
+        artists: Some(vec!["The Beatles".into()]),
+        length: None,
+        year,
+        track_number: None,
+        disc_number: None,
+        genre: None,
+        bpm: None,
+        user_rating: None,
+    };
+
+    set_year_in_cache_key(false);
+    assert_eq!(
+        get_cache_path(&meta(Some(1969))),
+        get_cache_path(&meta(None))
+    );
+
+    set_year_in_cache_key(true);
+    assert_ne!(
+        get_cache_path(&meta(Some(1969))),
+        get_cache_path(&meta(None))
+    );
+    assert_ne!(
+        get_cache_path(&meta(Some(1969))),
+        get_cache_path(&meta(Some(2019)))
+    );
+    set_year_in_cache_key(false);
+}
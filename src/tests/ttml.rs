@@ -0,0 +1,51 @@
+use std::time::Duration;
+
+use super::line;
+use crate::lyric_providers::utils::ttml::{parse_time, parse_ttml};
+
+#[test]
+fn time_formats() {
+    assert_eq!(parse_time("01:02.5"), Some(Duration::from_millis(62500)));
+    assert_eq!(
+        parse_time("00:01:02.500"),
+        Some(Duration::from_millis(62500))
+    );
+    assert_eq!(parse_time("62.5s"), Some(Duration::from_millis(62500)));
+    assert_eq!(parse_time("62500ms"), Some(Duration::from_millis(62500)));
+    assert_eq!(parse_time("abc"), None);
+}
+
+#[test]
+fn word_spans_with_prefixed_namespace() {
+    let ttml = r#"<tt:tt xmlns:tt="http://www.w3.org/ns/ttml" xmlns:ttm="http://www.w3.org/ns/ttml#metadata">
+  <tt:body>
+    <tt:div>
+      <tt:p begin="00:05.000" end="00:07.000"><tt:span begin="00:05.000">Hello</tt:span> <tt:span begin="00:06.000">world</tt:span><tt:span ttm:role="x-roman">romaji</tt:span></tt:p>
+      <tt:p begin="00:01.000" end="00:03.000">First</tt:p>
+      <tt:p>no timing</tt:p>
+    </tt:div>
+  </tt:body>
+</tt:tt>"#;
+    let lyric = parse_ttml(ttml).unwrap();
+    assert_eq!(
+        lyric.origin,
+        [line(1000, "First"), line(5000, "Hello world")]
+    );
+    assert!(lyric.translation.is_empty());
+}
+
+#[test]
+fn second_div_is_translation() {
+    let ttml = r#"<tt xmlns="http://www.w3.org/ns/ttml"><body>
+  <div><p begin="1s">origin</p></div>
+  <div><p begin="1s">translation</p></div>
+</body></tt>"#;
+    let lyric = parse_ttml(ttml).unwrap();
+    assert_eq!(lyric.origin, [line(1000, "origin")]);
+    assert_eq!(lyric.translation, [line(1000, "translation")]);
+}
+
+#[test]
+fn broken_ttml() {
+    assert!(parse_ttml("<tt><body>").is_err());
+}
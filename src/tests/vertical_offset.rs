@@ -0,0 +1,24 @@
+use crate::app::vertical_offset::vertical_margins;
+
+#[test]
+fn no_offset_keeps_lyrics_centered() {
+    assert_eq!(vertical_margins(0., 400, 100), ((0, 0), false));
+}
+
+#[test]
+fn positive_offset_moves_lyrics_down() {
+    // 10% of 400 px is 40 px, which needs 80 px of margin on top
+    assert_eq!(vertical_margins(10., 400, 100), ((80, 0), false));
+}
+
+#[test]
+fn negative_offset_moves_lyrics_up() {
+    assert_eq!(vertical_margins(-10., 400, 100), ((0, 80), false));
+}
+
+#[test]
+fn offset_is_limited_to_window() {
+    assert_eq!(vertical_margins(50., 400, 100), ((300, 0), true));
+    assert_eq!(vertical_margins(-200., 400, 100), ((0, 300), true));
+    assert_eq!(vertical_margins(10., 100, 200), ((0, 0), true));
+}
@@ -14,16 +14,41 @@ use crate::sync::{PlayAction, PLAY_ACTION};
 use crate::utils::gettext;
 
 use crate::config::{Align, LyricDisplayMode};
-use crate::sync::{OsImp, PlayerId, OS};
+use crate::sync::{OsImp, PlayerId, TrackState, OS, TRACK_PLAYING_STATE};
 
-use crate::log::error;
+use crate::log::{error, info};
 use crate::{INSTANCE_NAME, PACKAGE_NAME};
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum PlayState {
+    #[default]
+    Idle,
+    Playing,
+    Paused,
+}
+
+impl PlayState {
+    fn current() -> Self {
+        TRACK_PLAYING_STATE.with_borrow(
+            |TrackState {
+                 metainfo, paused, ..
+             }| {
+                match (metainfo, paused) {
+                    (None, _) => PlayState::Idle,
+                    (Some(_), true) => PlayState::Paused,
+                    (Some(_), false) => PlayState::Playing,
+                }
+            },
+        )
+    }
+}
+
 #[derive(Debug)]
 struct TrayIcon {
     // For calling list_players() inside main thread.
     req_tx: async_channel::Sender<()>,
     resp_rx: async_channel::Receiver<Vec<PlayerId>>,
+    state: PlayState,
 }
 
 impl TrayIcon {
@@ -31,7 +56,11 @@ impl TrayIcon {
         req_tx: async_channel::Sender<()>,
         resp_rx: async_channel::Receiver<Vec<PlayerId>>,
     ) -> Self {
-        Self { req_tx, resp_rx }
+        Self {
+            req_tx,
+            resp_rx,
+            state: PlayState::default(),
+        }
     }
 }
 
@@ -39,6 +68,13 @@ impl Tray for TrayIcon {
     fn icon_name(&self) -> String {
         INSTANCE_NAME.get().unwrap().to_string()
     }
+    fn overlay_icon_name(&self) -> String {
+        match self.state {
+            PlayState::Idle => String::default(),
+            PlayState::Playing => "media-playback-start".into(),
+            PlayState::Paused => "media-playback-pause".into(),
+        }
+    }
     fn id(&self) -> String {
         PACKAGE_NAME.into()
     }
@@ -148,6 +184,16 @@ impl Tray for TrayIcon {
                 ..Default::default()
             }
             .into(),
+            StandardItem {
+                label: gettext("Disconnect"),
+                icon_name: "media-playback-stop".into(),
+                enabled: self.state != PlayState::Idle,
+                activate: Box::new(|_| {
+                    let _ = play_action().send_blocking(PlayAction::Disconnect);
+                }),
+                ..Default::default()
+            }
+            .into(),
             #[cfg(feature = "import-lyric")]
             SubMenu {
                 label: gettext("Import Lyric"),
@@ -207,6 +253,15 @@ impl Tray for TrayIcon {
                 ..Default::default()
             }
             .into(),
+            StandardItem {
+                label: gettext("Reload Lyric"),
+                icon_name: "view-refresh".into(),
+                activate: Box::new(|_| {
+                    let _ = play_action().send_blocking(PlayAction::ReloadLyric);
+                }),
+                ..Default::default()
+            }
+            .into(),
             StandardItem {
                 label: gettext("Refetch Lyric"),
                 icon_name: "folder-download".into(),
@@ -216,6 +271,33 @@ impl Tray for TrayIcon {
                 ..Default::default()
             }
             .into(),
+            StandardItem {
+                label: gettext("Remove Lyric"),
+                icon_name: "edit-delete".into(),
+                activate: Box::new(|_| {
+                    let _ = play_action().send_blocking(PlayAction::RemoveLyric);
+                }),
+                ..Default::default()
+            }
+            .into(),
+            StandardItem {
+                label: gettext("Toggle Always on Top"),
+                icon_name: "go-top".into(),
+                activate: Box::new(|_| {
+                    let _ = ui_action().send_blocking(UIAction::ToggleAlwaysOnTop);
+                }),
+                ..Default::default()
+            }
+            .into(),
+            StandardItem {
+                label: gettext("Toggle Offline Mode"),
+                icon_name: "network-offline".into(),
+                activate: Box::new(|_| {
+                    let _ = ui_action().send_blocking(UIAction::ToggleOffline);
+                }),
+                ..Default::default()
+            }
+            .into(),
             MenuItem::Separator,
             StandardItem {
                 label: gettext("Restart"),
@@ -239,7 +321,8 @@ impl Tray for TrayIcon {
     }
 }
 
-pub fn start_tray_service() {
+/// `poll_interval` controls how often the tray icon checks playback state.
+pub fn start_tray_service(poll_interval: Duration) {
     // mpris::PlayerFinder would create a new DBus connection which has 4 matches attached,
     // but in TrayService, the messages matching could never be handled.
     // This would make messages stalling within dbus broker, and might finally make dbus-broker
@@ -258,9 +341,35 @@ pub fn start_tray_service() {
         }
     });
 
+    let (state_tx, state_rx) = async_channel::unbounded();
+    let mut last_state = PlayState::default();
+    glib::timeout_add_local(poll_interval, move || {
+        let state = PlayState::current();
+        if state != last_state {
+            last_state = state;
+            if state_tx.try_send(state).is_err() {
+                return glib::ControlFlow::Break;
+            }
+        }
+        glib::ControlFlow::Continue
+    });
+
     tokio_spawn!(async move {
         let service = TrayIcon::new(req_tx, resp_rx);
-        let _ = service.spawn_without_dbus_name().await;
+        // desktops without StatusNotifierWatcher (e.g. GNOME without AppIndicator extension)
+        // would fail here, we just leave tray-icon disabled
+        let handle = match service.spawn_without_dbus_name().await {
+            Ok(handle) => handle,
+            Err(e) => {
+                info!("tray-icon disabled: {e}");
+                return;
+            }
+        };
+        while let Ok(state) = state_rx.recv().await {
+            if handle.update(|tray| tray.state = state).await.is_none() {
+                break;
+            }
+        }
     });
 }
 
@@ -1,4 +1,4 @@
-use std::{env::args, mem, process::Command};
+use std::{env::args, mem, process::Command, time::Duration};
 
 use anyhow::Result;
 use async_channel::Sender;
@@ -11,19 +11,35 @@ use crate::{
     utils::gettext,
 };
 
-pub fn start_tray_service() -> Result<()> {
+/// tray-item could not switch icons at runtime, so `_poll_interval` is unused here.
+pub fn start_tray_service(_poll_interval: Duration) -> Result<()> {
     let icon = IconSource::Resource("icon0");
     let mut tray = TrayItem::new(crate::DEFAULT_TEXT, icon)?;
 
     tray.add_menu_item(&gettext("Search lyric"), || {
         let _ = play_action().send_blocking(PlayAction::SearchLyric);
     })?;
+    tray.add_menu_item(&gettext("Reload lyric"), || {
+        let _ = play_action().send_blocking(PlayAction::ReloadLyric);
+    })?;
     tray.add_menu_item(&gettext("Refetch lyric"), || {
         let _ = play_action().send_blocking(PlayAction::RefetchLyric);
     })?;
+    tray.add_menu_item(&gettext("Remove lyric"), || {
+        let _ = play_action().send_blocking(PlayAction::RemoveLyric);
+    })?;
+    tray.add_menu_item(&gettext("Toggle Offline Mode"), || {
+        let _ = ui_action().send_blocking(UIAction::ToggleOffline);
+    })?;
+    tray.add_menu_item(&gettext("Disconnect"), || {
+        let _ = play_action().send_blocking(PlayAction::Disconnect);
+    })?;
     tray.add_menu_item(&gettext("Toggle Decoration"), || {
         let _ = ui_action().send_blocking(UIAction::SwitchDecoration);
     })?;
+    tray.add_menu_item(&gettext("Toggle Always on Top"), || {
+        let _ = ui_action().send_blocking(UIAction::ToggleAlwaysOnTop);
+    })?;
     tray.add_menu_item(&gettext("Toggle Passthrough"), || {
         let _ = ui_action().send_blocking(UIAction::SwitchPassthrough);
     })?;
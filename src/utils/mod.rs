@@ -89,6 +89,8 @@ pub fn init_dirs() -> Result<(PathBuf, PathBuf)> {
 mod shortcut;
 pub use shortcut::bind_shortcut;
 mod theme;
-pub use theme::auto_theme_change;
+pub use theme::{auto_theme_change, load_theme};
 mod instance;
 pub use instance::acquire_instance_name;
+mod power;
+pub use power::{on_battery, on_battery_in, POWER_CHECK_INTERVAL};
@@ -0,0 +1,42 @@
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+/// how often `register_sync_task_on_power` checks the power state
+pub const POWER_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+const POWER_SUPPLY_DIR: &str = "/sys/class/power_supply";
+
+fn read_attr(supply: &Path, attr: &str) -> Option<String> {
+    fs::read_to_string(supply.join(attr))
+        .ok()
+        .map(|s| s.trim().to_owned())
+}
+
+/// Whether the system runs on battery, according to power supplies in `dir`
+/// laid out like `/sys/class/power_supply`.
+///
+/// `false` if there's no battery, or any AC adapter is online.
+pub fn on_battery_in(dir: &Path) -> bool {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return false;
+    };
+    let mut discharging = false;
+    for supply in entries.flatten().map(|entry| entry.path()) {
+        match read_attr(&supply, "type").as_deref() {
+            Some("Mains") if read_attr(&supply, "online").as_deref() == Some("1") => {
+                return false;
+            }
+            Some("Battery") => {
+                discharging |= read_attr(&supply, "status").as_deref() == Some("Discharging");
+            }
+            _ => (),
+        }
+    }
+    discharging
+}
+
+/// Whether the system runs on battery, always `false` without sysfs
+pub fn on_battery() -> bool {
+    on_battery_in(Path::new(POWER_SUPPLY_DIR))
+}
@@ -1,7 +1,35 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
 use futures_lite::StreamExt;
 use tracing::info;
 
-use crate::{config::ColorScheme, glib_spawn, log, THEME_PATH};
+use crate::{config::ColorScheme, glib_spawn, log, THEME_PATH, THEME_PRESETS_DIR};
+
+/// Find `<theme>.css` in `theme_dir`, then in `THEME_PRESETS_DIR`
+pub fn find_theme(theme_dir: &Path, theme: &str) -> Result<PathBuf> {
+    let theme_file_name = format!("{theme}.css");
+    let user_theme = theme_dir.join(&theme_file_name);
+    if user_theme.exists() {
+        return Ok(user_theme);
+    }
+
+    let Some(global_theme) = THEME_PRESETS_DIR.map(|d| PathBuf::from(d).join(&theme_file_name))
+    else {
+        anyhow::bail!("theme {theme_file_name} not found");
+    };
+    Ok(global_theme)
+}
+
+/// Apply `<theme>.css` and set it as `THEME_PATH`
+pub fn load_theme(theme_dir: &Path, theme: &str) -> Result<()> {
+    let theme_path = find_theme(theme_dir, theme)?;
+    log::debug!("theme path: {:?}", theme_path);
+    let css_style = std::fs::read_to_string(&theme_path)?;
+    crate::app::utils::merge_css(&css_style);
+    THEME_PATH.set(theme_path);
+    Ok(())
+}
 
 pub fn auto_theme_change(color_scheme: ColorScheme, theme_switch: bool) {
     let Some(settings) = gtk::Settings::default() else {